@@ -389,6 +389,7 @@ context_menu_open_dependency_manager = Open &Dependency Manager
 context_menu_open_containing_folder = Open &Containing Folder
 context_menu_open_with_external_program = Open with &External Program
 context_menu_open_notes = Open &Notes
+context_menu_open_merged_table = Open &Merged Table
 
 context_menu_check_tables = &Check Tables
 context_menu_merge_tables = &Merge Tables
@@ -421,9 +422,6 @@ settings_extra_paths_title = Extra Paths
 settings_paths_mymod = MyMod's Folder
 settings_paths_mymod_ph = This is the folder where you want to store all "MyMod" related files.
 
-settings_paths_zip = 7Zip Exe's Path
-settings_paths_zip_ph = This is the full path to 7Zip's executable.
-
 settings_game_label = Game Folder
 settings_asskit_label = Assembly Kit Folder
 settings_game_line_ph = This is the folder where you have {"{"}{"}"} installed, where the .exe is.
@@ -744,6 +742,34 @@ pfs_diagnostics_files_to_ignore_description_label =
 
 pfs_import_files_to_ignore_label = <h3>Files to Ignore when Importing</h3>
 pfs_import_files_to_ignore_description_label = <p>The files on this list will be ignored when importing from a MyMod folder. Only for MyMods. Paths are relative, the glory of the empire is absolute.</p>
+pfs_compression_rules_label =
+    <span>&nbsp;</span>
+    <h3>Compression Rules</h3>
+pfs_compression_rules_description_label =
+    <span>&nbsp;</span>
+    <p>The rules on this list decide which PackedFiles get compressed when saving the PackFile. Only for PackFiles that support compression. If more than one rule matches a PackedFile, the last one wins. PackedFiles not matching any rule keep their current compression state, except tables, which are never compressed unless a rule says so.</p><p><b>One rule per line. Comment lines with #.</b> Use <code>*</code> to match any text within a folder, <code>**</code> to match any text across folders, and <code>?</code> to match a single character. Patterns without a <code>/</code> match against any folder or file name in the path. The following are valid examples:</p>
+    <ul style="list-style-type: none">
+        <li>
+            <code>compress;*.loc</code>
+            <ul><li>All Loc PackedFiles will be compressed.</li></ul>
+        </li>
+        <li>
+            <code>compress;db/land_units_tables</code>
+            <ul><li>All tables in that folder will be compressed.</li></ul>
+        </li>
+        <li>
+            <code>skip;*.ca_vp8</code>
+            <ul><li>Videos will not be compressed.</li></ul>
+        </li>
+        <li>
+            <code>skip;ui/**.png</code>
+            <ul><li>PNG images within the ui folder, or any of its subfolders, will not be compressed.</li></ul>
+        </li>
+    </ul>
+    <br>
+
+pfs_compression_format_label = <h3>Compression Format</h3>
+pfs_compression_format_description_label = <p>The format used to compress PackedFiles when saving the PackFile. Valid formats are <code>lzma1</code>, <code>lz4</code> and <code>zstd</code>. Only <code>lzma1</code> is supported by all games supporting compression. If the Game Selected doesn't support the format, the PackFile will fail to save.</p>
 pfs_disable_autosaves_label = <h3>Disable Autosaves for this PackFile</h3>
 pfs_disable_autosaves_description_label = <p></p>
 
@@ -946,6 +972,9 @@ label_invalid_packfile_name = Invalid PackFile Name:
 invalid_packfile_name_explanation = PackFile names cannot contain whitespace characters.
 
     To fix it, replace any whitespace in the PackFile's name with underscores.
+file_overridden_explanation = This file exists in more than one PackFile (or in the vanilla files). Only the one in the last PackFile loaded is used by the game.
+duplicated_db_key_across_packs_explanation = More than one PackFile adds a row with these keys to the same table. The game reads the table files by name and only uses the first row it finds with these keys.
+duplicated_loc_key_across_packs_explanation = More than one PackFile defines this Loc key. The game reads the Loc files by path and only uses the first definition it finds.
 
 label_table_name_ends_in_number = Table name ends in number:
 table_name_ends_in_number_explanation = Numbers at the end of a DB Table's name usually cause a very weird issue, where a mod crashes for anyone but the modder who makes it.
@@ -1230,3 +1259,49 @@ tt_about_check_lua_autogen_updates = Checks if there is any update available for
 update_lua_autogen_checker = Update TW Autogen Checker
 settings_disable_mymod_automatic_git_repo = Disable initial Git Repo on MyMods:
 settings_disable_mymod_automatic_configs = Disable initial editor configs on MyMods:
+merged_table_title = Merged Table: {"{"}{"}"}
+merged_table_pack = Pack
+merged_table_file = File
+esf_query = Type a path query here and press Enter to select the matching nodes. For example: CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION
+esf_query_no_matches = No nodes match the query.
+texture_path_not_found_explanation = The reported RigidModel uses a texture that has not been found in either this mod, its parent mods, or the game files. The mod may not show the texture correctly ingame.
+anim_skeleton = Skeleton:
+anim_end_time = End Time:
+anim_bones = Bones:
+anim_tracks_not_decoded = Unknown (tracks not decoded)
+anim_skeleton_mismatch_explanation = The reported AnimFragment uses an Anim made for a different skeleton than the one of the fragment or its entry. The animation may look broken ingame.
+lua_syntax_error_explanation = The reported Lua script has a syntax error. The game will fail to load it, and anything done by the script will not happen ingame.
+unknown_api_function_explanation = The reported Lua script calls a function that is not part of the known scripting API of the game. It may be a typo, or a function the game doesn't have.
+missing_db_key_explanation = The reported Lua script uses a string that looks like a DB key, but it has not been found in any table of this mod, its parent mods, or the game files. It may be a typo, or a key from a mod that's not loaded.
+invalid_diagnostics_rules_explanation = The diagnostics rules of this PackFile, or the ones in the .diagnostics.toml file next to it, are not valid or contain rules that don't exist. Invalid rules are not applied, and unknown ones are ignored.
+pfs_diagnostics_rules_label =
+    <span>&nbsp;</span>
+    <h3>Diagnostics Rules</h3>
+pfs_diagnostics_rules_description_label =
+    <span>&nbsp;</span>
+    <p>The rules on this list enable, disable or change the level of the results of the diagnostics check. They're written in TOML, and applied in order, so if more than one rule matches a result, the last one wins. Rules in a <code>.diagnostics.toml</code> file next to the PackFile (like <code>my_mod.diagnostics.toml</code> for MyMods) are applied after these ones.</p><p>Each rule can have a <code>rule</code> (rule ID like <code>DB/InvalidReference</code>, report type like <code>InvalidReference</code>, or <code>*</code>), <code>enabled</code> (true or false), <code>level</code> (info, warning or error), and optionally be limited to <code>paths</code> (path patterns), <code>tables</code> and <code>columns</code>. The following is a valid example:</p>
+    <pre>
+    [[rules]]
+    rule = "InvalidReference"
+    tables = ["land_units"]
+    columns = ["category"]
+    level = "warning"
+
+    [[rules]]
+    rule = "DB/EmptyRow"
+    paths = ["db/**/test_*"]
+    enabled = false
+    </pre>
+    <br>
+diagnostics_button_fix_all = Fix All
+label_missing_loc_data = Missing Loc Data:
+missing_loc_data_explanation = This row of the table has a localisable field without a loc entry, neither in this PackFile nor in its dependencies. The game will show an empty text in its place.
+label_orphan_loc_key = Orphan Loc Key:
+orphan_loc_key_explanation = This loc key belongs to a localisable field of a table, but no row of that table with the key it expects exists, neither in this PackFile nor in its dependencies. It's probably a leftover from a renamed or deleted row.
+label_invalid_enum_value = Invalid Enum Value:
+invalid_enum_value_explanation = The value of this cell is not one of the values of the enum of its column. The game may not know what to do with it.
+label_invalid_bitwise_value = Invalid Bitwise Value:
+invalid_bitwise_value_explanation = The value of this bitwise cell has bits set outside the flags declared in the schema. These bits are not shown in the table, and they are lost when saving it.
+label_value_out_of_range = Value Out Of Range:
+value_out_of_range_explanation = The value of this cell is out of the min/max range set for its column in the schema patches.
+uic_layout_not_decoded = The layout of this UI Component could not be decoded, so it is shown as raw data. It will be saved back without changes.
@@ -1550,9 +1550,6 @@ settings_extra_paths_title = Прочие расположения
 settings_paths_mymod = Папка { -mymod(case: "genitive") }
 settings_paths_mymod_ph = Это папка, в которой будут храниться все относящиеся к функции "{ -mymod }" файлы.
 
-settings_paths_zip = Путь до .exe-файла 7Zip
-settings_paths_zip_ph = Полный путь до исполняемого файла 7Zip.
-
 settings_game_label = Папка игры
 settings_asskit_label = Папка { -AssemblyKit(case:"genitive") }
 settings_game_line_ph = Это папка, где находится .exe-файл установленной игры {"{"}{"}"}.
@@ -89,7 +89,189 @@ pub fn initialize_app<'a>() -> Command<'a> {
                 .value_name("PACKFILES TO CHECK, IN LOAD ORDER")
                 .help("Performs a diagnostics check over the PackFiles provided.")
                 .takes_value(true)
-                .min_values(1)))
+                .min_values(1))
+            .arg(Arg::new("load-order")
+                .short('l')
+                .long("load-order")
+                .value_name("PACKFILES TO CHECK")
+                .help("Reports the conflicts between the PackFiles provided and the vanilla files, resolved following the game's load order.")
+                .takes_value(true)
+                .min_values(1))
+
+            // `Format` option. To choose the format of the diagnostics output.
+            .arg(Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format used to print the diagnostics. One of: json, sarif, junit, text. If not provided, json is used.")
+                .takes_value(true))
+
+            // `Fail On` option. To return an error code if there are diagnostics of a certain level, so it can be used in CI.
+            .arg(Arg::new("fail-on")
+                .long("fail-on")
+                .value_name("LEVEL")
+                .help("Exits with an error code if any diagnostic of this level or a more severe one is found. One of: warning, error.")
+                .takes_value(true))
+
+            // `Rules` option. To enable, disable or change the level of diagnostics from a TOML file.
+            .arg(Arg::new("rules")
+                .short('r')
+                .long("rules")
+                .value_name("RULES FILE")
+                .help("TOML file with rules to enable, disable or change the level of diagnostics. The file is validated, and unknown rules are reported as errors. If used alone, it only validates the file.")
+                .takes_value(true))
+
+            // `Fix` option. To automatically fix the diagnostics that have a fix.
+            .arg(Arg::new("fix")
+                .long("fix")
+                .help("Automatically fixes the diagnostics that can be fixed, and saves the PackFile. Only works with --check and one PackFile.")
+                .requires("check")))
+
+        // `ESF` Subcommand. To convert ESF files to text and back.
+        .subcommand(Command::new("esf")
+            .about("Allows you to convert ESF files (startpos, save games…) to JSON and back, so they can be edited as text.")
+
+            // `Export` option. To export ESF files to JSON.
+            .arg(Arg::new("export")
+                .short('e')
+                .long("export")
+                .value_name("ESF FILE - DESTINATION FILE")
+                .help("Export an ESF file to a JSON file.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Import` option. To import JSON files back into ESF files.
+            .arg(Arg::new("import")
+                .short('i')
+                .long("import")
+                .value_name("JSON FILE - DESTINATION FILE")
+                .help("Import a JSON file exported with the \"export\" option as an ESF file.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Query` option. To print the nodes matching a path query.
+            .arg(Arg::new("query")
+                .short('q')
+                .long("query")
+                .value_name("ESF FILE - QUERY")
+                .help("Prints as JSON the nodes of an ESF file matching the provided path query, like \"CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION[name='wh_main_emp_empire']/TREASURY\".")
+                .takes_value(true)
+                .number_of_values(2))
+
+            // `Set` option. To change the value of the nodes matching a path query.
+            .arg(Arg::new("set")
+                .short('s')
+                .long("set")
+                .value_name("ESF FILE - QUERY - VALUE - DESTINATION FILE")
+                .help("Sets the value of the nodes of an ESF file matching the provided path query. If no destination file is provided, the ESF file is overwritten.")
+                .takes_value(true)
+                .min_values(3)
+                .max_values(4))
+
+            // `Diff` option. Requires you to provide the two ESF files to compare.
+            .arg(Arg::new("diff")
+                .long("diff")
+                .value_name("OLD ESF FILE - NEW ESF FILE")
+                .help("Compares two ESF files, printing the added, removed and modified nodes with their paths.")
+                .takes_value(true)
+                .number_of_values(2))
+
+            // `Json` option. To print the diff as JSON instead of text.
+            .arg(Arg::new("json")
+                .long("json")
+                .help("Prints the diff as JSON, instead of as text.")
+                .requires("diff")))
+
+        // `RigidModel` Subcommand. To export RigidModels to other 3D formats.
+        .subcommand(Command::new("rigidmodel")
+            .about("Allows you to export RigidModel files to other 3D formats.")
+
+            // `Export OBJ` option. To export a LOD of a RigidModel as a Wavefront OBJ file.
+            .arg(Arg::new("export-obj")
+                .long("export-obj")
+                .value_name("RIGIDMODEL FILE - DESTINATION FILE")
+                .help("Export a LOD of a RigidModel to a Wavefront OBJ file.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `Export glTF` option. To export a LOD of a RigidModel as a glTF file, with its data in a .bin file next to it.
+            .arg(Arg::new("export-gltf")
+                .long("export-gltf")
+                .value_name("RIGIDMODEL FILE - DESTINATION FILE")
+                .help("Export a LOD of a RigidModel to a glTF file. Its binary data is saved next to it, in a file with the same name and the \"bin\" extension.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2))
+
+            // `LOD` option. To choose the LOD to export.
+            .arg(Arg::new("lod")
+                .short('l')
+                .long("lod")
+                .value_name("LOD")
+                .help("LOD to export. If not provided, the first LOD is exported.")
+                .takes_value(true)))
+
+        // `Image` Subcommand. To inspect images and convert them from and to PNG.
+        .subcommand(Command::new("image")
+            .about("Allows you to inspect images and convert them from and to PNG.")
+
+            // `Info` option. To print the format, size and mipmaps of images.
+            .arg(Arg::new("info")
+                .short('i')
+                .long("info")
+                .value_name("IMAGE FILES")
+                .help("Print the format, size and mipmap count of one or more DDS, TGA, PNG or JPEG images.")
+                .takes_value(true)
+                .min_values(1))
+
+            // `To PNG` option. To convert images to PNG.
+            .arg(Arg::new("to-png")
+                .short('p')
+                .long("to-png")
+                .value_name("IMAGE FILES")
+                .help("Convert one or more DDS or TGA images to PNG. Each PNG is saved next to its image, with the \"png\" extension.")
+                .takes_value(true)
+                .min_values(1))
+
+            // `From PNG` option. To convert a PNG to DDS or TGA.
+            .arg(Arg::new("from-png")
+                .long("from-png")
+                .value_name("PNG FILE - DESTINATION FILE")
+                .help("Convert a PNG image to DDS or TGA, depending on the extension of the destination file.")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(2))
+
+            // `DDS Format` option. To choose the format of the DDS images we create.
+            .arg(Arg::new("dds-format")
+                .short('f')
+                .long("dds-format")
+                .value_name("FORMAT")
+                .help("Format used when converting to DDS. One of: bc1, bc2, bc3, bc4, bc5, bgra8, rgba8. If not provided, bc3 is used.")
+                .takes_value(true))
+
+            // `Mipmaps` option. To generate mipmaps when converting to DDS.
+            .arg(Arg::new("mipmaps")
+                .short('m')
+                .long("mipmaps")
+                .help("Generate all the mipmaps when converting to DDS.")))
+
+        // `UIC` Subcommand. To export UI Components to other formats.
+        .subcommand(Command::new("uic")
+            .about("Allows you to export UI Components to other formats.")
+
+            // `Export XML` option. To export an UI Component as XML, for diffing.
+            .arg(Arg::new("export-xml")
+                .short('x')
+                .long("export-xml")
+                .value_name("UIC FILE - DESTINATION FILE")
+                .help("Export an UI Component to an XML file. If no destination file is provided, the XML file is saved next to the UI Component, with the \"xml\" extension.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(2)))
 
         // `PackFile` Subcommand. Every command that edits PackFiles in any way goes here.
         .subcommand(Command::new("packfile")
@@ -148,6 +330,38 @@ pub fn initialize_app<'a>() -> Command<'a> {
                 .takes_value(true)
                 .min_values(2))
 
+            // `Compression Rules` option. Requires you to provide the rules, or nothing to remove them.
+            .arg(Arg::new("compression-rules")
+                .short('c')
+                .long("compression-rules")
+                .value_name("RULES")
+                .help("Replaces the compression rules of the PackFile, then saves it. Each rule is either \"compress;PATTERN\" or \"skip;PATTERN\". If no rules are provided, the current ones are removed.")
+                .takes_value(true)
+                .min_values(0))
+
+            // `Compression Format` option. Requires you to provide the format.
+            .arg(Arg::new("compression-format")
+                .short('C')
+                .long("compression-format")
+                .value_name("FORMAT")
+                .help("Sets the format used to compress the PackedFiles of the PackFile, then saves it. Valid formats are \"lzma1\", \"lz4\" and \"zstd\", but which ones can be used depends on the game selected.")
+                .takes_value(true)
+                .max_values(1))
+
+            // `Diff` option. Requires you to provide the PackFile to compare against.
+            .arg(Arg::new("diff")
+                .long("diff")
+                .value_name("PACKFILE TO COMPARE")
+                .help("Compares the PackFile against the provided one, printing the added, removed and modified files. For tables, it also prints the changes row by row.")
+                .takes_value(true)
+                .max_values(1))
+
+            // `Json` option. To print the diff as JSON instead of text.
+            .arg(Arg::new("json")
+                .long("json")
+                .help("Prints the diff as JSON, instead of as text.")
+                .requires("diff"))
+
             // `List` option.
             .arg(Arg::new("list")
                 .short('l')
@@ -169,7 +383,7 @@ pub fn initialize_app<'a>() -> Command<'a> {
                 .short('i')
                 .long("import")
                 .value_name("TSV FILE - DESTINATION FILE")
-                .help("Import a compatible TSV file as a DB/LOC table.")
+                .help("Import a compatible TSV file as a DB/LOC table, or as a GroupFormations file.")
                 .takes_value(true)
                 .min_values(1)
                 .max_values(2))
@@ -179,10 +393,19 @@ pub fn initialize_app<'a>() -> Command<'a> {
                 .short('e')
                 .long("export")
                 .value_name("DB FILE - DESTINATION FILE")
-                .help("Export a DB/LOC Table's data to a TSV file.")
+                .help("Export a DB/LOC Table's data, or a GroupFormations file, to a TSV file.")
                 .takes_value(true)
                 .min_values(1)
-                .max_values(2)))
+                .max_values(2))
+
+            // `Merged` option. To get the effective version of a DB table, once vanilla, parent mods and the PackFile are loaded.
+            .arg(Arg::new("merged")
+                .short('m')
+                .long("merged")
+                .value_name("TABLE NAME")
+                .help("Prints as JSON the effective version of a DB table once vanilla, the parent mods and the PackFile (if provided) are loaded, with the source of each row.")
+                .takes_value(true)
+                .max_values(1)))
 
         // `Schema` Subcommand. Basically, here goes commands destined to keep schemas up-to-date.
         .subcommand(Command::new("schema")
@@ -18,7 +18,9 @@ use std::path::PathBuf;
 use rpfm_error::{ErrorKind, Result};
 
 use rpfm_lib::dependencies::Dependencies;
-use rpfm_lib::diagnostics::Diagnostics;
+use rpfm_lib::diagnostics::{DiagnosticLevel, Diagnostics};
+use rpfm_lib::diagnostics::output::DiagnosticsOutputFormat;
+use rpfm_lib::diagnostics::rules::DiagnosticsRules;
 use rpfm_lib::packedfile::PackedFileType;
 use rpfm_lib::packfile::PackFile;
 use rpfm_lib::schema::Schema;
@@ -32,15 +34,27 @@ use crate::config::Config;
 //---------------------------------------------------------------------------//
 
 /// This function checks the PackFiles on the paths received for errors.
+///
+/// If `fail_on` is provided, this fails if any diagnostic with that level or a more severe one is found.
+/// If `rules` are provided, they're applied after the ones of the PackFiles.
+/// If `fix` is true, the fixable diagnostics are fixed and the PackFile is saved before printing the remaining diagnostics. Only works with one PackFile.
 pub fn check(
     config: &Config,
     pack_files: &[&str],
-    asskit_path: Option<&str>
+    asskit_path: Option<&str>,
+    format: DiagnosticsOutputFormat,
+    fail_on: Option<DiagnosticLevel>,
+    rules: Option<&DiagnosticsRules>,
+    fix: bool,
 ) -> Result<()> {
     if config.verbosity_level > 0 {
         info!("Checking the following PackFiles for errors: {:?}", pack_files);
     }
 
+    if fix && pack_files.len() != 1 {
+        return Err(ErrorKind::NoHTMLError("Fixing diagnostics only works with one PackFile.".to_owned()).into());
+    }
+
     // Prepare the diagnostic data. If it fails, try to regenerate the dependencies.
     let mut dependencies = Dependencies::default();
     match &config.game_selected {
@@ -60,29 +74,127 @@ pub fn check(
                 });
             }
 
-            if dependencies.rebuild(&[], false).is_err() {
+            load_dependencies(config, &mut dependencies, game_selected.get_raw_db_version(), &asskit_path)?;
+
+            let mut diagnostics = Diagnostics::default();
+            diagnostics.check(&pack_file, &mut dependencies);
+            if let Some(rules) = rules {
+                diagnostics.apply_rules(rules, Some(&pack_file))?;
+            }
+
+            // If we have to fix the diagnostics, fix them, save the PackFile and check it again, so only the remaining diagnostics are printed.
+            if fix && diagnostics.has_fixes() {
+                let edited_paths = diagnostics.apply_fixes(&mut pack_file, &dependencies, None)?;
+                pack_file.save(None)?;
+
                 if config.verbosity_level > 0 {
-                    info!("Dependencies rebuild failed. Regenerating…");
+                    for (path_before, path_after) in &edited_paths {
+                        if path_before == path_after {
+                            info!("Fixed: {}", path_before.join("/"));
+                        } else {
+                            info!("Fixed: {} (renamed to {})", path_before.join("/"), path_after.join("/"));
+                        }
+                    }
                 }
 
-                let version = game_selected.get_raw_db_version();
-
-                dependencies.generate_dependencies_cache(&asskit_path, version)?;
-                dependencies.save_to_binary()?;
-                dependencies.rebuild(&[], false)?;
+                diagnostics = Diagnostics::default();
+                diagnostics.check(&pack_file, &mut dependencies);
+                if let Some(rules) = rules {
+                    diagnostics.apply_rules(rules, Some(&pack_file))?;
+                }
             }
 
-            let mut diagnostics = Diagnostics::default();
-            diagnostics.check(&pack_file, &mut dependencies);
-
-            println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+            println!("{}", diagnostics.to_format(format)?);
 
             if config.verbosity_level > 0 {
                 info!("File(s) added successfully to the PackFile.");
             }
+
+            fail_on_level(&diagnostics, fail_on)?;
+        },
+        None => return Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+
+    Ok(())
+}
+
+/// This function checks the PackFiles on the paths received for conflicts between them, following the game's load order.
+///
+/// If `fail_on` is provided, this fails if any diagnostic with that level or a more severe one is found.
+/// If `rules` are provided, they're applied to the conflicts found.
+pub fn check_load_order(
+    config: &Config,
+    pack_files: &[&str],
+    asskit_path: Option<&str>,
+    format: DiagnosticsOutputFormat,
+    fail_on: Option<DiagnosticLevel>,
+    rules: Option<&DiagnosticsRules>,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Checking the following PackFiles for load order conflicts: {:?}", pack_files);
+    }
+
+    let mut dependencies = Dependencies::default();
+    match &config.game_selected {
+        Some(game_selected) => {
+            *SCHEMA.write().unwrap() = Some(Schema::load(game_selected.get_schema_name())?);
+            let asskit_path = asskit_path.map(PathBuf::from);
+
+            // Unlike the normal check, here we need each PackFile separated, so we know where each file comes from.
+            let pack_files = pack_files.iter().map(|path| PackFile::read(&PathBuf::from(path), true)).collect::<Result<Vec<PackFile>>>()?;
+
+            load_dependencies(config, &mut dependencies, game_selected.get_raw_db_version(), &asskit_path)?;
+
+            let mut diagnostics = Diagnostics::default();
+            diagnostics.check_load_order(&pack_files, &mut dependencies);
+            if let Some(rules) = rules {
+                diagnostics.apply_rules(rules, None)?;
+            }
+
+            println!("{}", diagnostics.to_format(format)?);
+            fail_on_level(&diagnostics, fail_on)?;
         },
         None => return Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 
     Ok(())
 }
+
+/// This function loads the diagnostics rules from the provided TOML file, returning an error if any of them is invalid or unknown.
+pub fn load_rules(config: &Config, path: &str) -> Result<DiagnosticsRules> {
+    if config.verbosity_level > 0 {
+        info!("Loading diagnostics rules from: {}", path);
+    }
+
+    let rules = DiagnosticsRules::load(&PathBuf::from(path))?;
+    rules.validate()?;
+
+    if config.verbosity_level > 0 {
+        info!("Diagnostics rules loaded successfully.");
+    }
+
+    Ok(rules)
+}
+
+/// This function returns an error if there are diagnostics with the provided level or a more severe one, so the CLI exits with an error code.
+fn fail_on_level(diagnostics: &Diagnostics, fail_on: Option<DiagnosticLevel>) -> Result<()> {
+    match fail_on {
+        Some(level) if diagnostics.has_level(level) => Err(ErrorKind::NoHTMLError(format!("Diagnostics with level \"{}\" or higher found.", level)).into()),
+        _ => Ok(()),
+    }
+}
+
+/// This function loads the dependencies of the game selected. If it fails, it tries to regenerate them.
+fn load_dependencies(config: &Config, dependencies: &mut Dependencies, version: i16, asskit_path: &Option<PathBuf>) -> Result<()> {
+    if dependencies.rebuild(&[], false).is_err() {
+        if config.verbosity_level > 0 {
+            info!("Dependencies rebuild failed. Regenerating…");
+        }
+
+        dependencies.generate_dependencies_cache(asskit_path, version)?;
+        dependencies.save_to_binary()?;
+        dependencies.rebuild(&[], false)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+
+use std::fs::{read, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::esf::ESF;
+
+use crate::config::Config;
+
+/// Extension added to the ESF files when exporting them to JSON.
+const JSON_EXTENSION: &str = "json";
+
+//---------------------------------------------------------------------------//
+// 							ESF Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function exports a binary ESF file to JSON.
+///
+/// If no destination path was provided, it leaves the JSON file in the same place as the ESF file, with `.json` added to its name.
+pub fn export_json(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Export ESF File as JSON: {}", source_path);
+	}
+
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => PathBuf::from(format!("{}.{}", source_path, JSON_EXTENSION)),
+    };
+
+    let esf = ESF::read(&read(source_path)?)?;
+    write(&destination_path, esf.to_json()?)?;
+
+	if config.verbosity_level > 0 {
+        info!("ESF File exported to: {}", destination_path.to_string_lossy());
+	}
+    Ok(())
+}
+
+/// This function imports a JSON file exported with `export_json` back into a binary ESF file.
+///
+/// If no destination path was provided, it leaves the ESF file in the same place as the JSON file, with `.json` removed from its name.
+pub fn import_json(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Import JSON File as ESF File: {}", source_path);
+	}
+
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => {
+            let source_path = Path::new(source_path);
+            match source_path.extension() {
+                Some(extension) if extension == JSON_EXTENSION => source_path.with_extension(""),
+                _ => source_path.with_extension("esf"),
+            }
+        }
+    };
+
+    let esf = ESF::from_json(&read_to_string(source_path)?)?;
+    write(&destination_path, esf.save()?)?;
+
+	if config.verbosity_level > 0 {
+        info!("JSON File imported to: {}", destination_path.to_string_lossy());
+	}
+    Ok(())
+}
+
+/// This function prints as JSON the nodes of an ESF file matching the provided query.
+pub fn query(
+    config: &Config,
+    source_path: &str,
+    query: &str,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Query ESF File: {} - {}", source_path, query);
+	}
+
+    let esf = ESF::read(&read(source_path)?)?;
+    let nodes = esf.query(query)?;
+    println!("{}", serde_json::to_string_pretty(&nodes)?);
+    Ok(())
+}
+
+/// This function sets the value of the nodes of an ESF file matching the provided query.
+///
+/// If no destination path was provided, the source ESF file is overwritten.
+pub fn set(
+    config: &Config,
+    source_path: &str,
+    query: &str,
+    value: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Set value in ESF File: {} - {} - {}", source_path, query, value);
+	}
+
+    let mut esf = ESF::read(&read(source_path)?)?;
+    let count = esf.set_query_value(query, value)?;
+    write(destination_path.unwrap_or(source_path), esf.save()?)?;
+
+	if config.verbosity_level > 0 {
+        info!("{} nodes changed.", count);
+	}
+    Ok(())
+}
+
+/// This function compares two ESF files, printing their differences as text or JSON.
+pub fn diff(
+    config: &Config,
+    old_path: &str,
+    new_path: &str,
+    as_json: bool,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Comparing ESF Files: {} against {}", old_path, new_path);
+	}
+
+    let old_esf = ESF::read(&read(old_path)?)?;
+    let new_esf = ESF::read(&read(new_path)?)?;
+    let diff = old_esf.diff(&new_esf);
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `esf` command.
+!*/
+
+use std::fs::{read, read_to_string, remove_file, write};
+
+use rpfm_lib::packedfile::esf::ESF;
+
+use crate::config::Config;
+
+use super::esf::{export_json, import_json};
+
+/// ESF file with non-finite floats and a compressed node, as JSON.
+const ESF_JSON: &str = r#"{"signature":"CAAB","unknown_1":7,"creation_date":333,"root_node":{"Record":{"record_flags":{"bits":128},"version":0,"name":"ROOT","children":[[
+    {"F64":"inf"},
+    {"F32Array":[1.5,"-inf","NaN"]},
+    {"Coord2d":{"x":"NaN","y":2.0}},
+    {"Record":{"record_flags":{"bits":128},"version":0,"name":"COMPRESSED_DATA","children":[[
+        {"Record":{"record_flags":{"bits":128},"version":0,"name":"INNER","children":[[{"Ascii":"compressed_compressed_compressed"},{"F32":{"value":"NaN","optimized":false}}]]}}
+    ]]}}
+]]}}}"#;
+
+/// Test to make sure the `esf export` and `esf import` commands turn ESF files into JSON and back without changing them.
+#[test]
+fn test_esf_command_export_import() {
+    let config = Config {
+        game_selected: None,
+        verbosity_level: 0,
+    };
+
+    let esf_path = "../test_files/esf_command_test.esf";
+    let json_path = "../test_files/esf_command_test.esf.json";
+    let esf_copy_path = "../test_files/esf_command_test_copy.esf";
+    let data = ESF::from_json(ESF_JSON).unwrap().save().unwrap();
+    write(esf_path, &data).unwrap();
+
+    // Without destination, the JSON file is left next to the ESF file, and imported back over it.
+    export_json(&config, esf_path, None).unwrap();
+    let json = read_to_string(json_path).unwrap();
+    assert!(!json.contains("null") && json.contains("\"INNER\""));
+
+    import_json(&config, json_path, None).unwrap();
+    assert_eq!(read(esf_path).unwrap(), data);
+
+    // With destination, the files go where we tell them.
+    import_json(&config, json_path, Some(esf_copy_path)).unwrap();
+    assert_eq!(read(esf_copy_path).unwrap(), data);
+
+    export_json(&config, esf_copy_path, Some(json_path)).unwrap();
+    assert_eq!(read_to_string(json_path).unwrap(), json);
+
+    // Broken JSON files must fail instead of generating broken ESF files.
+    write(json_path, json.replacen("\"NaN\"", "null", 1)).unwrap();
+    assert!(import_json(&config, json_path, Some(esf_copy_path)).is_err());
+
+    remove_file(esf_path).unwrap();
+    remove_file(json_path).unwrap();
+    remove_file(esf_copy_path).unwrap();
+}
@@ -0,0 +1,95 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+
+use std::fs::{read, write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::packedfile::image::{Image, RgbaImage};
+use rpfm_lib::packedfile::image::dds::DdsFormat;
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							Image Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function prints the format, size and mipmap count of the provided images.
+pub fn info(
+    config: &Config,
+    paths: &[&str],
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Reading metadata of {} image(s).", paths.len());
+	}
+
+    for path in paths {
+        let metadata = Image::read(&read(path)?)?.get_metadata()?;
+        println!("{}: {} - {}x{} - {} mipmap(s)", path, metadata.get_ref_format(), metadata.get_ref_width(), metadata.get_ref_height(), metadata.get_ref_mipmap_count());
+    }
+
+    Ok(())
+}
+
+/// This function converts the provided images to PNG, leaving each one next to its source image, with the `.png` extension.
+pub fn to_png(
+    config: &Config,
+    paths: &[&str],
+) -> Result<()> {
+    for path in paths {
+        if config.verbosity_level > 0 {
+            info!("Converting image to PNG: {}", path);
+        }
+
+        let destination_path = Path::new(path).with_extension("png");
+        if destination_path == Path::new(path) {
+            return Err(ErrorKind::NoHTMLError(format!("The image {} is already a PNG.", path)).into());
+        }
+
+        write(&destination_path, Image::read(&read(path)?)?.to_png()?)?;
+    }
+
+	if config.verbosity_level > 0 {
+        info!("Image(s) successfully converted to PNG.");
+	}
+    Ok(())
+}
+
+/// This function converts a PNG image to DDS or TGA, depending on the extension of the destination path.
+///
+/// DDS images are encoded in the provided format, optionally with all their mipmaps.
+pub fn from_png(
+    config: &Config,
+    source_path: &str,
+    destination_path: &str,
+    dds_format: &DdsFormat,
+    mipmaps: bool,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Converting PNG image: {}", source_path);
+	}
+
+    let image = RgbaImage::from_png(&read(source_path)?)?;
+    let extension = Path::new(destination_path).extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let data = match &*extension {
+        "dds" => image.to_dds(dds_format, mipmaps)?,
+        "tga" => image.to_tga()?,
+        _ => return Err(ErrorKind::NoHTMLError(format!("PNG images can only be converted to DDS or TGA, not to \"{}\".", extension)).into()),
+    };
+
+    write(destination_path, data)?;
+
+	if config.verbosity_level > 0 {
+        info!("Image converted to: {}", destination_path);
+	}
+    Ok(())
+}
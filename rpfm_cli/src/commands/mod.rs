@@ -14,13 +14,22 @@
 use clap::ArgMatches;
 
 use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::diagnostics::DiagnosticLevel;
+use rpfm_lib::diagnostics::output::DiagnosticsOutputFormat;
+use rpfm_lib::packedfile::image::dds::DdsFormat;
 
 use crate::config::Config;
 
 mod diagnostic;
+mod esf;
+#[cfg(test)]
+mod esf_test;
+mod image;
 mod table;
 mod packfile;
+mod rigidmodel;
 mod schema;
+mod uic;
 
 //---------------------------------------------------------------------------//
 // 								Command Variants
@@ -96,6 +105,25 @@ pub fn command_packfile(config: &Config, matches: &ArgMatches, packfile: Option<
                 }
             }
 
+            else if matches.is_present("compression-rules") {
+                let rules = matches.values_of("compression-rules").map(|values| values.collect::<Vec<&str>>()).unwrap_or_default();
+                packfile::set_compression_rules(config, packfile_path, &rules)
+            }
+
+            else if matches.is_present("compression-format") {
+                match matches.value_of("compression-format") {
+                    Some(format) => packfile::set_compression_format(config, packfile_path, format),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("diff") {
+                match matches.value_of("diff") {
+                    Some(other_packfile_path) => packfile::diff_packfiles(config, packfile_path, other_packfile_path, matches.is_present("json")),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
 			else if matches.is_present("list") { packfile::list_packfile_contents(config, packfile_path) }
             else if matches.is_present("new-packfile") { packfile::new_packfile(config, packfile_path)}
 
@@ -106,7 +134,7 @@ pub fn command_packfile(config: &Config, matches: &ArgMatches, packfile: Option<
 }
 
 /// This function triggers functions that require the `Table` command.
-pub fn command_table(config: &Config, matches: &ArgMatches, _packfile: Option<&str>) -> Result<()> {
+pub fn command_table(config: &Config, matches: &ArgMatches, packfile: Option<&str>) -> Result<()> {
     if matches.is_present("import") {
 		match matches.values_of("import") {
 			Some(values) => {
@@ -127,6 +155,13 @@ pub fn command_table(config: &Config, matches: &ArgMatches, _packfile: Option<&s
 		}
     }
 
+    else if matches.is_present("merged") {
+		match matches.value_of("merged") {
+			Some(table_name) => table::merged(config, packfile, table_name),
+			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+		}
+    }
+
 	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
 }
 
@@ -148,11 +183,178 @@ pub fn command_schema(config: &Config, matches: &ArgMatches) -> Result<()> {
 
 /// This function triggers functions that require the `Diagnostics` command.
 pub fn command_diagnostic(config: &Config, matches: &ArgMatches, asskit_db_path: Option<&str>) -> Result<()> {
+    let format = match matches.value_of("format") {
+        Some(format) => format.parse::<DiagnosticsOutputFormat>()?,
+        None => DiagnosticsOutputFormat::Json,
+    };
+
+    let fail_on = match matches.value_of("fail-on") {
+        Some(level) => Some(level.parse::<DiagnosticLevel>()?),
+        None => None,
+    };
+
+    let rules = match matches.value_of("rules") {
+        Some(path) => Some(diagnostic::load_rules(config, path)?),
+        None => None,
+    };
+
     if matches.is_present("check") {
         match matches.values_of("check") {
             Some(values) => {
                 let pack_file_paths = values.collect::<Vec<&str>>();
-                diagnostic::check(config, &pack_file_paths, asskit_db_path)
+                diagnostic::check(config, &pack_file_paths, asskit_db_path, format, fail_on, rules.as_ref(), matches.is_present("fix"))
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("load-order") {
+        match matches.values_of("load-order") {
+            Some(values) => {
+                let pack_file_paths = values.collect::<Vec<&str>>();
+                diagnostic::check_load_order(config, &pack_file_paths, asskit_db_path, format, fail_on, rules.as_ref())
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    // If we only got the rules, we're done after validating them.
+    else if rules.is_some() { Ok(()) }
+
+    else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `ESF` command.
+pub fn command_esf(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("export") {
+        match matches.values_of("export") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                esf::export_json(config, source_path, values.next())
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("import") {
+        match matches.values_of("import") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                esf::import_json(config, source_path, values.next())
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("query") {
+        match matches.values_of("query") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                let query = values.next().unwrap();
+                esf::query(config, source_path, query)
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("set") {
+        match matches.values_of("set") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                let query = values.next().unwrap();
+                let value = values.next().unwrap();
+                esf::set(config, source_path, query, value, values.next())
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("diff") {
+        match matches.values_of("diff") {
+            Some(mut values) => {
+                let old_path = values.next().unwrap();
+                let new_path = values.next().unwrap();
+                esf::diff(config, old_path, new_path, matches.is_present("json"))
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `RigidModel` command.
+pub fn command_rigidmodel(config: &Config, matches: &ArgMatches) -> Result<()> {
+    let lod = match matches.value_of("lod") {
+        Some(lod) => lod.parse::<usize>().map_err(|_| ErrorKind::NoHTMLError(format!("Invalid LOD: {}.", lod)))?,
+        None => 0,
+    };
+
+    if matches.is_present("export-obj") {
+        match matches.values_of("export-obj") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                rigidmodel::export_obj(config, source_path, values.next(), lod)
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("export-gltf") {
+        match matches.values_of("export-gltf") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                rigidmodel::export_gltf(config, source_path, values.next(), lod)
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `Image` command.
+pub fn command_image(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("info") {
+        match matches.values_of("info") {
+            Some(values) => image::info(config, &values.collect::<Vec<&str>>()),
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("to-png") {
+        match matches.values_of("to-png") {
+            Some(values) => image::to_png(config, &values.collect::<Vec<&str>>()),
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("from-png") {
+        let dds_format = match matches.value_of("dds-format") {
+            Some(dds_format) => dds_format.parse::<DdsFormat>()?,
+            None => DdsFormat::Bc3,
+        };
+
+        match matches.values_of("from-png") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                let destination_path = values.next().unwrap();
+                image::from_png(config, source_path, destination_path, &dds_format, matches.is_present("mipmaps"))
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+}
+
+/// This function triggers functions that require the `UIC` command.
+pub fn command_uic(config: &Config, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("export-xml") {
+        match matches.values_of("export-xml") {
+            Some(mut values) => {
+                let source_path = values.next().unwrap();
+                uic::export_xml(config, source_path, values.next())
             },
             None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
         }
@@ -18,7 +18,9 @@ use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
 use rpfm_lib::packedfile::PackedFileType;
-use rpfm_lib::packfile::{PackFile, PathType, PFHFileType};
+use rpfm_lib::packfile::{CompressionFormat, CompressionRule, PackFile, PathType, PFHFileType};
+use rpfm_lib::schema::Schema;
+use rpfm_lib::SCHEMA;
 
 use crate::config::Config;
 
@@ -210,17 +212,91 @@ pub fn list_packfile_contents(config: &Config, packfile: &str) -> Result<()> {
 	let packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
 
 	let mut table = Table::new();
-    table.add_row(row!["PackedFile Path", "Type", "Size"]);
+    table.add_row(row!["PackedFile Path", "Type", "Compressed", "Size", "Uncompressed Size"]);
     for file in packfile.get_ref_packed_files_all() {
     	let packedfile_type = PackedFileType::get_packed_file_type(file.get_ref_raw(), true);
-    	let size = ByteSize::kib((file.get_raw_data_size() / 1024).into());
-    	table.add_row(row![file.get_path().join("/"), packedfile_type, size]);
+        let is_compressed = file.get_ref_raw().get_compression_state();
+    	let size = ByteSize::b(file.get_raw_data_size().into());
+    	let uncompressed_size = ByteSize::b(file.get_ref_raw().get_uncompressed_size()?.into());
+    	table.add_row(row![file.get_path().join("/"), packedfile_type, is_compressed, size, uncompressed_size]);
     }
 
 	table.printstd();
 	Ok(())
 }
 
+/// This function replaces the compression rules of a PackFile, then saves it, applying them.
+pub fn set_compression_rules(config: &Config, packfile: &str, rules: &[&str]) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Setting the compression rules of the PackFile: {}", packfile);
+    }
+
+    let rules = rules.iter().map(|rule| CompressionRule::from_line(rule)).collect::<Result<Vec<CompressionRule>>>()?;
+
+    let packfile_path = PathBuf::from(packfile);
+    let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+    let mut settings = packfile.get_settings().clone();
+    settings.set_compression_rules(&rules);
+    packfile.set_settings(&settings);
+    let result = packfile.save(None);
+
+    if config.verbosity_level > 0 {
+        info!("Compression rules successfully applied to the PackFile.");
+    }
+
+    result
+}
+
+/// This function compares two PackFiles, printing their differences as text or as JSON.
+pub fn diff_packfiles(config: &Config, packfile: &str, other_packfile: &str, as_json: bool) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Comparing PackFiles: {} against {}", packfile, other_packfile);
+    }
+
+    // Tables need the schema to be decoded. If we don't have it, we can still report changed files.
+    if let Some(ref game_selected) = config.game_selected {
+        if let Ok(schema) = Schema::load(game_selected.get_schema_name()) {
+            *SCHEMA.write().unwrap() = Some(schema);
+        }
+    }
+
+    let packfile = PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?;
+    let other_packfile = PackFile::open_packfiles(&[PathBuf::from(other_packfile)], true, false, false)?;
+    let diff = packfile.diff(&other_packfile)?;
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+
+    Ok(())
+}
+
+/// This function sets the compression format of a PackFile, then saves it, recompressing its PackedFiles if needed.
+pub fn set_compression_format(config: &Config, packfile: &str, format: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Setting the compression format of the PackFile: {}", packfile);
+    }
+
+    let format = CompressionFormat::get_format(format)?;
+
+    let packfile_path = PathBuf::from(packfile);
+    let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+    let mut settings = packfile.get_settings().clone();
+    settings.set_compression_format(format);
+    packfile.set_settings(&settings);
+    let result = packfile.save(None);
+
+    if config.verbosity_level > 0 {
+        info!("Compression format successfully changed.");
+    }
+
+    result
+}
+
 /// This function creates a new packfile with the provided path.
 pub fn new_packfile(config: &Config, packfile: &str) -> Result<()> {
     if config.verbosity_level > 0 {
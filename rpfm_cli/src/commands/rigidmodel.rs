@@ -0,0 +1,82 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::info;
+
+use std::fs::{read, write};
+use std::path::{Path, PathBuf};
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::rigidmodel::RigidModel;
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							RigidModel Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function exports a LOD of a RigidModel to a Wavefront OBJ file.
+///
+/// If no destination path was provided, it leaves the OBJ file in the same place as the RigidModel, with the `.obj` extension.
+pub fn export_obj(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+    lod: usize,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Export RigidModel as OBJ: {} - LOD {}", source_path, lod);
+	}
+
+    let destination_path = get_destination_path(source_path, destination_path, "obj");
+    let rigid_model = RigidModel::read(&read(source_path)?)?;
+    write(&destination_path, rigid_model.to_obj(lod)?)?;
+
+	if config.verbosity_level > 0 {
+        info!("RigidModel exported to: {}", destination_path.to_string_lossy());
+	}
+    Ok(())
+}
+
+/// This function exports a LOD of a RigidModel to a glTF file. Its binary data is saved next to it, with the `.bin` extension.
+///
+/// If no destination path was provided, it leaves the glTF file in the same place as the RigidModel, with the `.gltf` extension.
+pub fn export_gltf(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+    lod: usize,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Export RigidModel as glTF: {} - LOD {}", source_path, lod);
+	}
+
+    let destination_path = get_destination_path(source_path, destination_path, "gltf");
+    let buffer_path = destination_path.with_extension("bin");
+    let buffer_uri = buffer_path.file_name().unwrap().to_string_lossy().to_string();
+
+    let rigid_model = RigidModel::read(&read(source_path)?)?;
+    let (gltf, buffer) = rigid_model.to_gltf(lod, &buffer_uri)?;
+    write(&destination_path, gltf)?;
+    write(&buffer_path, buffer)?;
+
+	if config.verbosity_level > 0 {
+        info!("RigidModel exported to: {}", destination_path.to_string_lossy());
+	}
+    Ok(())
+}
+
+/// This function returns the destination path of an export, using the source path with the provided extension if no destination path was provided.
+fn get_destination_path(source_path: &str, destination_path: Option<&str>, extension: &str) -> PathBuf {
+    match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => Path::new(source_path).with_extension(extension),
+    }
+}
@@ -13,8 +13,11 @@ use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
 
+use rpfm_lib::dependencies::Dependencies;
 use rpfm_lib::packedfile::table::db::DB;
+use rpfm_lib::packfile::PackFile;
 use rpfm_lib::schema::Schema;
+use rpfm_lib::SCHEMA;
 
 use crate::config::Config;
 
@@ -68,3 +71,40 @@ pub fn export_tsv(
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
+
+/// This function prints the effective version of a DB table once vanilla, the parent mods and the PackFile are loaded.
+///
+/// If no PackFile is provided, only vanilla files are used.
+pub fn merged(
+    config: &Config,
+    packfile: Option<&str>,
+    table_name: &str,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Get merged table: {}", table_name);
+	}
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *SCHEMA.write().unwrap() = Some(Schema::load(game_selected.get_schema_name())?);
+
+            let pack_file = match packfile {
+                Some(packfile) => Some(PackFile::open_packfiles(&[PathBuf::from(packfile)], true, false, false)?),
+                None => None,
+            };
+
+            let mut dependencies = Dependencies::default();
+            let parent_pack_files = pack_file.as_ref().map(|pack_file| pack_file.get_packfiles_list().to_vec()).unwrap_or_default();
+            dependencies.rebuild(&parent_pack_files, false)?;
+
+            let merged_table = match pack_file {
+                Some(pack_file) => pack_file.get_merged_db_table(table_name, &dependencies)?,
+                None => dependencies.get_merged_db_table(table_name)?,
+            };
+
+            println!("{}", serde_json::to_string_pretty(&merged_table)?);
+            Ok(())
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
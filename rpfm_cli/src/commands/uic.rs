@@ -0,0 +1,54 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+use log::{info, warn};
+
+use std::fs::{read, write};
+use std::path::{Path, PathBuf};
+
+use rpfm_error::Result;
+use rpfm_lib::packedfile::uic::UIC;
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							UIC Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function exports an UI Component to XML.
+///
+/// If no destination path was provided, it leaves the XML file in the same place as the UI Component, with the `.xml` extension.
+pub fn export_xml(
+    config: &Config,
+    source_path: &str,
+    destination_path: Option<&str>,
+) -> Result<()> {
+	if config.verbosity_level > 0 {
+		info!("Export UI Component as XML: {}", source_path);
+	}
+
+    let destination_path = match destination_path {
+        Some(destination_path) => PathBuf::from(destination_path),
+        None => Path::new(source_path).with_extension("xml"),
+    };
+
+    let uic = UIC::read(&read(source_path)?)?;
+    write(&destination_path, uic.to_xml()?)?;
+
+    // Files we couldn't decode the layout of are exported raw, so make sure the user knows it.
+    if !uic.is_decoded() {
+        warn!("The layout of the UI Component {} could not be decoded. Its data has been exported raw.", source_path);
+    }
+
+	if config.verbosity_level > 0 {
+        info!("UI Component exported to: {}", destination_path.to_string_lossy());
+	}
+    Ok(())
+}
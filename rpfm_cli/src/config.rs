@@ -15,7 +15,7 @@
 use rpfm_error::Result;
 use rpfm_lib::games::*;
 use rpfm_lib::settings::init_config_path;
-use rpfm_lib::SUPPORTED_GAMES;
+use rpfm_lib::{GAME_SELECTED, SUPPORTED_GAMES};
 
 /// This struct serves to hold the configuration used during the execution of the program.
 pub struct Config {
@@ -29,8 +29,13 @@ impl Config {
 	/// This function creates a new Config struct configured for the provided game.
 	pub fn new(game_selected: String, verbosity_level: u8) -> Result<Self> {
 		init_config_path()?;
+
+        // Make the lib use the game we selected too, as some checks (like the compression formats supported) depend on it.
+        let game_selected = SUPPORTED_GAMES.get_supported_game_from_key(&game_selected)?;
+        *GAME_SELECTED.write().unwrap() = game_selected;
+
 		Ok(Self {
-            game_selected: Some(SUPPORTED_GAMES.get_supported_game_from_key(&game_selected)?.clone()),
+            game_selected: Some(game_selected.clone()),
 			verbosity_level,
 		})
 	}
@@ -67,8 +67,12 @@ fn main() {
     // If we reached here, execute the commands.
     let result = match matches.subcommand() {
         Some(("diagnostic", matches)) => commands::command_diagnostic(&config, matches, asskit_db_path),
+        Some(("esf", matches)) => commands::command_esf(&config, matches),
         Some(("packfile", matches)) => commands::command_packfile(&config, matches, packfile),
+        Some(("image", matches)) => commands::command_image(&config, matches),
+        Some(("rigidmodel", matches)) => commands::command_rigidmodel(&config, matches),
         Some(("table", matches)) => commands::command_table(&config, matches, packfile),
+        Some(("uic", matches)) => commands::command_uic(&config, matches),
         Some(("schema", matches)) => commands::command_schema(&config, matches),
         _ => { Ok(()) }
     };
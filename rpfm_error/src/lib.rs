@@ -69,6 +69,9 @@ pub enum ErrorKind {
     /// Error for when deserializing from `XML` fails.
     XMLDeserializerError,
 
+    /// Error for when serializing to `XML` fails.
+    XMLSerializerError,
+
     /// Error for when serializing and deserializing to/from `Bincode` fails.
     BincodeSerializerError,
 
@@ -120,6 +123,9 @@ pub enum ErrorKind {
     /// Generic error for when trying to parse something as a bool.
     NotABooleanValue,
 
+    /// Generic error for when a path pattern is invalid. Contains the pattern.
+    InvalidPathPattern(String),
+
     /// Generic error for when we don't find the dependencies cache or it's out of date.
     DependenciesCacheNotGeneratedorOutOfDate,
 
@@ -242,6 +248,12 @@ pub enum ErrorKind {
     /// Error for when the PackedFile we want to get doesn't exists.
     PackedFileNotFound,
 
+    /// Error for when we try to build a merged table, but no source has a table with the provided name.
+    MergedTableNotFound(String),
+
+    /// Error for when we try to open the merged version of a table without a DB Table selected.
+    NoDBTableSelected,
+
     /// Error for when we are trying to do an operation that cannot be done with the PackedFile open.
     PackedFileIsOpen,
 
@@ -257,6 +269,18 @@ pub enum ErrorKind {
     /// Error for when the compressed PackedFile is either incomplete (<9 bytes) or the decompression failed.
     PackedFileDataCouldNotBeDecompressed,
 
+    /// Error for when the compression of a PackedFile failed.
+    PackedFileDataCouldNotBeCompressed,
+
+    /// Error for when a compression rule of a PackFile cannot be parsed. Contains the rule.
+    InvalidCompressionRule(String),
+
+    /// Error for when the compression format set in a PackFile is not a valid one.
+    InvalidCompressionFormat(String),
+
+    /// Error for when the compression format set in a PackFile is not supported by the Game Selected.
+    CompressionFormatNotSupported(String),
+
     /// Error for when we expect data to be in memory, but it isn't.
     PackedFileDataIsNotInMemory,
 
@@ -328,6 +352,16 @@ pub enum ErrorKind {
     /// Error for when we can't find a vanilla version of a table to compare with.
     NoTableInGameFilesToCompare,
 
+    //--------------------------------//
+    // Anim Errors
+    //--------------------------------//
+
+    /// Error for when an Anim fails to decode. Contains the error message.
+    AnimDecode(String),
+
+    /// Error for when an Anim fails to encode. Contains the error message.
+    AnimEncode(String),
+
     //--------------------------------//
     // RigidModel Errors
     //--------------------------------//
@@ -356,6 +390,9 @@ pub enum ErrorKind {
     /// Error for when the decal texture directory hasn't been found while examining a rigidmodel.
     RigidModelDecalTextureDirectoryNotFound,
 
+    /// Error for when we try to export a LOD a RigidModel doesn't have. Contains the index of the LOD.
+    RigidModelLodNotFound(usize),
+
     //--------------------------------//
     // Text Errors
     //--------------------------------//
@@ -420,6 +457,13 @@ pub enum ErrorKind {
     /// Error for when an AnimFragment PackedFile fails to decode. Contains the error message.
     AnimFragmentDecode(String),
 
+    //--------------------------------//
+    // GroupFormations Errors
+    //--------------------------------//
+
+    /// Error for when a GroupFormations PackedFile fails to decode. Contains the error message.
+    GroupFormationsDecode(String),
+
     //--------------------------------//
     // MatchedCombat Errors
     //--------------------------------//
@@ -434,6 +478,9 @@ pub enum ErrorKind {
     /// Error for when an UIC PackedFile fails to decode. Contains the error message.
     UICDecode(String),
 
+    /// Error for when an UIC PackedFile fails to encode. Contains the error message.
+    UICEncode(String),
+
     //--------------------------------//
     // Unit Variant Errors
     //--------------------------------//
@@ -466,6 +513,12 @@ pub enum ErrorKind {
     /// Error for when we fail ot find an specific string.
     ESFStringNotFound(u32),
 
+    /// Error for when a query over an ESF file is not valid. Contains the query and the reason.
+    ESFInvalidQuery(String, String),
+
+    /// Error for when we try to set a value that's not valid for the type of an ESF node. Contains the value.
+    ESFInvalidNodeValue(String),
+
     //--------------------------------//
     // PAK File Errors
     //--------------------------------//
@@ -555,6 +608,22 @@ pub enum ErrorKind {
     /// Error for when we try to create a Queek PackedFile in a folder that doesn't fit the requirements.
     NoQueekPackedFileHere,
 
+    //-----------------------------------------------------//
+    //                 Diagnostics Errors
+    //-----------------------------------------------------//
+
+    /// Error for when we try to output the diagnostics in a format we don't support. Contains the format.
+    DiagnosticsOutputFormatUnknown(String),
+
+    /// Error for when we try to fail on a diagnostic level that doesn't exist. Contains the level.
+    DiagnosticLevelUnknown(String),
+
+    /// Error for when the diagnostics rules cannot be parsed. Contains the reason.
+    DiagnosticsRulesInvalid(String),
+
+    /// Error for when the diagnostics rules contain rules that don't exist. Contains the unknown rules.
+    DiagnosticsRulesUnknownRules(Vec<String>),
+
     //-----------------------------------------------------//
     //                Assembly Kit Errors
     //-----------------------------------------------------//
@@ -571,13 +640,6 @@ pub enum ErrorKind {
     /// Error for when we try to get the assembly kit, but it's not yet installed.
     AssemblyKitNotFound,
 
-    //-----------------------------------------------------//
-    //                  7-Zip Errors
-    //-----------------------------------------------------//
-
-    /// Error for when 7-zip is not found in the specified path.
-    ZipFolderNotFound,
-
     //-----------------------------------------------------//
     //                  Common Errors
     //-----------------------------------------------------//
@@ -774,6 +836,7 @@ impl Display for ErrorKind {
             ErrorKind::RonSerializerError => write!(f, "<p>This should never happen.</p>"),
             ErrorKind::RonDeserializerError => write!(f, "<p>This should never happen.</p>"),
             ErrorKind::XMLDeserializerError => write!(f, "<p>This should never happen.</p>"),
+            ErrorKind::XMLSerializerError => write!(f, "<p>This should never happen.</p>"),
             ErrorKind::BincodeSerializerError => write!(f, "<p>Error while reading or writing a bincoded file. This means the file is not of the format we expected.</p>"),
             ErrorKind::JsonErrorSyntax => write!(f, "<p>Error while trying to read JSON data:</p><p>Invalid syntax found.</p>"),
             ErrorKind::JsonErrorData => write!(f, "<p>Error while trying to read JSON data:</p><p>Semantically incorrect data found.</p>"),
@@ -791,6 +854,7 @@ impl Display for ErrorKind {
             ErrorKind::InitializingLoggerError => write!(f, "<p>Error while trying to initialize the logger.</p>"),
             //ErrorKind::ParsingLongIntegerError => write!(f, "<p>Error while trying to parse a String as a Long Integer.</p>"),
             ErrorKind::NotABooleanValue => write!(f, "<p>Error while trying to parse something as a bool.</p>"),
+            ErrorKind::InvalidPathPattern(pattern) => write!(f, "<p>The following path pattern is invalid: {}</p>", pattern),
             ErrorKind::DependenciesCacheNotGeneratedorOutOfDate => write!(f, "<p>The dependencies cache for the Game Selected is either missing, outdated, or it was generated without the Assembly Kit. Please, re-generate it and try again.</p>"),
 
             //-----------------------------------------------------//
@@ -864,11 +928,17 @@ impl Display for ErrorKind {
             //                PackedFile Errors
             //-----------------------------------------------------//
             ErrorKind::PackedFileNotFound => write!(f, "<p>This PackedFile no longer exists in the PackFile.</p>"),
+            ErrorKind::MergedTableNotFound(table_name) => write!(f, "<p>There is no table named <i>{}</i> in any of the loaded sources.</p>", table_name),
+            ErrorKind::NoDBTableSelected => write!(f, "<p>To open the merged version of a table, you need to select a DB Table, or the folder of a DB Table.</p>"),
             ErrorKind::PackedFileIsOpen => write!(f, "<p>That operation cannot be done while the PackedFile involved on it is open. Please, close it by selecting a Folder/PackFile in the TreeView and try again.</p>"),
             ErrorKind::PackedFileIsOpenInAnotherView => write!(f, "<p>That PackedFile is already open in another view. Opening the same PackedFile in multiple views is not supported.</p>"),
             ErrorKind::PackedFileDataCouldNotBeLoaded => write!(f, "<p>This PackedFile's data could not be loaded. This means RPFM can no longer read the PackFile from the disk.</p>"),
             ErrorKind::PackedFileSizeIsNotWhatWeExpect(reported_size, expected_size) => write!(f, "<p>This PackedFile's reported size is <i><b>{}</b></i> bytes, but we expected it to be <i><b>{}</b></i> bytes. This means that either the decoding logic in RPFM is broken for this PackedFile, or this PackedFile is corrupted.</p>", reported_size, expected_size),
             ErrorKind::PackedFileDataCouldNotBeDecompressed => write!(f, "<p>This is a compressed file and the decompression failed for some reason. This means this PackedFile cannot be opened in RPFM.</p>"),
+            ErrorKind::PackedFileDataCouldNotBeCompressed => write!(f, "<p>The compression of this PackedFile failed. Compressed PackedFiles are limited to 4GB of uncompressed data.</p>"),
+            ErrorKind::InvalidCompressionRule(rule) => write!(f, "<p>The following compression rule is invalid: <i>{}</i></p><p>Compression rules must be <code>compress;pattern</code> or <code>skip;pattern</code>.</p>", rule),
+            ErrorKind::InvalidCompressionFormat(format) => write!(f, "<p>The following compression format is invalid: <i>{}</i></p><p>Valid compression formats are <code>lzma1</code>, <code>lz4</code> and <code>zstd</code>.</p>", format),
+            ErrorKind::CompressionFormatNotSupported(format) => write!(f, "<p>The compression format <i>{}</i> is not supported by the Game Selected. Change the compression format in the PackFile Settings, or disable compression.</p>", format),
             ErrorKind::PackedFileDataIsNotInMemory => write!(f, "<p>This PackedFile's data is not in memory. If you see this, report it, as it's a bug.</p>"),
             ErrorKind::PackedFileNotInFilter => write!(f, "<p>This PackedFile is not in the current TreeView filter. If you want to open it, remove the filter.</p>"),
             ErrorKind::PackedFileCouldNotBeImported(paths) => write!(f, "<p>The following failed to be imported:<ul>{}</ul></p>", paths.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>()),
@@ -898,6 +968,12 @@ impl Display for ErrorKind {
             ErrorKind::NoDefinitionUpdateAvailable => write!(f, "<p>This table already has the newer definition available.</p>"),
             ErrorKind::NoTableInGameFilesToCompare => write!(f, "<p>This table cannot be found in the Game Files, so it cannot be automatically updated (yet).</p>"),
 
+            //--------------------------------//
+            // Anim Errors
+            //--------------------------------//
+            ErrorKind::AnimDecode(cause) => write!(f, "<p>Error while trying to decode the Anim PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::AnimEncode(cause) => write!(f, "<p>Error while trying to encode the Anim PackedFile:</p><p>{}</p>", cause),
+
             //--------------------------------//
             // RigidModel Errors
             //--------------------------------//
@@ -909,6 +985,7 @@ impl Display for ErrorKind {
             ErrorKind::RigidModelUnknownMaskTypeFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Texture with unknown Mask Type found.</li></ul>"),
             ErrorKind::RigidModelTextureDirectoryNotFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Texture Directories not found.</li></ul>"),
             ErrorKind::RigidModelDecalTextureDirectoryNotFound => write!(f, "<p>Error while trying to decode the RigidModel file:</p><p><ul><li>Decal Texture Directory not found.</li></ul>"),
+            ErrorKind::RigidModelLodNotFound(index) => write!(f, "<p>This RigidModel has no LOD {}.</p>", index),
 
             //--------------------------------//
             // Text Errors
@@ -950,6 +1027,11 @@ impl Display for ErrorKind {
             //--------------------------------//
             ErrorKind::AnimFragmentDecode(cause) => write!(f, "<p>Error while trying to decode the AnimFragment PackedFile:</p><p>{}</p>", cause),
 
+            //--------------------------------//
+            // GroupFormations Errors
+            //--------------------------------//
+            ErrorKind::GroupFormationsDecode(cause) => write!(f, "<p>Error while trying to decode the GroupFormations PackedFile:</p><p>{}</p>", cause),
+
             //--------------------------------//
             // MatchedCombat Errors
             //--------------------------------//
@@ -959,6 +1041,7 @@ impl Display for ErrorKind {
             // UIC Errors
             //--------------------------------//
             ErrorKind::UICDecode(cause) => write!(f, "<p>Error while trying to decode the UIC PackedFile:</p><p>{}</p>", cause),
+            ErrorKind::UICEncode(cause) => write!(f, "<p>Error while trying to encode the UIC PackedFile:</p><p>{}</p>", cause),
 
             //--------------------------------//
             // ESF Errors
@@ -968,6 +1051,8 @@ impl Display for ErrorKind {
             ErrorKind::ESFUnsupportedSignature(signature) => write!(f, "<p>Unsupported signature: {}</p>", signature),
             ErrorKind::ESFIncompleteDecoding => write!(f, "<p>There are bytes still to decode, but the decoding process has finished. This means RPFM cannot yet decode this file correctly</p><p>If you see this message, please report it to RPFM's author so support for the file that caused the error can be implemented.</p>"),
             ErrorKind::ESFStringNotFound(index) => write!(f, "<p>String not found: {}</p>", index),
+            ErrorKind::ESFInvalidQuery(query, reason) => write!(f, "<p>Invalid query: {}</p><p>{}</p>", query, reason),
+            ErrorKind::ESFInvalidNodeValue(value) => write!(f, "<p>Invalid value for the node: {}</p>", value),
             ErrorKind::ESFRecordNameNotFound(index) => write!(f, "<p>Record name not found: {}</p>", index),
 
             //--------------------------------//
@@ -1021,6 +1106,14 @@ impl Display for ErrorKind {
             ErrorKind::FolderAlreadyInPackFile => write!(f, "<p>That folder already exists in the current path.</p>"),
             ErrorKind::NoQueekPackedFileHere => write!(f, "<p>I don't know what type of file goes in that folder, boi.</p>"),
 
+            //-----------------------------------------------------//
+            //                 Diagnostics Errors
+            //-----------------------------------------------------//
+            ErrorKind::DiagnosticsOutputFormatUnknown(format) => write!(f, "<p>Unknown diagnostics format: {}. Valid formats are json, sarif, junit and text.</p>", format),
+            ErrorKind::DiagnosticLevelUnknown(level) => write!(f, "<p>Unknown diagnostic level: {}. Valid levels are info, warning and error.</p>", level),
+            ErrorKind::DiagnosticsRulesInvalid(reason) => write!(f, "<p>The diagnostics rules are not valid: {}</p>", reason),
+            ErrorKind::DiagnosticsRulesUnknownRules(rules) => write!(f, "<p>The following diagnostics rules are unknown: {}.</p>", rules.join(", ")),
+
             //-----------------------------------------------------//
             //                Assembly Kit Errors
             //-----------------------------------------------------//
@@ -1029,11 +1122,6 @@ impl Display for ErrorKind {
             ErrorKind::AssemblyKitTableTableIgnored => write!(f, "<p>One of the Assembly Kit Tables you tried to decode has been blacklisted due to issues.</p>"),
             ErrorKind::AssemblyKitNotFound => write!(f, "<p>The Assembly Kit Folder could not be read. You may need to install the Assembly Kit for the current game selected.</p>"),
 
-            //-----------------------------------------------------//
-            //                  7-Zip Errors
-            //-----------------------------------------------------//
-            ErrorKind::ZipFolderNotFound => write!(f, "<p>7Zip path not found, or the 7Zip path you put in the settings is wrong.</p>"),
-
             //-----------------------------------------------------//
             //                  Common Errors
             //-----------------------------------------------------//
@@ -1182,6 +1270,13 @@ impl From<quick_xml::DeError> for Error {
     }
 }
 
+/// Implementation to create an `Error` from a `quick_xml::Error`.
+impl From<quick_xml::Error> for Error {
+    fn from(_: quick_xml::Error) -> Self {
+        Self::from(ErrorKind::XMLSerializerError)
+    }
+}
+
 /// Implementation to create an `Error` from a `Box<bincode::ErrorKind>`.
 impl From<Box<bincode::ErrorKind>> for Error {
     fn from(_: Box<bincode::ErrorKind>) -> Self {
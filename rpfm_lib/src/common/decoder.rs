@@ -20,7 +20,7 @@ Note: If you change anything from here, remember to update the `decoder_test.rs`
 
 use byteorder::{ByteOrder, LittleEndian};
 use encoding::{Encoding, DecoderTrap};
-use encoding::all::ISO_8859_1;
+use encoding::all::{ISO_8859_1, ISO_8859_15};
 
 use rpfm_error::{Error, ErrorKind, Result};
 
@@ -90,6 +90,9 @@ pub trait Decoder {
     /// This function allows us to decode an ISO_8859_1 String from raw data.
     fn decode_string_u8_iso_8859_1(&self, offset: usize, size: usize) -> Result<String>;
 
+    /// This function allows us to decode an ISO_8859_15 String from raw data.
+    fn decode_string_u8_iso_8859_15(&self, offset: usize, size: usize) -> Result<String>;
+
     /// This function allows us to decode a 00-Padded UTF-8 String from raw data.
     ///
     /// This type of String has a fixed size and, when the characters end, it's filled with `00` bytes until it reach his size.
@@ -105,6 +108,9 @@ pub trait Decoder {
     /// This function allows us to decode an UTF-16 String from raw data.
     fn decode_string_u16(&self, offset: usize, size: usize) -> Result<String>;
 
+    /// This function allows us to decode a Big Endian UTF-16 String from raw data.
+    fn decode_string_u16_be(&self, offset: usize, size: usize) -> Result<String>;
+
     /// This function allows us to decode a 00-Padded UTF-16 String from raw data.
     ///
     /// This type of String has a fixed size and, when the characters end, it's filled with `00` bytes until it reach his size.
@@ -294,6 +300,13 @@ impl Decoder for [u8] {
         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode an UTF-8 String:</p><ul><li>Required bytes: {}.</li><li>Provided bytes: {:?}.</li></ul>", size, offset.checked_sub(self.len()))).into()) }
     }
 
+    fn decode_string_u8_iso_8859_15(&self, offset: usize, size: usize) -> Result<String> {
+        if self.len() >= offset + size {
+            ISO_8859_15.decode(&self[offset..offset + size], DecoderTrap::Replace).map_err(|_| Error::from(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode an ISO-8859-15 String.</p>".to_owned())))
+        }
+        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode an ISO-8859-15 String:</p><ul><li>Required bytes: {}.</li><li>Provided bytes: {:?}.</li></ul>", size, offset.checked_sub(self.len()))).into()) }
+    }
+
     fn decode_string_u8_0padded(&self, offset: usize, size: usize) -> Result<(String, usize)> {
         if self.len() >= offset + size {
             let size_no_zeros = self[offset..offset + size].iter().position(|x| *x == 0).map_or(size, |x| x);
@@ -319,6 +332,14 @@ impl Decoder for [u8] {
         else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode an UTF-16 String:</p><ul><li>Required bytes: {}.</li><li>Provided bytes: {:?}.</li></ul>", size, offset.checked_sub(self.len()))).into()) }
     }
 
+    fn decode_string_u16_be(&self, offset: usize, size: usize) -> Result<String> {
+        if self.len() >= offset + size && size % 2 == 0 {
+            let u16_characters = self[offset..offset + size].chunks_exact(2).map(|x| u16::from_be_bytes([x[0], x[1]])).collect::<Vec<u16>>();
+            String::from_utf16(&u16_characters).map_err(|_| Error::from(ErrorKind::HelperDecodingEncodingError("<p>Error trying to decode a Big Endian UTF-16 String.</p>".to_owned())))
+        }
+        else { Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode a Big Endian UTF-16 String:</p><ul><li>Required bytes: {}.</li><li>Provided bytes: {:?}.</li></ul>", size, offset.checked_sub(self.len()))).into()) }
+    }
+
     fn decode_string_u16_0padded(&self, offset: usize, size: usize) -> Result<(String, usize)> {
         if self.len() >= offset + size {
             let size_no_zeros = self[offset..offset + size].iter().step_by(2).position(|x| *x == 0).map_or(size, |x| x * 2);
@@ -186,6 +186,17 @@ fn test_decode_string_u8() {
     assert_eq!(Decoder::decode_string_u8([87, 97, 104, 97, 255, 104, 97, 104, 97, 104, 97].as_ref(), 0, 10).is_err(), true);
 }
 
+/// Test to make sure the ISO-8859-15 string decoder (`decode_string_u8_iso_8859_15()`) works and fails properly.
+#[test]
+fn test_decode_string_u8_iso_8859_15() {
+
+    // Check the decoding works for a proper encoded string, including the characters that differ from ISO-8859-1 (€ and Š).
+    assert_eq!(Decoder::decode_string_u8_iso_8859_15([0xA4, 32, 0xA6, 97, 0xE9].as_ref(), 0, 5).unwrap(), "€ Šaé");
+
+    // Check the decoder returns an error for a slice shorter than the requested size.
+    assert!(Decoder::decode_string_u8_iso_8859_15([87, 97, 104, 97].as_ref(), 0, 5).is_err());
+}
+
 /// Test to make sure the u8 0-padded string decoder (`decode_string_u8_0padded()`) works and fails properly.
 #[test]
 fn test_decode_string_u8_0padded() {
@@ -251,6 +262,19 @@ fn test_decode_string_u16() {
     assert_eq!(Decoder::decode_string_u16([87, 0, 0, 216, 104, 0, 97, 0, 104, 0, 97, 0].as_ref(), 0, 12).is_err(), true);
 }
 
+/// Test to make sure the big endian u16 string decoder (`decode_string_u16_be()`) works and fails properly.
+#[test]
+fn test_decode_string_u16_be() {
+
+    // Check the decoding works for a proper encoded string.
+    assert_eq!(Decoder::decode_string_u16_be([0, 87, 0, 97, 0, 104, 0, 97, 0, 104, 0, 97].as_ref(), 0, 12).unwrap(), "Wahaha");
+
+    // Check the decoder returns an error for a slice with non-UTF16 characters (216), an odd size, or not enough bytes.
+    assert!(Decoder::decode_string_u16_be([0, 87, 216, 0, 0, 104, 0, 97, 0, 104, 0, 97].as_ref(), 0, 12).is_err());
+    assert!(Decoder::decode_string_u16_be([0, 87, 0, 97, 0].as_ref(), 0, 5).is_err());
+    assert!(Decoder::decode_string_u16_be([0, 87, 0, 97].as_ref(), 0, 6).is_err());
+}
+
 /// Test to make sure the u16 0-padded string decoder (`decode_string_u16_0padded()`) works and fails properly.
 #[test]
 fn test_decode_string_u16_0padded() {
@@ -19,7 +19,7 @@ Note: If you change anything from here, remember to update the `encoder_test.rs`
 !*/
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use encoding::all::ISO_8859_1;
+use encoding::all::{ISO_8859_1, ISO_8859_15};
 use encoding::types::{Encoding, EncoderTrap};
 
 use rpfm_error::{ErrorKind, Result};
@@ -79,6 +79,9 @@ pub trait Encoder {
     /// This function allows us to encode an UTF-8 String into the provided `Vec<u8>` as an ISO-8859-1 encoded String.
     fn encode_string_u8_iso_8859_1(&mut self, string: &str);
 
+    /// This function allows us to encode an UTF-8 String into the provided `Vec<u8>` as an ISO-8859-15 encoded String.
+    fn encode_string_u8_iso_8859_15(&mut self, string: &str);
+
     /// This function allows us to encode a 00-Padded UTF-8 String into the provided `Vec<u8>`.
     ///
     /// This one is a bit special. It's uses a tuple with the String to encode and the total size of the encoded string.
@@ -89,6 +92,9 @@ pub trait Encoder {
     /// This function allows us to encode an UTF-16 String into the provided `Vec<u8>`.
     fn encode_string_u16(&mut self, string: &str);
 
+    /// This function allows us to encode an UTF-8 String into the provided `Vec<u8>` as a Big Endian UTF-16 String.
+    fn encode_string_u16_be(&mut self, string: &str);
+
     /// This function allows us to encode a 00-Padded UTF-16 String into the provided `Vec<u8>`.
     ///
     /// This one is a bit special. It's uses a tuple with the String to encode and the total size of the encoded string.
@@ -202,6 +208,10 @@ impl Encoder for Vec<u8> {
         self.extend_from_slice(&ISO_8859_1.encode(string, EncoderTrap::Replace).unwrap());
     }
 
+    fn encode_string_u8_iso_8859_15(&mut self, string: &str) {
+        self.extend_from_slice(&ISO_8859_15.encode(string, EncoderTrap::Replace).unwrap());
+    }
+
     fn encode_string_u8_0padded(&mut self, (string, size): &(String, usize)) -> Result<()> {
         if string.len() <= *size {
             self.extend_from_slice(string.as_bytes());
@@ -216,6 +226,10 @@ impl Encoder for Vec<u8> {
         string.encode_utf16().for_each(|character| self.encode_integer_u16(character));
     }
 
+    fn encode_string_u16_be(&mut self, string: &str) {
+        string.encode_utf16().for_each(|character| self.extend_from_slice(&character.to_be_bytes()));
+    }
+
     fn encode_string_u16_0padded(&mut self, (string, size): &(&str, usize)) -> Result<()> {
         if string.len() * 2 <= *size {
             self.encode_string_u16(string);
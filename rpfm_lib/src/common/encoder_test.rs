@@ -172,6 +172,21 @@ fn test_encode_string_u8() {
     assert_eq!(data, vec![87, 97, 104, 97, 104, 97, 104, 97, 104, 97]);
 }
 
+/// Test to make sure the ISO-8859-15 string encoder (`encode_string_u8_iso_8859_15()`) works properly.
+#[test]
+fn test_encode_string_u8_iso_8859_15() {
+
+    // Check the encoder works for a proper encoded string, including the characters that differ from ISO-8859-1 (€ and Š).
+    let mut data = vec![];
+    data.encode_string_u8_iso_8859_15("€ Šaé");
+    assert_eq!(data, vec![0xA4, 32, 0xA6, 97, 0xE9]);
+
+    // Check the encoder replaces the characters that cannot be encoded, instead of failing.
+    let mut data = vec![];
+    data.encode_string_u8_iso_8859_15("a漢");
+    assert_eq!(data, vec![97, 63]);
+}
+
 /// Test to make sure the u8 0-padded string encoder (`encode_string_u8_0padded()`) works and fails properly.
 #[test]
 fn test_encode_string_u8_0padded() {
@@ -197,6 +212,16 @@ fn test_encode_string_u16() {
     assert_eq!(data, vec![87, 0, 97, 0, 104, 0, 97, 0, 104, 0, 97, 0]);
 }
 
+/// Test to make sure the big endian u16 string encoder (`encode_string_u16_be()`) works properly.
+#[test]
+fn test_encode_string_u16_be() {
+
+    // Check the encoder works for a proper encoded string.
+    let mut data = vec![];
+    data.encode_string_u16_be("Wahaha");
+    assert_eq!(data, vec![0, 87, 0, 97, 0, 104, 0, 97, 0, 104, 0, 97]);
+}
+
 /// Test to make sure the u16 0-padded string encoder (`encode_string_u16_0padded()`) works and fails properly.
 #[test]
 fn test_encode_string_u16_0padded() {
@@ -19,6 +19,7 @@ use pelite::resources::{FindError, Resources};
 use pelite::resources::version_info::VersionInfo;
 
 use chrono::{Utc, DateTime};
+use regex::{Regex, RegexBuilder};
 
 use rpfm_error::{Error, ErrorKind, Result};
 
@@ -191,6 +192,35 @@ pub fn parse_str_as_bool(string: &str) -> Result<bool> {
     }
 }
 
+/// This function builds a case-insensitive `Regex` to match PackedFile paths against a glob-like pattern.
+///
+/// The pattern follows these rules:
+/// - `*` matches anything except `/`, and `**` matches anything, including `/`.
+/// - `?` matches any single character except `/`.
+/// - If the pattern contains a `/`, it's matched against the full path. Otherwise, it's matched against every file or folder name.
+/// - If the pattern matches a folder, it also matches everything inside that folder.
+pub fn get_path_pattern_regex(pattern: &str) -> Result<Regex> {
+    let pattern = pattern.trim().trim_matches('/');
+    let mut regex = if pattern.contains('/') { "^".to_owned() } else { "(^|/)".to_owned() };
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '*' => if chars.peek() == Some(&'*') {
+                chars.next();
+                regex.push_str(".*");
+            } else {
+                regex.push_str("[^/]*");
+            },
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&character.to_string())),
+        }
+    }
+
+    regex.push_str("(/.*)?$");
+    RegexBuilder::new(&regex).case_insensitive(true).build().map_err(|_| ErrorKind::InvalidPathPattern(pattern.to_owned()).into())
+}
+
 /// This function gets the version number of the exe for the current GameSelected, if it exists.
 #[allow(dead_code)]
 pub fn get_game_selected_exe_version_number() -> Result<u32> {
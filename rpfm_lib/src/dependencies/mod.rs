@@ -19,7 +19,7 @@ use rayon::iter::Either;
 use serde_derive::{Serialize, Deserialize};
 use unicase::UniCase;
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fs::{DirBuilder, File};
 use std::io::{BufReader, Read, Write};
@@ -39,7 +39,7 @@ use crate::packfile::packedfile::PackedFile;
 use crate::packfile::packedfile::PackedFileInfo;
 use crate::packfile::packedfile::CachedPackedFile;
 use crate::packedfile::{DecodedPackedFile, PackedFileType};
-use crate::packedfile::table::DependencyData;
+use crate::packedfile::table::{DependencyData, MergedTable, RowProvenance, Table};
 use crate::SCHEMA;
 use crate::settings::get_config_path;
 
@@ -304,8 +304,11 @@ impl Dependencies {
         Ok(cache)
     }
 
-    /// This function returns the provided dbs from the cache with their full path, according to the params you pass it. Table name must end in _tables.
-    pub fn get_db_tables_with_path_from_cache(&self, table_name: &str, include_vanilla: bool, include_modded: bool) -> Result<Vec<(String, DB)>> {
+    /// This function returns the provided dbs from the cache with their full path and the name of their PackFile, according to the params you pass it. Table name must end in _tables.
+    ///
+    /// The tables are returned sorted by path, which is the order the game reads them. If both vanilla and modded tables are included,
+    /// vanilla tables overwritten by a parent file with the same path are skipped, as the game never loads them.
+    pub fn get_db_tables_with_path_from_cache(&self, table_name: &str, include_vanilla: bool, include_modded: bool) -> Result<Vec<(String, String, DB)>> {
         let mut cache = vec![];
         let mut table_folder = "db/".to_owned();
         table_folder.push_str(&table_name.to_lowercase());
@@ -313,16 +316,16 @@ impl Dependencies {
         if include_vanilla {
             cache.append(&mut self.vanilla_packed_files_cache.read().unwrap().par_iter().filter_map(|(path, packed_file)| {
                 let packed_file_type = PackedFileType::get_packed_file_type(packed_file.get_ref_raw(), false);
-                if packed_file_type == PackedFileType::DB && path.to_lowercase().starts_with(&table_folder) {
+                if packed_file_type == PackedFileType::DB && path.to_lowercase().starts_with(&table_folder) && !(include_modded && self.parent_cached_packed_files.contains_key(path)) {
                     if let Ok(DecodedPackedFile::DB(db)) = packed_file.get_decoded_from_memory() {
-                        Some((path.to_owned(), db.clone()))
+                        Some((path.to_owned(), get_pack_file_name(packed_file), db.clone()))
                     } else {
                         None
                     }
                 } else {
                     None
                 }
-            }).collect())
+            }).collect());
         }
 
         if include_modded {
@@ -330,19 +333,38 @@ impl Dependencies {
                 let packed_file_type = PackedFileType::get_packed_file_type(packed_file.get_ref_raw(), false);
                 if packed_file_type == PackedFileType::DB && path.to_lowercase().starts_with(&table_folder) {
                     if let Ok(DecodedPackedFile::DB(db)) = packed_file.get_decoded_from_memory() {
-                        Some((path.to_owned(), db.clone()))
+                        Some((path.to_owned(), get_pack_file_name(packed_file), db.clone()))
                     } else {
                         None
                     }
                 } else {
                     None
                 }
-            }).collect())
+            }).collect());
         }
 
+        cache.sort_by(|x, y| x.0.cmp(&y.0));
         Ok(cache)
     }
 
+    /// This function returns the effective version of the provided table once vanilla and parent files are loaded, with the source of each row.
+    ///
+    /// Parent files overwrite vanilla tables with the same path. Rows with the same keys are resolved like the game does,
+    /// keeping the one from the table file that sorts first.
+    ///
+    /// Table name must end in _tables.
+    pub fn get_merged_db_table(&self, table_name: &str) -> Result<MergedTable> {
+        let tables = self.get_db_tables_with_path_from_cache(table_name, true, true)?;
+        let sources = tables.iter()
+            .map(|(path, pack, db)| (RowProvenance {
+                pack: pack.to_owned(),
+                path: path.split('/').map(|x| x.to_owned()).collect(),
+            }, db.get_ref_table()))
+            .collect::<Vec<(RowProvenance, &Table)>>();
+
+        MergedTable::new(&sources).ok_or_else(|| ErrorKind::MergedTableNotFound(table_name.to_owned()).into())
+    }
+
     /// This function checks if the current Game Selected has a dependencies file created.
     pub fn game_has_dependencies_generated(&self) -> bool {
         let mut file_path = get_config_path().unwrap().join(DEPENDENCIES_FOLDER);
@@ -951,18 +973,38 @@ impl Dependencies {
         }
     }
 
+    /// This function returns the files the game will load for the provided paths, taking them from the parent files if they're there, or from the game files otherwise.
+    ///
+    /// Folder paths are expanded to every file within them, sorted by path.
     pub fn get_most_relevant_files_by_paths(&self, paths: &[PathType]) -> Vec<PackedFile> {
         let mut packed_files = vec![];
 
         for path in paths {
-            if let PathType::File(path) = path {
-
-                if let Ok(packed_file) = self.get_packedfile_from_parent_files(&path) {
-                    packed_files.push(packed_file);
+            match path {
+                PathType::File(path) => {
+                    if let Ok(packed_file) = self.get_packedfile_from_parent_files(path) {
+                        packed_files.push(packed_file);
+                    }
+                    else if let Ok(packed_file) = self.get_packedfile_from_game_files(path) {
+                        packed_files.push(packed_file);
+                    }
                 }
-                else if let Ok(packed_file) = self.get_packedfile_from_game_files(&path) {
-                    packed_files.push(packed_file);
+
+                PathType::Folder(path) => {
+                    let mut folder = path.join("/").to_lowercase();
+                    folder.push('/');
+
+                    let file_paths = self.parent_cached_packed_files.keys()
+                        .chain(self.vanilla_cached_packed_files.keys())
+                        .filter(|x| x.to_lowercase().starts_with(&folder))
+                        .collect::<BTreeSet<&String>>()
+                        .into_iter()
+                        .map(|x| PathType::File(x.split('/').map(|x| x.to_owned()).collect()))
+                        .collect::<Vec<PathType>>();
+
+                    packed_files.append(&mut self.get_most_relevant_files_by_paths(&file_paths));
                 }
+                _ => {}
             }
         }
 
@@ -988,3 +1030,14 @@ impl From<&Dependencies> for DependenciesInfo {
         }
     }
 }
+
+//-------------------------------------------------------------------------------//
+//                             Util functions
+//-------------------------------------------------------------------------------//
+
+/// This function returns the name of the PackFile the provided PackedFile comes from.
+///
+/// PackedFiles loaded from the cache store the full path of their PackFile, so we need to remove the folders from it.
+fn get_pack_file_name(packed_file: &PackedFile) -> String {
+    packed_file.get_ref_raw().get_packfile_name().replace('\\', "/").rsplit('/').next().unwrap_or_default().to_owned()
+}
@@ -45,6 +45,7 @@ pub struct AnimFragmentDiagnosticReport {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AnimFragmentDiagnosticReportType {
     FieldWithPathNotFound,
+    AnimSkeletonMismatch,
 }
 
 //---------------------------------------------------------------p----------------//
@@ -73,10 +74,28 @@ impl AnimFragmentDiagnostic {
     }
 }
 
+impl AnimFragmentDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::FieldWithPathNotFound];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::FieldWithPathNotFound) => Some(Self::AnimSkeletonMismatch),
+            Some(Self::AnimSkeletonMismatch) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
 impl Display for AnimFragmentDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
-            Self::FieldWithPathNotFound => "FieldWithPathNotFound"
+            Self::FieldWithPathNotFound => "FieldWithPathNotFound",
+            Self::AnimSkeletonMismatch => "AnimSkeletonMismatch",
         }, f)
     }
 }
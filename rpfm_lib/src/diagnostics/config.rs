@@ -67,6 +67,25 @@ impl ConfigDiagnostic {
     }
 }
 
+impl ConfigDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::DependenciesCacheNotGenerated];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::DependenciesCacheNotGenerated) => Some(Self::DependenciesCacheOutdated),
+            Some(Self::DependenciesCacheOutdated) => Some(Self::DependenciesCacheCouldNotBeLoaded(String::new())),
+            Some(Self::DependenciesCacheCouldNotBeLoaded(_)) => Some(Self::IncorrectGamePath),
+            Some(Self::IncorrectGamePath) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
 impl Display for ConfigDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
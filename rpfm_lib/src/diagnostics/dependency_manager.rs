@@ -73,6 +73,22 @@ impl DependencyManagerDiagnostic {
     }
 }
 
+impl DependencyManagerDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::InvalidDependencyPackFileName];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::InvalidDependencyPackFileName) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
 impl Display for DependencyManagerDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
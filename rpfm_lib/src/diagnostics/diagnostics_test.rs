@@ -0,0 +1,544 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Diagnostics` module.
+!*/
+
+use crate::{GAME_SELECTED, SCHEMA_PATCHES};
+use crate::packedfile::table::loc::Loc;
+use crate::packedfile::table::table_test::{get_test_definition, get_test_field, get_test_row};
+use crate::packfile::{PFHFileType, PFHVersion};
+use crate::packfile::packedfile::RawPackedFile;
+use crate::schema::{Definition, VersionedFile};
+
+use super::*;
+use super::output::DiagnosticsOutputFormat;
+
+/// Test to make sure the skeletons of the anims are found by their path, ignoring its case, and only read once.
+#[test]
+fn test_diagnostics_anim_skeleton_name() {
+    let mut anim = Anim::new();
+    anim.set_skeleton_name("humanoid01".to_owned());
+
+    let path = vec!["animations".to_owned(), "battle".to_owned(), "test.anim".to_owned()];
+    let mut packed_file = PackedFile::new(path, "test.pack".to_owned());
+    packed_file.set_raw_data(&anim.save().unwrap());
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&packed_file, true).unwrap();
+
+    let local_anims = Diagnostics::get_local_anims(&pack_file);
+    assert_eq!(local_anims.len(), 1);
+
+    let dependencies = Dependencies::default();
+    let mut anim_skeletons = HashMap::new();
+    assert_eq!(Diagnostics::get_anim_skeleton_name("animations/battle/test.anim", &local_anims, &mut anim_skeletons, &dependencies).as_deref(), Some("humanoid01"));
+    assert_eq!(Diagnostics::get_anim_skeleton_name("Animations/Battle/TEST.anim", &local_anims, &mut anim_skeletons, &dependencies).as_deref(), Some("humanoid01"));
+    assert_eq!(anim_skeletons.len(), 1);
+
+    // Once found, the skeleton is taken from the cache, not from the anim.
+    anim_skeletons.insert(UniCase::new("animations/battle/test.anim".to_owned()), Some("humanoid02".to_owned()));
+    assert_eq!(Diagnostics::get_anim_skeleton_name("animations/battle/test.anim", &local_anims, &mut anim_skeletons, &dependencies).as_deref(), Some("humanoid02"));
+}
+
+/// Test to make sure lua scripts get the keys of all the tables, including the ones not decoded yet and the ones of the parent PackFiles.
+#[test]
+fn test_diagnostics_lua_db_keys() {
+    let table_name = "units_tables";
+    let definition = get_test_definition();
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB(table_name.to_owned(), vec![definition.clone()]));
+
+    let table = |key: &str| {
+        let mut db = DB::new(table_name, None, &definition);
+        db.set_table_data(&[get_test_row(key, 100)]).unwrap();
+        db
+    };
+
+    // The local table is only in binary form, as tables are not decoded until they're opened.
+    let mut local_table = PackedFile::new(vec!["db".to_owned(), table_name.to_owned(), "mod".to_owned()], "test.pack".to_owned());
+    local_table.set_raw_data(&table("wh2_main_mod_unit").save().unwrap());
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&local_table, true).unwrap();
+
+    let parent_table = PackedFile::new_from_decoded(&DecodedPackedFile::DB(table("wh2_main_parent_unit")), &["db".to_owned(), table_name.to_owned(), "parent".to_owned()]);
+    let asskit_table = table("wh2_main_asskit_unit");
+
+    let db_keys = Diagnostics::get_lua_db_keys(&pack_file, &schema, &[parent_table], &[asskit_table]);
+    assert!(db_keys.contains("wh2_main_mod_unit"));
+    assert!(db_keys.contains("wh2_main_parent_unit"));
+    assert!(db_keys.contains("wh2_main_asskit_unit"));
+    assert!(!db_keys.contains("100"));
+    assert!(db_keys.is_key_candidate("wh2_main_missing_unit"));
+}
+
+/// Test to make sure all the report types are known to the diagnostics rules, and none of them is repeated.
+#[test]
+fn test_diagnostics_known_rule_ids() {
+    let rule_ids = DiagnosticsRules::get_known_rule_ids();
+
+    // Table report types are used by both DB and Loc tables.
+    let report_type_count = AnimFragmentDiagnosticReportType::all().len() +
+        ConfigDiagnosticReportType::all().len() +
+        TableDiagnosticReportType::all().len() * 2 +
+        PackFileDiagnosticReportType::all().len() +
+        DependencyManagerDiagnosticReportType::all().len() +
+        LoadOrderDiagnosticReportType::all().len() +
+        RigidModelDiagnosticReportType::all().len() +
+        LuaDiagnosticReportType::all().len();
+    assert_eq!(rule_ids.len(), report_type_count);
+    assert_eq!(rule_ids.iter().collect::<HashSet<_>>().len(), rule_ids.len());
+    assert!(rule_ids.contains(&"Config/DependenciesCacheCouldNotBeLoaded".to_owned()));
+    assert!(rule_ids.contains(&"Loc/ValueOutOfRange".to_owned()));
+    assert!(rule_ids.contains(&"Lua/MissingDBKey".to_owned()));
+    assert!(rule_ids.contains(&"DB/OutdatedTable".to_owned()));
+    assert!(rule_ids.contains(&"LoadOrder/DuplicatedDBKeyAcrossPacks".to_owned()));
+}
+
+/// Test to make sure the files overwritten across PackFiles are reported, with the PackFile that wins following the load order of the game.
+#[test]
+fn test_diagnostics_load_order_conflicts() {
+    let path = vec!["text".to_owned(), "conflict.txt".to_owned()];
+    let mut pack_files = vec![];
+    for (name, pfh_file_type) in [("b.pack", PFHFileType::Mod), ("movie.pack", PFHFileType::Movie), ("a.pack", PFHFileType::Mod)] {
+        let mut pack_file = PackFile::new_with_name(name, PFHVersion::PFH5);
+        pack_file.set_pfh_file_type(pfh_file_type);
+
+        let raw = RawPackedFile::read_from_vec(path.to_vec(), name.to_owned(), 0, false, name.as_bytes().to_vec());
+        pack_file.add_packed_file(&PackedFile::new_from_raw(&raw), true).unwrap();
+        pack_files.push(pack_file);
+    }
+
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.check_load_order(&pack_files, &mut Dependencies::default());
+
+    let report = diagnostics.get_ref_diagnostics().iter().find_map(|diagnostic| match diagnostic {
+        DiagnosticType::LoadOrder(diagnostic) => diagnostic.get_ref_result().first(),
+        _ => None,
+    }).unwrap();
+
+    assert_eq!(report.path, path);
+    assert_eq!(report.packs, vec!["a.pack".to_owned(), "b.pack".to_owned(), "movie.pack".to_owned()]);
+    assert_eq!(report.winner, "movie.pack");
+}
+
+/// Test to make sure keys defined in more than one PackFile are won by the first file the game reads, like in merged tables.
+#[test]
+fn test_diagnostics_load_order_key_conflicts() {
+    let pack_names = vec!["a.pack".to_owned(), "b.pack".to_owned()];
+    let path = |file: &str| vec!["db".to_owned(), "units_tables".to_owned(), file.to_owned()];
+    let (mod_first, vanilla_like, mod_last) = (path("!my_mod"), path("data__"), path("zz_my_mod"));
+
+    // The PackFile loaded last doesn't win if its file sorts after the other one.
+    let (packs, winner_index, winner_path) = Diagnostics::get_load_order_conflict(&[(0, &vanilla_like), (1, &mod_last)], &pack_names).unwrap();
+    assert_eq!(packs, pack_names);
+    assert_eq!(winner_index, 0);
+    assert_eq!(winner_path, vanilla_like);
+
+    let (_, winner_index, winner_path) = Diagnostics::get_load_order_conflict(&[(0, &vanilla_like), (1, &mod_first)], &pack_names).unwrap();
+    assert_eq!(winner_index, 1);
+    assert_eq!(winner_path, mod_first);
+
+    // Keys defined more than once in the same PackFile are not conflicts between PackFiles.
+    assert!(Diagnostics::get_load_order_conflict(&[(1, &mod_first), (1, &mod_last)], &pack_names).is_none());
+}
+
+/// Test to make sure lua scripts are tokenized properly, and their syntax errors, unknown API calls and db key candidates are found.
+#[test]
+fn test_diagnostics_lua() {
+    let script = LuaScript::tokenize(r#"
+-- Long comments, [[long strings]] and methods defined by the script must work.
+--[==[ local broken = ( ]==]
+local faction_key = "wh_main_emp_empire"
+local description = [[The "Empire".]]
+
+function cm:my_helper(faction, ...)
+	for i = 1, #faction, 2 do
+		out(i .. "")
+	end
+	return faction
+end
+
+core:add_listener("listener", "FactionTurnStart", true, function(context)
+	local t = { key = "wh2_main_hef_eataine", [1] = -1.5e3; 0x1F }
+	cm:my_helper(faction_key)
+	cm:not_a_real_function(t.key)
+	cm:get_game_interface():some_function()
+end, true)
+"#).unwrap();
+
+    assert!(script.check_syntax().is_ok());
+    assert_eq!(script.get_unknown_api_calls(), vec![((17, 5), "cm:not_a_real_function".to_owned())]);
+    let db_keys = LuaDBKeys::new(["wh_main_emp_empire", "wh2_main_hef_cothique", "3k_main_faction_cao_cao"].iter().map(|key| key.to_string()).collect());
+    assert_eq!(script.get_db_key_candidates(&db_keys), vec![((4, 21), "wh_main_emp_empire".to_owned()), ((15, 20), "wh2_main_hef_eataine".to_owned())]);
+    assert!(db_keys.contains("wh_main_emp_empire"));
+    assert!(!db_keys.contains("wh2_main_hef_eataine"));
+
+    // Keys are detected by the prefixes of the keys we have, not by game.
+    assert!(db_keys.is_key_candidate("3k_main_faction_liu_bei"));
+    assert!(!db_keys.is_key_candidate("3k_dlc04_faction_liu_bei"));
+    assert!(!db_keys.is_key_candidate("3k_main"));
+    assert!(!db_keys.is_key_candidate("3k_main_Faction"));
+
+    // Syntax errors must point to where the problem was found.
+    let error = |code: &str| {
+        let error = LuaScript::tokenize(code).and_then(|script| script.check_syntax()).unwrap_err();
+        (error.line, error.column, error.message)
+    };
+
+    assert_eq!(error("if a then\n\tb()\n"), (3, 1, "'end' expected (to close 'if' at line 1) near '<eof>'".to_owned()));
+    assert_eq!(error("local a = b +\n"), (2, 1, "unexpected symbol near '<eof>'".to_owned()));
+    assert_eq!(error("a = 1\nb\n"), (3, 1, "syntax error near '<eof>'".to_owned()));
+    assert_eq!(error("f(a, 'b)\n"), (1, 6, "unfinished string".to_owned()));
+    assert_eq!(error("return 1\nx = 2"), (2, 1, "'<eof>' expected near 'x'".to_owned()));
+    assert_eq!(error("local t = { a = 1 b = 2 }"), (1, 19, "'}' expected near 'b'".to_owned()));
+}
+
+/// Test to make sure diagnostics are exported to SARIF, JUnit and text with the positions starting at 1.
+#[test]
+fn test_diagnostics_output_formats() {
+    let mut table = TableDiagnostic::new(&["db".to_owned(), "units_tables".to_owned(), "mod".to_owned()]);
+    table.get_ref_mut_result().push(TableDiagnosticReport {
+        cells_affected: vec![(2, 1), (-1, 3)],
+        message: "Invalid reference \"a & b\".".to_owned(),
+        report_type: TableDiagnosticReportType::InvalidReference,
+        level: DiagnosticLevel::Error,
+        fix: None,
+    });
+
+    let mut pack_file = PackFileDiagnostic::new();
+    pack_file.get_ref_mut_result().push(PackFileDiagnosticReport {
+        message: "Invalid PackFile name.".to_owned(),
+        report_type: PackFileDiagnosticReportType::InvalidPackFileName,
+        level: DiagnosticLevel::Warning,
+    });
+
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::DB(table));
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::PackFile(pack_file));
+
+    // Positions start at 1, and full columns have no row.
+    let entries = diagnostics.get_entries();
+    assert_eq!(entries[0].rule_id, "DB/InvalidReference");
+    assert_eq!(entries[0].path, "db/units_tables/mod");
+    assert_eq!(entries[0].positions, vec![(Some(3), Some(2)), (None, Some(4))]);
+    assert_eq!(entries[1].rule_id, "Packfile/InvalidPackFileName");
+
+    assert!(diagnostics.has_level(DiagnosticLevel::Error));
+    assert!(diagnostics.has_level(DiagnosticLevel::Warning));
+    assert!(!Diagnostics::default().has_level(DiagnosticLevel::Info));
+
+    let sarif: serde_json::Value = serde_json::from_str(&diagnostics.to_format("sarif".parse().unwrap()).unwrap()).unwrap();
+    let results = &sarif["runs"][0]["results"];
+    assert_eq!(results[0]["ruleId"], "DB/InvalidReference");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["locations"][0]["physicalLocation"]["region"], serde_json::json!({ "startLine": 3, "startColumn": 2 }));
+    assert!(results[0]["locations"][1]["physicalLocation"]["region"].is_null());
+    assert_eq!(results[1]["level"], "warning");
+    assert!(results[1]["locations"].is_null());
+
+    let junit = diagnostics.to_format(DiagnosticsOutputFormat::JUnit).unwrap();
+    assert!(junit.contains("<testsuites name=\"RPFM Diagnostics\" tests=\"2\" failures=\"2\">"));
+    assert!(junit.contains("<testcase classname=\"db/units_tables/mod\" name=\"DB/InvalidReference (3:2, *:4)\">"));
+    assert!(junit.contains("<failure message=\"Invalid reference &quot;a &amp; b&quot;.\" type=\"Error\">"));
+
+    let text = diagnostics.to_format(DiagnosticsOutputFormat::Text).unwrap();
+    assert_eq!(text, "db/units_tables/mod:3:2: Error [DB/InvalidReference] Invalid reference \"a & b\".\n\
+        db/units_tables/mod:*:4: Error [DB/InvalidReference] Invalid reference \"a & b\".\n\
+        PackFile: Warning [Packfile/InvalidPackFileName] Invalid PackFile name.\n");
+
+    assert!("yaml".parse::<DiagnosticsOutputFormat>().is_err());
+    assert_eq!("WARNING".parse::<DiagnosticLevel>().unwrap(), DiagnosticLevel::Warning);
+}
+
+/// Test to make sure diagnostics rules are parsed and validated properly, and change the level of the reports or disable them.
+#[test]
+fn test_diagnostics_rules() {
+    let rules = DiagnosticsRules::from_toml(r#"
+		[[rules]]
+		rule = "InvalidReference"
+		tables = ["units"]
+		columns = ["category"]
+		level = "warning"
+
+		[[rules]]
+		rule = "Packfile/InvalidPackFileName"
+		enabled = false
+
+		[[rules]]
+		rule = "DB/NotARule"
+		paths = ["db/**"]
+	"#).unwrap();
+
+    assert_eq!(rules.get_unknown_rules(), vec!["DB/NotARule".to_owned()]);
+    assert!(rules.validate().is_err());
+    assert!(DiagnosticsRules::from_toml("rules = 1").is_err());
+
+    // Rules stored in the PackFile must survive a roundtrip through the settings.
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    let mut settings = pack_file.get_settings().clone();
+    settings.settings_text.insert("diagnostics_rules".to_owned(), rules.to_toml().unwrap());
+    pack_file.set_settings(&settings);
+    assert_eq!(pack_file.get_settings().get_diagnostics_rules().unwrap(), rules);
+
+    // Column names come from the definition of the table in the PackFile.
+    let path = vec!["db".to_owned(), "units_tables".to_owned(), "mod".to_owned()];
+    let mut definition = Definition::new(1);
+    definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    definition.get_ref_mut_fields().push(get_test_field("category", FieldType::StringU8, false, 1));
+    let db = DB::new("units_tables", None, &definition);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(db), &path), true).unwrap();
+
+    let mut table = TableDiagnostic::new(&path);
+    table.get_ref_mut_result().push(TableDiagnosticReport {
+        cells_affected: vec![(0, 0), (1, 1)],
+        message: "Invalid reference.".to_owned(),
+        report_type: TableDiagnosticReportType::InvalidReference,
+        level: DiagnosticLevel::Error,
+        fix: None,
+    });
+
+    let mut pack_file_diagnostic = PackFileDiagnostic::new();
+    pack_file_diagnostic.get_ref_mut_result().push(PackFileDiagnosticReport {
+        message: "Invalid PackFile name.".to_owned(),
+        report_type: PackFileDiagnosticReportType::InvalidPackFileName,
+        level: DiagnosticLevel::Error,
+    });
+
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::DB(table));
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::PackFile(pack_file_diagnostic));
+    diagnostics.apply_rules(&rules, Some(&pack_file)).unwrap();
+
+    // The report gets split, so only the cell in the column of the rule changes its level. The disabled diagnostic disappears.
+    let entries = diagnostics.get_entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!((entries[0].level, &*entries[0].positions), (DiagnosticLevel::Error, &[(Some(1), Some(1))][..]));
+    assert_eq!((entries[1].level, &*entries[1].positions), (DiagnosticLevel::Warning, &[(Some(2), Some(2))][..]));
+}
+
+/// Test to make sure the fixes of the diagnostics are applied to the selected tables, including the ones that rename them.
+#[test]
+fn test_diagnostics_fixes() {
+    let path = vec!["db".to_owned(), "units_tables".to_owned(), "my mod".to_owned()];
+    let mut definition = Definition::new(1);
+    definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    definition.get_ref_mut_fields().push(get_test_field("text", FieldType::StringU8, false, 1));
+
+    let mut db = DB::new("units_tables", None, &definition);
+    db.set_table_data(&[
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::StringU8("line\\nbreak".to_owned())],
+        vec![DecodedData::StringU8(String::new()), DecodedData::StringU8(String::new())],
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::StringU8("line\\nbreak".to_owned())],
+    ]).unwrap();
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(db), &path), true).unwrap();
+
+    let mut table = TableDiagnostic::new(&path);
+    let reports = vec![
+        (vec![(0, 1), (2, 1)], TableDiagnosticReportType::InvalidEscape, TableDiagnosticFix::EscapeText),
+        (vec![(1, -1)], TableDiagnosticReportType::EmptyRow, TableDiagnosticFix::RemoveRows),
+        (vec![(2, -1)], TableDiagnosticReportType::DuplicatedRow, TableDiagnosticFix::RemoveRows),
+        (vec![(-1, -1)], TableDiagnosticReportType::TableNameHasSpace, TableDiagnosticFix::ReplaceSpacesInName),
+    ];
+
+    for (cells_affected, report_type, fix) in reports {
+        table.get_ref_mut_result().push(TableDiagnosticReport {
+            cells_affected,
+            message: String::new(),
+            report_type,
+            level: DiagnosticLevel::Warning,
+            fix: Some(fix),
+        });
+    }
+
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::DB(table));
+    assert!(diagnostics.has_fixes());
+
+    // Nothing gets fixed if the table is not in the selection.
+    assert!(diagnostics.apply_fixes(&mut pack_file, &Dependencies::default(), Some(&[vec!["db".to_owned()]])).unwrap().is_empty());
+
+    let new_path = vec!["db".to_owned(), "units_tables".to_owned(), "my_mod".to_owned()];
+    assert_eq!(diagnostics.apply_fixes(&mut pack_file, &Dependencies::default(), None).unwrap(), vec![(path, new_path.to_vec())]);
+
+    match pack_file.get_ref_packed_file_by_path(&new_path).unwrap().get_decoded() {
+        DecodedPackedFile::DB(db) => assert_eq!(db.get_table_data(), vec![
+            vec![DecodedData::StringU8("a".to_owned()), DecodedData::StringU8("line\\\\nbreak".to_owned())],
+        ]),
+        _ => panic!("Fixed table is not a DB table."),
+    }
+}
+
+/// Test to make sure missing loc data and orphan loc keys are reported, taking into account the tables of the dependencies.
+#[test]
+fn test_diagnostics_loc_completeness() {
+    let mut definition = Definition::new(1);
+    definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    definition.get_ref_mut_localised_fields().push(get_test_field("onscreen_name", FieldType::StringU8, false, 0));
+
+    let mut db = DB::new("units_tables", None, &definition);
+    db.set_table_data(&[
+        vec![DecodedData::StringU8("a".to_owned())],
+        vec![DecodedData::StringU8("b".to_owned())],
+    ]).unwrap();
+
+    let mut loc_definition = Definition::new(1);
+    loc_definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU16, true, 0));
+    loc_definition.get_ref_mut_fields().push(get_test_field("text", FieldType::StringU16, false, 0));
+
+    let mut loc = Loc::new(&loc_definition);
+    loc.set_table_data(&[
+        vec![DecodedData::StringU16("units_onscreen_name_a".to_owned()), DecodedData::StringU16("Unit A".to_owned())],
+        vec![DecodedData::StringU16("units_onscreen_name_c".to_owned()), DecodedData::StringU16("Unit C".to_owned())],
+        vec![DecodedData::StringU16("random_text".to_owned()), DecodedData::StringU16("Not linked to any table".to_owned())],
+    ]).unwrap();
+
+    let db_path = vec!["db".to_owned(), "units_tables".to_owned(), "mod".to_owned()];
+    let loc_path = vec!["text".to_owned(), "mod.loc".to_owned()];
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(db), &db_path), true).unwrap();
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), &loc_path), true).unwrap();
+
+    // Row "b" has no loc entry, and "c" is not a row of the table. Lines not linked to a table are not reported.
+    let diagnostics = Diagnostics::check_loc_completeness(&pack_file, &[], &[], &[], &None);
+    assert_eq!(diagnostics.len(), 2);
+    match &diagnostics[0] {
+        DiagnosticType::DB(diagnostic) => {
+            assert_eq!(diagnostic.get_path(), &db_path[..]);
+            assert_eq!(diagnostic.get_ref_result().len(), 1);
+            assert_eq!(diagnostic.get_ref_result()[0].cells_affected, vec![(1, 0)]);
+            assert!(diagnostic.get_ref_result()[0].message.contains("units_onscreen_name_b"));
+        }
+        _ => panic!("Missing loc data is not a DB diagnostic."),
+    }
+
+    match &diagnostics[1] {
+        DiagnosticType::Loc(diagnostic) => {
+            assert_eq!(diagnostic.get_path(), &loc_path[..]);
+            assert_eq!(diagnostic.get_ref_result().len(), 1);
+            assert_eq!(diagnostic.get_ref_result()[0].cells_affected, vec![(1, 0)]);
+            assert!(diagnostic.get_ref_result()[0].message.contains("\"c\""));
+        }
+        _ => panic!("Orphan loc key is not a Loc diagnostic."),
+    }
+
+    // Ignored files are not reported.
+    let files_to_ignore = Some(vec![(loc_path.to_vec(), vec![], vec!["OrphanLocKey".to_owned()])]);
+    assert_eq!(Diagnostics::check_loc_completeness(&pack_file, &[], &[], &[], &files_to_ignore).len(), 1);
+
+    // Loc entries and rows only found in the dependencies are not reported either.
+    let mut dependency_loc = Loc::new(&loc_definition);
+    dependency_loc.set_table_data(&[
+        vec![DecodedData::StringU16("units_onscreen_name_b".to_owned()), DecodedData::StringU16("Unit B".to_owned())],
+    ]).unwrap();
+
+    let mut dependency_db = DB::new("units_tables", None, &definition);
+    dependency_db.set_table_data(&[
+        vec![DecodedData::StringU8("c".to_owned())],
+    ]).unwrap();
+
+    let loc_dependencies = vec![PackedFile::new_from_decoded(&DecodedPackedFile::Loc(dependency_loc), &["text".to_owned(), "vanilla.loc".to_owned()])];
+    let db_dependencies = vec![PackedFile::new_from_decoded(&DecodedPackedFile::DB(dependency_db), &["db".to_owned(), "units_tables".to_owned(), "vanilla".to_owned()])];
+    let diagnostics = Diagnostics::check_loc_completeness(&pack_file, &[], &loc_dependencies, &[], &None);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(&diagnostics[0], DiagnosticType::Loc(_)));
+
+    assert!(Diagnostics::check_loc_completeness(&pack_file, &db_dependencies, &loc_dependencies, &[], &None).is_empty());
+}
+
+/// Test to make sure invalid enum values, undeclared bits and values out of range are reported, and their fixes applied.
+#[test]
+fn test_diagnostics_value_validation() {
+    let table_name = "value_validation_tables";
+    let mut enum_values = BTreeMap::new();
+    enum_values.insert(0, "melee".to_owned());
+    enum_values.insert(1, "ranged".to_owned());
+
+    // The raw table has no enum nor bitwise fields, so we can write values the schema doesn't declare.
+    let mut raw_definition = Definition::new(1);
+    raw_definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    raw_definition.get_ref_mut_fields().push(get_test_field("type", FieldType::I32, false, 1));
+    raw_definition.get_ref_mut_fields().push(get_test_field("flags", FieldType::I32, false, 2));
+    raw_definition.get_ref_mut_fields().push(get_test_field("cost", FieldType::I32, false, 3));
+
+    let mut raw_db = DB::new(table_name, None, &raw_definition);
+    raw_db.set_table_data(&[
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::I32(7), DecodedData::I32(0b1101), DecodedData::I32(150)],
+        vec![DecodedData::StringU8("b".to_owned()), DecodedData::I32(1), DecodedData::I32(0b10), DecodedData::I32(50)],
+    ]).unwrap();
+    let raw_data = raw_db.save().unwrap();
+
+    let mut fields = raw_definition.get_ref_fields().to_vec();
+    fields[1] = Field::new("type".to_owned(), FieldType::I32, false, None, false, None, None, None, String::new(), 1, 0, enum_values, None);
+    fields[2].set_is_bitwise(2);
+    let db = DB::read_with_fields(&raw_data, table_name, &fields, false).unwrap();
+
+    let game = GAME_SELECTED.read().unwrap().get_game_key_name();
+    let mut cost_patch = HashMap::new();
+    cost_patch.insert("max_value".to_owned(), "100".to_owned());
+    SCHEMA_PATCHES.write().unwrap().get_ref_mut_patches().entry(game.clone()).or_default().get_ref_mut_tables().entry(table_name.to_owned()).or_default().insert("cost".to_owned(), cost_patch);
+
+    // Columns are: key, type, flags_1, flags_2 and cost.
+    let reports = Diagnostics::check_db_values(&db, Some(&raw_data), &[], &[], &HashMap::new());
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0].cells_affected, vec![(0, 1)]);
+    assert_eq!(reports[0].fix, Some(TableDiagnosticFix::ReplaceValue("melee".to_owned())));
+    assert_eq!(reports[1].cells_affected, vec![(0, 4)]);
+    assert_eq!(reports[1].fix, Some(TableDiagnosticFix::ReplaceValue("100".to_owned())));
+    assert_eq!(reports[2].cells_affected, vec![(0, 2)]);
+    assert_eq!(reports[2].fix, Some(TableDiagnosticFix::ClearUndeclaredBits));
+    assert_eq!(DB::get_undeclared_bitwise_bits(&raw_data, db.get_ref_definition()).unwrap(), vec![(0, 2, 0b1100)]);
+
+    // Fields with as many flags as bits cannot have undeclared bits, and the ones close to it must not overflow.
+    let mut wide_definition = Definition::new(1);
+    wide_definition.get_ref_mut_fields().push(get_test_field("flags", FieldType::I64, false, 0));
+    let mut wide_db = DB::new(table_name, None, &wide_definition);
+    wide_db.set_table_data(&[vec![DecodedData::I64(-1)]]).unwrap();
+    let wide_data = wide_db.save().unwrap();
+
+    wide_definition.get_ref_mut_fields()[0].set_is_bitwise(64);
+    assert!(DB::get_undeclared_bitwise_bits(&wide_data, &wide_definition).unwrap().is_empty());
+
+    wide_definition.get_ref_mut_fields()[0].set_is_bitwise(63);
+    assert_eq!(DB::get_undeclared_bitwise_bits(&wide_data, &wide_definition).unwrap(), vec![(0, 0, i64::MIN)]);
+
+    // Ignored fields are not reported.
+    assert!(Diagnostics::check_db_values(&db, Some(&raw_data), &["type".to_owned(), "flags".to_owned(), "cost".to_owned()], &[], &HashMap::new()).is_empty());
+
+    let path = vec!["db".to_owned(), table_name.to_owned(), "mod".to_owned()];
+    let mut table = TableDiagnostic::new(&path);
+    table.get_ref_mut_result().extend(reports);
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(db), &path), true).unwrap();
+
+    let mut diagnostics = Diagnostics::default();
+    diagnostics.get_ref_mut_diagnostics().push(DiagnosticType::DB(table));
+    assert_eq!(diagnostics.apply_fixes(&mut pack_file, &Dependencies::default(), None).unwrap(), vec![(path.to_vec(), path.to_vec())]);
+
+    let packed_file = pack_file.get_ref_packed_file_by_path(&path).unwrap();
+    match packed_file.get_decoded() {
+        DecodedPackedFile::DB(db) => {
+            assert_eq!(db.get_table_data()[0], vec![DecodedData::StringU8("a".to_owned()), DecodedData::StringU8("melee".to_owned()), DecodedData::Boolean(true), DecodedData::Boolean(false), DecodedData::I32(100)]);
+            assert!(DB::get_undeclared_bitwise_bits(&packed_file.get_raw_data().unwrap(), db.get_ref_definition()).unwrap().is_empty());
+        }
+        _ => panic!("Fixed table is not a DB table."),
+    }
+
+    // Remove the patch, so it doesn't leak into other tests.
+    if let Some(patch) = SCHEMA_PATCHES.write().unwrap().get_ref_mut_patches().get_mut(&game) {
+        patch.get_ref_mut_tables().remove(table_name);
+    }
+}
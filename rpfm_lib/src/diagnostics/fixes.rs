@@ -0,0 +1,209 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to automatically fix the diagnostic results that have an obvious fix.
+
+Fixes are applied per PackedFile, in an order that keeps the reported cells valid:
+escaped and replaced values first, then removed rows, then definition updates, and finally renames.
+!*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::dependencies::Dependencies;
+use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::table::DecodedData;
+use crate::packfile::PackFile;
+use crate::schema::Field;
+
+use super::{Diagnostics, DiagnosticType};
+use super::table::{TableDiagnostic, TableDiagnosticFix};
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains all the fixes to apply to a single table.
+#[derive(Default)]
+struct Fixes {
+    rows_to_remove: BTreeSet<usize>,
+    cells_to_escape: BTreeSet<(usize, usize)>,
+    cells_to_replace: BTreeMap<(usize, usize), String>,
+    clear_undeclared_bits: bool,
+    replace_spaces_in_name: bool,
+    add_underscore_to_name: bool,
+    update_table: bool,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `Diagnostics`. Section of functions to fix diagnostics automatically.
+impl Diagnostics {
+
+    /// This function returns if any of the diagnostic results can be fixed automatically.
+    pub fn has_fixes(&self) -> bool {
+        self.get_ref_diagnostics().iter().any(|diagnostic| match diagnostic {
+            DiagnosticType::DB(ref diag) |
+            DiagnosticType::Loc(ref diag) => diag.get_ref_result().iter().any(|result| result.fix.is_some()),
+            _ => false,
+        })
+    }
+
+    /// This function applies the fixes of the diagnostic results to the provided PackFile.
+    ///
+    /// If a selection of paths is provided, only the PackedFiles in it are fixed. Otherwise, all the fixable results are fixed.
+    ///
+    /// It returns the paths of the edited PackedFiles, before and after the fixes, as some fixes rename them.
+    pub fn apply_fixes(&self, pack_file: &mut PackFile, dependencies: &Dependencies, selection: Option<&[Vec<String>]>) -> Result<Vec<(Vec<String>, Vec<String>)>> {
+        let mut edited_paths = vec![];
+        for diagnostic in self.get_ref_diagnostics() {
+            let diagnostic = match diagnostic {
+                DiagnosticType::DB(ref diag) |
+                DiagnosticType::Loc(ref diag) => diag,
+                _ => continue,
+            };
+
+            let path = diagnostic.get_path();
+            if let Some(selection) = selection {
+                if !selection.iter().any(|selected_path| selected_path == path) {
+                    continue;
+                }
+            }
+
+            let fixes = Fixes::new(diagnostic);
+            if fixes.is_empty() {
+                continue;
+            }
+
+            let packed_file = pack_file.get_ref_mut_packed_file_by_path(path).ok_or(ErrorKind::PackedFileNotFound)?;
+            let decoded = packed_file.decode_return_ref_mut()?;
+            match decoded {
+                DecodedPackedFile::DB(table) => {
+                    let mut data = table.get_table_data();
+                    fixes.fix_table_data(&mut data, &table.get_ref_definition().get_fields_processed());
+                    table.set_table_data(&data)?;
+                }
+                DecodedPackedFile::Loc(table) => {
+                    let mut data = table.get_table_data();
+                    fixes.fix_table_data(&mut data, &table.get_ref_definition().get_fields_processed());
+                    table.set_table_data(&data)?;
+                }
+                _ => return Err(ErrorKind::DBTableIsNotADBTable.into()),
+            }
+
+            if fixes.update_table {
+                decoded.update_table(dependencies)?;
+            }
+
+            packed_file.encode_no_load()?;
+
+            // Renames go last, as they change the path of the PackedFile.
+            let mut new_path = path.to_vec();
+            if let Some(name) = new_path.last_mut() {
+                if fixes.replace_spaces_in_name {
+                    *name = name.replace(' ', "_");
+                }
+
+                if fixes.add_underscore_to_name {
+                    name.push('_');
+                }
+            }
+
+            if new_path != path {
+                new_path = pack_file.move_packedfile(path, &new_path, false)?;
+            }
+
+            edited_paths.push((path.to_vec(), new_path));
+        }
+
+        Ok(edited_paths)
+    }
+}
+
+/// Implementation of `Fixes`.
+impl Fixes {
+
+    /// This function gathers the fixes of all the results of the provided diagnostic.
+    fn new(diagnostic: &TableDiagnostic) -> Self {
+        let mut fixes = Self::default();
+        for result in diagnostic.get_ref_result() {
+            match &result.fix {
+                Some(TableDiagnosticFix::RemoveRows) => fixes.rows_to_remove.extend(result.cells_affected.iter().filter(|(row, _)| *row >= 0).map(|(row, _)| *row as usize)),
+                Some(TableDiagnosticFix::EscapeText) => fixes.cells_to_escape.extend(result.cells_affected.iter().filter(|(row, column)| *row >= 0 && *column >= 0).map(|(row, column)| (*row as usize, *column as usize))),
+                Some(TableDiagnosticFix::ReplaceSpacesInName) => fixes.replace_spaces_in_name = true,
+                Some(TableDiagnosticFix::AddUnderscoreToName) => fixes.add_underscore_to_name = true,
+                Some(TableDiagnosticFix::UpdateTable) => fixes.update_table = true,
+                Some(TableDiagnosticFix::ReplaceValue(value)) => fixes.cells_to_replace.extend(result.cells_affected.iter().filter(|(row, column)| *row >= 0 && *column >= 0).map(|(row, column)| ((*row as usize, *column as usize), value.to_owned()))),
+
+                // The undeclared bits are lost when decoding the table, so we just need to encode it again.
+                Some(TableDiagnosticFix::ClearUndeclaredBits) => fixes.clear_undeclared_bits = true,
+                None => {},
+            }
+        }
+
+        fixes
+    }
+
+    /// This function returns if there is nothing to fix.
+    fn is_empty(&self) -> bool {
+        self.rows_to_remove.is_empty() &&
+            self.cells_to_escape.is_empty() &&
+            self.cells_to_replace.is_empty() &&
+            !self.clear_undeclared_bits &&
+            !self.replace_spaces_in_name &&
+            !self.add_underscore_to_name &&
+            !self.update_table
+    }
+
+    /// This function applies the fixes that edit the data of the table. Cells are escaped and replaced before removing rows, so their positions are still valid.
+    fn fix_table_data(&self, data: &mut Vec<Vec<DecodedData>>, fields: &[Field]) {
+        for (row, column) in &self.cells_to_escape {
+            if let Some(DecodedData::StringU8(text)) |
+                Some(DecodedData::StringU16(text)) |
+                Some(DecodedData::OptionalStringU8(text)) |
+                Some(DecodedData::OptionalStringU16(text)) = data.get_mut(*row).and_then(|row| row.get_mut(*column)) {
+                *text = escape_text(text);
+            }
+        }
+
+        for ((row, column), value) in &self.cells_to_replace {
+            if let (Some(cell), Some(field)) = (data.get_mut(*row).and_then(|row| row.get_mut(*column)), fields.get(*column)) {
+                *cell = DecodedData::default(field.get_ref_field_type(), &Some(value.to_owned()));
+            }
+        }
+
+        for row in self.rows_to_remove.iter().rev() {
+            if *row < data.len() {
+                data.remove(*row);
+            }
+        }
+    }
+}
+
+/// This function escapes the line jumps (`\n`) and tabulations (`\t`) of the provided text that are not already escaped.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut previous = None;
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character == '\\' && previous != Some('\\') && matches!(chars.peek(), Some('n') | Some('t')) {
+            escaped.push('\\');
+        }
+
+        escaped.push(character);
+        previous = Some(character);
+    }
+
+    escaped
+}
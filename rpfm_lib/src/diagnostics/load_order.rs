@@ -0,0 +1,104 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code related to the `LoadOrderDiagnostic`.
+
+This module contains the code needed to report conflicts between multiple `PackFiles` loaded at the same time.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::{fmt, fmt::Display};
+
+use super::DiagnosticLevel;
+
+/// Name used in the reports to refer to the vanilla files of the game.
+pub const VANILLA_PACK_NAME: &str = "vanilla";
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains the results of a load order check done over multiple PackFiles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadOrderDiagnostic {
+    result: Vec<LoadOrderDiagnosticReport>
+}
+
+/// This struct defines an individual load order diagnostic result.
+///
+/// Packs are always sorted in load order, so the last one is the one that wins the conflict.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadOrderDiagnosticReport {
+    pub path: Vec<String>,
+    pub key: Vec<String>,
+    pub packs: Vec<String>,
+    pub winner: String,
+    pub message: String,
+    pub report_type: LoadOrderDiagnosticReportType,
+    pub level: DiagnosticLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LoadOrderDiagnosticReportType {
+    FileOverridden,
+    DuplicatedDBKeyAcrossPacks,
+    DuplicatedLocKeyAcrossPacks,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `LoadOrderDiagnostic`.
+impl LoadOrderDiagnostic {
+    pub fn new() -> Self {
+        Self {
+            result: vec![],
+        }
+    }
+
+    pub fn get_ref_result(&self) -> &[LoadOrderDiagnosticReport] {
+        &self.result
+    }
+
+    pub fn get_ref_mut_result(&mut self) -> &mut Vec<LoadOrderDiagnosticReport> {
+        &mut self.result
+    }
+}
+
+impl LoadOrderDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::FileOverridden];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::FileOverridden) => Some(Self::DuplicatedDBKeyAcrossPacks),
+            Some(Self::DuplicatedDBKeyAcrossPacks) => Some(Self::DuplicatedLocKeyAcrossPacks),
+            Some(Self::DuplicatedLocKeyAcrossPacks) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
+impl Display for LoadOrderDiagnosticReportType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(match self {
+            Self::FileOverridden => "FileOverridden",
+            Self::DuplicatedDBKeyAcrossPacks => "DuplicatedDBKeyAcrossPacks",
+            Self::DuplicatedLocKeyAcrossPacks => "DuplicatedLocKeyAcrossPacks",
+        }, f)
+    }
+}
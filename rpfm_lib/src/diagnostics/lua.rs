@@ -0,0 +1,880 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code related to the `Diagnostics`.
+
+This module contains the code needed to get a `Diagnostics` over lua scripts.
+
+It includes a small Lua 5.1 tokenizer and syntax checker. It doesn't build any tree out of the script,
+it only checks the script follows the grammar, and keeps the tokens around for the rest of the checks.
+!*/
+
+use lazy_static::lazy_static;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{HashMap, HashSet};
+use std::{fmt, fmt::Display};
+
+use super::DiagnosticLevel;
+
+/// Keywords of Lua 5.1.
+const KEYWORDS: [&str; 21] = [
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Symbols of Lua 5.1, longest first so we always match the longest one.
+const SYMBOLS: [&str; 26] = [
+    "...", "..", "==", "~=", "<=", ">=", "+", "-", "*", "/", "%", "^", "#",
+    "<", ">", "=", "(", ")", "{", "}", "[", "]", ";", ":", ",", ".",
+];
+
+/// Binary operators of Lua 5.1.
+const BINARY_OPERATORS: [&str; 15] = ["+", "-", "*", "/", "%", "^", "..", "==", "~=", "<", "<=", ">", ">=", "and", "or"];
+
+lazy_static! {
+
+    /// Functions of the CA scripting API, taken from the type definitions of Warhammer 2.
+    static ref CA_API: LuaApi = LuaApi::new(include_str!("../../../lua_types/ca_types_wh2.lua"));
+}
+
+/// Minimum amount of `_`-separated parts a string needs to have to be considered a DB key.
+const DB_KEY_MIN_PARTS: usize = 3;
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains the results of a diagnostics check over a lua script.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LuaDiagnostic {
+    path: Vec<String>,
+    result: Vec<LuaDiagnosticReport>
+}
+
+/// This struct defines an individual diagnostic result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LuaDiagnosticReport {
+
+    /// Position of the problem in the script, in "line, column" format. Both start at 1.
+    pub position: (u32, u32),
+    pub message: String,
+    pub report_type: LuaDiagnosticReportType,
+    pub level: DiagnosticLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LuaDiagnosticReportType {
+    SyntaxError,
+    UnknownApiFunction,
+    MissingDBKey,
+}
+
+/// This struct contains a syntax error found in a lua script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuaSyntaxError {
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+}
+
+/// This struct contains the keys of the DB tables, so the keys used in lua scripts can be checked against them.
+#[derive(Debug, Clone, Default)]
+pub struct LuaDBKeys {
+    keys: HashSet<String>,
+
+    /// Prefixes of the keys, like `wh2_main` or `3k_main`. Strings in scripts starting with one of them are considered DB keys.
+    prefixes: HashSet<String>,
+}
+
+/// This struct represents a tokenized lua script.
+#[derive(Debug, Clone)]
+pub struct LuaScript {
+    tokens: Vec<Token>,
+}
+
+/// This struct represents a token of a lua script.
+#[derive(Debug, Clone)]
+struct Token {
+    token_type: TokenType,
+    text: String,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenType {
+    Name,
+    Keyword,
+    String,
+    Number,
+    Symbol,
+    Eof,
+}
+
+/// This struct contains the classes, their functions, and the global objects of the CA scripting API.
+struct LuaApi {
+    classes: HashMap<String, HashSet<String>>,
+    globals: HashMap<String, String>,
+}
+
+/// This enum defines what kind of expression ends a suffixed expression, so we know if it can be assigned or called.
+#[derive(PartialEq)]
+enum ExpressionType {
+    Call,
+    Assignable,
+    Other,
+}
+
+/// This struct checks the syntax of the tokens of a lua script.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    index: usize,
+}
+
+/// Result type of the syntax checks.
+type ParserResult<T> = std::result::Result<T, LuaSyntaxError>;
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `LuaDiagnostic`.
+impl LuaDiagnostic {
+    pub fn new(path: &[String]) -> Self {
+        Self {
+            path: path.to_vec(),
+            result: vec![],
+        }
+    }
+
+    pub fn get_path(&self) -> &[String] {
+        &self.path
+    }
+
+    pub fn get_ref_result(&self) -> &[LuaDiagnosticReport] {
+        &self.result
+    }
+
+    pub fn get_ref_mut_result(&mut self) -> &mut Vec<LuaDiagnosticReport> {
+        &mut self.result
+    }
+}
+
+/// Implementation of `LuaDBKeys`.
+impl LuaDBKeys {
+
+    /// This function creates a new `LuaDBKeys` from the provided keys, finding the prefixes used by them.
+    pub fn new(keys: HashSet<String>) -> Self {
+        let prefixes = keys.iter()
+            .filter_map(|key| Self::get_prefix(key))
+            .map(|prefix| prefix.to_owned())
+            .collect();
+
+        Self {
+            keys,
+            prefixes,
+        }
+    }
+
+    /// This function returns if we have no keys to check against.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// This function returns if the provided key is one of our keys.
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// This function returns if the provided string looks like a DB key, because it uses the prefix of one of our keys.
+    pub fn is_key_candidate(&self, text: &str) -> bool {
+        matches!(Self::get_prefix(text), Some(prefix) if self.prefixes.contains(prefix))
+    }
+
+    /// This function returns the prefix of a key, which are its first two `_`-separated parts.
+    ///
+    /// Only lowercase keys with at least `DB_KEY_MIN_PARTS` parts have prefix, as shorter ones are too generic.
+    fn get_prefix(key: &str) -> Option<&str> {
+        let parts = key.split('_').collect::<Vec<&str>>();
+        let is_key = parts.len() >= DB_KEY_MIN_PARTS && parts.iter().all(|part| !part.is_empty() && part.chars().all(|character| character.is_ascii_lowercase() || character.is_ascii_digit()));
+        if is_key {
+            Some(&key[..parts[0].len() + 1 + parts[1].len()])
+        } else { None }
+    }
+}
+
+/// Implementation of `LuaScript`.
+impl LuaScript {
+
+    /// This function splits the provided lua code into tokens, failing if there is any invalid token on it.
+    pub fn tokenize(code: &str) -> ParserResult<Self> {
+        let chars = code.chars().collect::<Vec<char>>();
+        let mut tokens = vec![];
+        let mut index = 0;
+        let mut line = 1;
+        let mut column = 1;
+
+        // Helper to move forward, keeping track of lines and columns.
+        let advance = |index: &mut usize, line: &mut u32, column: &mut u32, count: usize| {
+            for _ in 0..count {
+                if chars.get(*index) == Some(&'\n') {
+                    *line += 1;
+                    *column = 1;
+                } else {
+                    *column += 1;
+                }
+                *index += 1;
+            }
+        };
+
+        let error = |line, column, message: &str| LuaSyntaxError { line, column, message: message.to_owned() };
+
+        while index < chars.len() {
+            let character = chars[index];
+            let (start_line, start_column) = (line, column);
+
+            if character.is_whitespace() {
+                advance(&mut index, &mut line, &mut column, 1);
+            }
+
+            // Comments, either long or short ones.
+            else if character == '-' && chars.get(index + 1) == Some(&'-') {
+                advance(&mut index, &mut line, &mut column, 2);
+                if let Some(level) = Self::long_bracket_level(&chars, index) {
+                    let length = Self::long_bracket_length(&chars, index, level).ok_or_else(|| error(start_line, start_column, "unfinished long comment"))?;
+                    advance(&mut index, &mut line, &mut column, length);
+                } else {
+                    while index < chars.len() && chars[index] != '\n' {
+                        advance(&mut index, &mut line, &mut column, 1);
+                    }
+                }
+            }
+
+            // Long strings.
+            else if let Some(level) = Self::long_bracket_level(&chars, index) {
+                let length = Self::long_bracket_length(&chars, index, level).ok_or_else(|| error(start_line, start_column, "unfinished long string"))?;
+                let text = chars[index + level + 2..index + length - level - 2].iter().collect();
+                tokens.push(Token { token_type: TokenType::String, text, line, column });
+                advance(&mut index, &mut line, &mut column, length);
+            }
+
+            // Normal strings. We keep the escape sequences as they are, as we don't need their real value.
+            else if character == '"' || character == '\'' {
+                let mut text = String::new();
+                advance(&mut index, &mut line, &mut column, 1);
+                loop {
+                    match chars.get(index) {
+                        Some(current) if *current == character => break,
+                        Some('\\') if index + 1 < chars.len() => {
+                            text.push('\\');
+                            text.push(chars[index + 1]);
+                            advance(&mut index, &mut line, &mut column, 2);
+                        }
+                        Some('\n') | None => return Err(error(start_line, start_column, "unfinished string")),
+                        Some(current) => {
+                            text.push(*current);
+                            advance(&mut index, &mut line, &mut column, 1);
+                        }
+                    }
+                }
+
+                advance(&mut index, &mut line, &mut column, 1);
+                tokens.push(Token { token_type: TokenType::String, text, line: start_line, column: start_column });
+            }
+
+            // Numbers. We take everything that may be part of a number, and then check if it's valid.
+            else if character.is_ascii_digit() || (character == '.' && chars.get(index + 1).map(|x| x.is_ascii_digit()).unwrap_or(false)) {
+                let mut text = String::new();
+                while let Some(current) = chars.get(index) {
+                    let is_exponent_sign = (*current == '+' || *current == '-') && !text.starts_with("0x") && !text.starts_with("0X") && (text.ends_with('e') || text.ends_with('E'));
+                    if current.is_ascii_alphanumeric() || *current == '.' || *current == '_' || is_exponent_sign {
+                        text.push(*current);
+                        advance(&mut index, &mut line, &mut column, 1);
+                    } else {
+                        break;
+                    }
+                }
+
+                let is_valid = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                    Some(hex) => !hex.is_empty() && hex.chars().all(|x| x.is_ascii_hexdigit()),
+                    None => text.parse::<f64>().is_ok(),
+                };
+
+                if !is_valid {
+                    return Err(error(start_line, start_column, &format!("malformed number near '{}'", text)));
+                }
+
+                tokens.push(Token { token_type: TokenType::Number, text, line: start_line, column: start_column });
+            }
+
+            // Names and keywords.
+            else if character.is_alphabetic() || character == '_' {
+                let mut text = String::new();
+                while let Some(current) = chars.get(index) {
+                    if current.is_alphanumeric() || *current == '_' {
+                        text.push(*current);
+                        advance(&mut index, &mut line, &mut column, 1);
+                    } else {
+                        break;
+                    }
+                }
+
+                let token_type = if KEYWORDS.contains(&&*text) { TokenType::Keyword } else { TokenType::Name };
+                tokens.push(Token { token_type, text, line: start_line, column: start_column });
+            }
+
+            // Anything else must be a symbol.
+            else {
+                let symbol = SYMBOLS.iter().find(|symbol| symbol.chars().enumerate().all(|(offset, x)| chars.get(index + offset) == Some(&x)))
+                    .ok_or_else(|| error(start_line, start_column, &format!("unexpected symbol near '{}'", character)))?;
+
+                tokens.push(Token { token_type: TokenType::Symbol, text: symbol.to_string(), line: start_line, column: start_column });
+                advance(&mut index, &mut line, &mut column, symbol.len());
+            }
+        }
+
+        tokens.push(Token { token_type: TokenType::Eof, text: "<eof>".to_owned(), line, column });
+        Ok(Self { tokens })
+    }
+
+    /// This function checks the syntax of the script, returning the first error found.
+    pub fn check_syntax(&self) -> ParserResult<()> {
+        let mut parser = Parser { tokens: &self.tokens, index: 0 };
+        parser.block()?;
+        parser.expect_eof()
+    }
+
+    /// This function returns the calls to functions of CA objects (like `cm:function()`) that are not part of their API.
+    ///
+    /// Functions the script adds to these objects are considered part of their API.
+    pub fn get_unknown_api_calls(&self) -> Vec<((u32, u32), String)> {
+        let tokens = &self.tokens;
+
+        // Objects redefined as locals are no longer CA objects.
+        let shadowed = tokens.windows(2)
+            .filter(|x| x[0].is_keyword("local") && x[1].token_type == TokenType::Name)
+            .map(|x| &*x[1].text)
+            .collect::<HashSet<&str>>();
+
+        // Functions defined over CA objects by the script, either as `function cm:name()` or `cm.name = `.
+        let defined = tokens.windows(4)
+            .enumerate()
+            .filter(|(_, x)| x[0].token_type == TokenType::Name && (x[1].is_symbol(".") || x[1].is_symbol(":")) && x[2].token_type == TokenType::Name)
+            .filter(|(index, x)| x[3].is_symbol("=") || (x[3].is_symbol("(") && *index > 0 && tokens[index - 1].is_keyword("function")))
+            .map(|(_, x)| (&*x[0].text, &*x[2].text))
+            .collect::<HashSet<(&str, &str)>>();
+
+        let mut unknown_calls = vec![];
+        for (index, window) in tokens.windows(4).enumerate() {
+            let (object, separator, function, next) = (&window[0], &window[1], &window[2], &window[3]);
+            if object.token_type != TokenType::Name || function.token_type != TokenType::Name || shadowed.contains(&*object.text) {
+                continue;
+            }
+
+            // Skip fields of other objects and function definitions.
+            if index > 0 && (tokens[index - 1].is_symbol(".") || tokens[index - 1].is_symbol(":") || tokens[index - 1].is_keyword("function")) {
+                continue;
+            }
+
+            let is_call = (separator.is_symbol(":") || separator.is_symbol(".")) && (next.is_symbol("(") || next.is_symbol("{") || next.token_type == TokenType::String);
+            if is_call {
+                if let Some(functions) = CA_API.globals.get(&object.text).and_then(|class| CA_API.classes.get(class)) {
+                    if !functions.contains(&function.text) && !defined.contains(&(&*object.text, &*function.text)) {
+                        unknown_calls.push(((function.line, function.column), format!("{}{}{}", object.text, separator.text, function.text)));
+                    }
+                }
+            }
+        }
+
+        unknown_calls
+    }
+
+    /// This function returns the string literals of the script that look like the provided DB keys.
+    pub fn get_db_key_candidates(&self, db_keys: &LuaDBKeys) -> Vec<((u32, u32), String)> {
+        self.tokens.iter()
+            .filter(|token| token.token_type == TokenType::String && db_keys.is_key_candidate(&token.text))
+            .map(|token| ((token.line, token.column), token.text.to_owned()))
+            .collect()
+    }
+
+    /// This function returns the level of the long bracket (`[==[`) starting at the provided index, if there is one.
+    fn long_bracket_level(chars: &[char], index: usize) -> Option<usize> {
+        if chars.get(index) != Some(&'[') {
+            return None;
+        }
+
+        let level = chars[index + 1..].iter().take_while(|x| **x == '=').count();
+        if chars.get(index + level + 1) == Some(&'[') { Some(level) } else { None }
+    }
+
+    /// This function returns the full length of the long bracket starting at the provided index, including the closing bracket.
+    fn long_bracket_length(chars: &[char], index: usize, level: usize) -> Option<usize> {
+        let mut closing = vec![']'];
+        closing.extend(vec!['='; level]);
+        closing.push(']');
+
+        chars[index + level + 2..].windows(closing.len())
+            .position(|x| x == closing)
+            .map(|position| position + level + 2 + closing.len())
+    }
+}
+
+/// Implementation of `Token`.
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.token_type == TokenType::Keyword && self.text == keyword
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        self.token_type == TokenType::Symbol && self.text == symbol
+    }
+}
+
+/// Implementation of `LuaApi`.
+impl LuaApi {
+
+    /// This function builds the API from the `--# assume` lines of a type definitions file.
+    fn new(types: &str) -> Self {
+        let mut classes: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut globals = HashMap::new();
+
+        for line in types.lines().filter_map(|line| line.trim().strip_prefix("--# assume ")) {
+            if let Some(class) = line.strip_prefix("global class ") {
+                classes.entry(class.trim().to_owned()).or_default();
+            } else if let Some(global) = line.strip_prefix("global ") {
+                if let Some((name, global_type)) = global.split_once(':') {
+                    globals.insert(name.trim().to_owned(), global_type.trim().to_owned());
+                }
+            } else if let Some((member, _)) = line.split_once(':') {
+                if let Some((class, function)) = member.split_once('.') {
+                    classes.entry(class.trim().to_owned()).or_default().insert(function.trim().to_owned());
+                }
+            }
+        }
+
+        // We only care about globals that are objects of a known class.
+        globals.retain(|_, global_type| classes.contains_key(global_type));
+        Self { classes, globals }
+    }
+}
+
+/// Implementation of `Parser`.
+///
+/// It follows the grammar of the Lua 5.1 manual, but without caring about operator precedence, as we don't need it for checking the syntax.
+impl<'a> Parser<'a> {
+
+    fn current(&self) -> &'a Token {
+        &self.tokens[self.index]
+    }
+
+    fn next(&mut self) {
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+    }
+
+    fn error_near(&self, message: &str) -> LuaSyntaxError {
+        let token = self.current();
+        LuaSyntaxError {
+            line: token.line,
+            column: token.column,
+            message: format!("{} near '{}'", message, token.text),
+        }
+    }
+
+    fn test_symbol(&mut self, symbol: &str) -> bool {
+        if self.current().is_symbol(symbol) {
+            self.next();
+            true
+        } else { false }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> ParserResult<()> {
+        if self.test_symbol(symbol) { Ok(()) } else { Err(self.error_near(&format!("'{}' expected", symbol))) }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParserResult<()> {
+        if self.current().is_keyword(keyword) {
+            self.next();
+            Ok(())
+        } else { Err(self.error_near(&format!("'{}' expected", keyword))) }
+    }
+
+    /// This function expects the closing part of a block or bracket, pointing to the opening part if it's not found.
+    fn expect_match(&mut self, closing: &str, opening: &str, line: u32) -> ParserResult<()> {
+        let token = self.current();
+        if (token.token_type == TokenType::Keyword || token.token_type == TokenType::Symbol) && token.text == closing {
+            self.next();
+            Ok(())
+        } else if token.line == line {
+            Err(self.error_near(&format!("'{}' expected", closing)))
+        } else {
+            Err(self.error_near(&format!("'{}' expected (to close '{}' at line {})", closing, opening, line)))
+        }
+    }
+
+    fn expect_name(&mut self) -> ParserResult<()> {
+        if self.current().token_type == TokenType::Name {
+            self.next();
+            Ok(())
+        } else { Err(self.error_near("<name> expected")) }
+    }
+
+    fn expect_eof(&self) -> ParserResult<()> {
+        if self.current().token_type == TokenType::Eof { Ok(()) } else { Err(self.error_near("'<eof>' expected")) }
+    }
+
+    fn block_follows(&self) -> bool {
+        let token = self.current();
+        token.token_type == TokenType::Eof || ["else", "elseif", "end", "until"].iter().any(|keyword| token.is_keyword(keyword))
+    }
+
+    fn block(&mut self) -> ParserResult<()> {
+        while !self.block_follows() {
+            if self.current().is_keyword("return") {
+                self.next();
+                if !self.block_follows() && !self.current().is_symbol(";") {
+                    self.expression_list()?;
+                }
+
+                // Return must be the last statement of a block.
+                self.test_symbol(";");
+                return Ok(());
+            }
+
+            self.statement()?;
+            self.test_symbol(";");
+        }
+
+        Ok(())
+    }
+
+    fn statement(&mut self) -> ParserResult<()> {
+        let token = self.current();
+        let line = token.line;
+        if token.token_type != TokenType::Keyword {
+            return self.expression_statement();
+        }
+
+        match &*token.text {
+            "if" => {
+                self.next();
+                self.expression()?;
+                self.expect_keyword("then")?;
+                self.block()?;
+
+                while self.current().is_keyword("elseif") {
+                    self.next();
+                    self.expression()?;
+                    self.expect_keyword("then")?;
+                    self.block()?;
+                }
+
+                if self.current().is_keyword("else") {
+                    self.next();
+                    self.block()?;
+                }
+
+                self.expect_match("end", "if", line)
+            }
+            "while" => {
+                self.next();
+                self.expression()?;
+                self.expect_keyword("do")?;
+                self.block()?;
+                self.expect_match("end", "while", line)
+            }
+            "do" => {
+                self.next();
+                self.block()?;
+                self.expect_match("end", "do", line)
+            }
+            "for" => {
+                self.next();
+                self.expect_name()?;
+                if self.test_symbol("=") {
+                    self.expression()?;
+                    self.expect_symbol(",")?;
+                    self.expression()?;
+                    if self.test_symbol(",") {
+                        self.expression()?;
+                    }
+                } else if self.current().is_symbol(",") || self.current().is_keyword("in") {
+                    while self.test_symbol(",") {
+                        self.expect_name()?;
+                    }
+                    self.expect_keyword("in")?;
+                    self.expression_list()?;
+                } else {
+                    return Err(self.error_near("'=' or 'in' expected"));
+                }
+
+                self.expect_keyword("do")?;
+                self.block()?;
+                self.expect_match("end", "for", line)
+            }
+            "repeat" => {
+                self.next();
+                self.block()?;
+                self.expect_match("until", "repeat", line)?;
+                self.expression()
+            }
+            "function" => {
+                self.next();
+                self.expect_name()?;
+                while self.test_symbol(".") {
+                    self.expect_name()?;
+                }
+                if self.test_symbol(":") {
+                    self.expect_name()?;
+                }
+
+                self.function_body(line)
+            }
+            "local" => {
+                self.next();
+                if self.current().is_keyword("function") {
+                    self.next();
+                    self.expect_name()?;
+                    self.function_body(line)
+                } else {
+                    self.expect_name()?;
+                    while self.test_symbol(",") {
+                        self.expect_name()?;
+                    }
+                    if self.test_symbol("=") {
+                        self.expression_list()?;
+                    }
+                    Ok(())
+                }
+            }
+            "break" => {
+                self.next();
+                Ok(())
+            }
+            _ => self.expression_statement(),
+        }
+    }
+
+    /// This function checks statements that start with an expression. They can only be assignments or function calls.
+    fn expression_statement(&mut self) -> ParserResult<()> {
+        let expression_type = self.suffixed_expression()?;
+        if self.current().is_symbol("=") || self.current().is_symbol(",") {
+            if expression_type != ExpressionType::Assignable {
+                return Err(self.error_near("syntax error"));
+            }
+
+            while self.test_symbol(",") {
+                if self.suffixed_expression()? != ExpressionType::Assignable {
+                    return Err(self.error_near("syntax error"));
+                }
+            }
+
+            self.expect_symbol("=")?;
+            self.expression_list()
+        } else if expression_type == ExpressionType::Call {
+            Ok(())
+        } else {
+            Err(self.error_near("syntax error"))
+        }
+    }
+
+    fn function_body(&mut self, line: u32) -> ParserResult<()> {
+        self.expect_symbol("(")?;
+        if !self.current().is_symbol(")") {
+            loop {
+                if self.test_symbol("...") {
+                    break;
+                }
+
+                self.expect_name()?;
+                if !self.test_symbol(",") {
+                    break;
+                }
+            }
+        }
+
+        self.expect_symbol(")")?;
+        self.block()?;
+        self.expect_match("end", "function", line)
+    }
+
+    fn expression_list(&mut self) -> ParserResult<()> {
+        self.expression()?;
+        while self.test_symbol(",") {
+            self.expression()?;
+        }
+        Ok(())
+    }
+
+    fn expression(&mut self) -> ParserResult<()> {
+        let token = self.current();
+        if token.is_keyword("not") || token.is_symbol("-") || token.is_symbol("#") {
+            self.next();
+            self.expression()?;
+        } else {
+            self.simple_expression()?;
+        }
+
+        let token = self.current();
+        if (token.token_type == TokenType::Symbol || token.token_type == TokenType::Keyword) && BINARY_OPERATORS.contains(&&*token.text) {
+            self.next();
+            self.expression()?;
+        }
+
+        Ok(())
+    }
+
+    fn simple_expression(&mut self) -> ParserResult<()> {
+        let token = self.current();
+        match token.token_type {
+            TokenType::Number | TokenType::String => self.next(),
+            TokenType::Keyword if ["nil", "true", "false"].contains(&&*token.text) => self.next(),
+            TokenType::Keyword if token.text == "function" => {
+                self.next();
+                self.function_body(token.line)?;
+            }
+            TokenType::Symbol if token.text == "..." => self.next(),
+            TokenType::Symbol if token.text == "{" => self.table_constructor()?,
+            _ => { self.suffixed_expression()?; },
+        }
+
+        Ok(())
+    }
+
+    fn suffixed_expression(&mut self) -> ParserResult<ExpressionType> {
+        let token = self.current();
+        let mut expression_type = match token.token_type {
+            TokenType::Name => {
+                self.next();
+                ExpressionType::Assignable
+            }
+            TokenType::Symbol if token.text == "(" => {
+                self.next();
+                self.expression()?;
+                self.expect_match(")", "(", token.line)?;
+                ExpressionType::Other
+            }
+            _ => return Err(self.error_near("unexpected symbol")),
+        };
+
+        loop {
+            let token = self.current();
+            match token.token_type {
+                TokenType::Symbol if token.text == "." => {
+                    self.next();
+                    self.expect_name()?;
+                    expression_type = ExpressionType::Assignable;
+                }
+                TokenType::Symbol if token.text == "[" => {
+                    self.next();
+                    self.expression()?;
+                    self.expect_symbol("]")?;
+                    expression_type = ExpressionType::Assignable;
+                }
+                TokenType::Symbol if token.text == ":" => {
+                    self.next();
+                    self.expect_name()?;
+                    self.function_arguments()?;
+                    expression_type = ExpressionType::Call;
+                }
+                TokenType::Symbol if token.text == "(" || token.text == "{" => {
+                    self.function_arguments()?;
+                    expression_type = ExpressionType::Call;
+                }
+                TokenType::String => {
+                    self.function_arguments()?;
+                    expression_type = ExpressionType::Call;
+                }
+                _ => return Ok(expression_type),
+            }
+        }
+    }
+
+    fn function_arguments(&mut self) -> ParserResult<()> {
+        let token = self.current();
+        match token.token_type {
+            TokenType::String => {
+                self.next();
+                Ok(())
+            }
+            TokenType::Symbol if token.text == "{" => self.table_constructor(),
+            TokenType::Symbol if token.text == "(" => {
+                self.next();
+                if !self.current().is_symbol(")") {
+                    self.expression_list()?;
+                }
+                self.expect_match(")", "(", token.line)
+            }
+            _ => Err(self.error_near("function arguments expected")),
+        }
+    }
+
+    fn table_constructor(&mut self) -> ParserResult<()> {
+        let line = self.current().line;
+        self.expect_symbol("{")?;
+        while !self.current().is_symbol("}") {
+            if self.current().token_type == TokenType::Name && self.tokens[self.index + 1].is_symbol("=") {
+                self.next();
+                self.next();
+                self.expression()?;
+            } else if self.test_symbol("[") {
+                self.expression()?;
+                self.expect_symbol("]")?;
+                self.expect_symbol("=")?;
+                self.expression()?;
+            } else {
+                self.expression()?;
+            }
+
+            if !self.test_symbol(",") && !self.test_symbol(";") {
+                break;
+            }
+        }
+
+        self.expect_match("}", "{", line)
+    }
+}
+
+impl LuaDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::SyntaxError];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::SyntaxError) => Some(Self::UnknownApiFunction),
+            Some(Self::UnknownApiFunction) => Some(Self::MissingDBKey),
+            Some(Self::MissingDBKey) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
+impl Display for LuaDiagnosticReportType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(match self {
+            Self::SyntaxError => "SyntaxError",
+            Self::UnknownApiFunction => "UnknownApiFunction",
+            Self::MissingDBKey => "MissingDBKey",
+        }, f)
+    }
+}
+
+impl Display for LuaSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
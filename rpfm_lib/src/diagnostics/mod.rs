@@ -26,7 +26,10 @@ use rayon::prelude::*;
 use unicase::UniCase;
 
 use std::{fmt, fmt::Display};
+
+use rpfm_error::{ErrorKind, Result};
 use std::cmp::Ordering;
+use std::str::FromStr;
 use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::DB;
@@ -34,23 +37,39 @@ use crate::dependencies::Dependencies;
 use crate::games::VanillaDBTableNameLogic;
 use crate::GAME_SELECTED;
 use crate::packfile::{PackFile, PathType};
-use crate::packedfile::{table::{DecodedData, DependencyData}, DecodedPackedFile, PackedFileType};
+use crate::packedfile::{table::{DecodedData, DependencyData, MergedTable}, DecodedPackedFile, PackedFileType};
+use crate::packedfile::anim::{self, Anim};
+use crate::packedfile::rigidmodel::RigidModel;
+use crate::packedfile::text::{Text, TextType};
 use crate::packfile::packedfile::{PackedFile, PackedFileInfo};
-use crate::schema::FieldType;
+use crate::schema::{Field, FieldType, Schema};
 use crate::SCHEMA;
 
 use self::anim_fragment::{AnimFragmentDiagnostic, AnimFragmentDiagnosticReport, AnimFragmentDiagnosticReportType};
 use self::config::{ConfigDiagnostic, ConfigDiagnosticReport, ConfigDiagnosticReportType};
 use self::dependency_manager::{DependencyManagerDiagnostic, DependencyManagerDiagnosticReport, DependencyManagerDiagnosticReportType};
+use self::lua::{LuaDBKeys, LuaDiagnostic, LuaDiagnosticReport, LuaDiagnosticReportType, LuaScript};
+use self::load_order::{LoadOrderDiagnostic, LoadOrderDiagnosticReport, LoadOrderDiagnosticReportType, VANILLA_PACK_NAME};
 use self::packfile::{PackFileDiagnostic, PackFileDiagnosticReport, PackFileDiagnosticReportType};
-use self::table::{TableDiagnostic, TableDiagnosticReport, TableDiagnosticReportType};
+use self::rigid_model::{RigidModelDiagnostic, RigidModelDiagnosticReport, RigidModelDiagnosticReportType};
+use self::rules::DiagnosticsRules;
+use self::table::{TableDiagnostic, TableDiagnosticFix, TableDiagnosticReport, TableDiagnosticReportType};
 
 pub mod anim_fragment;
 pub mod config;
 pub mod dependency_manager;
+pub mod fixes;
+pub mod load_order;
+pub mod lua;
+pub mod output;
 pub mod packfile;
+pub mod rigid_model;
+pub mod rules;
 pub mod table;
 
+#[cfg(test)]
+mod diagnostics_test;
+
 //-------------------------------------------------------------------------------//
 //                              Enums & Structs
 //-------------------------------------------------------------------------------//
@@ -67,13 +86,23 @@ pub enum DiagnosticType {
     PackFile(PackFileDiagnostic),
     DependencyManager(DependencyManagerDiagnostic),
     Config(ConfigDiagnostic),
+    LoadOrder(LoadOrderDiagnostic),
+    RigidModel(RigidModelDiagnostic),
+    Lua(LuaDiagnostic),
 }
 
 /// This enum defines the possible results for a result of a diagnostic check.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// They're sorted by severity, so they can be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DiagnosticLevel {
+    #[serde(alias = "info")]
     Info,
+
+    #[serde(alias = "warning")]
     Warning,
+
+    #[serde(alias = "error")]
     Error,
 }
 
@@ -97,6 +126,9 @@ impl DiagnosticType {
             Self::PackFile(_) => &[],
             Self::DependencyManager(diag) => diag.get_path(),
             Self::Config(_) => &[],
+            Self::LoadOrder(_) => &[],
+            Self::RigidModel(ref diag) => diag.get_path(),
+            Self::Lua(ref diag) => diag.get_path(),
         }
     }
 }
@@ -144,38 +176,22 @@ impl Diagnostics {
 
         // Logic here: we want to process the tables on batches containing all the tables of the same type, so we can check duplicates in different tables.
         // To do that, we have to sort/split the file list, the process that.
-        let packed_files = pack_file.get_ref_packed_files_by_types(&[PackedFileType::AnimFragment, PackedFileType::DB, PackedFileType::Loc], false);
-        let mut packed_files_split: BTreeMap<&str, Vec<&PackedFile>> = BTreeMap::new();
+        let packed_files = pack_file.get_ref_packed_files_by_types(&[PackedFileType::AnimFragment, PackedFileType::DB, PackedFileType::Loc, PackedFileType::RigidModel, PackedFileType::Text(TextType::Lua)], false);
+        let packed_files_split = Self::split_packed_files_in_batches(&packed_files);
 
-        for packed_file in &packed_files {
-            match packed_file.get_packed_file_type(false) {
-                PackedFileType::AnimFragment => {
-                    if let Some(table_set) = packed_files_split.get_mut("anim_fragments") {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert("anim_fragments", vec![packed_file]);
-                    }
-                },
-                PackedFileType::DB => {
-                    if let Some(table_set) = packed_files_split.get_mut(&*packed_file.get_path()[1]) {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert(&packed_file.get_path()[1], vec![packed_file]);
-                    }
-                },
-                PackedFileType::Loc => {
-                    if let Some(table_set) = packed_files_split.get_mut("locs") {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert("locs", vec![packed_file]);
-                    }
-                },
-                _ => {},
-            }
-        }
+        // AnimFragments need the skeleton of each anim they use, so index the anims of the PackFile once instead of searching them for each cell.
+        let local_anims = if packed_files_split.contains_key("anim_fragments") {
+            Self::get_local_anims(pack_file)
+        } else { HashMap::new() };
 
         if let Some(ref schema) = *SCHEMA.read().unwrap() {
 
+            // Lua scripts need the keys of all the tables, so only get them if there are scripts to check.
+            // If we don't have the vanilla data, we don't get them, as otherwise any vanilla key would be reported as missing.
+            let lua_db_keys = if packed_files_split.contains_key("lua_scripts") && dependencies.game_has_vanilla_data_loaded(false) {
+                Self::get_lua_db_keys(pack_file, schema, &vanilla_dependencies, asskit_dependencies)
+            } else { LuaDBKeys::default() };
+
             // Getting this here speeds up a lot path-checking later.
             let local_packed_file_path_list = pack_file.get_packed_files_all_paths_as_string();
             let local_folder_path_list = pack_file.get_folder_all_paths_as_string();
@@ -186,6 +202,7 @@ impl Diagnostics {
                 let mut diagnostics = Vec::with_capacity(packed_files.len());
                 let mut data_prev: BTreeMap<String, HashMap<String, Vec<(i32, i32)>>> = BTreeMap::new();
                 let mut dependency_data_for_table = BTreeMap::new();
+                let mut anim_skeletons = HashMap::new();
 
                 for packed_file in packed_files {
 
@@ -228,7 +245,7 @@ impl Diagnostics {
 
                     let diagnostic = match packed_file.get_packed_file_type(false) {
                         PackedFileType::AnimFragment => if let Ok(decoded) = packed_file.decode_return_ref_no_cache_no_locks(schema) {
-                            Self::check_anim_fragment(&decoded, packed_file.get_path(), dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &local_packed_file_path_list, &local_folder_path_list)
+                            Self::check_anim_fragment(&decoded, packed_file.get_path(), &local_anims, &mut anim_skeletons, dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &local_packed_file_path_list, &local_folder_path_list)
                         } else { None }
                         PackedFileType::DB => {
 
@@ -249,9 +266,11 @@ impl Diagnostics {
                                 }
                             }
 
-                            Self::check_db(packed_file.get_ref_decoded(), packed_file.get_path(), dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev, &local_packed_file_path_list, &local_folder_path_list, &dependency_data_for_table)
+                            Self::check_db(packed_file, dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev, &local_packed_file_path_list, &local_folder_path_list, &dependency_data_for_table)
                         },
                         PackedFileType::Loc => Self::check_loc(packed_file.get_ref_decoded(), packed_file.get_path(), &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev),
+                        PackedFileType::RigidModel => Self::check_rigid_model(packed_file, dependencies, &ignored_diagnostics, &local_packed_file_path_list, &local_folder_path_list),
+                        PackedFileType::Text(TextType::Lua) => Self::check_lua(packed_file, &ignored_diagnostics, &lua_db_keys),
                         _ => None,
                     };
 
@@ -283,6 +302,7 @@ impl Diagnostics {
                                                 message: format!("Duplicated combined keys: {}.", key),
                                                 report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
                                                 level: DiagnosticLevel::Error,
+                                                fix: None,
                                             });
                                         }
 
@@ -305,6 +325,7 @@ impl Diagnostics {
                                                 message: format!("Duplicated combined keys: {}.", key),
                                                 report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
                                                 level: DiagnosticLevel::Error,
+                                                fix: None,
                                             });
                                         }
 
@@ -338,6 +359,14 @@ impl Diagnostics {
             }).flatten().collect();
         }
 
+        // Loc completeness needs all the DB and Loc tables at once, so it cannot be checked in batches.
+        // If we don't have dependency data, skip it, as otherwise any loc from the game files would be reported as missing.
+        if !vanilla_dependencies.is_empty() || !asskit_dependencies.is_empty() {
+            let loc_dependencies = dependencies.get_db_and_loc_tables_from_cache(false, true, true, true).unwrap_or_default();
+            let diagnostics = Self::check_loc_completeness(pack_file, &vanilla_dependencies, &loc_dependencies, asskit_dependencies, &files_to_ignore);
+            self.merge_table_diagnostics(diagnostics);
+        }
+
         if let Some(diagnostics) = Self::check_dependency_manager(pack_file) {
             self.0.push(diagnostics);
         }
@@ -346,6 +375,7 @@ impl Diagnostics {
             self.0.push(diagnostics);
         }
 
+        self.apply_pack_file_rules(pack_file);
         self.get_ref_mut_diagnostics().sort_by(|a, b| {
             if !a.get_path().is_empty() && !b.get_path().is_empty() {
                 a.get_path().cmp(b.get_path())
@@ -361,8 +391,7 @@ impl Diagnostics {
 
     /// This function takes care of checking the db tables of your mod for errors.
     fn check_db(
-        packed_file: &DecodedPackedFile,
-        path: &[String],
+        packed_file: &PackedFile,
         dependencies: &Dependencies,
         ignored_fields: &[String],
         ignored_diagnostics: &[String],
@@ -372,7 +401,8 @@ impl Diagnostics {
         local_folder_list: &HashSet<UniCase<String>>,
         dependency_data: &BTreeMap<i32, DependencyData>
     ) ->Option<DiagnosticType> {
-        if let DecodedPackedFile::DB(table) = packed_file {
+        if let DecodedPackedFile::DB(table) = packed_file.get_ref_decoded() {
+            let path = packed_file.get_path();
             let mut diagnostic = TableDiagnostic::new(path);
 
             // Check all the columns with reference data.
@@ -389,6 +419,7 @@ impl Diagnostics {
                         message: "Possibly outdated table.".to_owned(),
                         report_type: TableDiagnosticReportType::OutdatedTable,
                         level: DiagnosticLevel::Error,
+                        fix: Some(TableDiagnosticFix::UpdateTable),
                     });
                 }
             }
@@ -401,6 +432,7 @@ impl Diagnostics {
                         message: "Banned table.".to_owned(),
                         report_type: TableDiagnosticReportType::BannedTable,
                         level: DiagnosticLevel::Error,
+                        fix: None,
                     });
                 }
             }
@@ -424,6 +456,7 @@ impl Diagnostics {
                             message: "Table name ends in number.".to_owned(),
                             report_type: TableDiagnosticReportType::TableNameEndsInNumber,
                             level: DiagnosticLevel::Error,
+                            fix: Some(TableDiagnosticFix::AddUnderscoreToName),
                         });
                     }
                 }
@@ -435,6 +468,7 @@ impl Diagnostics {
                             message: "Table name contains spaces.".to_owned(),
                             report_type: TableDiagnosticReportType::TableNameHasSpace,
                             level: DiagnosticLevel::Error,
+                            fix: Some(TableDiagnosticFix::ReplaceSpacesInName),
                         });
                     }
                 }
@@ -448,6 +482,7 @@ impl Diagnostics {
                                     message: "Table is datacoring.".to_owned(),
                                     report_type: TableDiagnosticReportType::TableIsDataCoring,
                                     level: DiagnosticLevel::Warning,
+                                    fix: None,
                                 });
                             }
                         }
@@ -459,6 +494,7 @@ impl Diagnostics {
                                     message: "Table is datacoring.".to_owned(),
                                     report_type: TableDiagnosticReportType::TableIsDataCoring,
                                     level: DiagnosticLevel::Warning,
+                                    fix: None,
                                 });
                             }
                         }
@@ -539,6 +575,7 @@ impl Diagnostics {
                                         message: format!("Path not found: {}.", paths.iter().join(" || ")),
                                         report_type: TableDiagnosticReportType::FieldWithPathNotFound,
                                         level: DiagnosticLevel::Warning,
+                                        fix: None,
                                     });
                                 }
                             }
@@ -578,6 +615,7 @@ impl Diagnostics {
                                                 message: format!("Invalid reference \"{}\" in column \"{}\".", &cell_data, field.get_name()),
                                                 report_type: TableDiagnosticReportType::InvalidReference,
                                                 level: DiagnosticLevel::Error,
+                                                fix: None,
                                             });
                                         }
                                     }
@@ -607,6 +645,7 @@ impl Diagnostics {
                                 message: format!("Empty key for column \"{}\".", field.get_name()),
                                 report_type: TableDiagnosticReportType::EmptyKeyField,
                                 level: DiagnosticLevel::Warning,
+                                fix: None,
                             });
                         }
                     }
@@ -618,6 +657,7 @@ impl Diagnostics {
                                 message: format!("Empty value for column \"{}\".", field.get_name()),
                                 report_type: TableDiagnosticReportType::ValueCannotBeEmpty,
                                 level: DiagnosticLevel::Error,
+                                fix: None,
                             });
                         }
                     }
@@ -634,6 +674,7 @@ impl Diagnostics {
                             message: "Empty row.".to_string(),
                             report_type: TableDiagnosticReportType::EmptyRow,
                             level: DiagnosticLevel::Error,
+                            fix: Some(TableDiagnosticFix::RemoveRows),
                         });
                     }
                 }
@@ -645,6 +686,7 @@ impl Diagnostics {
                             message: "Empty key fields.".to_string(),
                             report_type: TableDiagnosticReportType::EmptyKeyFields,
                             level: DiagnosticLevel::Warning,
+                            fix: None,
                         });
                     }
                 }
@@ -663,6 +705,7 @@ impl Diagnostics {
                                     message: format!("Duplicated combined keys: {}.", &combined_keys),
                                     report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
                                     level: DiagnosticLevel::Error,
+                                    fix: None,
                                 });
 
                                 duplicated_combined_keys_already_marked.push(old_pos.0);
@@ -675,6 +718,7 @@ impl Diagnostics {
                                     message: format!("Duplicated combined keys: {}.", &combined_keys),
                                     report_type: TableDiagnosticReportType::DuplicatedCombinedKeys,
                                     level: DiagnosticLevel::Error,
+                                    fix: None,
                                 });
 
                                 duplicated_combined_keys_already_marked.push(row as i32);
@@ -692,6 +736,7 @@ impl Diagnostics {
                         message: format!("No reference table found for column \"{}\".", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
                         report_type: TableDiagnosticReportType::NoReferenceTableFound,
                         level: DiagnosticLevel::Info,
+                        fix: None,
                     });
                 }
             }
@@ -703,6 +748,7 @@ impl Diagnostics {
                             message: format!("No reference column found in referenced table for column \"{}\". Did you forgot to generate the Dependencies Cache, or did you generated it before installing the Assembly kit?", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
                             report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundNoPak,
                             level: DiagnosticLevel::Warning,
+                            fix: None,
                         });
                     }
                 }
@@ -712,10 +758,20 @@ impl Diagnostics {
                         message: format!("No reference column found in referenced table for column \"{}\". Maybe a problem with the schema?", table.get_ref_definition().get_fields_processed()[*column as usize].get_name()),
                         report_type: TableDiagnosticReportType::NoReferenceTableNorColumnFoundPak,
                         level: DiagnosticLevel::Info,
+                        fix: None,
                     });
                 }
             }
 
+            // Check the values against the enum, bitwise and range metadata of their fields.
+            // The raw data is only needed for bitwise fields, and only if it still matches the decoded table.
+            let raw_data = if table.get_ref_definition().get_ref_fields().iter().any(|field| field.get_is_bitwise() > 1) {
+                packed_file.get_raw_data().ok()
+                    .filter(|raw_data| matches!(DB::read_header(raw_data), Ok((_, _, _, entry_count, _)) if entry_count as usize == table.get_ref_table_data().len()))
+            } else { None };
+
+            diagnostic.get_ref_mut_result().append(&mut Self::check_db_values(table, raw_data.as_deref(), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields));
+
             // Add this table's keys to the previous list, so they can be queried for for duplicate checks on other tables of the same type.
             previous_data.insert(path.join("/"), keys);
 
@@ -725,10 +781,121 @@ impl Diagnostics {
         } else { None }
     }
 
-    /// This function takes care of checking the loc tables of your mod for errors.
+    /// This function checks the values of the cells of a DB table against the metadata of their fields:
+    /// enum values, bitwise flags and the min/max values set through schema patches.
+    ///
+    /// The undeclared bits of bitwise fields are lost when decoding, so they're only checked if the raw data of the table is provided.
+    pub fn check_db_values(
+        table: &DB,
+        raw_data: Option<&[u8]>,
+        ignored_fields: &[String],
+        ignored_diagnostics: &[String],
+        ignored_diagnostics_for_fields: &HashMap<String, Vec<String>>,
+    ) -> Vec<TableDiagnosticReport> {
+        let mut results = vec![];
+        let table_name = table.get_ref_table_name();
+        let fields_processed = table.get_ref_definition().get_fields_processed();
+
+        // Get the bounds and the suggested enum values once per table, as they're behind the schema patches lock.
+        let bounds = fields_processed.iter()
+            .map(|field| (field.get_min_value(Some(table_name)), field.get_max_value(Some(table_name))))
+            .collect::<Vec<(Option<f64>, Option<f64>)>>();
+
+        let enum_suggestions = fields_processed.iter()
+            .map(|field| match field.get_default_value(Some(table_name)) {
+                Some(default_value) if field.get_enum_values().values().any(|name| name.to_lowercase() == default_value.to_lowercase()) => Some(default_value),
+                _ => field.get_enum_values().values().next().cloned(),
+            })
+            .collect::<Vec<Option<String>>>();
+
+        for (row, cells) in table.get_ref_table_data().iter().enumerate() {
+            for (column, field) in fields_processed.iter().enumerate() {
+                let cell = &cells[column];
+
+                // Enum checks. Values not in the enum are decoded as their number.
+                if field.get_is_bitwise() <= 1 && !field.get_enum_values().is_empty() && !Self::ignore_diagnostic(Some(field.get_name()), Some("InvalidEnumValue"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
+                    let cell_data = cell.data_to_string();
+                    if !field.get_enum_values().values().any(|name| name.to_lowercase() == cell_data.to_lowercase()) {
+                        results.push(TableDiagnosticReport {
+                            cells_affected: vec![(row as i32, column as i32)],
+                            message: format!("Invalid enum value \"{}\" in column \"{}\". Valid values are: {}.", cell_data, field.get_name(), field.get_enum_values().values().join(", ")),
+                            report_type: TableDiagnosticReportType::InvalidEnumValue,
+                            level: DiagnosticLevel::Error,
+                            fix: enum_suggestions[column].clone().map(TableDiagnosticFix::ReplaceValue),
+                        });
+                    }
+                }
+
+                // Range checks.
+                let (min_value, max_value) = bounds[column];
+                if (min_value.is_some() || max_value.is_some()) && !Self::ignore_diagnostic(Some(field.get_name()), Some("ValueOutOfRange"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
+                    let (value, is_integer) = match cell {
+                        DecodedData::I16(value) => (*value as f64, true),
+                        DecodedData::I32(value) => (*value as f64, true),
+                        DecodedData::I64(value) => (*value as f64, true),
+                        DecodedData::F32(value) => (*value as f64, false),
+                        DecodedData::F64(value) => (*value, false),
+                        _ => continue,
+                    };
+
+                    let clamped_value = match (min_value, max_value) {
+                        (Some(min_value), _) if value < min_value => if is_integer { min_value.ceil() } else { min_value },
+                        (_, Some(max_value)) if value > max_value => if is_integer { max_value.floor() } else { max_value },
+                        _ => continue,
+                    };
+
+                    let range = match (min_value, max_value) {
+                        (Some(min_value), Some(max_value)) => format!("between {} and {}", min_value, max_value),
+                        (Some(min_value), None) => format!("equal or greater than {}", min_value),
+                        (None, _) => format!("equal or lower than {}", max_value.unwrap_or_default()),
+                    };
+
+                    results.push(TableDiagnosticReport {
+                        cells_affected: vec![(row as i32, column as i32)],
+                        message: format!("Value \"{}\" in column \"{}\" is out of range. It must be {}.", cell.data_to_string(), field.get_name(), range),
+                        report_type: TableDiagnosticReportType::ValueOutOfRange,
+                        level: DiagnosticLevel::Warning,
+                        fix: Some(TableDiagnosticFix::ReplaceValue(clamped_value.to_string())),
+                    });
+                }
+            }
+        }
+
+        // Bitwise checks. These are reported in the column of the first flag of the field.
+        if let Some(raw_data) = raw_data {
+            if let Ok(undeclared_bits) = DB::get_undeclared_bitwise_bits(raw_data, table.get_ref_definition()) {
+                for (row, field_index, bits) in undeclared_bits {
+                    let field = &table.get_ref_definition().get_ref_fields()[field_index];
+                    if !Self::ignore_diagnostic(Some(field.get_name()), Some("InvalidBitwiseValue"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
+                        let first_flag_name = format!("{}_1", field.get_name());
+                        if let Some(column) = fields_processed.iter().position(|field| field.get_name() == first_flag_name) {
+                            results.push(TableDiagnosticReport {
+                                cells_affected: vec![(row as i32, column as i32)],
+                                message: format!("Bits set outside the {} declared flags of column \"{}\": {:#b}.", field.get_is_bitwise(), field.get_name(), bits),
+                                report_type: TableDiagnosticReportType::InvalidBitwiseValue,
+                                level: DiagnosticLevel::Warning,
+                                fix: Some(TableDiagnosticFix::ClearUndeclaredBits),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// This function takes care of checking the anim fragments of your mod for errors.
+    ///
+    /// The skeletons of the anims used are searched in `local_anims` and the dependencies, and cached in `anim_skeletons`.
+    ///
+    /// AnimTables are not checked here, as they only reference fragments by name, not anims. The anims they end up using
+    /// are the ones of their fragments, which are checked here.
     fn check_anim_fragment(
         packed_file: &DecodedPackedFile,
         path: &[String],
+        local_anims: &HashMap<UniCase<String>, &PackedFile>,
+        anim_skeletons: &mut HashMap<UniCase<String>, Option<String>>,
         dependencies: &Dependencies,
         ignored_fields: &[String],
         ignored_diagnostics: &[String],
@@ -738,19 +905,42 @@ impl Diagnostics {
     ) ->Option<DiagnosticType> {
         if let DecodedPackedFile::AnimFragment(table) = packed_file {
             let mut diagnostic = AnimFragmentDiagnostic::new(path);
+            let check_skeletons = !Self::ignore_diagnostic(None, Some("AnimSkeletonMismatch"), &[], ignored_diagnostics, &HashMap::new());
 
             // Check inside the of the table in the column [3].
             for (row, _) in table.get_ref_table_data().iter().enumerate() {
                 if let DecodedData::SequenceU32(ref data) = table.get_ref_table_data()[row][3] {
+                    let fragment_skeleton = Self::get_skeleton_name(&table.get_ref_table_data()[row], &table.get_ref_definition().get_fields_processed());
                     let fields_processed = data.get_ref_definition().get_fields_processed();
                     for (row, cells) in data.get_ref_table_data().iter().enumerate() {
+
+                        // Entries may use their own skeleton. If not, they use the one of the fragment.
+                        let skeleton = Self::get_skeleton_name(cells, &fields_processed).or(fragment_skeleton);
                         for (column, field) in fields_processed.iter().enumerate() {
 
+                            // Anims must be made for the skeleton they're used with.
+                            if check_skeletons && field.get_is_filename() {
+                                if let (Some(skeleton), DecodedData::StringU8(cell_data)) = (skeleton, &cells[column]) {
+                                    let anim_path = cell_data.replace('\\', "/");
+                                    if anim_path.to_lowercase().ends_with(anim::EXTENSION) {
+                                        if let Some(anim_skeleton) = Self::get_anim_skeleton_name(&anim_path, local_anims, anim_skeletons, dependencies) {
+                                            if !anim_skeleton.eq_ignore_ascii_case(skeleton) {
+                                                diagnostic.get_ref_mut_result().push(AnimFragmentDiagnosticReport {
+                                                    cells_affected: vec![(row as i32, column as i32)],
+                                                    message: format!("Anim {} is for the skeleton {}, but it's used with the skeleton {}.", anim_path, anim_skeleton, skeleton),
+                                                    report_type: AnimFragmentDiagnosticReportType::AnimSkeletonMismatch,
+                                                    level: DiagnosticLevel::Warning,
+                                                });
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+
                             if !Self::ignore_diagnostic(Some(field.get_name()), Some("FieldWithPathNotFound"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
                                 if let DecodedData::StringU8(cell_data) = &cells[column] {
                                     if !cell_data.is_empty() {
                                         if fields_processed[column].get_is_filename() {
-                                            let mut path_found = false;
                                             let mut path = cell_data.replace('\\', "/");
 
                                             // If it's a folder, remove the trailing /.
@@ -758,36 +948,7 @@ impl Diagnostics {
                                                 path.pop();
                                             }
 
-                                            let unicased = UniCase::new(path.to_owned());
-                                            if local_path_list.contains(&unicased) {
-                                                path_found = true;
-                                            }
-
-                                            if !path_found && local_folder_list.contains(&unicased) {
-                                                path_found = true;
-                                            }
-
-                                            if !path_found && dependencies.file_exists_on_parent_files(&unicased, true) {
-                                                path_found = true;
-                                            }
-
-                                            if !path_found && dependencies.folder_exists_on_parent_files(&unicased, true) {
-                                                path_found = true;
-                                            }
-
-                                            if !path_found && dependencies.file_exists_on_game_files(&unicased, true) {
-                                                path_found = true;
-                                            }
-
-                                            if !path_found && dependencies.folder_exists_on_game_files(&unicased, true) {
-                                                path_found = true;
-                                            }
-
-                                            if path_found {
-                                                continue;
-                                            }
-
-                                            if !path_found {
+                                            if !Self::path_exists(&path, dependencies, local_path_list, local_folder_list) {
                                                 diagnostic.get_ref_mut_result().push(AnimFragmentDiagnosticReport {
                                                     cells_affected: vec![(row as i32, column as i32)],
                                                     message: format!("Path not found: {}.", path),
@@ -810,6 +971,121 @@ impl Diagnostics {
         } else { None }
     }
 
+    /// This function takes care of checking the rigidmodels of your mod for errors.
+    fn check_rigid_model(
+        packed_file: &PackedFile,
+        dependencies: &Dependencies,
+        ignored_diagnostics: &[String],
+        local_path_list: &HashSet<UniCase<String>>,
+        local_folder_list: &HashSet<UniCase<String>>,
+    ) -> Option<DiagnosticType> {
+
+        // Rigidmodels may not be decoded on load, so if they're not in memory we only read their texture paths from their data.
+        let texture_paths = match packed_file.get_ref_decoded() {
+
+            #[cfg(feature = "support_rigidmodel")]
+            DecodedPackedFile::RigidModel(rigid_model) if rigid_model.is_decoded() => rigid_model.get_texture_paths().into_iter()
+                .map(|(lod, mesh, path)| (lod, mesh, path.to_owned()))
+                .collect(),
+            _ => RigidModel::read_texture_paths(&packed_file.get_raw_data().ok()?).ok()?,
+        };
+
+        let mut diagnostic = RigidModelDiagnostic::new(packed_file.get_path());
+        if !Self::ignore_diagnostic(None, Some("TexturePathNotFound"), &[], ignored_diagnostics, &HashMap::new()) {
+
+            // Meshes tend to share textures, so we only report each missing texture once.
+            let mut missing_paths: BTreeMap<String, Vec<(i32, i32)>> = BTreeMap::new();
+            for (lod, mesh, path) in texture_paths {
+                let path = path.replace('\\', "/");
+                if !Self::path_exists(&path, dependencies, local_path_list, local_folder_list) {
+                    missing_paths.entry(path).or_default().push((lod as i32, mesh as i32));
+                }
+            }
+
+            for (path, meshes_affected) in missing_paths {
+                diagnostic.get_ref_mut_result().push(RigidModelDiagnosticReport {
+                    meshes_affected,
+                    message: format!("Texture not found: {}.", path),
+                    report_type: RigidModelDiagnosticReportType::TexturePathNotFound,
+                    level: DiagnosticLevel::Warning,
+                });
+            }
+        }
+
+        if !diagnostic.get_ref_result().is_empty() {
+            Some(DiagnosticType::RigidModel(diagnostic))
+        } else { None }
+    }
+
+    /// This function takes care of checking the lua scripts of your mod for errors.
+    ///
+    /// If `db_keys` is empty, we don't have data to check the keys against, so that check is skipped.
+    fn check_lua(
+        packed_file: &PackedFile,
+        ignored_diagnostics: &[String],
+        db_keys: &LuaDBKeys,
+    ) -> Option<DiagnosticType> {
+
+        // Scripts may not be decoded on load, so if they're not in memory we decode them from their data.
+        let decoded;
+        let text = match packed_file.get_ref_decoded() {
+            DecodedPackedFile::Text(text) => text,
+            _ => {
+                decoded = Text::read(&packed_file.get_raw_data().ok()?).ok()?;
+                &decoded
+            }
+        };
+
+        let mut diagnostic = LuaDiagnostic::new(packed_file.get_path());
+        let script = LuaScript::tokenize(text.get_ref_contents());
+        let syntax_error = match script {
+            Ok(ref script) => script.check_syntax().err(),
+            Err(ref error) => Some(error.clone()),
+        };
+
+        if let Some(error) = syntax_error {
+            if !Self::ignore_diagnostic(None, Some("SyntaxError"), &[], ignored_diagnostics, &HashMap::new()) {
+                diagnostic.get_ref_mut_result().push(LuaDiagnosticReport {
+                    position: (error.line, error.column),
+                    message: format!("Syntax error: {}.", error.message),
+                    report_type: LuaDiagnosticReportType::SyntaxError,
+                    level: DiagnosticLevel::Error,
+                });
+            }
+        }
+
+        // The rest of the checks only need the tokens, so we can do them even if the syntax is broken.
+        if let Ok(script) = script {
+            if !Self::ignore_diagnostic(None, Some("UnknownApiFunction"), &[], ignored_diagnostics, &HashMap::new()) {
+                for (position, function) in script.get_unknown_api_calls() {
+                    diagnostic.get_ref_mut_result().push(LuaDiagnosticReport {
+                        position,
+                        message: format!("Unknown function: {}.", function),
+                        report_type: LuaDiagnosticReportType::UnknownApiFunction,
+                        level: DiagnosticLevel::Warning,
+                    });
+                }
+            }
+
+            if !db_keys.is_empty() && !Self::ignore_diagnostic(None, Some("MissingDBKey"), &[], ignored_diagnostics, &HashMap::new()) {
+                for (position, key) in script.get_db_key_candidates(db_keys) {
+                    if !db_keys.contains(&key) {
+                        diagnostic.get_ref_mut_result().push(LuaDiagnosticReport {
+                            position,
+                            message: format!("Key not found in any table: {}.", key),
+                            report_type: LuaDiagnosticReportType::MissingDBKey,
+                            level: DiagnosticLevel::Warning,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !diagnostic.get_ref_result().is_empty() {
+            Some(DiagnosticType::Lua(diagnostic))
+        } else { None }
+    }
+
     /// This function takes care of checking the loc tables of your mod for errors.
     fn check_loc(
         packed_file: &DecodedPackedFile,
@@ -840,6 +1116,7 @@ impl Diagnostics {
                             message: "Invalid localisation key.".to_string(),
                             report_type: TableDiagnosticReportType::InvalidLocKey,
                             level: DiagnosticLevel::Error,
+                            fix: None,
                         });
                     }
                 }
@@ -854,6 +1131,7 @@ impl Diagnostics {
                             message: "Empty row.".to_string(),
                             report_type: TableDiagnosticReportType::EmptyRow,
                             level: DiagnosticLevel::Warning,
+                            fix: Some(TableDiagnosticFix::RemoveRows),
                         });
                     }
                 }
@@ -865,6 +1143,7 @@ impl Diagnostics {
                             message: "Empty key.".to_string(),
                             report_type: TableDiagnosticReportType::EmptyKeyField,
                             level: DiagnosticLevel::Warning,
+                            fix: None,
                         });
                     }
                 }
@@ -877,6 +1156,7 @@ impl Diagnostics {
                             message: "Invalid line jump/tabulation detected in loc entry. Use \\\\n or \\\\t instead.".to_string(),
                             report_type: TableDiagnosticReportType::InvalidEscape,
                             level: DiagnosticLevel::Warning,
+                            fix: Some(TableDiagnosticFix::EscapeText),
                         });
                     }
                 }
@@ -898,18 +1178,20 @@ impl Diagnostics {
                                     message: format!("Duplicated row: {}.", &combined_keys),
                                     report_type: TableDiagnosticReportType::DuplicatedRow,
                                     level: DiagnosticLevel::Warning,
+                                    fix: None,
                                 });
 
                                 duplicated_rows_already_marked.push(old_pos.0);
                             }
 
-                            // Mark current row, if not yet marked.
+                            // Mark current row, if not yet marked. Only this one can be removed, as the previous one is the first with these values.
                             if !duplicated_rows_already_marked.contains(&(row as i32)) {
                                 diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
                                     cells_affected: row_keys.keys().map(|column| (row as i32, *column)).collect::<Vec<(i32, i32)>>(),
                                     message: format!("Duplicated row: {}.", &combined_keys),
                                     report_type: TableDiagnosticReportType::DuplicatedRow,
                                     level: DiagnosticLevel::Warning,
+                                    fix: Some(TableDiagnosticFix::RemoveRows),
                                 });
 
                                 duplicated_rows_already_marked.push(row as i32);
@@ -928,6 +1210,259 @@ impl Diagnostics {
         } else { None }
     }
 
+    /// This function checks the localisable fields of the DB tables of the PackFile, and the Loc tables of the PackFile, for missing and orphan loc data.
+    ///
+    /// - Missing loc data: a localisable field of a DB row of the PackFile without a loc entry in the PackFile or its dependencies.
+    /// - Orphan loc keys: a loc entry of the PackFile that belongs to a localisable field of a DB table, but doesn't match any row of that table in the PackFile or its dependencies.
+    ///
+    /// The DB tables of the dependencies are expected in `vanilla_dependencies`, and their Loc tables in `loc_dependencies`.
+    pub fn check_loc_completeness(
+        pack_file: &PackFile,
+        vanilla_dependencies: &[PackedFile],
+        loc_dependencies: &[PackedFile],
+        asskit_dependencies: &[DB],
+        files_to_ignore: &Option<Vec<(Vec<String>, Vec<String>, Vec<String>)>>,
+    ) -> Vec<DiagnosticType> {
+        let mut diagnostics = vec![];
+
+        let local_db_tables = pack_file.get_ref_packed_files_by_type(PackedFileType::DB, false);
+        let local_loc_tables = pack_file.get_ref_packed_files_by_type(PackedFileType::Loc, false);
+        let vanilla_tables = vanilla_dependencies.iter()
+            .chain(loc_dependencies.iter())
+            .filter_map(|packed_file| packed_file.get_decoded_from_memory().ok())
+            .collect::<Vec<&DecodedPackedFile>>();
+
+        // Missing loc data. Only tables with localisable fields matter here.
+        let missing_loc_data_tables = local_db_tables.iter()
+            .filter(|packed_file| !Self::is_loc_completeness_ignored(packed_file.get_path(), TableDiagnosticReportType::MissingLocData, files_to_ignore))
+            .filter_map(|packed_file| if let DecodedPackedFile::DB(table) = packed_file.get_ref_decoded() { Some((packed_file.get_path(), table)) } else { None })
+            .filter(|(_, table)| !table.get_ref_definition().get_localised_fields().is_empty())
+            .collect::<Vec<(&[String], &DB)>>();
+
+        if !missing_loc_data_tables.is_empty() {
+            let loc_keys = local_loc_tables.iter()
+                .map(|packed_file| packed_file.get_ref_decoded())
+                .chain(vanilla_tables.iter().copied())
+                .filter_map(|decoded| if let DecodedPackedFile::Loc(table) = decoded { Some(table.get_ref_table_data()) } else { None })
+                .flat_map(|data| data.iter().filter_map(|row| Self::get_loc_key(row)))
+                .collect::<HashSet<&str>>();
+
+            for (path, table) in missing_loc_data_tables {
+                let mut diagnostic = TableDiagnostic::new(path);
+                for (row, key_columns, key, loc_key) in Self::get_expected_loc_keys(table) {
+                    if !loc_keys.contains(&*loc_key) {
+                        diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                            cells_affected: key_columns.iter().map(|column| (row as i32, *column as i32)).collect(),
+                            message: format!("Missing loc entry for DB key \"{}\". Expected loc key: \"{}\".", key, loc_key),
+                            report_type: TableDiagnosticReportType::MissingLocData,
+                            level: DiagnosticLevel::Warning,
+                            fix: None,
+                        });
+                    }
+                }
+
+                if !diagnostic.get_ref_result().is_empty() {
+                    diagnostics.push(DiagnosticType::DB(diagnostic));
+                }
+            }
+        }
+
+        // Orphan loc keys. Only loc keys starting with the table and field of a localisable field are checked, as the rest cannot be linked to a table.
+        let orphan_loc_key_tables = local_loc_tables.iter()
+            .filter(|packed_file| !Self::is_loc_completeness_ignored(packed_file.get_path(), TableDiagnosticReportType::OrphanLocKey, files_to_ignore))
+            .filter_map(|packed_file| if let DecodedPackedFile::Loc(table) = packed_file.get_ref_decoded() { Some((packed_file.get_path(), table.get_ref_table_data())) } else { None })
+            .collect::<Vec<(&[String], &[Vec<DecodedData>])>>();
+
+        if !orphan_loc_key_tables.is_empty() {
+            let mut prefixes = HashMap::new();
+            let mut expected_loc_keys = HashSet::new();
+            let db_tables = local_db_tables.iter()
+                .map(|packed_file| packed_file.get_ref_decoded())
+                .chain(vanilla_tables.iter().copied())
+                .filter_map(|decoded| if let DecodedPackedFile::DB(table) = decoded { Some(table) } else { None })
+                .chain(asskit_dependencies.iter());
+
+            for table in db_tables {
+                let table_name = table.get_table_name_without_tables();
+                for field in table.get_ref_definition().get_localised_fields() {
+                    prefixes.insert(format!("{}_{}_", table_name, field.get_name()), table.get_ref_table_name().to_owned());
+                }
+
+                expected_loc_keys.extend(Self::get_expected_loc_keys(table).into_iter().map(|(_, _, _, loc_key)| loc_key));
+            }
+
+            for (path, data) in orphan_loc_key_tables {
+                let mut diagnostic = TableDiagnostic::new(path);
+                for (row, loc_key) in data.iter().enumerate().filter_map(|(row, cells)| Self::get_loc_key(cells).map(|loc_key| (row, loc_key))) {
+                    if expected_loc_keys.contains(loc_key) {
+                        continue;
+                    }
+
+                    // Use the longest prefix, as table and field names may contain other table and field names.
+                    if let Some((prefix, table_name)) = prefixes.iter().filter(|(prefix, _)| loc_key.starts_with(&***prefix)).max_by_key(|(prefix, _)| prefix.len()) {
+                        diagnostic.get_ref_mut_result().push(TableDiagnosticReport {
+                            cells_affected: vec![(row as i32, 0)],
+                            message: format!("Orphan loc key \"{}\": no row with the DB key \"{}\" found in \"{}\".", loc_key, &loc_key[prefix.len()..], table_name),
+                            report_type: TableDiagnosticReportType::OrphanLocKey,
+                            level: DiagnosticLevel::Warning,
+                            fix: None,
+                        });
+                    }
+                }
+
+                if !diagnostic.get_ref_result().is_empty() {
+                    diagnostics.push(DiagnosticType::Loc(diagnostic));
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// This function performs a load order check over the provided `PackFiles`, storing the conflicts between them.
+    ///
+    /// The `PackFiles` are checked in the order the game loads them, with the vanilla files loaded before any of them.
+    /// Unlike the normal check, this one doesn't stop if the dependencies cache is not available, as conflicts between mods can be found without it.
+    pub fn check_load_order(&mut self, pack_files: &[PackFile], dependencies: &mut Dependencies) {
+
+        // Clear the diagnostics first.
+        self.0.clear();
+
+        if let Some(diagnostics) = Self::check_config(dependencies) {
+            self.0.push(diagnostics);
+        }
+
+        dependencies.initialize_vanilla_paths();
+        if let Some(diagnostics) = Self::check_load_order_conflicts(pack_files, dependencies) {
+            self.0.push(diagnostics);
+        }
+    }
+
+    /// This function takes care of checking for conflicts between `PackFiles` loaded at the same time.
+    ///
+    /// It reports whole files overriding others, DB rows with the same keys in the same table, and Loc keys defined in more than one `PackFile`.
+    /// Rows and keys are only checked in the files that win their path's conflict, as the rest are never loaded by the game.
+    fn check_load_order_conflicts(pack_files: &[PackFile], dependencies: &Dependencies) -> Option<DiagnosticType> {
+        let mut diagnostic = LoadOrderDiagnostic::new();
+
+        let mut pack_files = pack_files.iter().collect::<Vec<&PackFile>>();
+        pack_files.sort_by(|x, y| x.cmp_by_load_order(y));
+        let pack_names = pack_files.iter().map(|pack_file| pack_file.get_file_name()).collect::<Vec<String>>();
+
+        // Paths are case-insensitive for the game, so we group the files by their caseless path, keeping the load order.
+        let mut files: BTreeMap<UniCase<String>, Vec<(usize, &PackedFile)>> = BTreeMap::new();
+        for (index, pack_file) in pack_files.iter().enumerate() {
+            for packed_file in pack_file.get_ref_packed_files_all() {
+                files.entry(UniCase::new(packed_file.get_path().join("/"))).or_default().push((index, packed_file));
+            }
+        }
+
+        // Whole file overrides. Mods overriding vanilla files are reported as info, while mods overriding other mods are reported as warnings.
+        for (path, packed_files) in &files {
+            let is_in_vanilla = dependencies.file_exists_on_game_files(path, true);
+            if packed_files.len() > 1 || is_in_vanilla {
+                let (winner_index, winner) = packed_files[packed_files.len() - 1];
+                let mut packs = packed_files.iter().map(|(index, _)| pack_names[*index].to_owned()).collect::<Vec<String>>();
+                if is_in_vanilla {
+                    packs.insert(0, VANILLA_PACK_NAME.to_owned());
+                }
+
+                diagnostic.get_ref_mut_result().push(LoadOrderDiagnosticReport {
+                    path: winner.get_path().to_vec(),
+                    key: vec![],
+                    message: format!("File \"{}\" is overridden. Loaded from: {}. Winner: {}.", path, packs.join(", "), pack_names[winner_index]),
+                    packs,
+                    winner: pack_names[winner_index].to_owned(),
+                    report_type: LoadOrderDiagnosticReportType::FileOverridden,
+                    level: if packed_files.len() > 1 { DiagnosticLevel::Warning } else { DiagnosticLevel::Info },
+                });
+            }
+        }
+
+        // Row-level conflicts need the tables decoded, so we can only check them if we have a schema.
+        if let Some(ref schema) = *SCHEMA.read().unwrap() {
+            let mut db_keys: BTreeMap<(String, Vec<String>), Vec<(usize, &[String])>> = BTreeMap::new();
+            let mut loc_keys: BTreeMap<String, Vec<(usize, &[String])>> = BTreeMap::new();
+
+            for (index, packed_file) in files.values().filter_map(|packed_files| packed_files.last()) {
+                match packed_file.get_packed_file_type(false) {
+                    PackedFileType::DB => if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_no_cache_no_locks(schema) {
+                        let key_columns = table.get_ref_definition().get_fields_processed().iter()
+                            .enumerate()
+                            .filter_map(|(column, field)| if field.get_is_key() { Some(column) } else { None })
+                            .collect::<Vec<usize>>();
+
+                        // Tables without keys cannot conflict by key.
+                        if key_columns.is_empty() {
+                            continue;
+                        }
+
+                        let table_name = packed_file.get_path()[1].to_lowercase();
+                        for row in table.get_ref_table_data() {
+                            let key = key_columns.iter().map(|column| row[*column].data_to_string()).collect::<Vec<String>>();
+                            db_keys.entry((table_name.to_owned(), key)).or_default().push((*index, packed_file.get_path()));
+                        }
+                    },
+
+                    PackedFileType::Loc => if let Ok(DecodedPackedFile::Loc(table)) = packed_file.decode_return_ref_no_cache_no_locks(schema) {
+                        for row in table.get_ref_table_data() {
+                            loc_keys.entry(row[0].data_to_string()).or_default().push((*index, packed_file.get_path()));
+                        }
+                    },
+                    _ => {},
+                }
+            }
+
+            for ((table_name, key), definitions) in db_keys {
+                if let Some((packs, winner_index, path)) = Self::get_load_order_conflict(&definitions, &pack_names) {
+                    diagnostic.get_ref_mut_result().push(LoadOrderDiagnosticReport {
+                        path,
+                        message: format!("Key \"{}\" in table \"{}\" is defined in multiple PackFiles: {}. Winner: {}.", key.join(", "), table_name, packs.join(", "), pack_names[winner_index]),
+                        key,
+                        packs,
+                        winner: pack_names[winner_index].to_owned(),
+                        report_type: LoadOrderDiagnosticReportType::DuplicatedDBKeyAcrossPacks,
+                        level: DiagnosticLevel::Warning,
+                    });
+                }
+            }
+
+            for (key, definitions) in loc_keys {
+                if let Some((packs, winner_index, path)) = Self::get_load_order_conflict(&definitions, &pack_names) {
+                    diagnostic.get_ref_mut_result().push(LoadOrderDiagnosticReport {
+                        path,
+                        message: format!("Loc key \"{}\" is defined in multiple PackFiles: {}. Winner: {}.", key, packs.join(", "), pack_names[winner_index]),
+                        key: vec![key],
+                        packs,
+                        winner: pack_names[winner_index].to_owned(),
+                        report_type: LoadOrderDiagnosticReportType::DuplicatedLocKeyAcrossPacks,
+                        level: DiagnosticLevel::Warning,
+                    });
+                }
+            }
+        }
+
+        if !diagnostic.get_ref_result().is_empty() {
+            Some(DiagnosticType::LoadOrder(diagnostic))
+        } else { None }
+    }
+
+    /// This function checks if the provided definitions of a key come from more than one `PackFile`.
+    ///
+    /// If they do, it returns the names of the `PackFiles` in load order, the index of the winner, and the path of the file where the winner defines it.
+    /// The winner is decided the same way as in `MergedTable`: the game keeps the first definition it reads, reading the files by path.
+    fn get_load_order_conflict(definitions: &[(usize, &[String])], pack_names: &[String]) -> Option<(Vec<String>, usize, Vec<String>)> {
+        let mut indexes = definitions.iter().map(|(index, _)| *index).collect::<Vec<usize>>();
+        indexes.sort_unstable();
+        indexes.dedup();
+
+        if indexes.len() > 1 {
+            let (winner_index, path) = definitions.iter().min_by(|(_, x), (_, y)| MergedTable::cmp_by_load_order(x, y))?;
+            let packs = indexes.iter().map(|index| pack_names[*index].to_owned()).collect();
+            Some((packs, *winner_index, path.to_vec()))
+        } else { None }
+    }
+
     /// This function takes care of checking for PackFile-Related for errors.
     fn check_packfile(pack_file: &PackFile) -> Option<DiagnosticType> {
         let mut diagnostic = PackFileDiagnostic::new();
@@ -941,11 +1476,40 @@ impl Diagnostics {
             });
         }
 
+        match DiagnosticsRules::load_for_pack_file(pack_file) {
+            Ok(rules) => {
+                let unknown_rules = rules.get_unknown_rules();
+                if !unknown_rules.is_empty() {
+                    diagnostic.get_ref_mut_result().push(PackFileDiagnosticReport {
+                        message: format!("Unknown diagnostics rules: {}", unknown_rules.join(", ")),
+                        report_type: PackFileDiagnosticReportType::InvalidDiagnosticsRules,
+                        level: DiagnosticLevel::Warning,
+                    });
+                }
+            }
+            Err(error) => diagnostic.get_ref_mut_result().push(PackFileDiagnosticReport {
+                message: format!("Invalid diagnostics rules: {}", error),
+                report_type: PackFileDiagnosticReportType::InvalidDiagnosticsRules,
+                level: DiagnosticLevel::Error,
+            }),
+        }
+
         if !diagnostic.get_ref_result().is_empty() {
             Some(DiagnosticType::PackFile(diagnostic))
         } else { None }
     }
 
+    /// This function applies the diagnostics rules of the provided PackFile to the current diagnostics.
+    ///
+    /// Invalid rules are not applied. Instead, they're reported by the PackFile check.
+    fn apply_pack_file_rules(&mut self, pack_file: &PackFile) {
+        if let Ok(rules) = DiagnosticsRules::load_for_pack_file(pack_file) {
+
+            // Loaded rules are already validated, so this cannot fail.
+            let _ = self.apply_rules(&rules, Some(pack_file));
+        }
+    }
+
     /// This function takes care of checking for errors in the Dependency Manager.
     fn check_dependency_manager(pack_file: &PackFile) ->Option<DiagnosticType> {
         let mut diagnostic = DependencyManagerDiagnostic::new();
@@ -1084,7 +1648,8 @@ impl Diagnostics {
         let mut locs_added = false;
         for packed_file in &packed_files {
             match packed_file.get_packed_file_type(false) {
-                PackedFileType::AnimFragment => {
+                PackedFileType::AnimFragment |
+                PackedFileType::RigidModel => {
                     packed_files_complete.push(packed_file);
                 }
                 PackedFileType::DB => {
@@ -1119,6 +1684,9 @@ impl Diagnostics {
             DiagnosticType::DependencyManager(_) => false,
             DiagnosticType::Loc(_) => true,
             DiagnosticType::PackFile(_) => false,
+            DiagnosticType::LoadOrder(_) => true,
+            DiagnosticType::RigidModel(_) => true,
+            DiagnosticType::Lua(_) => true,
         });
 
         let files_to_ignore = pack_file.get_settings().get_diagnostics_files_to_ignore();
@@ -1131,40 +1699,28 @@ impl Diagnostics {
 
         // Logic here: we want to process the tables on batches containing all the tables of the same type, so we can check duplicates in different tables.
         // To do that, we have to sort/split the file list, the process that.
-        let mut packed_files_split: BTreeMap<&str, Vec<&PackedFile>> = BTreeMap::new();
-        for packed_file in &packed_files_complete {
-            match packed_file.get_packed_file_type(false) {
-                PackedFileType::AnimFragment => {
-                    if let Some(table_set) = packed_files_split.get_mut("anim_fragments") {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert("anim_fragments", vec![packed_file]);
-                    }
-                },
-                PackedFileType::DB => {
-                    if let Some(table_set) = packed_files_split.get_mut(&*packed_file.get_path()[1]) {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert(&packed_file.get_path()[1], vec![packed_file]);
-                    }
-                },
-                PackedFileType::Loc => {
-                    if let Some(table_set) = packed_files_split.get_mut("locs") {
-                        table_set.push(packed_file);
-                    } else {
-                        packed_files_split.insert("locs", vec![packed_file]);
-                    }
-                },
-                _ => {},
-            }
-        }
+        let packed_files_split = Self::split_packed_files_in_batches(&packed_files_complete);
+
+        // AnimFragments need the skeleton of each anim they use, so index the anims of the PackFile once instead of searching them for each cell.
+        let local_anims = if packed_files_split.contains_key("anim_fragments") {
+            Self::get_local_anims(pack_file)
+        } else { HashMap::new() };
+
         if let Some(ref schema) = *SCHEMA.read().unwrap() {
+
+            // Lua scripts need the keys of all the tables, so only get them if there are scripts to check.
+            // If we don't have the vanilla data, we don't get them, as otherwise any vanilla key would be reported as missing.
+            let lua_db_keys = if packed_files_split.contains_key("lua_scripts") && dependencies.game_has_vanilla_data_loaded(false) {
+                Self::get_lua_db_keys(pack_file, schema, &vanilla_dependencies, asskit_dependencies)
+            } else { LuaDBKeys::default() };
+
             let local_packed_file_path_list = pack_file.get_packed_files_all_paths_as_string();
             let local_folder_path_list = pack_file.get_folder_all_paths_as_string();
 
             for packed_files in packed_files_split.values() {
                 let mut data_prev: BTreeMap<String, HashMap<String, Vec<(i32, i32)>>> = BTreeMap::new();
                 let mut dependency_data_for_table = BTreeMap::new();
+                let mut anim_skeletons = HashMap::new();
 
                 for packed_file in packed_files {
 
@@ -1214,7 +1770,7 @@ impl Diagnostics {
 
                     let diagnostic = match packed_file.get_packed_file_type(false) {
                         PackedFileType::AnimFragment => if let Ok(decoded) = packed_file.decode_return_ref_no_cache_no_locks(schema) {
-                                Self::check_anim_fragment(&decoded, packed_file.get_path(), dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &local_packed_file_path_list, &local_folder_path_list)
+                                Self::check_anim_fragment(&decoded, packed_file.get_path(), &local_anims, &mut anim_skeletons, dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &local_packed_file_path_list, &local_folder_path_list)
                             } else { None }
                         PackedFileType::DB => {
 
@@ -1235,9 +1791,11 @@ impl Diagnostics {
                                 }
                             }
 
-                            Self::check_db(packed_file.get_ref_decoded(), packed_file.get_path(), dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev, &local_packed_file_path_list, &local_folder_path_list, &dependency_data_for_table)
+                            Self::check_db(packed_file, dependencies, &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev, &local_packed_file_path_list, &local_folder_path_list, &dependency_data_for_table)
                         },
                         PackedFileType::Loc => Self::check_loc(packed_file.get_ref_decoded(), packed_file.get_path(), &ignored_fields, &ignored_diagnostics, &ignored_diagnostics_for_fields, &mut data_prev),
+                        PackedFileType::RigidModel => Self::check_rigid_model(packed_file, dependencies, &ignored_diagnostics, &local_packed_file_path_list, &local_folder_path_list),
+                        PackedFileType::Text(TextType::Lua) => Self::check_lua(packed_file, &ignored_diagnostics, &lua_db_keys),
                         _ => None,
                     };
 
@@ -1248,6 +1806,31 @@ impl Diagnostics {
             }
         }
 
+        // Loc completeness depends on all the DB and Loc tables, so if any of them changed, check it again for the entire PackFile.
+        let tables_updated = packed_files_complete.iter().any(|packed_file| matches!(packed_file.get_packed_file_type(false), PackedFileType::DB | PackedFileType::Loc)) ||
+            updated_paths.iter().any(|path_type| match path_type {
+                PathType::File(path) | PathType::Folder(path) => path.is_empty() || path[0] == "db" || path[0] == "text",
+                _ => false,
+            });
+
+        if tables_updated {
+            self.0.iter_mut().for_each(|diagnostic| if let DiagnosticType::DB(diagnostic) | DiagnosticType::Loc(diagnostic) = diagnostic {
+                diagnostic.get_ref_mut_result().retain(|result| !matches!(result.report_type, TableDiagnosticReportType::MissingLocData | TableDiagnosticReportType::OrphanLocKey));
+            });
+
+            self.0.retain(|diagnostic| match diagnostic {
+                DiagnosticType::DB(diagnostic) |
+                DiagnosticType::Loc(diagnostic) => !diagnostic.get_ref_result().is_empty(),
+                _ => true,
+            });
+
+            if !vanilla_dependencies.is_empty() || !asskit_dependencies.is_empty() {
+                let loc_dependencies = dependencies.get_db_and_loc_tables_from_cache(false, true, true, true).unwrap_or_default();
+                let diagnostics = Self::check_loc_completeness(pack_file, &vanilla_dependencies, &loc_dependencies, asskit_dependencies, &files_to_ignore);
+                self.merge_table_diagnostics(diagnostics);
+            }
+        }
+
         // Check for the dependency manager.
         if let Some(diagnostics) = Self::check_dependency_manager(pack_file) {
             self.0.push(diagnostics);
@@ -1258,6 +1841,7 @@ impl Diagnostics {
             self.0.push(diagnostics);
         }
 
+        self.apply_pack_file_rules(pack_file);
         self.get_ref_mut_diagnostics().sort_by(|a, b| {
             if !a.get_path().is_empty() && !b.get_path().is_empty() {
                 a.get_path().cmp(b.get_path())
@@ -1278,6 +1862,193 @@ impl Diagnostics {
         packed_files.iter().map(|x| From::from(*x)).collect()
     }
 
+    /// Function to get the skeleton of a row of an AnimFragment, from the first non-empty field with "skeleton" in its name.
+    fn get_skeleton_name<'a>(cells: &'a [DecodedData], fields: &[Field]) -> Option<&'a str> {
+        fields.iter().zip(cells.iter()).find_map(|(field, cell)| match cell {
+            DecodedData::StringU8(skeleton) if !skeleton.is_empty() && field.get_name().to_lowercase().contains("skeleton") => Some(&**skeleton),
+            _ => None,
+        })
+    }
+
+    /// Function to get the anims of the PackFile, indexed by their path.
+    fn get_local_anims(pack_file: &PackFile) -> HashMap<UniCase<String>, &PackedFile> {
+        pack_file.get_ref_packed_files_by_type(PackedFileType::Anim, false).into_iter()
+            .map(|packed_file| (UniCase::new(packed_file.get_path().join("/")), packed_file))
+            .collect()
+    }
+
+    /// Function to get the skeleton of the Anim in the provided path, searching it in the PackFile, its parent files and the game files.
+    ///
+    /// Anims are usually used by more than one fragment, so the skeletons found (or not found) are cached in `anim_skeletons`.
+    fn get_anim_skeleton_name(
+        path: &str,
+        local_anims: &HashMap<UniCase<String>, &PackedFile>,
+        anim_skeletons: &mut HashMap<UniCase<String>, Option<String>>,
+        dependencies: &Dependencies
+    ) -> Option<String> {
+        anim_skeletons.entry(UniCase::new(path.to_owned()))
+            .or_insert_with(|| Self::read_anim_skeleton_name(path, local_anims, dependencies))
+            .clone()
+    }
+
+    /// Function to read the skeleton of the Anim in the provided path, searching it in the PackFile, its parent files and the game files.
+    fn read_anim_skeleton_name(path: &str, local_anims: &HashMap<UniCase<String>, &PackedFile>, dependencies: &Dependencies) -> Option<String> {
+        let data = match local_anims.get(&UniCase::new(path.to_owned())) {
+            Some(packed_file) => packed_file.get_raw_data().ok()?,
+            None => {
+                let path = path.split('/').map(|x| x.to_owned()).collect::<Vec<String>>();
+                dependencies.get_packedfile_from_parent_files_unicased(&path)
+                    .or_else(|_| dependencies.get_packedfile_from_game_files_unicased(&path))
+                    .ok()?
+                    .get_raw_data()
+                    .ok()?
+            }
+        };
+
+        Anim::read_header(&data).ok().map(|anim| anim.get_ref_skeleton_name().to_owned())
+    }
+
+    /// Function to split the provided PackedFiles in batches of files that need to be checked together.
+    ///
+    /// DB tables are split by table, so we can check duplicates in different tables of the same type.
+    fn split_packed_files_in_batches<'a>(packed_files: &[&'a PackedFile]) -> BTreeMap<&'a str, Vec<&'a PackedFile>> {
+        let mut packed_files_split: BTreeMap<&str, Vec<&PackedFile>> = BTreeMap::new();
+        for packed_file in packed_files {
+            let batch = match packed_file.get_packed_file_type(false) {
+                PackedFileType::AnimFragment => "anim_fragments",
+                PackedFileType::DB => &packed_file.get_path()[1],
+                PackedFileType::Loc => "locs",
+                PackedFileType::RigidModel => "rigid_models",
+                PackedFileType::Text(TextType::Lua) => "lua_scripts",
+                _ => continue,
+            };
+
+            packed_files_split.entry(batch).or_default().push(packed_file);
+        }
+
+        packed_files_split
+    }
+
+    /// Function to get the values of the key columns of all the tables in the PackFile and its dependencies, so lua scripts can check their keys against them.
+    ///
+    /// The dependency tables include the ones of the parent PackFiles. Tables of the PackFile that are not decoded are decoded here.
+    fn get_lua_db_keys(pack_file: &PackFile, schema: &Schema, dependency_tables: &[PackedFile], asskit_dependencies: &[DB]) -> LuaDBKeys {
+        let mut keys = HashSet::new();
+        let local_tables = pack_file.get_ref_packed_files_by_type(PackedFileType::DB, false);
+        let local_tables_decoded = local_tables.iter()
+            .filter(|packed_file| !matches!(packed_file.get_ref_decoded(), DecodedPackedFile::DB(_)))
+            .filter_map(|packed_file| packed_file.decode_return_ref_no_cache_no_locks(schema).ok())
+            .collect::<Vec<DecodedPackedFile>>();
+
+        let tables = local_tables.iter()
+            .map(|packed_file| packed_file.get_ref_decoded())
+            .chain(local_tables_decoded.iter())
+            .chain(dependency_tables.iter().filter_map(|packed_file| packed_file.get_decoded_from_memory().ok()))
+            .filter_map(|decoded| if let DecodedPackedFile::DB(table) = decoded { Some(table) } else { None })
+            .chain(asskit_dependencies.iter());
+
+        for table in tables {
+            let key_columns = table.get_ref_definition().get_fields_processed().iter()
+                .enumerate()
+                .filter(|(_, field)| field.get_is_key())
+                .map(|(column, _)| column)
+                .collect::<Vec<usize>>();
+
+            for row in table.get_ref_table_data() {
+                for column in &key_columns {
+                    match row[*column] {
+                        DecodedData::StringU8(ref key) |
+                        DecodedData::StringU16(ref key) |
+                        DecodedData::OptionalStringU8(ref key) |
+                        DecodedData::OptionalStringU16(ref key) => { keys.insert(key.to_owned()); },
+                        _ => {},
+                    }
+                }
+            }
+        }
+
+        LuaDBKeys::new(keys)
+    }
+
+    /// Function to get the loc keys each row of the provided table should have, one per localisable field.
+    ///
+    /// Each entry contains the row, the key columns, the DB key, and the expected loc key.
+    fn get_expected_loc_keys(table: &DB) -> Vec<(usize, Vec<usize>, String, String)> {
+        let definition = table.get_ref_definition();
+        let loc_fields = definition.get_localised_fields();
+        if loc_fields.is_empty() {
+            return vec![];
+        }
+
+        // Keys may be concatenated, and they're concatenated IN THE ORDER THEY ARE IN THE BINARY FILE.
+        let processed_fields = definition.get_fields_processed();
+        let key_columns = definition.get_ref_fields().iter()
+            .filter(|field| field.get_is_key())
+            .filter_map(|field| processed_fields.iter().position(|processed_field| processed_field.get_name() == field.get_name()))
+            .collect::<Vec<usize>>();
+
+        let table_name = table.get_table_name_without_tables();
+        let mut loc_keys = vec![];
+        for (row, cells) in table.get_ref_table_data().iter().enumerate() {
+            let key = key_columns.iter().map(|column| cells[*column].data_to_string()).join("");
+            for loc_field in loc_fields {
+                let loc_key = format!("{}_{}_{}", table_name, loc_field.get_name(), key);
+                loc_keys.push((row, key_columns.to_vec(), key.to_owned(), loc_key));
+            }
+        }
+
+        loc_keys
+    }
+
+    /// Function to get the key of a row of a Loc table.
+    fn get_loc_key(row: &[DecodedData]) -> Option<&str> {
+        match row.first() {
+            Some(DecodedData::StringU8(key)) |
+            Some(DecodedData::StringU16(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Function to know if the loc completeness diagnostic provided has been disabled for an entire PackedFile through the PackFile Settings.
+    fn is_loc_completeness_ignored(path: &[String], report_type: TableDiagnosticReportType, files_to_ignore: &Option<Vec<(Vec<String>, Vec<String>, Vec<String>)>>) -> bool {
+        let report_type = report_type.to_string();
+        match files_to_ignore {
+            Some(files_to_ignore) => files_to_ignore.iter().any(|(path_to_ignore, fields, diags_to_ignore)|
+                !path_to_ignore.is_empty() && path.starts_with(path_to_ignore) && fields.is_empty() && (diags_to_ignore.is_empty() || diags_to_ignore.contains(&report_type))
+            ),
+            None => false,
+        }
+    }
+
+    /// Function to merge the provided table diagnostics with the ones we already have, so each PackedFile only has one diagnostic.
+    fn merge_table_diagnostics(&mut self, diagnostics: Vec<DiagnosticType>) {
+        for diagnostic in diagnostics {
+            let existing = self.0.iter_mut().find_map(|existing| match (existing, &diagnostic) {
+                (DiagnosticType::DB(existing), DiagnosticType::DB(new)) |
+                (DiagnosticType::Loc(existing), DiagnosticType::Loc(new)) if existing.get_path() == new.get_path() => Some(existing),
+                _ => None,
+            });
+
+            match (existing, diagnostic) {
+                (Some(existing), DiagnosticType::DB(mut new)) |
+                (Some(existing), DiagnosticType::Loc(mut new)) => existing.get_ref_mut_result().append(new.get_ref_mut_result()),
+                (None, diagnostic) => self.0.push(diagnostic),
+                _ => {},
+            }
+        }
+    }
+
+    /// Function to know if a path exists as a file or folder, either in the PackFile, its parent files or the game files.
+    fn path_exists(path: &str, dependencies: &Dependencies, local_path_list: &HashSet<UniCase<String>>, local_folder_list: &HashSet<UniCase<String>>) -> bool {
+        let unicased = UniCase::new(path.to_owned());
+        local_path_list.contains(&unicased) ||
+            local_folder_list.contains(&unicased) ||
+            dependencies.file_exists_on_parent_files(&unicased, true) ||
+            dependencies.folder_exists_on_parent_files(&unicased, true) ||
+            dependencies.file_exists_on_game_files(&unicased, true) ||
+            dependencies.folder_exists_on_game_files(&unicased, true)
+    }
+
     /// Function to know if an specific field/diagnostic must be ignored.
     fn ignore_diagnostic(field_name: Option<&str>, diagnostic: Option<&str>, ignored_fields: &[String], ignored_diagnostics: &[String], ignored_diagnostics_for_fields: &HashMap<String, Vec<String>>) -> bool {
         let mut ignore_diagnostic = false;
@@ -1316,6 +2087,32 @@ impl Display for DiagnosticType {
             Self::Loc(_) => "Loc",
             Self::PackFile(_) => "Packfile",
             Self::DependencyManager(_) => "DependencyManager",
+            Self::LoadOrder(_) => "LoadOrder",
+            Self::RigidModel(_) => "RigidModel",
+            Self::Lua(_) => "Lua",
+        }, f)
+    }
+}
+
+impl Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(match self {
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
         }, f)
     }
 }
+
+impl FromStr for DiagnosticLevel {
+    type Err = rpfm_error::Error;
+
+    fn from_str(level: &str) -> Result<Self> {
+        match &*level.to_lowercase() {
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(ErrorKind::DiagnosticLevelUnknown(level.to_owned()).into()),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to output `Diagnostics` in formats other tools can read, like SARIF or JUnit XML.
+
+Each report type has a stable rule ID in `DiagnosticType/ReportType` format, like `DB/InvalidReference`.
+Positions start at 1: tables use the row and column of the affected cells, and scripts the line and column of the problem.
+!*/
+
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde_json::{json, Value};
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::str::FromStr;
+use std::{fmt, fmt::Display};
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::{DiagnosticLevel, Diagnostics, DiagnosticType};
+
+/// Name we use for diagnostics without a path, like the ones about the PackFile itself.
+const NO_PATH_NAME: &str = "PackFile";
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This enum represents the formats we can output the diagnostics in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticsOutputFormat {
+    Json,
+    Sarif,
+    JUnit,
+    Text,
+}
+
+/// This struct represents a single diagnostic result, independently of the type of file it comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticEntry {
+    pub rule_id: String,
+    pub level: DiagnosticLevel,
+    pub path: String,
+
+    /// List of positions affected, in "row, column" format. Both start at 1. If the full row or full column are affected, that part is `None`.
+    pub positions: Vec<(Option<u32>, Option<u32>)>,
+    pub message: String,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `Diagnostics`. Section of functions to output diagnostics.
+impl Diagnostics {
+
+    /// This function returns all the diagnostic results as a flat list of entries, with their rule IDs.
+    pub fn get_entries(&self) -> Vec<DiagnosticEntry> {
+        let mut entries = vec![];
+        for diagnostic in self.get_ref_diagnostics() {
+            let path = diagnostic.get_path().join("/");
+            let mut push = |report_type: &dyn Display, level: &DiagnosticLevel, path: &str, positions: Vec<(Option<u32>, Option<u32>)>, message: &str| {
+                entries.push(DiagnosticEntry {
+                    rule_id: format!("{}/{}", diagnostic, report_type),
+                    level: *level,
+                    path: path.to_owned(),
+                    positions,
+                    message: message.to_owned(),
+                });
+            };
+
+            match diagnostic {
+                DiagnosticType::AnimFragment(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, cells_to_positions(&x.cells_affected), &x.message)),
+                DiagnosticType::DB(ref diag) |
+                DiagnosticType::Loc(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, cells_to_positions(&x.cells_affected), &x.message)),
+                DiagnosticType::PackFile(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, vec![], &x.message)),
+                DiagnosticType::DependencyManager(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, cells_to_positions(&x.cells_affected), &x.message)),
+                DiagnosticType::Config(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, vec![], &x.message)),
+                DiagnosticType::LoadOrder(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &x.path.join("/"), vec![], &x.message)),
+                DiagnosticType::RigidModel(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, vec![], &x.message)),
+                DiagnosticType::Lua(ref diag) => diag.get_ref_result().iter().for_each(|x| push(&x.report_type, &x.level, &path, vec![(Some(x.position.0), Some(x.position.1))], &x.message)),
+            }
+        }
+
+        entries
+    }
+
+    /// This function returns if there is any diagnostic result with the provided level, or a more severe one.
+    pub fn has_level(&self, level: DiagnosticLevel) -> bool {
+        self.get_entries().iter().any(|entry| entry.level >= level)
+    }
+
+    /// This function returns the diagnostics in the provided format.
+    pub fn to_format(&self, format: DiagnosticsOutputFormat) -> Result<String> {
+        match format {
+            DiagnosticsOutputFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            DiagnosticsOutputFormat::Sarif => self.to_sarif(),
+            DiagnosticsOutputFormat::JUnit => self.to_junit(),
+            DiagnosticsOutputFormat::Text => Ok(self.to_text()),
+        }
+    }
+
+    /// This function returns the diagnostics as a SARIF 2.1.0 log.
+    fn to_sarif(&self) -> Result<String> {
+        let entries = self.get_entries();
+        let mut rule_ids = entries.iter().map(|entry| &*entry.rule_id).collect::<Vec<&str>>();
+        rule_ids.sort_unstable();
+        rule_ids.dedup();
+
+        let rules = rule_ids.iter().map(|rule_id| json!({ "id": rule_id })).collect::<Vec<Value>>();
+        let results = entries.iter().map(|entry| {
+            let level = match entry.level {
+                DiagnosticLevel::Info => "note",
+                DiagnosticLevel::Warning => "warning",
+                DiagnosticLevel::Error => "error",
+            };
+
+            let mut result = json!({
+                "ruleId": entry.rule_id,
+                "ruleIndex": rule_ids.binary_search(&&*entry.rule_id).unwrap_or_default(),
+                "level": level,
+                "message": { "text": entry.message },
+            });
+
+            if !entry.path.is_empty() {
+                let artifact_location = json!({ "uri": entry.path });
+                let locations = if entry.positions.is_empty() {
+                    vec![json!({ "physicalLocation": { "artifactLocation": artifact_location } })]
+                } else {
+                    entry.positions.iter().map(|(row, column)| {
+                        let mut physical_location = json!({ "artifactLocation": artifact_location });
+
+                        // Regions need a line, so we cannot point to full columns.
+                        if let Some(row) = row {
+                            let mut region = json!({ "startLine": row });
+                            if let Some(column) = column {
+                                region["startColumn"] = json!(column);
+                            }
+                            physical_location["region"] = region;
+                        }
+
+                        json!({ "physicalLocation": physical_location })
+                    }).collect()
+                };
+
+                result["locations"] = json!(locations);
+            }
+
+            result
+        }).collect::<Vec<Value>>();
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "RPFM",
+                        "informationUri": "https://github.com/Frodo45127/rpfm",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        });
+
+        Ok(serde_json::to_string_pretty(&sarif)?)
+    }
+
+    /// This function returns the diagnostics as a JUnit XML report, with one test suite per file and one test case per result.
+    ///
+    /// Errors and warnings are reported as failures. Info results are reported as passed tests.
+    fn to_junit(&self) -> Result<String> {
+        let entries = self.get_entries();
+        let mut suites: BTreeMap<&str, Vec<&DiagnosticEntry>> = BTreeMap::new();
+        for entry in &entries {
+            let path = if entry.path.is_empty() { NO_PATH_NAME } else { &entry.path };
+            suites.entry(path).or_default().push(entry);
+        }
+
+        let is_failure = |entry: &DiagnosticEntry| entry.level >= DiagnosticLevel::Warning;
+        let mut writer = Writer::new_with_indent(Cursor::new(vec![]), b' ', 4);
+        writer.write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
+        start_element(&mut writer, "testsuites", &[
+            ("name", "RPFM Diagnostics".to_owned()),
+            ("tests", entries.len().to_string()),
+            ("failures", entries.iter().filter(|entry| is_failure(entry)).count().to_string()),
+        ])?;
+
+        for (path, entries) in &suites {
+            start_element(&mut writer, "testsuite", &[
+                ("name", path.to_string()),
+                ("tests", entries.len().to_string()),
+                ("failures", entries.iter().filter(|entry| is_failure(entry)).count().to_string()),
+            ])?;
+
+            for entry in entries {
+                start_element(&mut writer, "testcase", &[
+                    ("classname", path.to_string()),
+                    ("name", format!("{}{}", entry.rule_id, positions_to_string(&entry.positions))),
+                ])?;
+
+                if is_failure(entry) {
+                    start_element(&mut writer, "failure", &[("message", entry.message.to_owned()), ("type", entry.level.to_string())])?;
+                    writer.write_event(Event::Text(BytesText::from_plain_str(&entry.message)))?;
+                    end_element(&mut writer, "failure")?;
+                } else {
+                    start_element(&mut writer, "system-out", &[])?;
+                    writer.write_event(Event::Text(BytesText::from_plain_str(&entry.message)))?;
+                    end_element(&mut writer, "system-out")?;
+                }
+
+                end_element(&mut writer, "testcase")?;
+            }
+
+            end_element(&mut writer, "testsuite")?;
+        }
+
+        end_element(&mut writer, "testsuites")?;
+        Ok(String::from_utf8(writer.into_inner().into_inner())?)
+    }
+
+    /// This function returns the diagnostics as text, with one line per result, in `path:row:column: level [rule] message` format.
+    fn to_text(&self) -> String {
+        self.get_entries().iter().map(|entry| {
+            let path = if entry.path.is_empty() { NO_PATH_NAME } else { &entry.path };
+            let locations = if entry.positions.is_empty() {
+                vec![path.to_owned()]
+            } else {
+                entry.positions.iter().map(|(row, column)| match (row, column) {
+                    (Some(row), Some(column)) => format!("{}:{}:{}", path, row, column),
+                    (Some(row), None) => format!("{}:{}", path, row),
+                    (None, Some(column)) => format!("{}:*:{}", path, column),
+                    (None, None) => path.to_owned(),
+                }).collect()
+            };
+
+            locations.iter().map(|location| format!("{}: {} [{}] {}\n", location, entry.level, entry.rule_id, entry.message)).collect::<String>()
+        }).collect()
+    }
+}
+
+/// Implementation of `FromStr` for `DiagnosticsOutputFormat`.
+impl FromStr for DiagnosticsOutputFormat {
+    type Err = rpfm_error::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match &*format.to_lowercase() {
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            "junit" => Ok(Self::JUnit),
+            "text" => Ok(Self::Text),
+            _ => Err(ErrorKind::DiagnosticsOutputFormatUnknown(format.to_owned()).into()),
+        }
+    }
+}
+
+/// Display implementation of `DiagnosticsOutputFormat`.
+impl Display for DiagnosticsOutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(match self {
+            Self::Json => "json",
+            Self::Sarif => "sarif",
+            Self::JUnit => "junit",
+            Self::Text => "text",
+        }, f)
+    }
+}
+
+//-------------------------------------------------------------------------------//
+//                             Helper functions
+//-------------------------------------------------------------------------------//
+
+/// This function turns a list of affected cells, starting at 0 and with -1 for full rows/columns, into a list of positions.
+fn cells_to_positions(cells: &[(i32, i32)]) -> Vec<(Option<u32>, Option<u32>)> {
+    let to_position = |index: i32| if index < 0 { None } else { Some(index as u32 + 1) };
+    cells.iter()
+        .filter(|(row, column)| *row >= 0 || *column >= 0)
+        .map(|(row, column)| (to_position(*row), to_position(*column)))
+        .collect()
+}
+
+/// This function returns a list of positions as a string, to identify results of the same rule in the same file.
+fn positions_to_string(positions: &[(Option<u32>, Option<u32>)]) -> String {
+    if positions.is_empty() {
+        return String::new();
+    }
+
+    let positions = positions.iter().map(|(row, column)| format!("{}:{}",
+        row.map(|x| x.to_string()).unwrap_or_else(|| "*".to_owned()),
+        column.map(|x| x.to_string()).unwrap_or_else(|| "*".to_owned())
+    )).collect::<Vec<String>>();
+
+    format!(" ({})", positions.join(", "))
+}
+
+/// This function writes the opening tag of an element, with the provided attributes.
+fn start_element(writer: &mut Writer<Cursor<Vec<u8>>>, name: &str, attributes: &[(&str, String)]) -> Result<()> {
+    let mut element = BytesStart::borrowed_name(name.as_bytes());
+    attributes.iter().for_each(|(key, value)| element.push_attribute((*key, value.as_str())));
+    writer.write_event(Event::Start(element))?;
+    Ok(())
+}
+
+/// This function writes the closing tag of an element.
+fn end_element(writer: &mut Writer<Cursor<Vec<u8>>>, name: &str) -> Result<()> {
+    writer.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?;
+    Ok(())
+}
@@ -40,7 +40,8 @@ pub struct PackFileDiagnosticReport {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PackFileDiagnosticReportType {
-    InvalidPackFileName
+    InvalidPackFileName,
+    InvalidDiagnosticsRules,
 }
 
 //---------------------------------------------------------------p----------------//
@@ -64,10 +65,28 @@ impl PackFileDiagnostic {
     }
 }
 
+impl PackFileDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::InvalidPackFileName];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::InvalidPackFileName) => Some(Self::InvalidDiagnosticsRules),
+            Some(Self::InvalidDiagnosticsRules) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
 impl Display for PackFileDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
             Self::InvalidPackFileName => "InvalidPackFileName",
+            Self::InvalidDiagnosticsRules => "InvalidDiagnosticsRules",
         }, f)
     }
 }
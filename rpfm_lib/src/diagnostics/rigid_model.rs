@@ -0,0 +1,99 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+
+/*!
+Module with all the code related to the `Diagnostics`.
+
+This module contains the code needed to get a `Diagnostics` over rigidmodels.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::{fmt, fmt::Display};
+
+use super::DiagnosticLevel;
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains the results of a diagnostics check over a rigidmodel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RigidModelDiagnostic {
+    path: Vec<String>,
+    result: Vec<RigidModelDiagnosticReport>
+}
+
+/// This struct defines an individual diagnostic result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RigidModelDiagnosticReport {
+
+    /// List of meshes, in "lod, mesh" format.
+    pub meshes_affected: Vec<(i32, i32)>,
+    pub message: String,
+    pub report_type: RigidModelDiagnosticReportType,
+    pub level: DiagnosticLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RigidModelDiagnosticReportType {
+    TexturePathNotFound,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `RigidModelDiagnostic`.
+impl RigidModelDiagnostic {
+    pub fn new(path: &[String]) -> Self {
+        Self {
+            path: path.to_vec(),
+            result: vec![],
+        }
+    }
+
+    pub fn get_path(&self) -> &[String] {
+        &self.path
+    }
+
+    pub fn get_ref_result(&self) -> &[RigidModelDiagnosticReport] {
+        &self.result
+    }
+
+    pub fn get_ref_mut_result(&mut self) -> &mut Vec<RigidModelDiagnosticReport> {
+        &mut self.result
+    }
+}
+
+impl RigidModelDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::TexturePathNotFound];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::TexturePathNotFound) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
+impl Display for RigidModelDiagnosticReportType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(match self {
+            Self::TexturePathNotFound => "TexturePathNotFound"
+        }, f)
+    }
+}
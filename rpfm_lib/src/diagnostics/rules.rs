@@ -0,0 +1,420 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to configure which diagnostics are reported, and with what level.
+
+The rules are written in TOML, and can be stored in the `diagnostics_rules` setting of a PackFile,
+or in a `.diagnostics.toml` file next to the PackFile (like `my_mod.diagnostics.toml` for `my_mod.pack`),
+which is where MyMods keep them. Each rule looks like this:
+
+```toml
+[[rules]]
+rule = "DB/InvalidReference"     # Rule ID, report type (`InvalidReference`), or `*` for all of them.
+enabled = true                   # Optional. If false, the matching results are not reported.
+level = "warning"                # Optional. Level to report the matching results with.
+paths = ["db/land_units_tables"] # Optional. Globs the path of the file must match.
+tables = ["land_units"]          # Optional. DB tables the rule applies to, with or without the `_tables` suffix.
+columns = ["unit"]               # Optional. Columns the rule applies to. Only for table results.
+```
+
+Rules are applied in order, so if more than one rule matches a result, the last one wins.
+Rules from the file next to the PackFile are applied after the ones stored in the PackFile.
+!*/
+
+use regex::Regex;
+use serde_derive::{Serialize, Deserialize};
+
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::fmt::Display;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::common::get_path_pattern_regex;
+use crate::packedfile::DecodedPackedFile;
+use crate::packfile::PackFile;
+
+use super::{DiagnosticLevel, Diagnostics, DiagnosticType};
+use super::anim_fragment::{AnimFragmentDiagnosticReport, AnimFragmentDiagnosticReportType};
+use super::config::{ConfigDiagnosticReport, ConfigDiagnosticReportType};
+use super::dependency_manager::{DependencyManagerDiagnosticReport, DependencyManagerDiagnosticReportType};
+use super::load_order::{LoadOrderDiagnosticReport, LoadOrderDiagnosticReportType};
+use super::lua::{LuaDiagnosticReport, LuaDiagnosticReportType};
+use super::packfile::{PackFileDiagnosticReport, PackFileDiagnosticReportType};
+use super::rigid_model::{RigidModelDiagnosticReport, RigidModelDiagnosticReportType};
+use super::table::{TableDiagnosticReport, TableDiagnosticReportType};
+
+/// Extension of the files with diagnostics rules that can be placed next to a PackFile.
+pub const DIAGNOSTICS_RULES_EXTENSION: &str = ".diagnostics.toml";
+
+/// Rule name that matches every diagnostic.
+const ANY_RULE: &str = "*";
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains a list of rules to configure the diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsRules {
+    pub rules: Vec<DiagnosticRule>,
+}
+
+/// This struct represents a rule to enable, disable or change the level of a diagnostic.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticRule {
+
+    /// Rule ID (`DB/InvalidReference`), report type (`InvalidReference`) or `*` this rule applies to.
+    pub rule: String,
+
+    /// If the results matching this rule should be reported or not. `None` to keep them as they are.
+    pub enabled: Option<bool>,
+
+    /// Level the results matching this rule should be reported with. `None` to keep their level.
+    pub level: Option<DiagnosticLevel>,
+
+    /// Globs the path of the file must match. Empty to match all paths.
+    pub paths: Vec<String>,
+
+    /// DB tables this rule applies to. Empty to match all tables and non-table files.
+    pub tables: Vec<String>,
+
+    /// Columns this rule applies to. Empty to match the entire result.
+    pub columns: Vec<String>,
+}
+
+/// This struct contains a rule ready to be matched against diagnostic results.
+struct CompiledRule<'a> {
+    rule: &'a DiagnosticRule,
+    paths: Vec<Regex>,
+}
+
+/// This trait abstracts the parts of a diagnostic report the rules can change.
+trait RuleTarget: Clone {
+
+    /// This function returns the report type of the report, as used in its rule ID.
+    fn get_report_type(&self) -> String;
+
+    /// This function returns a mutable reference to the level of the report.
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel;
+
+    /// This function returns a mutable reference to the cells affected by the report, if it affects cells.
+    fn get_ref_mut_cells(&mut self) -> Option<&mut Vec<(i32, i32)>> {
+        None
+    }
+
+    /// This function returns the path of the report, if it doesn't share the path of its diagnostic.
+    fn get_own_path(&self) -> Option<String> {
+        None
+    }
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation of `DiagnosticsRules`.
+impl DiagnosticsRules {
+
+    /// This function parses the rules from the provided TOML text.
+    ///
+    /// Unknown rule names are not an error here, so old rules don't break diagnostics. Use `get_unknown_rules` to find them.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let rules: Self = toml::from_str(text).map_err(|error| ErrorKind::DiagnosticsRulesInvalid(error.to_string()))?;
+        rules.compile()?;
+        Ok(rules)
+    }
+
+    /// This function returns the rules in TOML format.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// This function loads the rules from the TOML file at the provided path.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_toml(&read_to_string(path)?)
+    }
+
+    /// This function returns the rules that apply to the provided PackFile: the ones in its settings, followed by the ones in the file next to it, if any.
+    pub fn load_for_pack_file(pack_file: &PackFile) -> Result<Self> {
+        let mut rules = pack_file.get_settings().get_diagnostics_rules()?;
+        if let Some(path) = Self::get_path_for_pack_file(pack_file.get_file_path()) {
+            if path.is_file() {
+                rules.rules.append(&mut Self::load(&path)?.rules);
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// This function returns the path of the rules file for the PackFile at the provided path, if the PackFile has a path.
+    pub fn get_path_for_pack_file(pack_file_path: &Path) -> Option<PathBuf> {
+        let stem = pack_file_path.file_stem()?.to_str()?;
+        Some(pack_file_path.with_file_name(format!("{}{}", stem, DIAGNOSTICS_RULES_EXTENSION)))
+    }
+
+    /// This function returns the rule names of these rules that don't match any known rule.
+    pub fn get_unknown_rules(&self) -> Vec<String> {
+        let known_rules = Self::get_known_rule_ids();
+        self.rules.iter()
+            .filter(|rule| rule.rule != ANY_RULE && !known_rules.iter().any(|known| rule.matches_rule_id(known)))
+            .map(|rule| rule.rule.to_owned())
+            .collect()
+    }
+
+    /// This function returns an error with the unknown rule names if there is any.
+    pub fn validate(&self) -> Result<()> {
+        let unknown_rules = self.get_unknown_rules();
+        if unknown_rules.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorKind::DiagnosticsRulesUnknownRules(unknown_rules).into())
+        }
+    }
+
+    /// This function returns the IDs of all the rules the diagnostics can report.
+    ///
+    /// The report types come from the `all()` function of each report type enum, which chains them through an exhaustive match,
+    /// so new report types cannot be left out of here.
+    pub fn get_known_rule_ids() -> Vec<String> {
+        fn rule_ids<T: Display>(diagnostic: &str, report_types: &[T]) -> Vec<String> {
+            report_types.iter().map(|report_type| format!("{}/{}", diagnostic, report_type)).collect()
+        }
+
+        let table_report_types = TableDiagnosticReportType::all();
+        [
+            rule_ids("AnimFragment", &AnimFragmentDiagnosticReportType::all()),
+            rule_ids("Config", &ConfigDiagnosticReportType::all()),
+            rule_ids("DB", &table_report_types),
+            rule_ids("Loc", &table_report_types),
+            rule_ids("Packfile", &PackFileDiagnosticReportType::all()),
+            rule_ids("DependencyManager", &DependencyManagerDiagnosticReportType::all()),
+            rule_ids("LoadOrder", &LoadOrderDiagnosticReportType::all()),
+            rule_ids("RigidModel", &RigidModelDiagnosticReportType::all()),
+            rule_ids("Lua", &LuaDiagnosticReportType::all()),
+        ].concat()
+    }
+
+    /// This function builds the path regexes of the rules, returning an error if any of the globs is invalid.
+    fn compile(&self) -> Result<Vec<CompiledRule<'_>>> {
+        self.rules.iter().map(|rule| Ok(CompiledRule {
+            rule,
+            paths: rule.paths.iter().map(|path| get_path_pattern_regex(path)).collect::<Result<Vec<Regex>>>()?,
+        })).collect()
+    }
+}
+
+/// Implementation of `DiagnosticRule`.
+impl DiagnosticRule {
+
+    /// This function returns if this rule applies to the provided rule ID.
+    fn matches_rule_id(&self, rule_id: &str) -> bool {
+        self.rule == ANY_RULE ||
+            self.rule.eq_ignore_ascii_case(rule_id) ||
+            rule_id.split_once('/').map(|(_, report_type)| self.rule.eq_ignore_ascii_case(report_type)).unwrap_or(false)
+    }
+
+    /// This function returns if this rule applies to the provided table name. Non-table files only match rules without tables.
+    fn matches_table(&self, table_name: Option<&str>) -> bool {
+        self.tables.is_empty() || match table_name {
+            Some(table_name) => self.tables.iter().any(|table| table == table_name || format!("{}_tables", table) == table_name),
+            None => false,
+        }
+    }
+}
+
+/// Implementation of `CompiledRule`.
+impl<'a> CompiledRule<'a> {
+
+    /// This function returns if this rule applies to the provided path.
+    fn matches_path(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|regex| regex.is_match(path))
+    }
+
+    /// This function applies the changes of this rule to a report.
+    fn apply<T: RuleTarget>(&self, report: &mut T, enabled: &mut bool) {
+        if let Some(rule_enabled) = self.rule.enabled {
+            *enabled = rule_enabled;
+        }
+
+        if let Some(level) = self.rule.level {
+            *report.get_ref_mut_level() = level;
+        }
+    }
+}
+
+/// Implementation of `Diagnostics`. Section of functions to apply the rules to the diagnostics.
+impl Diagnostics {
+
+    /// This function applies the provided rules to the current diagnostics, removing the disabled results and changing the levels of the rest.
+    ///
+    /// If a PackFile is provided, its table definitions are used to match the rules with columns.
+    pub fn apply_rules(&mut self, rules: &DiagnosticsRules, pack_file: Option<&PackFile>) -> Result<()> {
+        if rules.rules.is_empty() {
+            return Ok(());
+        }
+
+        let rules = rules.compile()?;
+        for diagnostic in self.get_ref_mut_diagnostics() {
+            let diagnostic_name = diagnostic.to_string();
+            let path = diagnostic.get_path().join("/");
+            let table_name = match diagnostic {
+                DiagnosticType::DB(_) => diagnostic.get_path().get(1).cloned(),
+                _ => None,
+            };
+
+            let columns = match diagnostic {
+                DiagnosticType::DB(_) | DiagnosticType::Loc(_) => pack_file.map(|pack_file| Self::get_column_names(pack_file, diagnostic.get_path())).unwrap_or_default(),
+                _ => vec![],
+            };
+
+            let table_name = table_name.as_deref();
+            match diagnostic {
+                DiagnosticType::AnimFragment(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::DB(ref mut diag) |
+                DiagnosticType::Loc(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::PackFile(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::DependencyManager(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::Config(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::LoadOrder(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::RigidModel(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+                DiagnosticType::Lua(ref mut diag) => apply_rules_to_reports(diag.get_ref_mut_result(), &rules, &diagnostic_name, &path, table_name, &columns),
+            }
+        }
+
+        // Remove the diagnostics that no longer have results.
+        self.get_ref_mut_diagnostics().retain(|diagnostic| match diagnostic {
+            DiagnosticType::AnimFragment(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::DB(ref diag) |
+            DiagnosticType::Loc(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::PackFile(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::DependencyManager(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::Config(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::LoadOrder(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::RigidModel(ref diag) => !diag.get_ref_result().is_empty(),
+            DiagnosticType::Lua(ref diag) => !diag.get_ref_result().is_empty(),
+        });
+
+        Ok(())
+    }
+
+    /// This function returns the names of the columns of the table in the provided path, if it's decoded in the PackFile.
+    fn get_column_names(pack_file: &PackFile, path: &[String]) -> Vec<String> {
+        match pack_file.get_ref_packed_file_by_path(path).map(|packed_file| packed_file.get_ref_decoded()) {
+            Some(DecodedPackedFile::DB(table)) => table.get_ref_definition().get_fields_processed().iter().map(|field| field.get_name().to_owned()).collect(),
+            Some(DecodedPackedFile::Loc(table)) => table.get_ref_definition().get_fields_processed().iter().map(|field| field.get_name().to_owned()).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// This function applies the provided rules to a list of reports of a diagnostic.
+///
+/// Rules with columns only affect the cells in those columns, so the reports affected partially by them are split in two.
+fn apply_rules_to_reports<T: RuleTarget>(reports: &mut Vec<T>, rules: &[CompiledRule], diagnostic_name: &str, path: &str, table_name: Option<&str>, columns: &[String]) {
+    let mut new_reports = Vec::with_capacity(reports.len());
+    for report in reports.drain(..) {
+        let rule_id = format!("{}/{}", diagnostic_name, report.get_report_type());
+        let path = report.get_own_path().unwrap_or_else(|| path.to_owned());
+
+        let mut results = vec![(report, true)];
+        for rule in rules.iter().filter(|rule| rule.rule.matches_rule_id(&rule_id) && rule.matches_path(&path) && rule.rule.matches_table(table_name)) {
+            if rule.rule.columns.is_empty() {
+                results.iter_mut().for_each(|(report, enabled)| rule.apply(report, enabled));
+                continue;
+            }
+
+            let mut split_results = Vec::with_capacity(results.len());
+            for (mut report, enabled) in results {
+                let is_column_in_rule = |column: i32| column >= 0 && columns.get(column as usize).map(|name| rule.rule.columns.contains(name)).unwrap_or(false);
+                let (matched, not_matched): (Vec<(i32, i32)>, Vec<(i32, i32)>) = match report.get_ref_mut_cells() {
+                    Some(cells) => cells.iter().partition(|(_, column)| is_column_in_rule(*column)),
+                    None => (vec![], vec![]),
+                };
+
+                if matched.is_empty() {
+                    split_results.push((report, enabled));
+                } else {
+                    if !not_matched.is_empty() {
+                        let mut not_matched_report = report.clone();
+                        if let Some(cells) = not_matched_report.get_ref_mut_cells() {
+                            *cells = not_matched;
+                        }
+                        split_results.push((not_matched_report, enabled));
+                    }
+
+                    let mut enabled = enabled;
+                    if let Some(cells) = report.get_ref_mut_cells() {
+                        *cells = matched;
+                    }
+                    rule.apply(&mut report, &mut enabled);
+                    split_results.push((report, enabled));
+                }
+            }
+
+            results = split_results;
+        }
+
+        new_reports.extend(results.into_iter().filter_map(|(report, enabled)| if enabled { Some(report) } else { None }));
+    }
+
+    *reports = new_reports;
+}
+
+//-------------------------------------------------------------------------------//
+//                        Implementations of RuleTarget
+//-------------------------------------------------------------------------------//
+
+impl RuleTarget for AnimFragmentDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+    fn get_ref_mut_cells(&mut self) -> Option<&mut Vec<(i32, i32)>> { Some(&mut self.cells_affected) }
+}
+
+impl RuleTarget for TableDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+    fn get_ref_mut_cells(&mut self) -> Option<&mut Vec<(i32, i32)>> { Some(&mut self.cells_affected) }
+}
+
+impl RuleTarget for PackFileDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+}
+
+impl RuleTarget for DependencyManagerDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+    fn get_ref_mut_cells(&mut self) -> Option<&mut Vec<(i32, i32)>> { Some(&mut self.cells_affected) }
+}
+
+impl RuleTarget for ConfigDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+}
+
+impl RuleTarget for LoadOrderDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+    fn get_own_path(&self) -> Option<String> { Some(self.path.join("/")) }
+}
+
+impl RuleTarget for RigidModelDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+}
+
+impl RuleTarget for LuaDiagnosticReport {
+    fn get_report_type(&self) -> String { self.report_type.to_string() }
+    fn get_ref_mut_level(&mut self) -> &mut DiagnosticLevel { &mut self.level }
+}
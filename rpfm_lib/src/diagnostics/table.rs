@@ -40,6 +40,10 @@ pub struct TableDiagnosticReport {
     pub message: String,
     pub report_type: TableDiagnosticReportType,
     pub level: DiagnosticLevel,
+
+    /// Fix that can be applied automatically to solve this result, if any.
+    #[serde(default)]
+    pub fix: Option<TableDiagnosticFix>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +66,37 @@ pub enum TableDiagnosticReportType {
     FieldWithPathNotFound,
     BannedTable,
     ValueCannotBeEmpty,
+    MissingLocData,
+    OrphanLocKey,
+    InvalidEnumValue,
+    InvalidBitwiseValue,
+    ValueOutOfRange,
+}
+
+/// This enum defines the fixes that can be applied automatically to a table diagnostic result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TableDiagnosticFix {
+
+    /// Remove the rows of the affected cells.
+    RemoveRows,
+
+    /// Escape the line jumps and tabulations of the affected cells.
+    EscapeText,
+
+    /// Replace the spaces in the name of the table with underscores.
+    ReplaceSpacesInName,
+
+    /// Add an underscore at the end of the name of the table, so it no longer ends in a number.
+    AddUnderscoreToName,
+
+    /// Update the table to the newest definition available.
+    UpdateTable,
+
+    /// Replace the value of the affected cells with the provided one.
+    ReplaceValue(String),
+
+    /// Clear the bits of the affected bitwise cells that are not declared in the schema.
+    ClearUndeclaredBits,
 }
 
 //---------------------------------------------------------------p----------------//
@@ -90,6 +125,44 @@ impl TableDiagnostic {
     }
 }
 
+impl TableDiagnosticReportType {
+
+    /// This function returns all the report types of this diagnostic, in the same order they're declared.
+    pub fn all() -> Vec<Self> {
+        let mut report_types = vec![Self::OutdatedTable];
+        while let Some(report_type) = match report_types.last() {
+            Some(Self::OutdatedTable) => Some(Self::InvalidReference),
+            Some(Self::InvalidReference) => Some(Self::EmptyRow),
+            Some(Self::EmptyRow) => Some(Self::EmptyKeyField),
+            Some(Self::EmptyKeyField) => Some(Self::EmptyKeyFields),
+            Some(Self::EmptyKeyFields) => Some(Self::DuplicatedCombinedKeys),
+            Some(Self::DuplicatedCombinedKeys) => Some(Self::NoReferenceTableFound),
+            Some(Self::NoReferenceTableFound) => Some(Self::NoReferenceTableNorColumnFoundPak),
+            Some(Self::NoReferenceTableNorColumnFoundPak) => Some(Self::NoReferenceTableNorColumnFoundNoPak),
+            Some(Self::NoReferenceTableNorColumnFoundNoPak) => Some(Self::InvalidEscape),
+            Some(Self::InvalidEscape) => Some(Self::DuplicatedRow),
+            Some(Self::DuplicatedRow) => Some(Self::InvalidLocKey),
+            Some(Self::InvalidLocKey) => Some(Self::TableNameEndsInNumber),
+            Some(Self::TableNameEndsInNumber) => Some(Self::TableNameHasSpace),
+            Some(Self::TableNameHasSpace) => Some(Self::TableIsDataCoring),
+            Some(Self::TableIsDataCoring) => Some(Self::FieldWithPathNotFound),
+            Some(Self::FieldWithPathNotFound) => Some(Self::BannedTable),
+            Some(Self::BannedTable) => Some(Self::ValueCannotBeEmpty),
+            Some(Self::ValueCannotBeEmpty) => Some(Self::MissingLocData),
+            Some(Self::MissingLocData) => Some(Self::OrphanLocKey),
+            Some(Self::OrphanLocKey) => Some(Self::InvalidEnumValue),
+            Some(Self::InvalidEnumValue) => Some(Self::InvalidBitwiseValue),
+            Some(Self::InvalidBitwiseValue) => Some(Self::ValueOutOfRange),
+            Some(Self::ValueOutOfRange) => None,
+            None => None,
+        } {
+            report_types.push(report_type);
+        }
+
+        report_types
+    }
+}
+
 impl Display for TableDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
@@ -111,6 +184,25 @@ impl Display for TableDiagnosticReportType {
             Self::FieldWithPathNotFound => "FieldWithPathNotFound",
             Self::BannedTable => "BannedTable",
             Self::ValueCannotBeEmpty => "ValueCannotBeEmpty",
+            Self::MissingLocData => "MissingLocData",
+            Self::OrphanLocKey => "OrphanLocKey",
+            Self::InvalidEnumValue => "InvalidEnumValue",
+            Self::InvalidBitwiseValue => "InvalidBitwiseValue",
+            Self::ValueOutOfRange => "ValueOutOfRange",
         }, f)
     }
 }
+
+impl Display for TableDiagnosticFix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RemoveRows => Display::fmt("Remove the row.", f),
+            Self::EscapeText => Display::fmt("Escape the line jumps and tabulations.", f),
+            Self::ReplaceSpacesInName => Display::fmt("Replace the spaces in the table name with underscores.", f),
+            Self::AddUnderscoreToName => Display::fmt("Add an underscore at the end of the table name.", f),
+            Self::UpdateTable => Display::fmt("Update the table to the newest definition.", f),
+            Self::ReplaceValue(value) => write!(f, "Replace the value with \"{}\".", value),
+            Self::ClearUndeclaredBits => Display::fmt("Clear the bits not declared in the schema.", f),
+        }
+    }
+}
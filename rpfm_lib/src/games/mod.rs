@@ -23,7 +23,7 @@ use rpfm_macros::*;
 
 use crate::common::{get_files_from_subdir, get_lua_autogen_path};
 use crate::settings::get_config_path;
-use crate::packfile::{Manifest, PFHFileType, PFHVersion};
+use crate::packfile::{CompressionFormat, Manifest, PFHFileType, PFHVersion};
 use crate::SETTINGS;
 
 pub mod supported_games;
@@ -62,6 +62,9 @@ pub struct GameInfo {
     /// It's in a hashmap of PFHFileType => PFHVersion, so we can have different PackFile versions depending on their type.
     pfh_versions: HashMap<PFHFileType, PFHVersion>,
 
+    /// This is the list of compression formats the game can read from PackedFiles. If empty, the game doesn't support compression.
+    compression_formats_supported: Vec<CompressionFormat>,
+
     /// This is the full name of the schema file used for the game. For example: `schema_wh2.ron`.
     schema_file_name: String,
 
@@ -189,6 +192,11 @@ impl GameInfo {
         &self.pfh_versions
     }
 
+    /// This function returns the list of compression formats supported by this game.
+    pub fn get_compression_formats_supported(&self) -> &[CompressionFormat] {
+        &self.compression_formats_supported
+    }
+
     /// This function returns this Game's schema file name.
     pub fn get_schema_name(&self) -> &str {
         &self.schema_file_name
@@ -21,7 +21,7 @@ use std::collections::HashMap;
 
 use rpfm_error::{Result, ErrorKind};
 
-use crate::packfile::{PFHFileType, PFHVersion};
+use crate::packfile::{CompressionFormat, PFHFileType, PFHVersion};
 use super::{GameInfo, VanillaDBTableNameLogic, InstallData, InstallType};
 
 // Display Name for all the Supported Games.
@@ -93,6 +93,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH5);
                 data
             },
+            compression_formats_supported: vec![CompressionFormat::Lzma1, CompressionFormat::Lz4, CompressionFormat::Zstd],
             schema_file_name: "schema_wh3.ron".to_owned(),
             dependencies_cache_file_name: "wh3.pak2".to_owned(),
             raw_db_version: 2,
@@ -171,6 +172,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH5);
                 data
             },
+            compression_formats_supported: vec![CompressionFormat::Lzma1],
             schema_file_name: "schema_troy.ron".to_owned(),
             dependencies_cache_file_name: "troy.pak2".to_owned(),
             raw_db_version: 2,
@@ -245,6 +247,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH5);
                 data
             },
+            compression_formats_supported: vec![CompressionFormat::Lzma1],
             schema_file_name: "schema_3k.ron".to_owned(),
             dependencies_cache_file_name: "3k.pak2".to_owned(),
             raw_db_version: 2,
@@ -420,6 +423,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH5);
                 data
             },
+            compression_formats_supported: vec![CompressionFormat::Lzma1],
             schema_file_name: "schema_wh2.ron".to_owned(),
             dependencies_cache_file_name: "wh2.pak2".to_owned(),
             raw_db_version: 2,
@@ -832,6 +836,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH4);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_wh.ron".to_owned(),
             dependencies_cache_file_name: "wh.pak2".to_owned(),
             raw_db_version: 2,
@@ -986,6 +991,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH4);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_tob.ron".to_owned(),
             dependencies_cache_file_name: "tob.pak2".to_owned(),
             raw_db_version: 2,
@@ -1088,6 +1094,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH4);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_att.ron".to_owned(),
             dependencies_cache_file_name: "att.pak2".to_owned(),
             raw_db_version: 2,
@@ -1161,6 +1168,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH4);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_rom2.ron".to_owned(),
             dependencies_cache_file_name: "rom2.pak2".to_owned(),
             raw_db_version: 2,
@@ -1224,6 +1232,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH2);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_sho2.ron".to_owned(),
             dependencies_cache_file_name: "sho2.pak2".to_owned(),
             raw_db_version: 1,
@@ -1297,6 +1306,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH0);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_nap.ron".to_owned(),
             dependencies_cache_file_name: "nap.pak2".to_owned(),
             raw_db_version: 0,
@@ -1383,6 +1393,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH0);
                 data
             },
+            compression_formats_supported: vec![],
             schema_file_name: "schema_emp.ron".to_owned(),
             dependencies_cache_file_name: "emp.pak2".to_owned(),
             raw_db_version: 0,
@@ -1547,6 +1558,7 @@ impl SupportedGames {
                 data.insert(PFHFileType::Movie, PFHVersion::PFH5);
                 data
             },
+            compression_formats_supported: vec![CompressionFormat::Lzma1],
             schema_file_name: "schema_are.ron".to_owned(),
             dependencies_cache_file_name: "are.pack2".to_owned(),
             raw_db_version: -1,
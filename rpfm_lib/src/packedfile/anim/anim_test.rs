@@ -0,0 +1,75 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Anim` module.
+!*/
+
+use super::*;
+
+/// Test to make sure Anims are saved back to the same bytes, keeping as raw data the frames of the versions we cannot decode.
+#[test]
+fn test_anim_roundtrip() {
+    let mut root = Bone::default();
+    root.set_name("root".to_owned());
+    root.set_parent_id(-1);
+
+    let mut pelvis = Bone::default();
+    pelvis.set_name("bn_pelvis".to_owned());
+    pelvis.set_parent_id(0);
+
+    let mut static_frame = Frame::default();
+    static_frame.set_translations(vec![[0.0, 1.0, 0.0]]);
+
+    let mut frames = vec![];
+    for index in 0..3 {
+        let mut frame = Frame::default();
+        frame.set_rotations(vec![[0, 0, index * 100, i16::MAX], [index, 0, 0, i16::MAX]]);
+        frames.push(frame);
+    }
+
+    let mut tracks = Tracks::default();
+    tracks.set_translation_mappings(vec![0, -1]);
+    tracks.set_rotation_mappings(vec![0, 1]);
+    tracks.set_static_frame(static_frame);
+    tracks.set_frames(frames);
+
+    let mut anim = Anim::new();
+    anim.set_skeleton_name("humanoid01".to_owned());
+    anim.set_end_time(0.1);
+    anim.set_bones(vec![root, pelvis]);
+    anim.set_tracks(AnimTracks::Decoded(tracks.clone()));
+
+    let data = anim.save().unwrap();
+    let decoded = Anim::read(&data).unwrap();
+    assert!(decoded.is_decoded());
+    assert_eq!(decoded, anim);
+    assert_eq!(decoded.get_frame_count(), Some(3));
+    assert_eq!(decoded.save().unwrap(), data);
+    assert_eq!(Anim::read_header(&data).unwrap().get_ref_skeleton_name(), "humanoid01");
+
+    // All frames must have the same amount of tracks.
+    tracks.get_ref_mut_frames()[1].get_ref_mut_rotations().pop();
+    anim.set_tracks(AnimTracks::Decoded(tracks));
+    assert!(anim.save().is_err());
+
+    // Newer versions have flags, and their tracks are kept as they are.
+    anim.set_version(7);
+    anim.set_flags(vec!["frame_data_compressed".to_owned()]);
+    anim.set_tracks(AnimTracks::Raw(vec![1, 2, 3, 4]));
+    let data = anim.save().unwrap();
+    let decoded = Anim::read(&data).unwrap();
+    assert!(!decoded.is_decoded());
+    assert_eq!(decoded, anim);
+    assert_eq!(decoded.save().unwrap(), data);
+
+    // Truncated files must fail, not panic.
+    assert!(Anim::read(&data[..10]).is_err());
+}
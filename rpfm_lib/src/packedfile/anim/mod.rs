@@ -0,0 +1,393 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with Anim PackedFiles.
+
+Anims are the binary animations used by skeletons, usually found inside AnimPacks. Their structure is:
+
+- Header: version, an unknown value (always 1), frame rate, skeleton name, (since version 7) flags and end time.
+- Bone table: amount of bones, and the name and parent id of each bone. Root bones have -1 as parent.
+- Frame tracks: translation and rotation mappings for each bone, a static frame and the dynamic frames.
+
+Each frame contains translations (3 floats) and rotations (a quaternion quantized to 4 i16). The mapping of each bone
+points to its track in the frames, or is negative if the bone doesn't use one.
+
+Versions newer than 6 use quantized tracks split in parts we cannot decode yet. Their tracks, and any tracks
+that don't encode back to the same bytes, are kept as raw data, so they can still be saved back.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::{GetRef, GetRefMut, Set};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+#[cfg(test)]
+mod anim_test;
+
+/// Extension of Anim PackedFiles.
+pub const EXTENSION: &str = ".anim";
+
+/// Range of versions we can decode the frame tracks of.
+const MIN_SUPPORTED_VERSION: u32 = 5;
+const MAX_SUPPORTED_VERSION: u32 = 6;
+
+/// First version with flags in its header.
+const VERSION_FLAGS: u32 = 7;
+
+/// Value of the unknown field of the header in all the files we've seen.
+const DEFAULT_UNKNOWN: u32 = 1;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This holds an entire Anim PackedFile decoded in memory.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Anim {
+    version: u32,
+    unknown: u32,
+    frame_rate: f32,
+    skeleton_name: String,
+
+    /// Only used since version 7.
+    flags: Vec<String>,
+
+    /// Time, in seconds, when the animation ends.
+    end_time: f32,
+    bones: Vec<Bone>,
+    tracks: AnimTracks,
+}
+
+/// This struct represents a bone of the skeleton the animation is for.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Bone {
+    name: String,
+
+    /// Index of the parent bone in the bone table. -1 for root bones.
+    parent_id: i32,
+}
+
+/// This enum represents the frame tracks of an Anim.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum AnimTracks {
+
+    /// Tracks we could decode.
+    Decoded(Tracks),
+
+    /// Data after the bone table we couldn't decode.
+    Raw(Vec<u8>),
+}
+
+/// This struct represents the decoded frame tracks of an Anim.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Tracks {
+
+    /// One per bone. Index of the translation track of each bone, or negative if it has none.
+    translation_mappings: Vec<i32>,
+
+    /// One per bone. Index of the rotation track of each bone, or negative if it has none.
+    rotation_mappings: Vec<i32>,
+
+    /// Frame with the tracks that don't change during the animation.
+    static_frame: Frame,
+
+    /// Frames with the tracks that change during the animation. All of them must have the same amount of tracks.
+    frames: Vec<Frame>,
+}
+
+/// This struct represents a frame of an animation.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Frame {
+    translations: Vec<[f32; 3]>,
+
+    /// Quaternions, in XYZW order, quantized to the full range of an i16.
+    rotations: Vec<[i16; 4]>,
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of Anim
+//---------------------------------------------------------------------------//
+
+/// Default implementation of `Anim`.
+impl Default for Anim {
+    fn default() -> Self {
+        Self {
+            version: MAX_SUPPORTED_VERSION,
+            unknown: DEFAULT_UNKNOWN,
+            frame_rate: 20.0,
+            skeleton_name: String::new(),
+            flags: vec![],
+            end_time: 0.0,
+            bones: vec![],
+            tracks: AnimTracks::default(),
+        }
+    }
+}
+
+/// Implementation of `Anim`.
+impl Anim {
+
+    /// This function creates a new empty `Anim`. Akin to `default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This function creates an `Anim` from a `&[u8]`.
+    ///
+    /// If the frame tracks of the file cannot be decoded, they're kept as raw data.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let mut index = 0;
+        let mut anim = Self::read_header_and_bones(packed_file_data, &mut index)?;
+        let data = &packed_file_data[index..];
+
+        // Only keep the decoded tracks if they encode back to the same data.
+        anim.tracks = if (MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&anim.version) {
+            let mut index = 0;
+            match Tracks::read(data, &mut index, anim.bones.len()) {
+                Ok(tracks) if index == data.len() && tracks.save(anim.bones.len()).ok().as_deref() == Some(data) => AnimTracks::Decoded(tracks),
+                _ => AnimTracks::Raw(data.to_vec()),
+            }
+        } else {
+            AnimTracks::Raw(data.to_vec())
+        };
+
+        Ok(anim)
+    }
+
+    /// This function reads only the header and the bone table of an Anim, leaving its tracks empty.
+    ///
+    /// Use it when you only need the metadata of an Anim, like its skeleton.
+    pub fn read_header(packed_file_data: &[u8]) -> Result<Self> {
+        Self::read_header_and_bones(packed_file_data, &mut 0)
+    }
+
+    /// This function decodes the header and the bone table of an Anim, moving the provided index to the byte where the tracks start.
+    fn read_header_and_bones(data: &[u8], index: &mut usize) -> Result<Self> {
+        let version = data.decode_packedfile_integer_u32(*index, index)?;
+        let unknown = data.decode_packedfile_integer_u32(*index, index)?;
+        let frame_rate = data.decode_packedfile_float_f32(*index, index)?;
+        let skeleton_name = data.decode_packedfile_string_u8(*index, index)?;
+
+        let mut flags = vec![];
+        if version >= VERSION_FLAGS {
+            for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+                flags.push(data.decode_packedfile_string_u8(*index, index)?);
+            }
+        }
+
+        let end_time = data.decode_packedfile_float_f32(*index, index)?;
+
+        // Each bone needs at least 6 bytes, so any count bigger than that is corrupted data.
+        let bone_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+        if bone_count > data.len().saturating_sub(*index) / 6 {
+            return Err(ErrorKind::AnimDecode(format!("This Anim says it has {} bones, but there is no space for them in the file.", bone_count)).into());
+        }
+
+        let mut bones = Vec::with_capacity(bone_count);
+        for _ in 0..bone_count {
+            bones.push(Bone {
+                name: data.decode_packedfile_string_u8(*index, index)?,
+                parent_id: data.decode_packedfile_integer_i32(*index, index)?,
+            });
+        }
+
+        Ok(Self {
+            version,
+            unknown,
+            frame_rate,
+            skeleton_name,
+            flags,
+            end_time,
+            bones,
+            tracks: AnimTracks::default(),
+        })
+    }
+
+    /// This function takes an `Anim` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        if self.version < VERSION_FLAGS && !self.flags.is_empty() {
+            return Err(ErrorKind::AnimEncode(format!("Anims of version {} cannot have flags.", self.version)).into());
+        }
+
+        let mut data = vec![];
+        data.encode_integer_u32(self.version);
+        data.encode_integer_u32(self.unknown);
+        data.encode_float_f32(self.frame_rate);
+        data.encode_packedfile_string_u8(&self.skeleton_name);
+
+        if self.version >= VERSION_FLAGS {
+            data.encode_integer_u32(self.flags.len() as u32);
+            self.flags.iter().for_each(|flag| data.encode_packedfile_string_u8(flag));
+        }
+
+        data.encode_float_f32(self.end_time);
+
+        data.encode_integer_u32(self.bones.len() as u32);
+        for bone in &self.bones {
+            data.encode_packedfile_string_u8(&bone.name);
+            data.encode_integer_i32(bone.parent_id);
+        }
+
+        match self.tracks {
+            AnimTracks::Decoded(ref tracks) => {
+                if !(MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&self.version) {
+                    return Err(ErrorKind::AnimEncode(format!("Tracks of Anims of version {} cannot be encoded.", self.version)).into());
+                }
+
+                data.append(&mut tracks.save(self.bones.len())?);
+            }
+            AnimTracks::Raw(ref raw_data) => data.extend_from_slice(raw_data),
+        }
+
+        Ok(data)
+    }
+
+    /// This function returns if the tracks of this `Anim` have been decoded.
+    pub fn is_decoded(&self) -> bool {
+        matches!(self.tracks, AnimTracks::Decoded(_))
+    }
+
+    /// This function returns the amount of frames of this `Anim`, if its tracks have been decoded.
+    pub fn get_frame_count(&self) -> Option<usize> {
+        match self.tracks {
+            AnimTracks::Decoded(ref tracks) => Some(tracks.frames.len()),
+            AnimTracks::Raw(_) => None,
+        }
+    }
+}
+
+/// Default implementation of `AnimTracks`.
+impl Default for AnimTracks {
+    fn default() -> Self {
+        Self::Raw(vec![])
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of Tracks
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Tracks`.
+impl Tracks {
+
+    /// This function decodes the tracks of an Anim with the provided amount of bones, moving the provided index to the end of them.
+    fn read(data: &[u8], index: &mut usize, bone_count: usize) -> Result<Self> {
+        let mut translation_mappings = Vec::with_capacity(bone_count);
+        for _ in 0..bone_count {
+            translation_mappings.push(data.decode_packedfile_integer_i32(*index, index)?);
+        }
+
+        let mut rotation_mappings = Vec::with_capacity(bone_count);
+        for _ in 0..bone_count {
+            rotation_mappings.push(data.decode_packedfile_integer_i32(*index, index)?);
+        }
+
+        let translation_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+        let rotation_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+        let static_frame = Frame::read(data, index, translation_count, rotation_count)?;
+
+        let frame_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+        let translation_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+        let rotation_count = data.decode_packedfile_integer_u32(*index, index)? as usize;
+
+        // Do not trust the counts of corrupted files. Frames without tracks take no space, so we count them as one byte.
+        let frame_size = (translation_count * 12 + rotation_count * 8).max(1);
+        if frame_count > data.len().saturating_sub(*index) / frame_size {
+            return Err(ErrorKind::AnimDecode(format!("This Anim says it has {} frames, but there is no space for them in the file.", frame_count)).into());
+        }
+
+        let mut frames = Vec::with_capacity(frame_count);
+        for _ in 0..frame_count {
+            frames.push(Frame::read(data, index, translation_count, rotation_count)?);
+        }
+
+        Ok(Self {
+            translation_mappings,
+            rotation_mappings,
+            static_frame,
+            frames,
+        })
+    }
+
+    /// This function encodes the tracks of an Anim with the provided amount of bones to `Vec<u8>`.
+    fn save(&self, bone_count: usize) -> Result<Vec<u8>> {
+        if self.translation_mappings.len() != bone_count || self.rotation_mappings.len() != bone_count {
+            return Err(ErrorKind::AnimEncode(format!("The Anim has {} bones, but {} translation mappings and {} rotation mappings.", bone_count, self.translation_mappings.len(), self.rotation_mappings.len())).into());
+        }
+
+        let mut data = vec![];
+        self.translation_mappings.iter().for_each(|mapping| data.encode_integer_i32(*mapping));
+        self.rotation_mappings.iter().for_each(|mapping| data.encode_integer_i32(*mapping));
+
+        data.encode_integer_u32(self.static_frame.translations.len() as u32);
+        data.encode_integer_u32(self.static_frame.rotations.len() as u32);
+        self.static_frame.save(&mut data);
+
+        let (translation_count, rotation_count) = match self.frames.first() {
+            Some(frame) => (frame.translations.len(), frame.rotations.len()),
+            None => (0, 0),
+        };
+
+        if let Some(frame) = self.frames.iter().position(|frame| frame.translations.len() != translation_count || frame.rotations.len() != rotation_count) {
+            return Err(ErrorKind::AnimEncode(format!("Frame {} doesn't have the same amount of tracks as the first frame.", frame)).into());
+        }
+
+        data.encode_integer_u32(self.frames.len() as u32);
+        data.encode_integer_u32(translation_count as u32);
+        data.encode_integer_u32(rotation_count as u32);
+        self.frames.iter().for_each(|frame| frame.save(&mut data));
+
+        Ok(data)
+    }
+}
+
+/// Implementation of `Frame`.
+impl Frame {
+
+    /// This function decodes a frame with the provided amount of tracks.
+    fn read(data: &[u8], index: &mut usize, translation_count: usize, rotation_count: usize) -> Result<Self> {
+        if translation_count * 12 + rotation_count * 8 > data.len().saturating_sub(*index) {
+            return Err(ErrorKind::AnimDecode(format!("The frame at byte {} is bigger than the file.", index)).into());
+        }
+
+        let mut translations = Vec::with_capacity(translation_count);
+        for _ in 0..translation_count {
+            translations.push([
+                data.decode_packedfile_float_f32(*index, index)?,
+                data.decode_packedfile_float_f32(*index, index)?,
+                data.decode_packedfile_float_f32(*index, index)?,
+            ]);
+        }
+
+        let mut rotations = Vec::with_capacity(rotation_count);
+        for _ in 0..rotation_count {
+            rotations.push([
+                data.decode_packedfile_integer_i16(*index, index)?,
+                data.decode_packedfile_integer_i16(*index, index)?,
+                data.decode_packedfile_integer_i16(*index, index)?,
+                data.decode_packedfile_integer_i16(*index, index)?,
+            ]);
+        }
+
+        Ok(Self {
+            translations,
+            rotations,
+        })
+    }
+
+    /// This function encodes a frame, appending it to the provided data.
+    fn save(&self, data: &mut Vec<u8>) {
+        self.translations.iter().flatten().for_each(|value| data.encode_float_f32(*value));
+        self.rotations.iter().flatten().for_each(|value| data.encode_integer_i16(*value));
+    }
+}
@@ -17,6 +17,7 @@ use std::collections::BTreeMap;
 use rpfm_error::{ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
+use crate::packfile::compression::{compress_data_lzma1_raw, decompress_data_lzma1_raw};
 
 use super::*;
 
@@ -74,13 +75,16 @@ impl ESF {
         offset = nodes_offset;
 
         // This file is a big tree hanging from the root node, so just decode everything recursively.
-        let root_node = Self::read_node(packed_file_data, &mut offset, true, &record_names, &strings_utf8, &strings_utf16)?;
+        let mut root_node = Self::read_node(packed_file_data, &mut offset, true, &record_names, &strings_utf8, &strings_utf16)?;
 
         // If we're not at the exact end of the nodes, something failed.
         if offset != record_names_offset as usize {
             return Err(ErrorKind::ESFIncompleteDecoding.into());
         }
 
+        // Compressed nodes contain entire ESF files, so decode them too.
+        Self::decompress_nodes(&mut root_node);
+
         let esf = Self{
             signature,
             unknown_1,
@@ -92,13 +96,13 @@ impl ESF {
         // Re-encodes the decoded file and saves it to disk.
         //use std::io::Write;
         //let mut x = std::fs::File::create("encoded_starpos.esf")?;
-        //x.write_all(&esf.save())?;
+        //x.write_all(&esf.save()?)?;
 
         Ok(esf)
     }
 
     /// This function takes a `ESF` of type CAAB and encodes it to `Vec<u8>`.
-    pub(crate) fn save_caab(&self) -> Vec<u8> {
+    pub(crate) fn save_caab(&self) -> Result<Vec<u8>> {
         let mut data = vec![];
 
         // Encode the header info, except the offsets, because those are calculated later.
@@ -106,14 +110,17 @@ impl ESF {
         data.encode_integer_u32(self.unknown_1);
         data.encode_integer_u32(self.creation_date);
 
+        // Compressed nodes are kept decompressed in memory, so compress them again before anything else.
+        let root_node = Self::compress_nodes(&self.root_node, self.unknown_1, self.creation_date)?;
+
         // First, get the strings encoded, as we need to have them in order before encoding the nodes.
         let mut record_names = vec![];
         let mut strings_utf8 = vec![];
         let mut strings_utf16 = vec![];
-        Self::read_string_from_node(&self.root_node, &mut record_names, &mut strings_utf8, &mut strings_utf16);
+        Self::read_string_from_node(&root_node, &mut record_names, &mut strings_utf8, &mut strings_utf16);
 
         // Next, encode the nodes. We need them (and the strings) encoded in order to know their offsets.
-        let mut nodes_data = Self::save_node(&self.root_node, true, &record_names, &strings_utf8, &strings_utf16);
+        let mut nodes_data = Self::save_node(&root_node, true, &record_names, &strings_utf8, &strings_utf16)?;
 
         // Then, encode the strings.
         let mut strings_data: Vec<u8> = vec![];
@@ -142,11 +149,11 @@ impl ESF {
         data.encode_integer_u32((data.len() + nodes_data.len() + 4) as u32);
         data.append(&mut nodes_data);
         data.append(&mut strings_data);
-        data
+        Ok(data)
     }
 
     /// This function takes care of reading a node's data into the appropriate NodeType.
-    fn read_node(
+    pub(crate) fn read_node(
         packed_file_data: &[u8],
         mut offset: &mut usize,
         is_root_node: bool,
@@ -228,6 +235,7 @@ impl ESF {
                 version,
                 name,
                 children,
+                compressed_data: None,
             };
 
             NodeType::Record(node_data)
@@ -715,13 +723,13 @@ impl ESF {
         Ok(node_type)
     }
 
-    /// This function takes care of reading a node's data into the appropriate NodeType.
-    fn save_node(node_type: &NodeType, is_root_node: bool, record_names: &[String], strings_utf8: &[String], strings_utf16: &[String]) -> Vec<u8> {
+    /// This function takes care of encoding a NodeType into its binary data.
+    pub(crate) fn save_node(node_type: &NodeType, is_root_node: bool, record_names: &[String], strings_utf8: &[String], strings_utf16: &[String]) -> Result<Vec<u8>> {
         let mut data = vec![];
         match node_type {
 
-            // Crash with this for now.
-            NodeType::Invalid => unimplemented!(),
+            // Invalid nodes cannot be encoded.
+            NodeType::Invalid => return Err(ErrorKind::ESFUnsupportedDataType(format!("{}", INVALID)).into()),
 
             //------------------------------------------------//
             // Primitive nodes.
@@ -931,26 +939,21 @@ impl ESF {
             NodeType::I32Array(value) => {
                 let mut list = vec![];
                 if *value.get_ref_optimized() {
-                    if let Some(max_value) = value.get_ref_value().iter().max() {
-                        if let Some(min_value) = value.get_ref_value().iter().min() {
-                            let max_value = std::cmp::max(min_value.abs(), max_value.abs());
-
-                            if max_value == 0 {
-                                data.push(I32_ZERO_ARRAY);
-                            } else if max_value <= i8::MAX as i32 {
-                                data.push(I32_BYTE_ARRAY);
-                                value.get_ref_value().iter().for_each(|x| list.encode_integer_i8(*x as i8));
-                            } else if max_value <= i16::MAX as i32 {
-                                data.push(I32_16BIT_ARRAY);
-                                value.get_ref_value().iter().for_each(|x| list.encode_integer_i16(*x as i16));
-                            } else if max_value <= 8_388_607 {
-                                data.push(I32_24BIT_ARRAY);
-                                value.get_ref_value().iter().for_each(|x| list.encode_integer_i24(*x));
-                            } else {
-                                data.push(I32_ARRAY);
-                                value.get_ref_value().iter().for_each(|x| list.encode_integer_i32(*x));
-                            }
-                        }
+
+                    // Empty arrays and arrays of zeros are encoded as arrays of bytes, as there is no way to know the length of a zero array.
+                    let max_value = value.get_ref_value().iter().map(|x| x.unsigned_abs()).max().unwrap_or(0);
+                    if max_value <= i8::MAX as u32 {
+                        data.push(I32_BYTE_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_i8(*x as i8));
+                    } else if max_value <= i16::MAX as u32 {
+                        data.push(I32_16BIT_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_i16(*x as i16));
+                    } else if max_value <= 8_388_607 {
+                        data.push(I32_24BIT_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_i24(*x));
+                    } else {
+                        data.push(I32_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_i32(*x));
                     }
                 } else {
                     data.push(I32_ARRAY);
@@ -988,22 +991,21 @@ impl ESF {
             NodeType::U32Array(value) => {
                 let mut list = vec![];
                 if *value.get_ref_optimized() {
-                    if let Some(max_value) = value.get_ref_value().iter().max() {
-                        if *max_value == 0 {
-                            data.push(U32_ZERO_ARRAY);
-                        } else if max_value < &0xFF {
-                            data.push(U32_BYTE_ARRAY);
-                            value.get_ref_value().iter().for_each(|x| list.push(*x as u8));
-                        } else if max_value < &0xFFFF {
-                            data.push(U32_16BIT_ARRAY);
-                            value.get_ref_value().iter().for_each(|x| list.encode_integer_u16(*x as u16));
-                        } else if max_value < &0xFFFFFF {
-                            data.push(U32_24BIT_ARRAY);
-                            value.get_ref_value().iter().for_each(|x| list.encode_integer_u24(*x));
-                        } else {
-                            data.push(U32_ARRAY);
-                            value.get_ref_value().iter().for_each(|x| list.encode_integer_u32(*x));
-                        }
+
+                    // Empty arrays and arrays of zeros are encoded as arrays of bytes, as there is no way to know the length of a zero array.
+                    let max_value = value.get_ref_value().iter().max().unwrap_or(&0);
+                    if max_value < &0xFF {
+                        data.push(U32_BYTE_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.push(*x as u8));
+                    } else if max_value < &0xFFFF {
+                        data.push(U32_16BIT_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_u16(*x as u16));
+                    } else if max_value < &0xFFFFFF {
+                        data.push(U32_24BIT_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_u24(*x));
+                    } else {
+                        data.push(U32_ARRAY);
+                        value.get_ref_value().iter().for_each(|x| list.encode_integer_u32(*x));
                     }
                 } else {
                     data.push(U32_ARRAY);
@@ -1078,7 +1080,7 @@ impl ESF {
                     list.encode_integer_u32(strings_utf16.iter().position(|x| x == y).unwrap() as u32);
                 });
 
-                data.encode_integer_u32(list.len() as u32);
+                data.encode_integer_cauleb128(list.len() as u32);
                 data.extend_from_slice(&list);
             },
             NodeType::AsciiArray(value) => {
@@ -1131,7 +1133,7 @@ impl ESF {
                     for group_node in &value.children {
                         let mut group_node_data = vec![];
                         for node in group_node {
-                            let child_node = Self::save_node(node, false, record_names, strings_utf8, strings_utf16);
+                            let child_node = Self::save_node(node, false, record_names, strings_utf8, strings_utf16)?;
                             group_node_data.extend_from_slice(&child_node);
                         }
 
@@ -1146,7 +1148,7 @@ impl ESF {
                     // For non-nested nodes, we just get the first and only children group.
                     if let Some(children) = value.children.get(0) {
                         for node in children {
-                            let child_node = Self::save_node(node, false, record_names, strings_utf8, strings_utf16);
+                            let child_node = Self::save_node(node, false, record_names, strings_utf8, strings_utf16)?;
                             children_data.extend_from_slice(&child_node);
                         }
                     }
@@ -1157,7 +1159,7 @@ impl ESF {
             },
         }
 
-        data
+        Ok(data)
     }
 
     //---------------------------------------------------------------------------//
@@ -1167,7 +1169,7 @@ impl ESF {
     /// This function reads the strings from the provided node and all its children.
     ///
     /// This function is recursive: if you pass it the root node, it'll read all the strings in the ESF file.
-    fn read_string_from_node(node_type: &NodeType, record_names: &mut Vec<String>, strings_utf8: &mut Vec<String>, strings_utf16: &mut Vec<String>) {
+    pub(crate) fn read_string_from_node(node_type: &NodeType, record_names: &mut Vec<String>, strings_utf8: &mut Vec<String>, strings_utf16: &mut Vec<String>) {
         match node_type {
             NodeType::Utf16(value) => if !strings_utf16.contains(value) { strings_utf16.push(value.to_owned()) },
             NodeType::Ascii(value) => if !strings_utf8.contains(value) { strings_utf8.push(value.to_owned()) },
@@ -1189,78 +1191,143 @@ impl ESF {
         }
     }
 
-    /*
-    fn decompress_compressed_data(data: &[u8], uncompressed_size: Option<&u32>, properties: &[u8]) -> Result<Vec<u8>> {
-        use xz2::read::XzDecoder;
-        use xz2::write::XzEncoder;
-        use xz2::stream::Stream;
-        use xz2::stream::LzmaOptions;
-        use xz2::stream::Action;
-        use std::io::{BufReader, Read, SeekFrom};
-
-
-
-            //int lc = properties[0] % 9;
-            //int remainder = properties[0] / 9;
-            //int lp = remainder % 5;
-            //int pb = remainder / 5;
-            //if (pb > Base.kNumPosStatesBitsMax)
-            //    throw new InvalidParamException();
-            //UInt32 dictionarySize = 0;
-            //for (int i = 0; i < 4; i++)
-            //    dictionarySize += ((UInt32)(properties[1 + i])) << (i * 8);
-            //SetDictionarySize(dictionarySize);
-            //SetLiteralProperties(lp, lc);
-            //SetPosBitsProperties(pb);
-
-
-        if properties.len() != 5 {
-            return Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into());
+    /// This function decompress in place all the compressed nodes within the provided node, replacing their data with the decoded nodes.
+    ///
+    /// The decompressed data is a full ESF file, so its root node replaces the compressed data, while the info record is kept
+    /// next to it. The header of the decompressed file and the original compressed data are kept in the node, so it can be saved
+    /// back unchanged if it's not edited. If a compressed node fails to decompress, it's left as it is.
+    fn decompress_nodes(node_type: &mut NodeType) {
+        if let NodeType::Record(node) = node_type {
+            if node.name == COMPRESSED_DATA_TAG {
+                if let Ok((children, compressed_data)) = Self::decompress_children(&node.children) {
+                    node.children = children;
+                    node.compressed_data = Some(Box::new(compressed_data));
+                }
+            } else {
+                node.children.iter_mut().flatten().for_each(Self::decompress_nodes);
+            }
         }
+    }
 
-        let lc = properties[0] % 9;
-        let remainder = properties[0] / 9;
-        let lp = remainder % 5;
-        let pb = remainder / 5;
+    /// This function decompress the children of a compressed node, returning the decoded root node and the info record, and the original data of the node.
+    fn decompress_children(children: &[Vec<NodeType>]) -> Result<(Vec<Vec<NodeType>>, CompressedNodeData)> {
+        let nodes = children.first().ok_or(ErrorKind::ESFIncompleteDecoding)?;
+        if let (Some(NodeType::U8Array(data)), Some(NodeType::Record(info))) = (nodes.first(), nodes.get(1)) {
+            let esf = Self::read(&Self::decompress_data(data, info)?)?;
+            let compressed_data = CompressedNodeData {
+                signature: esf.signature,
+                unknown_1: esf.unknown_1,
+                creation_date: esf.creation_date,
+                data: data.to_vec(),
+            };
 
-        let mut dictionary_size: u32 = 0;
-        for (index, property) in properties[1..].iter().enumerate() {
-            dictionary_size += (*property as u32) << (index * 8);
+            return Ok((vec![vec![esf.root_node, NodeType::Record(info.clone())]], compressed_data));
         }
 
-        let mut lzma_options = LzmaOptions::new_preset(6).unwrap();
-        lzma_options.dict_size(dictionary_size);
-        lzma_options.position_bits(pb as u32);
-        lzma_options.literal_position_bits(lp as u32);
-        lzma_options.literal_context_bits(lc as u32);
-
-        let mut output = vec![];
-        let mut encoder_stream = Stream::new_lzma_encoder(&lzma_options).unwrap();
-        {
-            let mut encoder = XzEncoder::new_stream(&mut output, encoder_stream);
-            let _ = encoder.write(&[]);
-        }
-        dbg!(&output);
+        Err(ErrorKind::ESFIncompleteDecoding.into())
+    }
 
+    /// This function decompress the data of a compressed node, using the size and properties of its info record.
+    fn decompress_data(data: &[u8], info: &RecordNode) -> Result<Vec<u8>> {
+        let info_nodes = info.children.first().ok_or(ErrorKind::ESFIncompleteDecoding)?;
+        if let (Some(NodeType::U32(size)), Some(NodeType::U8Array(properties))) = (info_nodes.first(), info_nodes.get(1)) {
+            decompress_data_lzma1_raw(data, properties, size.value)
+        } else {
+            Err(ErrorKind::ESFIncompleteDecoding.into())
+        }
+    }
 
-        let mut stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+    /// This function returns a copy of the provided node with all its decompressed nodes compressed again.
+    ///
+    /// The header of the compressed ESF files is the one they had when decompressed. New compressed nodes take it from the parent file.
+    fn compress_nodes(node_type: &NodeType, unknown_1: u32, creation_date: u32) -> Result<NodeType> {
+        match node_type {
+            NodeType::Record(node) => {
+                let is_decompressed = matches!(node.children.first().and_then(|nodes| nodes.first()), Some(NodeType::Record(_)));
+                let children = if node.name == COMPRESSED_DATA_TAG && is_decompressed {
+                    Self::compress_children(&node.children, node.compressed_data.as_deref(), unknown_1, creation_date)?
+                } else {
+                    node.children.iter()
+                        .map(|nodes| nodes.iter()
+                            .map(|node| Self::compress_nodes(node, unknown_1, creation_date))
+                            .collect::<Result<Vec<_>>>())
+                        .collect::<Result<Vec<_>>>()?
+                };
 
-        dbg!(&output);
-        output.extend_from_slice(data);
+                Ok(NodeType::Record(RecordNode {
+                    record_flags: node.record_flags,
+                    version: node.version,
+                    name: node.name.to_owned(),
+                    children,
+                    compressed_data: None,
+                }))
+            }
+            _ => Ok(node_type.clone()),
+        }
+    }
 
-                use std::io::Write;
-        let mut x = std::fs::File::create("compressed_esf_data.lzma")?;
-        x.write_all(&output)?;
-        let mut encoder = XzDecoder::new_stream(&*output, stream);
-        let mut compressed_data = match uncompressed_size {
-            Some(size) => Vec::with_capacity(*size as usize),
-            None => vec![],
+    /// This function compress the children of a decompressed node, returning the compressed data and the updated info record.
+    ///
+    /// If the decompressed nodes encode to the same data that was originally decompressed, the original compressed data is reused,
+    /// so files are not changed just by opening and saving them.
+    fn compress_children(children: &[Vec<NodeType>], original: Option<&CompressedNodeData>, unknown_1: u32, creation_date: u32) -> Result<Vec<Vec<NodeType>>> {
+        let nodes = &children[0];
+        let esf = match original {
+            Some(original) => Self {
+                signature: original.signature,
+                unknown_1: original.unknown_1,
+                creation_date: original.creation_date,
+                root_node: nodes[0].clone(),
+            },
+            None => Self {
+                signature: ESFSignature::CAAB,
+                unknown_1,
+                creation_date,
+                root_node: nodes[0].clone(),
+            },
         };
 
-        match encoder.read_to_end(&mut compressed_data) {
-            Ok(_) => Ok(compressed_data),
-            Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into())
+        let data = esf.save()?;
+        if let (Some(original), Some(NodeType::Record(info))) = (original, nodes.get(1)) {
+            if matches!(Self::decompress_data(&original.data, info), Ok(original_data) if original_data == data) {
+                return Ok(vec![vec![NodeType::U8Array(original.data.to_vec()), NodeType::Record(info.clone())]]);
+            }
         }
-    }*/
+
+        let (compressed_data, properties) = compress_data_lzma1_raw(&data);
+
+        // Keep the info record metadata if we have it, and just update the size and properties.
+        let mut info = match nodes.get(1) {
+            Some(NodeType::Record(info)) => RecordNode {
+                record_flags: info.record_flags,
+                version: info.version,
+                name: info.name.to_owned(),
+                children: vec![],
+                compressed_data: None,
+            },
+            _ => RecordNode {
+                record_flags: RecordNodeFlags::IS_RECORD_NODE,
+                version: 0,
+                name: COMPRESSED_DATA_INFO_TAG.to_owned(),
+                children: vec![],
+                compressed_data: None,
+            },
+        };
+
+        let optimized = match nodes.get(1) {
+            Some(NodeType::Record(info)) => matches!(info.children.first().and_then(|nodes| nodes.first()), Some(NodeType::U32(size)) if size.optimized),
+            _ => false,
+        };
+
+        info.children = vec![vec![
+            NodeType::U32(U32Node {
+                value: data.len() as u32,
+                optimized,
+            }),
+            NodeType::U8Array(properties),
+        ]];
+
+        Ok(vec![vec![NodeType::U8Array(compressed_data), NodeType::Record(info)]])
+    }
 }
 
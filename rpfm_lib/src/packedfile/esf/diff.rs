@@ -0,0 +1,305 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to compare two `ESF` files.
+
+Nodes are reported by their path, using the same syntax as the queries, so they can be queried later:
+
+- Record nodes are matched by name and, if there are multiple records with the same name, by their order.
+- Groups of records with nested blocks are matched by name, so groups with keys are matched by key.
+- Values are matched by their order within their record or group, ignoring how they're encoded.
+
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::{fmt, fmt::Display};
+
+use super::*;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the differences between two `ESF` files.
+///
+/// All the changes are from the point of view of the first (old) `ESF`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ESFDiff {
+
+    /// Nodes only present in the new `ESF`.
+    added: Vec<NodeDiff>,
+
+    /// Nodes only present in the old `ESF`.
+    removed: Vec<NodeDiff>,
+
+    /// Values present in both `ESF`, but different.
+    modified: Vec<NodeDiff>,
+}
+
+/// This struct contains the change of a single node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeDiff {
+
+    /// Path of the node, as a query.
+    path: String,
+
+    /// Old value of the node. None if the node was added, or if it's a record.
+    old_value: Option<String>,
+
+    /// New value of the node. None if the node was removed, or if it's a record.
+    new_value: Option<String>,
+}
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESF`. Section of functions to compare ESF files.
+impl ESF {
+
+    /// This function compares this `ESF` with the provided one, returning their differences.
+    pub fn diff(&self, other: &Self) -> ESFDiff {
+        ESFDiff::new(self, other)
+    }
+}
+
+/// Implementation of `ESFDiff`.
+impl ESFDiff {
+
+    /// This function compares two `ESF` files, returning their differences.
+    pub fn new(old: &ESF, new: &ESF) -> Self {
+        let mut diff = Self::default();
+        match (&old.root_node, &new.root_node) {
+            (NodeType::Record(old_node), NodeType::Record(new_node)) if old_node.name == new_node.name => diff.diff_record(old_node, new_node, &old_node.name),
+            (old_node, new_node) if !old_node.eq_value(new_node) => {
+                diff.removed.push(NodeDiff::new(&root_path(old_node), Some(old_node), None));
+                diff.added.push(NodeDiff::new(&root_path(new_node), None, Some(new_node)));
+            }
+            _ => {}
+        }
+
+        diff
+    }
+
+    /// This function returns the nodes only present in the new `ESF`.
+    pub fn get_ref_added(&self) -> &[NodeDiff] {
+        &self.added
+    }
+
+    /// This function returns the nodes only present in the old `ESF`.
+    pub fn get_ref_removed(&self) -> &[NodeDiff] {
+        &self.removed
+    }
+
+    /// This function returns the values present in both `ESF`, but different.
+    pub fn get_ref_modified(&self) -> &[NodeDiff] {
+        &self.modified
+    }
+
+    /// This function returns if both `ESF` are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// This function compares two versions of the same record node.
+    fn diff_record(&mut self, old: &RecordNode, new: &RecordNode, path: &str) {
+        if old.eq_value(new) {
+            return;
+        }
+
+        let old_nested = old.record_flags.contains(RecordNodeFlags::HAS_NESTED_BLOCKS);
+        let new_nested = new.record_flags.contains(RecordNodeFlags::HAS_NESTED_BLOCKS);
+        match (old_nested, new_nested) {
+
+            // Groups are matched by name, which means by key if they have one, or by index if they don't.
+            (true, true) => {
+                let old_names = group_names(old);
+                let new_names = group_names(new);
+                let old_groups = old_names.iter().enumerate().map(|(index, name)| (name, index)).collect::<HashMap<&String, usize>>();
+
+                for (new_index, new_name) in new_names.iter().enumerate() {
+                    let group_path = format!("{}/{}", path, new_name);
+                    match old_groups.get(new_name) {
+                        Some(old_index) => self.diff_nodes(&old.children[*old_index], &new.children[new_index], &group_path),
+                        None => self.added.push(NodeDiff::new(&group_path, None, None)),
+                    }
+                }
+
+                let new_names = new_names.iter().collect::<HashSet<&String>>();
+                for old_name in old_names.iter().filter(|name| !new_names.contains(name)) {
+                    self.removed.push(NodeDiff::new(&format!("{}/{}", path, old_name), None, None));
+                }
+            }
+            (false, false) => {
+                let old_nodes = old.children.iter().flatten().collect::<Vec<&NodeType>>();
+                let new_nodes = new.children.iter().flatten().collect::<Vec<&NodeType>>();
+                self.diff_nodes(&old_nodes, &new_nodes, path);
+            }
+
+            // If one has nested blocks and the other doesn't, there's nothing we can match.
+            _ => self.modified.push(NodeDiff {
+                path: path.to_owned(),
+                old_value: Some(format!("{} groups", old.children.len())),
+                new_value: Some(format!("{} groups", new.children.len())),
+            }),
+        }
+    }
+
+    /// This function compares two versions of the same list of nodes.
+    fn diff_nodes<T: Borrow<NodeType>>(&mut self, old: &[T], new: &[T], path: &str) {
+        let old_records = records_with_paths(old, path);
+        let new_records = records_with_paths(new, path);
+        let old_records_by_key = old_records.iter().map(|(key, _, node)| (key, *node)).collect::<HashMap<&(&str, usize), &RecordNode>>();
+
+        for (new_key, new_path, new_node) in &new_records {
+            match old_records_by_key.get(new_key) {
+                Some(old_node) => self.diff_record(old_node, new_node, new_path),
+                None => self.added.push(NodeDiff::new(new_path, None, None)),
+            }
+        }
+
+        let new_keys = new_records.iter().map(|(key, _, _)| key).collect::<HashSet<&(&str, usize)>>();
+        for (old_key, old_path, _) in &old_records {
+            if !new_keys.contains(old_key) {
+                self.removed.push(NodeDiff::new(old_path, None, None));
+            }
+        }
+
+        // Values are matched by order. Their paths use their position in the list they belong to.
+        let old_values = old.iter().map(Borrow::borrow).enumerate().filter(|(_, node)| !matches!(node, NodeType::Record(_))).collect::<Vec<(usize, &NodeType)>>();
+        let new_values = new.iter().map(Borrow::borrow).enumerate().filter(|(_, node)| !matches!(node, NodeType::Record(_))).collect::<Vec<(usize, &NodeType)>>();
+        for index in 0..old_values.len().max(new_values.len()) {
+            match (old_values.get(index), new_values.get(index)) {
+                (Some((_, old_node)), Some((new_position, new_node))) => if !old_node.eq_value(new_node) {
+                    self.modified.push(NodeDiff::new(&format!("{}/@{}", path, new_position), Some(old_node), Some(new_node)));
+                },
+                (None, Some((new_position, new_node))) => self.added.push(NodeDiff::new(&format!("{}/@{}", path, new_position), None, Some(new_node))),
+                (Some((old_position, old_node)), None) => self.removed.push(NodeDiff::new(&format!("{}/@{}", path, old_position), Some(old_node), None)),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+/// Implementation of `NodeDiff`.
+impl NodeDiff {
+
+    /// This function creates a new `NodeDiff`. Only values of non-record nodes are stored.
+    fn new(path: &str, old_node: Option<&NodeType>, new_node: Option<&NodeType>) -> Self {
+        Self {
+            path: path.to_owned(),
+            old_value: old_node.and_then(node_to_string),
+            new_value: new_node.and_then(node_to_string),
+        }
+    }
+
+    /// This function returns the path of the node, as a query.
+    pub fn get_ref_path(&self) -> &str {
+        &self.path
+    }
+
+    /// This function returns the old value of the node, if any.
+    pub fn get_ref_old_value(&self) -> &Option<String> {
+        &self.old_value
+    }
+
+    /// This function returns the new value of the node, if any.
+    pub fn get_ref_new_value(&self) -> &Option<String> {
+        &self.new_value
+    }
+}
+
+/// Display implementation of `ESFDiff`, to get a human-readable report of the differences.
+impl Display for ESFDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in &self.added {
+            match node.new_value {
+                Some(ref value) => writeln!(f, "+ {} = {:?}", node.path, value)?,
+                None => writeln!(f, "+ {}", node.path)?,
+            }
+        }
+
+        for node in &self.removed {
+            writeln!(f, "- {}", node.path)?;
+        }
+
+        for node in &self.modified {
+            writeln!(f, "~ {}: {:?} => {:?}", node.path, node.old_value.as_deref().unwrap_or_default(), node.new_value.as_deref().unwrap_or_default())?;
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Utility functions for diffs
+//---------------------------------------------------------------------------//
+
+/// This function returns the path of a root node. Non-record root nodes have no name, so we use a wildcard for them.
+fn root_path(node: &NodeType) -> String {
+    match node {
+        NodeType::Record(node) => node.name.to_owned(),
+        _ => "*".to_owned(),
+    }
+}
+
+/// This function returns the value of a node as a string. Arrays are returned as JSON, and records have no value.
+fn node_to_string(node: &NodeType) -> Option<String> {
+    match node {
+        NodeType::Record(_) => None,
+        _ => node.value_to_string().or_else(|| serde_json::to_string(node).ok()),
+    }
+}
+
+/// This function returns the names used to match the groups of a record. If a name is duplicated, the index of the group is used instead.
+fn group_names(record: &RecordNode) -> Vec<String> {
+    let names = (0..record.children.len()).map(|index| record.get_group_name(index)).collect::<Vec<String>>();
+    let mut counts: HashMap<&String, usize> = HashMap::new();
+    names.iter().for_each(|name| *counts.entry(name).or_default() += 1);
+
+    names.iter().enumerate().map(|(index, name)| {
+        if counts[name] > 1 {
+            index.to_string()
+        } else {
+            name.to_owned()
+        }
+    }).collect()
+}
+
+/// This function returns the record nodes in the provided list with their paths, and the name and position used to match them.
+///
+/// Records with the same name as other records in the list get their position between them as predicate.
+fn records_with_paths<'a, T: Borrow<NodeType>>(nodes: &'a [T], path: &str) -> Vec<((&'a str, usize), String, &'a RecordNode)> {
+    let records = nodes.iter().filter_map(|node| match node.borrow() {
+        NodeType::Record(record) => Some(record),
+        _ => None,
+    }).collect::<Vec<&RecordNode>>();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    records.iter().for_each(|record| *counts.entry(&record.name).or_default() += 1);
+
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    records.iter().map(|record| {
+        let position = positions.entry(&record.name).or_default();
+        let record_path = if counts[record.name.as_str()] > 1 {
+            format!("{}/{}[{}]", path, record.name, position)
+        } else {
+            format!("{}/{}", path, record.name)
+        };
+
+        let key = (record.name.as_str(), *position);
+        *position += 1;
+        (key, record_path, *record)
+    }).collect()
+}
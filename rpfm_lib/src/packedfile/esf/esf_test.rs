@@ -0,0 +1,282 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `ESF` module.
+!*/
+
+use xz2::stream::{LzmaOptions, Stream};
+use xz2::write::XzEncoder;
+
+use std::io::Write;
+
+use crate::packfile::compression::compress_data_lzma1_raw;
+
+use super::*;
+
+/// This function creates a record node with the provided name, flags and children.
+fn record(name: &str, record_flags: RecordNodeFlags, children: Vec<Vec<NodeType>>) -> NodeType {
+    record_with_version(name, record_flags, 0, children)
+}
+
+/// This function creates a record node with the provided name, flags, version and children.
+fn record_with_version(name: &str, record_flags: RecordNodeFlags, version: u8, children: Vec<Vec<NodeType>>) -> NodeType {
+    NodeType::Record(RecordNode {
+        record_flags,
+        version,
+        name: name.to_owned(),
+        children,
+        compressed_data: None,
+    })
+}
+
+/// Test to make sure compressed nodes made by other encoders are saved back unchanged, keeping the header of the file inside them.
+#[test]
+fn test_esf_compressed_nodes_unchanged() {
+    let inner = ESF {
+        signature: ESFSignature::CAAB,
+        unknown_1: 3,
+        creation_date: 111,
+        root_node: record("INNER", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::Ascii("inner".repeat(8)), NodeType::U64(42)]]),
+    };
+    let inner_data = inner.save().unwrap();
+
+    // Compress it with liblzma instead of our own encoder, so we can detect if the node is compressed again.
+    let mut encoder = XzEncoder::new_stream(vec![], Stream::new_lzma_encoder(&LzmaOptions::new_preset(6).unwrap()).unwrap());
+    encoder.write_all(&inner_data).unwrap();
+    let lzma_alone = encoder.finish().unwrap();
+    let properties = lzma_alone[..5].to_vec();
+    let compressed_data = lzma_alone[13..].to_vec();
+    assert_ne!(compressed_data, compress_data_lzma1_raw(&inner_data).0);
+
+    let info = record(COMPRESSED_DATA_INFO_TAG, RecordNodeFlags::IS_RECORD_NODE, vec![vec![
+        NodeType::U32(U32Node {
+            value: inner_data.len() as u32,
+            optimized: false,
+        }),
+        NodeType::U8Array(properties),
+    ]]);
+    let compressed = record(COMPRESSED_DATA_TAG, RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::U8Array(compressed_data), info]]);
+    let esf = ESF {
+        signature: ESFSignature::CAAB,
+        unknown_1: 5,
+        creation_date: 222,
+        root_node: record("ROOT", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::Utf16("outer".to_owned()), compressed]]),
+    };
+    let data = esf.save().unwrap();
+
+    // The compressed node must be decompressed on read, and saved back byte for byte, both directly and through JSON.
+    let esf_decoded = ESF::read(&data).unwrap();
+    let compressed_node = |esf: &ESF| match &esf.root_node {
+        NodeType::Record(root) => match &root.children[0][1] {
+            NodeType::Record(compressed) => compressed.clone(),
+            _ => panic!("Compressed node not found."),
+        },
+        _ => panic!("Root node not found."),
+    };
+
+    let compressed = compressed_node(&esf_decoded);
+    assert_eq!(compressed.children[0][0], inner.root_node);
+    assert!(matches!(compressed.compressed_data.as_deref(), Some(original) if original.unknown_1 == 3 && original.creation_date == 111));
+    assert_eq!(esf_decoded.save().unwrap(), data);
+    assert_eq!(ESF::from_json(&esf_decoded.to_json().unwrap()).unwrap().save().unwrap(), data);
+
+    // Editing the decompressed nodes compresses them again, keeping the header of the inner file.
+    let mut esf_edited = esf_decoded;
+    if let NodeType::Record(root) = &mut esf_edited.root_node {
+        if let NodeType::Record(compressed) = &mut root.children[0][1] {
+            if let NodeType::Record(inner) = &mut compressed.children[0][0] {
+                inner.children[0][1] = NodeType::U64(43);
+            }
+        }
+    }
+
+    let data_edited = esf_edited.save().unwrap();
+    assert_ne!(data_edited, data);
+
+    let compressed = compressed_node(&ESF::read(&data_edited).unwrap());
+    assert!(matches!(&compressed.children[0][0], NodeType::Record(inner) if inner.children[0][1] == NodeType::U64(43)));
+    assert!(matches!(compressed.compressed_data.as_deref(), Some(original) if original.unknown_1 == 3 && original.creation_date == 111));
+}
+
+/// Test to make sure ESF files exported to JSON, including non-finite floats and compressed nodes, are imported back to the same binary file.
+#[test]
+fn test_esf_json_roundtrip() {
+    let payload_nan = f32::from_bits(0x7fc0_1234);
+    let mut nodes = vec![
+        NodeType::F32(F32Node { value: f32::NAN, optimized: false }),
+        NodeType::F32(F32Node { value: f32::INFINITY, optimized: false }),
+        NodeType::F32(F32Node { value: payload_nan, optimized: false }),
+        NodeType::F32(F32Node { value: 1.5, optimized: false }),
+        NodeType::F64(f64::NEG_INFINITY),
+        NodeType::F64(f64::from_bits(0xfff8_0000_0000_0001)),
+        NodeType::F32Array(vec![0.25, f32::NEG_INFINITY, f32::NAN]),
+        NodeType::F64Array(vec![f64::INFINITY, -2.0]),
+        NodeType::Coord2d(Coordinates2DNode { x: f32::NAN, y: 3.0 }),
+        NodeType::Coord3d(Coordinates3DNode { x: 1.0, y: f32::INFINITY, z: payload_nan }),
+    ];
+
+    // A decompressed node without original data gets compressed with our own encoder on save.
+    let inner = record("INNER", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::Ascii("inner".repeat(8)), NodeType::F32(F32Node { value: f32::NAN, optimized: false })]]);
+    nodes.push(record(COMPRESSED_DATA_TAG, RecordNodeFlags::IS_RECORD_NODE, vec![vec![inner]]));
+
+    let esf = ESF {
+        signature: ESFSignature::CAAB,
+        unknown_1: 5,
+        creation_date: 222,
+        root_node: record("ROOT", RecordNodeFlags::IS_RECORD_NODE, vec![nodes]),
+    };
+    let data = esf.save().unwrap();
+
+    let esf_decoded = ESF::read(&data).unwrap();
+    let json = esf_decoded.to_json().unwrap();
+    assert!(!json.contains("null") && json.contains("\"INNER\""));
+    assert!(json.contains("\"NaN\"") && json.contains("\"inf\"") && json.contains("\"-inf\"") && json.contains("\"NaN:0x7fc01234\""));
+
+    let esf_imported = ESF::from_json(&json).unwrap();
+    assert_eq!(esf_imported.save().unwrap(), data);
+    assert_eq!(esf_imported.to_json().unwrap(), json);
+
+    // Invalid float strings must fail instead of being imported as something else.
+    assert!(ESF::from_json(&json.replacen("\"-inf\"", "\"-infinite\"", 1)).is_err());
+}
+
+/// Test to make sure the CAAB, CEAB and CFAB formats are saved and read back without changes, and invalid files fail to save.
+#[test]
+fn test_esf_legacy_roundtrip() {
+    let nested = record_with_version("NESTED", RecordNodeFlags::IS_RECORD_NODE | RecordNodeFlags::HAS_NESTED_BLOCKS, 2, vec![
+        vec![NodeType::Utf16("first".to_owned()), NodeType::I16(-5)],
+        vec![],
+        vec![NodeType::Ascii("second".to_owned()), NodeType::U8Array(vec![1, 2, 3])],
+    ]);
+    let root = record_with_version("ROOT", RecordNodeFlags::IS_RECORD_NODE, 2, vec![vec![
+        NodeType::U64(u64::MAX),
+        NodeType::F64(1.5),
+        NodeType::Utf16("first".to_owned()),
+        NodeType::Utf16Array(vec!["a".to_owned(), "first".to_owned()]),
+        NodeType::AsciiArray(vec![]),
+        NodeType::F32Array(vec![0.5, -0.25]),
+        nested,
+        record_with_version("EMPTY", RecordNodeFlags::IS_RECORD_NODE, 2, vec![vec![]]),
+    ]]);
+
+    for signature in &[ESFSignature::CAAB, ESFSignature::CEAB, ESFSignature::CFAB] {
+        let mut esf = ESF::default();
+        esf.set_signature(*signature);
+        esf.set_creation_date(1234);
+        esf.set_root_node(root.clone());
+
+        let data = esf.save().unwrap();
+        assert!(ESF::is_esf(&data));
+        let esf_decoded = ESF::read(&data).unwrap();
+        assert_eq!(esf_decoded, esf);
+        assert_eq!(esf_decoded.save().unwrap(), data);
+
+        // Exporting to JSON and importing it back must generate the same file.
+        let esf_imported = ESF::from_json(&esf.to_json().unwrap()).unwrap();
+        assert_eq!(esf_imported.save().unwrap(), data);
+    }
+
+    // Invalid nodes must fail to save instead of generating a broken file.
+    let mut esf = ESF::default();
+    esf.set_signature(ESFSignature::CFAB);
+    assert!(esf.save().is_err());
+}
+
+/// Test to make sure the compressed blocks made by us can be read back, and saved again without changes.
+#[test]
+fn test_esf_compressed_nodes() {
+    let hidden = "hidden_string_inside_compressed_data".repeat(4);
+    let inner = record("INNER", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::Ascii(hidden.to_owned()), NodeType::U64(42)]]);
+    let compressed = record(COMPRESSED_DATA_TAG, RecordNodeFlags::IS_RECORD_NODE, vec![vec![inner.clone(), record(COMPRESSED_DATA_INFO_TAG, RecordNodeFlags::IS_RECORD_NODE, vec![vec![]])]]);
+    let root = record("ROOT", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::Utf16("outer".to_owned()), compressed]]);
+
+    let mut esf = ESF::default();
+    esf.set_root_node(root);
+
+    // The inner nodes must be compressed on save, and decompressed back on read.
+    let data = esf.save().unwrap();
+    assert!(!data.windows(hidden.len()).any(|window| window == hidden.as_bytes()));
+
+    let esf_decoded = ESF::read(&data).unwrap();
+    if let NodeType::Record(root) = esf_decoded.get_ref_root_node() {
+        if let NodeType::Record(compressed) = &root.get_ref_children()[0][1] {
+            assert_eq!(compressed.get_ref_children()[0][0], inner);
+            assert!(matches!(&compressed.get_ref_children()[0][1], NodeType::Record(info) if info.get_ref_name() == COMPRESSED_DATA_INFO_TAG));
+        } else { panic!("Compressed node not found."); }
+    } else { panic!("Root node not found."); }
+
+    assert_eq!(esf_decoded.save().unwrap(), data);
+}
+
+/// Test to make sure path queries find the nodes and values we expect, and values set through them keep their type.
+#[test]
+fn test_esf_query() {
+    let faction = |name: &str, treasury: i64| record("FACTION", RecordNodeFlags::IS_RECORD_NODE, vec![vec![
+        NodeType::Ascii(name.to_owned()),
+        record("TREASURY", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::I64(treasury)]]),
+    ]]);
+
+    let factions = record("FACTION_ARRAY", RecordNodeFlags::IS_RECORD_NODE | RecordNodeFlags::HAS_NESTED_BLOCKS, vec![
+        vec![faction("wh_main_brt_bretonnia", 500)],
+        vec![faction("wh_main_emp_empire", 1000)],
+    ]);
+
+    let mut esf = ESF::default();
+    esf.set_root_node(record("CAMPAIGN_SAVE_GAME", RecordNodeFlags::IS_RECORD_NODE, vec![vec![factions]]));
+
+    let query = r#"CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION[name="wh_main_emp_empire"]/TREASURY/@0"#;
+    assert_eq!(esf.query_paths(query).unwrap(), vec![vec![(0, 0), (1, 0), (0, 1), (0, 0)]]);
+    assert_eq!(esf.query(query).unwrap(), vec![&NodeType::I64(1000)]);
+    assert_eq!(esf.query("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/FACTION_ARRAY_0/FACTION/@0").unwrap(), vec![&NodeType::Ascii("wh_main_brt_bretonnia".to_owned())]);
+    assert_eq!(esf.query("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION[@0='wh_main_brt_bretonnia']/TREASURY/@0").unwrap(), vec![&NodeType::I64(500)]);
+    assert_eq!(esf.query("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION/TREASURY").unwrap().len(), 2);
+    assert_eq!(esf.query("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*[1]/FACTION/@0").unwrap(), vec![&NodeType::Ascii("wh_main_emp_empire".to_owned())]);
+    assert!(esf.query("CAMPAIGN_SAVE_GAME/MISSING").unwrap().is_empty());
+    assert!(esf.query("CAMPAIGN_SAVE_GAME/@0/FACTION_ARRAY").is_err());
+
+    // Setting values must keep their type, and fail without changes if the value is not valid.
+    assert_eq!(esf.set_query_value("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION/TREASURY/@0", "25").unwrap(), 2);
+    assert_eq!(esf.query("CAMPAIGN_SAVE_GAME/FACTION_ARRAY/*/FACTION/TREASURY/@0").unwrap(), vec![&NodeType::I64(25), &NodeType::I64(25)]);
+    assert!(esf.set_query_value(query, "not_a_number").is_err());
+    assert_eq!(esf.query(query).unwrap(), vec![&NodeType::I64(25)]);
+}
+
+/// Test to make sure the diff of two ESF files reports the added, removed and modified nodes, with paths we can query.
+#[test]
+fn test_esf_diff() {
+    let build = |treasury: i64, factions: &[&str], regions: usize| {
+        let factions = factions.iter().map(|name| vec![NodeType::Ascii(name.to_string()), record("FACTION", RecordNodeFlags::IS_RECORD_NODE, vec![vec![NodeType::I64(treasury)]])]).collect();
+        let regions = (0..regions).map(|_| record("REGION", RecordNodeFlags::IS_RECORD_NODE, vec![vec![]])).collect::<Vec<NodeType>>();
+        let mut children = vec![NodeType::Utf16("save".to_owned()), record("FACTION_ARRAY", RecordNodeFlags::IS_RECORD_NODE | RecordNodeFlags::HAS_NESTED_BLOCKS, factions)];
+        children.extend(regions);
+
+        let mut esf = ESF::default();
+        esf.set_root_node(record("CAMPAIGN_SAVE_GAME", RecordNodeFlags::IS_RECORD_NODE, vec![children]));
+        esf
+    };
+
+    let old = build(100, &["wh_main_emp_empire", "wh_main_brt_bretonnia"], 2);
+    let new = build(250, &["wh_main_emp_empire", "wh_main_dwf_dwarfs"], 3);
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&new);
+    let added = diff.get_ref_added().iter().map(|node| node.get_ref_path()).collect::<Vec<&str>>();
+    let removed = diff.get_ref_removed().iter().map(|node| node.get_ref_path()).collect::<Vec<&str>>();
+    assert_eq!(added, vec!["CAMPAIGN_SAVE_GAME/FACTION_ARRAY/wh_main_dwf_dwarfs", "CAMPAIGN_SAVE_GAME/REGION[2]"]);
+    assert_eq!(removed, vec!["CAMPAIGN_SAVE_GAME/FACTION_ARRAY/wh_main_brt_bretonnia"]);
+
+    // Changed values must be reported with paths we can query.
+    let modified = diff.get_ref_modified();
+    assert_eq!(modified.len(), 1);
+    assert_eq!(modified[0].get_ref_path(), "CAMPAIGN_SAVE_GAME/FACTION_ARRAY/wh_main_emp_empire/FACTION/@0");
+    assert_eq!(modified[0].get_ref_old_value().as_deref(), Some("100"));
+    assert_eq!(new.query(modified[0].get_ref_path()).unwrap(), vec![&NodeType::I64(250)]);
+}
@@ -0,0 +1,146 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the serde functions used to serialize the floats of ESF nodes.
+
+JSON has no representation for NaN and infinite values, so these are serialized as strings instead.
+NaNs other than the default one are serialized with their bits, so they're not changed when deserialized.
+!*/
+
+use serde::{Deserializer, Serializer};
+use serde_derive::Deserialize;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This trait contains the functions needed to serialize a float type.
+pub trait Float: Copy + Display + FromStr + serde::Serialize + for<'de> serde::Deserialize<'de> {
+
+    /// This function returns the float as a string, only for non-finite floats.
+    fn to_non_finite_string(self) -> String;
+
+    /// This function parses a string generated by `to_non_finite_string`.
+    fn from_non_finite_string(value: &str) -> Option<Self>;
+
+    /// This function returns if the float is finite.
+    fn is_finite_float(self) -> bool;
+}
+
+/// A float serialized as a number, or as a string if it's not finite.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FloatValue<T> {
+    Number(T),
+    String(String),
+}
+
+/// Wrapper to serialize the floats inside collections.
+struct FloatWrapper<T>(T);
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Float` for `f32`.
+impl Float for f32 {
+    fn to_non_finite_string(self) -> String {
+        if self.is_nan() && self.to_bits() != f32::NAN.to_bits() {
+            format!("NaN:{:#010x}", self.to_bits())
+        } else {
+            self.to_string()
+        }
+    }
+
+    fn from_non_finite_string(value: &str) -> Option<Self> {
+        match value.strip_prefix("NaN:0x") {
+            Some(bits) => u32::from_str_radix(bits, 16).ok().map(f32::from_bits),
+            None => value.parse().ok(),
+        }
+    }
+
+    fn is_finite_float(self) -> bool {
+        self.is_finite()
+    }
+}
+
+/// Implementation of `Float` for `f64`.
+impl Float for f64 {
+    fn to_non_finite_string(self) -> String {
+        if self.is_nan() && self.to_bits() != f64::NAN.to_bits() {
+            format!("NaN:{:#018x}", self.to_bits())
+        } else {
+            self.to_string()
+        }
+    }
+
+    fn from_non_finite_string(value: &str) -> Option<Self> {
+        match value.strip_prefix("NaN:0x") {
+            Some(bits) => u64::from_str_radix(bits, 16).ok().map(f64::from_bits),
+            None => value.parse().ok(),
+        }
+    }
+
+    fn is_finite_float(self) -> bool {
+        self.is_finite()
+    }
+}
+
+/// Serialize implementation for `FloatWrapper`.
+impl<T: Float> serde::Serialize for FloatWrapper<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+/// Deserialize implementation for `FloatWrapper`.
+impl<'de, T: Float> serde::Deserialize<'de> for FloatWrapper<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(FloatWrapper)
+    }
+}
+
+/// This function serializes a float.
+pub fn serialize<S: Serializer, T: Float>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    if value.is_finite_float() {
+        value.serialize(serializer)
+    } else {
+        serializer.serialize_str(&value.to_non_finite_string())
+    }
+}
+
+/// This function deserializes a float serialized with `serialize`.
+pub fn deserialize<'de, D: Deserializer<'de>, T: Float>(deserializer: D) -> Result<T, D::Error> {
+    match <FloatValue<T> as serde::Deserialize>::deserialize(deserializer)? {
+        FloatValue::Number(value) => Ok(value),
+        FloatValue::String(value) => T::from_non_finite_string(&value).ok_or_else(|| serde::de::Error::custom(format!("invalid float: {}", value))),
+    }
+}
+
+/// Module with the serde functions for lists of floats.
+pub mod vec {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Float, FloatWrapper};
+
+    /// This function serializes a list of floats.
+    pub fn serialize<S: Serializer, T: Float>(values: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| FloatWrapper(*value)))
+    }
+
+    /// This function deserializes a list of floats serialized with `serialize`.
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Float>(deserializer: D) -> Result<Vec<T>, D::Error> {
+        <Vec<FloatWrapper<T>> as serde::Deserialize>::deserialize(deserializer).map(|values| values.into_iter().map(|value| value.0).collect())
+    }
+}
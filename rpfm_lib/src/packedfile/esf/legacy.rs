@@ -0,0 +1,390 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code needed to support the legacy CEAB and CFAB formats for ESF files.
+
+Both formats share most of their node encoding with CAAB. The differences are:
+
+- Records use a type byte instead of flags, their info is never optimized, and they use absolute offsets instead of sizes.
+- Arrays use the absolute offset of their end instead of their size.
+- CEAB stores the strings inline in their nodes. CFAB stores them in string tables at the end of the file, like CAAB.
+
+!*/
+
+use std::collections::BTreeMap;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+use super::*;
+
+//---------------------------------------------------------------------------//
+//                           Implementation of ESF
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESF`. Section of functions specific for the CEAB and CFAB formats.
+impl ESF {
+
+    /// This function creates a `ESF` of type CEAB or CFAB from a `Vec<u8>`.
+    pub(crate) fn read_legacy(packed_file_data: &[u8], signature: ESFSignature) -> Result<Self> {
+        let mut offset = 4;
+        let unknown_1 = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+        let creation_date = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+        let record_names_offset = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+        let nodes_offset = offset;
+
+        // We need this data decoded first, because some nodes reference to it, and we can use that to populate the nodes.
+        offset = record_names_offset as usize;
+
+        // Get the name list for the record/record block entries.
+        let record_names_count = packed_file_data.decode_packedfile_integer_u16(offset, &mut offset)?;
+        let mut record_names = vec![];
+        for _ in 0..record_names_count {
+            record_names.push(packed_file_data.decode_packedfile_string_u8(offset, &mut offset)?);
+        }
+
+        // Only CFAB has string tables. CEAB has its strings inline.
+        let mut strings_utf16 = BTreeMap::new();
+        let mut strings_utf8 = BTreeMap::new();
+        if signature == ESFSignature::CFAB {
+            let strings_count_utf16 = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+            for _ in 0..strings_count_utf16 {
+                let name = packed_file_data.decode_packedfile_string_u16(offset, &mut offset)?;
+                let index = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+                strings_utf16.insert(index, name);
+            }
+
+            let strings_count_utf8 = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+            for _ in 0..strings_count_utf8 {
+                let name = packed_file_data.decode_packedfile_string_u8(offset, &mut offset)?;
+                let index = packed_file_data.decode_packedfile_integer_u32(offset, &mut offset)?;
+                strings_utf8.insert(index, name);
+            }
+        }
+
+        // If we're not at the end of the file, something failed.
+        if offset != packed_file_data.len() {
+            return Err(ErrorKind::ESFIncompleteDecoding.into());
+        }
+
+        // Restore the index before continuing.
+        offset = nodes_offset;
+
+        // This file is a big tree hanging from the root node, so just decode everything recursively.
+        let nodes_data = packed_file_data.get(..record_names_offset as usize).ok_or_else(|| Error::from(ErrorKind::NotEnoughBytesToDecode))?;
+        let root_node = Self::read_node_legacy(nodes_data, &mut offset, signature, &record_names, &strings_utf8, &strings_utf16)?;
+
+        // If we're not at the exact end of the nodes, something failed.
+        if offset != record_names_offset as usize {
+            return Err(ErrorKind::ESFIncompleteDecoding.into());
+        }
+
+        Ok(Self {
+            signature,
+            unknown_1,
+            creation_date,
+            root_node,
+        })
+    }
+
+    /// This function takes a `ESF` of type CEAB or CFAB and encodes it to `Vec<u8>`.
+    pub(crate) fn save_legacy(&self) -> Result<Vec<u8>> {
+        let mut data = vec![];
+
+        // Encode the header info, except the offsets, because those are calculated later.
+        match self.signature {
+            ESFSignature::CEAB => data.extend_from_slice(SIGNATURE_CEAB),
+            ESFSignature::CFAB => data.extend_from_slice(SIGNATURE_CFAB),
+            ESFSignature::CAAB => return Err(ErrorKind::ESFUnsupportedSignature(self.signature.to_string()).into()),
+        }
+
+        data.encode_integer_u32(self.unknown_1);
+        data.encode_integer_u32(self.creation_date);
+
+        // First, get the strings, as we need to have them in order before encoding the nodes.
+        let mut record_names = vec![];
+        let mut strings_utf8 = vec![];
+        let mut strings_utf16 = vec![];
+        Self::read_string_from_node(&self.root_node, &mut record_names, &mut strings_utf8, &mut strings_utf16);
+
+        // Next, encode the nodes. As offsets are absolute, we encode them directly after the header.
+        let record_names_offset_position = data.len();
+        data.encode_integer_u32(0);
+        Self::save_node_legacy(&self.root_node, &mut data, self.signature, &record_names, &strings_utf8, &strings_utf16)?;
+        Self::set_end_offset_legacy(&mut data, record_names_offset_position);
+
+        // Then, encode the strings. First record names.
+        data.encode_integer_u16(record_names.len() as u16);
+        for name in &record_names {
+            data.encode_packedfile_string_u8(name);
+        }
+
+        // Then, if we have string tables, UTF-16 Strings and UTF-8 Strings.
+        if self.signature == ESFSignature::CFAB {
+            data.encode_integer_u32(strings_utf16.len() as u32);
+            for (index, string) in strings_utf16.iter().enumerate() {
+                data.encode_packedfile_string_u16(string);
+                data.encode_integer_u32(index as u32);
+            }
+
+            data.encode_integer_u32(strings_utf8.len() as u32);
+            for (index, string) in strings_utf8.iter().enumerate() {
+                data.encode_packedfile_string_u8(string);
+                data.encode_integer_u32(index as u32);
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// This function takes care of reading a node's data into the appropriate NodeType.
+    ///
+    /// Only records, arrays and (on CEAB) strings are decoded here. Everything else is decoded like in CAAB.
+    fn read_node_legacy(
+        packed_file_data: &[u8],
+        offset: &mut usize,
+        signature: ESFSignature,
+        record_names: &[String],
+        strings_utf8: &BTreeMap<u32, String>,
+        strings_utf16: &BTreeMap<u32, String>
+    ) -> Result<NodeType> {
+
+        let next_byte = packed_file_data.decode_packedfile_integer_u8(*offset, offset)?;
+        let node_type = match next_byte {
+
+            //------------------------------------------------//
+            // Record nodes.
+            //------------------------------------------------//
+            RECORD | RECORD_BLOCK => {
+                let name_index = packed_file_data.decode_packedfile_integer_u16(*offset, offset)?;
+                let version = packed_file_data.decode_packedfile_integer_u8(*offset, offset)?;
+                let name = match record_names.get(name_index as usize) {
+                    Some(name) => name.to_owned(),
+                    None => return Err(ErrorKind::ESFRecordNameNotFound(name_index as u32).into())
+                };
+
+                let final_block_offset = packed_file_data.decode_packedfile_integer_u32(*offset, offset)? as usize;
+                let mut record_flags = RecordNodeFlags::IS_RECORD_NODE;
+                let mut children = vec![];
+
+                // Record blocks contain multiple groups of nodes, each one with its own end offset.
+                if next_byte == RECORD_BLOCK {
+                    record_flags |= RecordNodeFlags::HAS_NESTED_BLOCKS;
+
+                    let group_count = packed_file_data.decode_packedfile_integer_u32(*offset, offset)?;
+                    for _ in 0..group_count {
+                        let final_entry_offset = packed_file_data.decode_packedfile_integer_u32(*offset, offset)? as usize;
+                        children.push(Self::read_nodes_legacy(packed_file_data, offset, final_entry_offset, signature, record_names, strings_utf8, strings_utf16)?);
+                    }
+                } else {
+                    children.push(Self::read_nodes_legacy(packed_file_data, offset, final_block_offset, signature, record_names, strings_utf8, strings_utf16)?);
+                }
+
+                // Make sure we decoded exactly the data we wanted.
+                if *offset != final_block_offset {
+                    return Err(ErrorKind::ESFIncompleteDecoding.into())
+                }
+
+                NodeType::Record(RecordNode {
+                    record_flags,
+                    version,
+                    name,
+                    children,
+                    compressed_data: None,
+                })
+            },
+
+            // Any other record-like type is not supported in these formats.
+            0x82..=0xFF => return Err(ErrorKind::ESFUnsupportedDataType(format!("{}", next_byte)).into()),
+
+            //------------------------------------------------//
+            // Inline strings, only in CEAB.
+            //------------------------------------------------//
+            UTF16 if signature == ESFSignature::CEAB => NodeType::Utf16(packed_file_data.decode_packedfile_string_u16(*offset, offset)?),
+            ASCII if signature == ESFSignature::CEAB => NodeType::Ascii(packed_file_data.decode_packedfile_string_u8(*offset, offset)?),
+            UTF16_ARRAY if signature == ESFSignature::CEAB => {
+                let mut node_data = vec![];
+                let final_offset = packed_file_data.decode_packedfile_integer_u32(*offset, offset)? as usize;
+                while *offset < final_offset {
+                    node_data.push(packed_file_data.decode_packedfile_string_u16(*offset, offset)?);
+                }
+
+                NodeType::Utf16Array(node_data)
+            },
+            ASCII_ARRAY if signature == ESFSignature::CEAB => {
+                let mut node_data = vec![];
+                let final_offset = packed_file_data.decode_packedfile_integer_u32(*offset, offset)? as usize;
+                while *offset < final_offset {
+                    node_data.push(packed_file_data.decode_packedfile_string_u8(*offset, offset)?);
+                }
+
+                NodeType::AsciiArray(node_data)
+            },
+
+            //------------------------------------------------//
+            // Arrays. Same as CAAB, but using their end offset instead of their size.
+            //------------------------------------------------//
+            BOOL_ARRAY..=ANGLE_ARRAY |
+            BOOL_TRUE_ARRAY..=F32_ZERO_ARRAY => {
+                let final_offset = packed_file_data.decode_packedfile_integer_u32(*offset, offset)? as usize;
+                let size = final_offset.checked_sub(*offset).ok_or_else(|| Error::from(ErrorKind::ESFIncompleteDecoding))?;
+
+                let mut node_data = vec![next_byte];
+                node_data.encode_integer_cauleb128(size as u32);
+                node_data.extend_from_slice(packed_file_data.get_bytes_checked(*offset, size)?);
+                *offset = final_offset;
+
+                let mut node_offset = 0;
+                Self::read_node(&node_data, &mut node_offset, false, record_names, strings_utf8, strings_utf16)?
+            },
+
+            //------------------------------------------------//
+            // Everything else is encoded exactly like in CAAB.
+            //------------------------------------------------//
+            _ => {
+                *offset -= 1;
+                Self::read_node(packed_file_data, offset, false, record_names, strings_utf8, strings_utf16)?
+            }
+        };
+
+        Ok(node_type)
+    }
+
+    /// This function reads all the nodes between the current offset and the provided final offset.
+    fn read_nodes_legacy(
+        packed_file_data: &[u8],
+        offset: &mut usize,
+        final_offset: usize,
+        signature: ESFSignature,
+        record_names: &[String],
+        strings_utf8: &BTreeMap<u32, String>,
+        strings_utf16: &BTreeMap<u32, String>
+    ) -> Result<Vec<NodeType>> {
+        let packed_file_data = packed_file_data.get(..final_offset).ok_or_else(|| Error::from(ErrorKind::NotEnoughBytesToDecode))?;
+
+        let mut node_list = vec![];
+        while *offset < final_offset {
+            node_list.push(Self::read_node_legacy(packed_file_data, offset, signature, record_names, strings_utf8, strings_utf16)?);
+        }
+
+        // Make sure we decoded exactly the data we wanted.
+        if *offset != final_offset {
+            return Err(ErrorKind::ESFIncompleteDecoding.into())
+        }
+
+        Ok(node_list)
+    }
+
+    /// This function takes care of encoding a NodeType into the provided data.
+    ///
+    /// Nodes are encoded directly at the end of the data because record and array offsets are absolute.
+    fn save_node_legacy(node_type: &NodeType, data: &mut Vec<u8>, signature: ESFSignature, record_names: &[String], strings_utf8: &[String], strings_utf16: &[String]) -> Result<()> {
+        match node_type {
+
+            //------------------------------------------------//
+            // Record nodes.
+            //------------------------------------------------//
+            NodeType::Record(value) => {
+                let has_nested_blocks = value.record_flags.contains(RecordNodeFlags::HAS_NESTED_BLOCKS);
+                data.push(if has_nested_blocks { RECORD_BLOCK } else { RECORD });
+                data.encode_integer_u16(record_names.iter().position(|x| x == &value.name).unwrap() as u16);
+                data.push(value.version);
+
+                let final_block_offset_position = data.len();
+                data.encode_integer_u32(0);
+
+                if has_nested_blocks {
+                    data.encode_integer_u32(value.children.len() as u32);
+                    for group_node in &value.children {
+                        let final_entry_offset_position = data.len();
+                        data.encode_integer_u32(0);
+
+                        for node in group_node {
+                            Self::save_node_legacy(node, data, signature, record_names, strings_utf8, strings_utf16)?;
+                        }
+
+                        Self::set_end_offset_legacy(data, final_entry_offset_position);
+                    }
+                }
+
+                // For non-nested nodes, we just get the first and only children group.
+                else if let Some(children) = value.children.first() {
+                    for node in children {
+                        Self::save_node_legacy(node, data, signature, record_names, strings_utf8, strings_utf16)?;
+                    }
+                }
+
+                Self::set_end_offset_legacy(data, final_block_offset_position);
+            },
+
+            //------------------------------------------------//
+            // Inline strings, only in CEAB.
+            //------------------------------------------------//
+            NodeType::Utf16(value) if signature == ESFSignature::CEAB => {
+                data.push(UTF16);
+                data.encode_packedfile_string_u16(value);
+            },
+            NodeType::Ascii(value) if signature == ESFSignature::CEAB => {
+                data.push(ASCII);
+                data.encode_packedfile_string_u8(value);
+            },
+            NodeType::Utf16Array(value) if signature == ESFSignature::CEAB => {
+                data.push(UTF16_ARRAY);
+
+                let final_offset_position = data.len();
+                data.encode_integer_u32(0);
+                value.iter().for_each(|x| data.encode_packedfile_string_u16(x));
+                Self::set_end_offset_legacy(data, final_offset_position);
+            },
+            NodeType::AsciiArray(value) if signature == ESFSignature::CEAB => {
+                data.push(ASCII_ARRAY);
+
+                let final_offset_position = data.len();
+                data.encode_integer_u32(0);
+                value.iter().for_each(|x| data.encode_packedfile_string_u8(x));
+                Self::set_end_offset_legacy(data, final_offset_position);
+            },
+
+            //------------------------------------------------//
+            // Everything else is encoded like in CAAB, except arrays, which use their end offset instead of their size.
+            //------------------------------------------------//
+            _ => {
+                let node_data = Self::save_node(node_type, false, record_names, strings_utf8, strings_utf16)?;
+                match node_data.first() {
+                    Some(BOOL_ARRAY..=ANGLE_ARRAY) |
+                    Some(BOOL_TRUE_ARRAY..=F32_ZERO_ARRAY) => {
+                        let mut list_offset = 1;
+                        let size = node_data.decode_packedfile_integer_cauleb128(&mut list_offset)? as usize;
+                        let final_offset = data.len() + 5 + size;
+
+                        data.push(node_data[0]);
+                        data.encode_integer_u32(final_offset as u32);
+                        data.extend_from_slice(&node_data[list_offset..]);
+                    }
+                    _ => data.extend_from_slice(&node_data),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //---------------------------------------------------------------------------//
+    //                   Utility functions for CEAB and CFAB
+    //---------------------------------------------------------------------------//
+
+    /// This function replaces the placeholder offset at the provided position with the current end of the data.
+    fn set_end_offset_legacy(data: &mut [u8], position: usize) {
+        let final_offset = data.len() as u32;
+        data[position..position + 4].copy_from_slice(&final_offset.to_le_bytes());
+    }
+}
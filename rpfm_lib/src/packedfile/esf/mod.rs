@@ -19,7 +19,7 @@ use serde_derive::{Serialize, Deserialize};
 
 use std::{fmt, fmt::Display};
 
-use rpfm_error::{ErrorKind, Result};
+use rpfm_error::{Error, ErrorKind, Result};
 use rpfm_macros::*;
 
 use crate::common::decoder::Decoder;
@@ -33,7 +33,13 @@ pub const SIGNATURE_CEAB: &[u8; 4] = &[0xCE, 0xAB, 0x00, 0x00];
 pub const SIGNATURE_CFAB: &[u8; 4] = &[0xCF, 0xAB, 0x00, 0x00];
 
 pub mod caab;
-//pub mod diff;
+pub mod diff;
+mod float_serde;
+pub mod legacy;
+pub mod query;
+
+#[cfg(test)]
+mod esf_test;
 
 //---------------------------------------------------------------------------//
 //                              Markers, from ESFEdit
@@ -42,6 +48,10 @@ pub mod caab;
 /// Invalid marker.
 pub const INVALID: u8 = 0x00;
 
+/// Record markers used by the CEAB and CFAB formats. CAAB uses `RecordNodeFlags` instead.
+pub const RECORD: u8 = 0x80;
+pub const RECORD_BLOCK: u8 = 0x81;
+
 /// Primitives
 pub const BOOL: u8 = 0x01;
 pub const I8: u8 = 0x02;
@@ -155,7 +165,11 @@ pub enum ESFSignature {
 
     /// Signature found on 3K files.
     CAAB,
+
+    /// Legacy signature, with the strings stored inline in their nodes.
     CEAB,
+
+    /// Legacy signature, with the strings stored in string tables at the end of the file.
     CFAB
 }
 
@@ -179,7 +193,7 @@ pub enum NodeType {
     U32(U32Node),
     U64(u64),
     F32(F32Node),
-    F64(f64),
+    F64(#[serde(with = "float_serde")] f64),
     Coord2d(Coordinates2DNode),
     Coord3d(Coordinates3DNode),
     Utf16(String),
@@ -203,8 +217,8 @@ pub enum NodeType {
     U16Array(Vec<u16>),
     U32Array(VecU32Node),
     U64Array(Vec<u64>),
-    F32Array(Vec<f32>),
-    F64Array(Vec<f64>),
+    F32Array(#[serde(with = "float_serde::vec")] Vec<f32>),
+    F64Array(#[serde(with = "float_serde::vec")] Vec<f64>),
     Coord2dArray(Vec<Coordinates2DNode>),
     Coord3dArray(Vec<Coordinates3DNode>),
     Utf16Array(Vec<String>),
@@ -239,6 +253,7 @@ pub struct U32Node {
 /// Node containing an f32 value, and if the node should be optimized or not.
 #[derive(GetRef, GetRefMut, PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct F32Node {
+    #[serde(with = "float_serde")]
     value: f32,
     optimized: bool,
 }
@@ -260,15 +275,20 @@ pub struct VecU32Node {
 /// Node containing a pair of X/Y coordinates.
 #[derive(GetRef, GetRefMut, PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Coordinates2DNode {
+    #[serde(with = "float_serde")]
     x: f32,
+    #[serde(with = "float_serde")]
     y: f32,
 }
 
 /// Node containing a group of X/Y/Z coordinates.
 #[derive(GetRef, GetRefMut, PartialEq, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Coordinates3DNode {
+    #[serde(with = "float_serde")]
     x: f32,
+    #[serde(with = "float_serde")]
     y: f32,
+    #[serde(with = "float_serde")]
     z: f32,
 }
 
@@ -278,7 +298,20 @@ pub struct RecordNode {
     record_flags: RecordNodeFlags,
     version: u8,
     name: String,
-    children: Vec<Vec<NodeType>>
+    children: Vec<Vec<NodeType>>,
+
+    /// Original data of a decompressed node. Only used in compressed nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compressed_data: Option<Box<CompressedNodeData>>,
+}
+
+/// Original data of a compressed node, so it can be saved back unchanged if its decompressed nodes are not edited.
+#[derive(GetRef, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CompressedNodeData {
+    signature: ESFSignature,
+    unknown_1: u32,
+    creation_date: u32,
+    data: Vec<u8>,
 }
 
 //---------------------------------------------------------------------------//
@@ -291,7 +324,7 @@ impl ESF {
     /// This function returns if the provided data corresponds to a ESF or not.
     pub fn is_esf(data: &[u8]) -> bool {
         match data.get_bytes_checked(0, 4) {
-            Ok(signature) => signature == SIGNATURE_CAAB,
+            Ok(signature) => signature == SIGNATURE_CAAB || signature == SIGNATURE_CEAB || signature == SIGNATURE_CFAB,
             Err(_) => false,
         }
     }
@@ -308,24 +341,34 @@ impl ESF {
 
         let esf = match signature {
             ESFSignature::CAAB => Self::read_caab(packed_file_data)?,
-            _ => return  Err(ErrorKind::ESFUnsupportedSignature(format!("{:#X}{:#X}", signature_bytes[0], signature_bytes[1])).into())
+            ESFSignature::CEAB |
+            ESFSignature::CFAB => Self::read_legacy(packed_file_data, signature)?,
         };
 
-        //use std::io::Write;
-        //let mut x = std::fs::File::create("ceo.json")?;
-        //x.write_all(&serde_json::to_string_pretty(&esf).unwrap().as_bytes())?;
-
         Ok(esf)
     }
 
     /// This function takes a `ESF` and encodes it to `Vec<u8>`.
-    pub fn save(&self) -> Vec<u8> {
+    pub fn save(&self) -> Result<Vec<u8>> {
         match self.signature {
             ESFSignature::CAAB => self.save_caab(),
-            _ => return vec![],
+            ESFSignature::CEAB |
+            ESFSignature::CFAB => self.save_legacy(),
         }
     }
 
+    /// This function exports the `ESF` to a JSON document, so it can be edited as text and imported back later.
+    ///
+    /// The document keeps every detail needed to encode the file again, so importing it back generates the same binary file.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
+    }
+
+    /// This function creates a `ESF` from a JSON document generated with `to_json`.
+    pub fn from_json(data: &str) -> Result<Self> {
+        serde_json::from_str(data).map_err(From::from)
+    }
+
     /// This function creates a copy of an ESF without the root node..
     pub fn clone_without_root_node(&self) -> Self {
         Self {
@@ -348,6 +391,7 @@ impl NodeType {
                 new_node.set_name(node.get_ref_name().to_owned());
                 new_node.set_record_flags(*node.get_ref_record_flags());
                 new_node.set_version(*node.get_ref_version());
+                new_node.set_compressed_data(node.get_ref_compressed_data().clone());
 
                 Self::Record(new_node)
             }
@@ -356,6 +400,79 @@ impl NodeType {
         }
     }
 
+    /// This function returns the value of a primitive node as a string, or `None` if the node is not a primitive node.
+    ///
+    /// Coordinates are returned as their components separated by commas.
+    pub fn value_to_string(&self) -> Option<String> {
+        match self {
+            Self::Bool(node) => Some(node.value.to_string()),
+            Self::I8(value) => Some(value.to_string()),
+            Self::I16(value) |
+            Self::Angle(value) => Some(value.to_string()),
+            Self::I32(node) => Some(node.value.to_string()),
+            Self::I64(value) => Some(value.to_string()),
+            Self::U8(value) |
+            Self::Unknown23(value) => Some(value.to_string()),
+            Self::U16(value) => Some(value.to_string()),
+            Self::U32(node) => Some(node.value.to_string()),
+            Self::Unknown21(value) |
+            Self::Unknown25(value) => Some(value.to_string()),
+            Self::U64(value) => Some(value.to_string()),
+            Self::F32(node) => Some(node.value.to_string()),
+            Self::F64(value) => Some(value.to_string()),
+            Self::Coord2d(node) => Some(format!("{},{}", node.x, node.y)),
+            Self::Coord3d(node) => Some(format!("{},{},{}", node.x, node.y, node.z)),
+            Self::Utf16(value) |
+            Self::Ascii(value) => Some(value.to_owned()),
+            _ => None,
+        }
+    }
+
+    /// This function sets the value of a primitive node from a string, keeping the type of the node.
+    ///
+    /// The string must have the same format `value_to_string` returns.
+    pub fn set_value_from_str(&mut self, value: &str) -> Result<()> {
+        let error = || Error::from(ErrorKind::ESFInvalidNodeValue(value.to_owned()));
+        let coordinates = |count: usize| {
+            let coordinates = value.split(',').map(|x| x.trim().parse::<f32>()).collect::<std::result::Result<Vec<f32>, _>>().map_err(|_| error())?;
+            if coordinates.len() == count { Ok(coordinates) } else { Err(error()) }
+        };
+
+        match self {
+            Self::Bool(node) => node.value = value.parse().map_err(|_| error())?,
+            Self::I8(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::I16(node_value) |
+            Self::Angle(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::I32(node) => node.value = value.parse().map_err(|_| error())?,
+            Self::I64(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::U8(node_value) |
+            Self::Unknown23(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::U16(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::U32(node) => node.value = value.parse().map_err(|_| error())?,
+            Self::Unknown21(node_value) |
+            Self::Unknown25(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::U64(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::F32(node) => node.value = value.parse().map_err(|_| error())?,
+            Self::F64(node_value) => *node_value = value.parse().map_err(|_| error())?,
+            Self::Coord2d(node) => {
+                let coordinates = coordinates(2)?;
+                node.x = coordinates[0];
+                node.y = coordinates[1];
+            }
+            Self::Coord3d(node) => {
+                let coordinates = coordinates(3)?;
+                node.x = coordinates[0];
+                node.y = coordinates[1];
+                node.z = coordinates[2];
+            }
+            Self::Utf16(node_value) |
+            Self::Ascii(node_value) => *node_value = value.to_owned(),
+            _ => return Err(error()),
+        }
+
+        Ok(())
+    }
+
     /*pub fn get_removed_nodes(&self, vanilla_node: &NodeType) -> NodeType {
         match vanilla_node {
             Self::Record(vanilla_node) => {
@@ -372,166 +489,39 @@ impl NodeType {
             }
         }
     }*/
-/*
-    /// This function checks if the provided NodeType values are "equal", even if the type is different.
+
+    /// This function checks if the provided nodes have the same value, ignoring how they're encoded (like if they're optimized or not).
+    ///
+    /// For record nodes, this checks their children recursively.
     pub fn eq_value(&self, other: &Self) -> bool {
-        match self {
-           // Invalid type.
-            Self::Invalid => other == &Self::Invalid,
-
-            // Primitive nodes.
-            Self::Bool(value) => match other {
-                Self::Bool(other_value) => value.optimized == other_value.optimized && value.value == other_value.value,
-                Self::BoolTrue => value.optimized && value.value,
-                Self::BoolFalse => value.optimized && !value.value,
-                _ => false
-            },
-            Self::I8(value) => match other {
-                Self::I8(other_value) => value == other_value,
-                _ => false
-            },
-            Self::I16(value) => match other {
-                Self::I16(other_value) => value == other_value,
-                _ => false
-            },
-            Self::I32(value) => match other {
-                Self::I32Zero => *value == 0,
-                Self::I32Byte(other_value) => value == other_value,
-                Self::I32_16bit(other_value) => value == other_value,
-                Self::I32_24bit(other_value) => value == other_value,
-                Self::I32(other_value) => value == other_value,
-                _ => false
-            },
-            Self::I64(value) => match other {
-                Self::I64(other_value) => value == other_value,
-                _ => false
-            },
-            Self::U8(value) => match other {
-                Self::U8(other_value) => value == other_value,
-                _ => false
-            },
-            Self::U16(value) => match other {
-                Self::U16(other_value) => value == other_value,
-                _ => false
-            },
-            Self::U32(value) => match other {
-                Self::U32Zero => *value == 0,
-                Self::U32One => *value == 1,
-                Self::U32Byte(other_value) => value == other_value,
-                Self::U32_16bit(other_value) => value == other_value,
-                Self::U32_24bit(other_value) => value == other_value,
-                Self::U32(other_value) => value == other_value,
-                _ => false
-            },
-            Self::U64(value) => match other {
-                Self::U64(other_value) => value == other_value,
-                _ => false
-            },
-            Self::F32(value) => match other {
-                Self::F32(other_value) =>  (value - other_value).abs() >= std::f32::EPSILON,
-                Self::F32Zero =>  (value - 0.0).abs() >= std::f32::EPSILON,
-                _ => false
-            },
-            Self::F64(value) => match other {
-                Self::F64(other_value) => value == other_value,
-                _ => false
-            },
-            Self::Coord2d(value) => match other {
-                Self::Coord2d(other_value) => value == other_value,
-                _ => false
-            },
-            Self::Coord3d(value) => match other {
-                Self::Coord3d(other_value) => value == other_value,
-                _ => false
-            },
-            Self::Utf16(value) => match other {
-                Self::Utf16(other_value) => value == other_value,
-                _ => false
-            },
-            Self::Ascii(value) => match other {
-                Self::Ascii(other_value) => value == other_value,
-                _ => false
-            },
-            Self::Angle(value) => match other {
-                Self::Angle(other_value) => value == other_value,
-                _ => false
-            },
-
-            // Optimized Primitives
-            Self::BoolTrue => match other {
-                Self::Bool(other_value) => other_value.optimized && other_value.value,
-                Self::BoolTrue => true,
-                _ => false
-            },
-            Self::BoolFalse => match other {
-                Self::Bool(other_value) => other_value.optimized && !other_value.value,
-                Self::BoolFalse => true,
-                _ => false
-            },
-            Self::U32Zero => match other {
-                Self::U32Zero => true,
-                Self::U32One => false,
-                Self::U32Byte(other_value) => *other_value == 0,
-                Self::U32_16bit(other_value) => *other_value == 0,
-                Self::U32_24bit(other_value) => *other_value == 0,
-                Self::U32(other_value) => *other_value == 0,
-                _ => false
-            },
-            Self::U32One => match other {
-                Self::U32Zero => false,
-                Self::U32One => true,
-                Self::U32Byte(other_value) => *other_value == 1,
-                Self::U32_16bit(other_value) => *other_value == 1,
-                Self::U32_24bit(other_value) => *other_value == 1,
-                Self::U32(other_value) => *other_value == 1,
-                _ => false
-            },
-            Self::U32Byte(value) => {false},
-            Self::U32_16bit(value) => {false},
-            Self::U32_24bit(value) => {false},
-            Self::I32Zero => {false},
-            Self::I32Byte(value) => {false},
-            Self::I32_16bit(value) => {false},
-            Self::I32_24bit(value) => {false},
-            Self::F32Zero => {false},
-
-            // Unknown Types
-            Self::Unknown21(value) => {false},
-            Self::Unknown23(value) => {false},
-            //Self::Unknown24(u32) => {false},
-            Self::Unknown25(value) => {false},
-            Self::Unknown26(value) => {false},
-
-            // Primitive Arrays
-            Self::BoolArray(value) => {false},
-            Self::I8Array(value) => {false},
-            Self::I16Array(value) => {false},
-            Self::I32Array(value) => {false},
-            Self::I64Array(value) => {false},
-            Self::U8Array(value) => {false},
-            Self::U16Array(value) => {false},
-            Self::U32Array(value) => {false},
-            Self::U64Array(value) => {false},
-            Self::F32Array(value) => {false},
-            Self::F64Array(value) => {false},
-            Self::Coord2dArray(value) => {false},
-            Self::Coord3dArray(value) => {false},
-            Self::Utf16Array(value) => {false},
-            Self::AsciiArray(value) => {false},
-            Self::AngleArray(value) => {false},
-
-            // Optimized Arrays
-            Self::U32ByteArray(value) => {false},
-            Self::U32_16bitArray(value) => {false},
-            Self::U32_24bitArray(value) => {false},
-            Self::I32ByteArray(value) => {false},
-            Self::I32_16bitArray(value) => {false},
-            Self::I32_24bitArray(value) => {false},
-
-            // Record nodes
-            Self::Record(value) => {false},
+        match (self, other) {
+            (Self::Bool(value), Self::Bool(other_value)) => value.value == other_value.value,
+            (Self::I32(value), Self::I32(other_value)) => value.value == other_value.value,
+            (Self::U32(value), Self::U32(other_value)) => value.value == other_value.value,
+            (Self::F32(value), Self::F32(other_value)) => value.value == other_value.value || (value.value.is_nan() && other_value.value.is_nan()),
+            (Self::F64(value), Self::F64(other_value)) => value == other_value || (value.is_nan() && other_value.is_nan()),
+            (Self::I32Array(value), Self::I32Array(other_value)) => value.value == other_value.value,
+            (Self::U32Array(value), Self::U32Array(other_value)) => value.value == other_value.value,
+            (Self::Record(value), Self::Record(other_value)) => value.eq_value(other_value),
+            _ => self == other,
         }
-    }*/
+    }
+}
+
+/// Implementation of `RecordNode`.
+impl RecordNode {
+
+    /// This function checks if the provided record nodes have the same values, ignoring how they're encoded.
+    pub fn eq_value(&self, other: &Self) -> bool {
+        let encoding_flags = RecordNodeFlags::HAS_NON_OPTIMIZED_INFO;
+        self.name == other.name &&
+            self.version == other.version &&
+            self.record_flags - encoding_flags == other.record_flags - encoding_flags &&
+            self.children.len() == other.children.len() &&
+            self.children.iter().zip(other.children.iter()).all(|(group, other_group)| {
+                group.len() == other_group.len() && group.iter().zip(other_group.iter()).all(|(node, other_node)| node.eq_value(other_node))
+            })
+    }
 }
 
 /// Default implementation for `ESF`.
@@ -0,0 +1,400 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code needed to query the nodes of ESF files using paths.
+
+Queries are XPath-like paths, made of segments separated by `/`. Each segment matches one level of the tree:
+
+- The first segment matches the root node.
+- For record nodes, a segment matches their child record nodes by name, or all of them with `*`.
+- For record nodes with nested blocks, a segment matches their groups instead: by index (`3`), by the name
+  the ESF View gives them (`FACTION_ARRAY_3`), or all of them with `*`.
+- The last segment can be `@N`, which matches the value at the position N of the node (or group) matched by the previous segment.
+
+If a query ends in a group, it matches all the nodes in that group.
+
+Segments can be filtered with predicates between brackets:
+
+- `[N]`: only the match at the position N (zero-based) within its parent.
+- `[@N="value"]`: only the matches with `value` at the position N. Values can use double or single quotes.
+- `[name="value"]`: only the matches with any value equal to `value`. As values have no names in ESF files,
+  the identifier before `=` is just there for readability, and can be omitted.
+
+For example: `CAMPAIGN_SAVE_GAME/FACTION_ARRAY/0/FACTION[name="wh_main_emp_empire"]/TREASURY`. Replacing the `0`
+with `*` searches the faction in all the groups of the array.
+!*/
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use super::*;
+
+/// Separator between the segments of a query.
+const SEGMENT_SEPARATOR: char = '/';
+
+/// Path to a node within an ESF file. For each level, it contains the group and the position within the group of the node.
+///
+/// The root node has an empty path.
+pub type ESFNodePath = Vec<(usize, usize)>;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a segment of a query.
+#[derive(Clone, Debug)]
+struct Segment {
+    selector: Selector,
+    predicates: Vec<Predicate>,
+}
+
+/// This enum represents the different ways a segment can select nodes.
+#[derive(Clone, Debug)]
+enum Selector {
+
+    /// Matches all records, or all groups.
+    Any,
+
+    /// Matches records or groups by name.
+    Name(String),
+
+    /// Matches groups by index.
+    Index(usize),
+
+    /// Matches the value at the provided position.
+    Value(usize),
+}
+
+/// This enum represents the different filters a segment can have.
+#[derive(Clone, Debug)]
+enum Predicate {
+
+    /// Only the match at this position.
+    Position(usize),
+
+    /// Only the matches with this value at this position.
+    ValueAt(usize, String),
+
+    /// Only the matches with this value at any position.
+    AnyValue(String),
+}
+
+/// This enum represents an intermediate match of a query: either a record node, or a group of a record node.
+#[derive(Clone, Debug)]
+enum Cursor<'a> {
+    Record(&'a RecordNode, ESFNodePath),
+    Group(&'a RecordNode, ESFNodePath, usize),
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementation of ESF
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ESF`. Section of functions to query its nodes.
+impl ESF {
+
+    /// This function returns the paths of all the nodes matching the provided query.
+    pub fn query_paths(&self, query: &str) -> Result<Vec<ESFNodePath>> {
+        let mut segments = parse_query(query)?;
+        let value_position = match segments.last().map(|segment| &segment.selector) {
+            Some(Selector::Value(position)) => {
+                let position = *position;
+                segments.pop();
+                Some(position)
+            }
+            _ => None,
+        };
+
+        // The first segment always matches the root node.
+        let mut cursors = match &self.root_node {
+            NodeType::Record(node) if !segments.is_empty() => {
+                let cursor = Cursor::Record(node, vec![]);
+                let matches = match &segments[0].selector {
+                    Selector::Any => true,
+                    Selector::Name(name) => name == &node.name,
+                    Selector::Index(_) | Selector::Value(_) => false,
+                };
+
+                if matches { filter_cursors(vec![cursor], &segments[0].predicates) } else { vec![] }
+            }
+            _ => vec![],
+        };
+
+        for segment in segments.iter().skip(1) {
+            cursors = cursors.iter().flat_map(|cursor| filter_cursors(cursor.children(&segment.selector), &segment.predicates)).collect();
+        }
+
+        let paths = cursors.iter().flat_map(|cursor| match value_position {
+            Some(position) => cursor.value_path(position).into_iter().collect::<Vec<_>>(),
+            None => cursor.paths(),
+        }).collect();
+
+        Ok(paths)
+    }
+
+    /// This function returns all the nodes matching the provided query.
+    pub fn query(&self, query: &str) -> Result<Vec<&NodeType>> {
+        Ok(self.query_paths(query)?.iter().filter_map(|path| self.get_node(path)).collect())
+    }
+
+    /// This function sets the value of all the primitive nodes matching the provided query, returning how many nodes were changed.
+    ///
+    /// If the value is not valid for any of the nodes, no node is changed.
+    pub fn set_query_value(&mut self, query: &str, value: &str) -> Result<usize> {
+        let paths = self.query_paths(query)?;
+        let mut new_nodes = Vec::with_capacity(paths.len());
+        for path in &paths {
+            if let Some(node) = self.get_node(path) {
+                let mut node = node.clone();
+                node.set_value_from_str(value)?;
+                new_nodes.push(node);
+            }
+        }
+
+        for (path, new_node) in paths.iter().zip(new_nodes) {
+            if let Some(node) = self.get_node_mut(path) {
+                *node = new_node;
+            }
+        }
+
+        Ok(paths.len())
+    }
+
+    /// This function returns the node at the provided path, if exists.
+    pub fn get_node(&self, path: &[(usize, usize)]) -> Option<&NodeType> {
+        let mut node = &self.root_node;
+        for (group, index) in path {
+            match node {
+                NodeType::Record(record) => node = record.children.get(*group)?.get(*index)?,
+                _ => return None,
+            }
+        }
+        Some(node)
+    }
+
+    /// This function returns a mutable reference to the node at the provided path, if exists.
+    pub fn get_node_mut(&mut self, path: &[(usize, usize)]) -> Option<&mut NodeType> {
+        let mut node = &mut self.root_node;
+        for (group, index) in path {
+            match node {
+                NodeType::Record(record) => node = record.children.get_mut(*group)?.get_mut(*index)?,
+                _ => return None,
+            }
+        }
+        Some(node)
+    }
+}
+
+/// Implementation of `RecordNode`. Section of functions related to queries.
+impl RecordNode {
+
+    /// This function returns the name of the group at the provided index, as shown in the ESF View.
+    ///
+    /// Groups with just a key and a record use the key as name. The rest use the name of the record and their index.
+    pub fn get_group_name(&self, index: usize) -> String {
+        match self.children.get(index) {
+            Some(group) if group.len() == 2 => match &group[0] {
+                NodeType::Ascii(key) => key.to_owned(),
+                _ => format!("{}_{}", self.name, index),
+            },
+            _ => format!("{}_{}", self.name, index),
+        }
+    }
+}
+
+/// Implementation of `Cursor`.
+impl<'a> Cursor<'a> {
+
+    /// This function returns the cursors of the children of this cursor matching the provided selector.
+    fn children(&self, selector: &Selector) -> Vec<Cursor<'a>> {
+        match self {
+            Self::Record(record, path) if record.record_flags.contains(RecordNodeFlags::HAS_NESTED_BLOCKS) => {
+                (0..record.children.len()).filter(|index| match selector {
+                    Selector::Any => true,
+                    Selector::Name(name) => &record.get_group_name(*index) == name,
+                    Selector::Index(group) => group == index,
+                    Selector::Value(_) => false,
+                }).map(|index| Self::Group(record, path.to_vec(), index)).collect()
+            }
+
+            _ => self.nodes().filter_map(|(path, node)| match node {
+                NodeType::Record(child) => match selector {
+                    Selector::Any => Some(Self::Record(child, path)),
+                    Selector::Name(name) if name == &child.name => Some(Self::Record(child, path)),
+                    _ => None,
+                },
+                _ => None,
+            }).collect()
+        }
+    }
+
+    /// This function returns an iterator over all the direct child nodes of this cursor, with their paths.
+    fn nodes(&self) -> Box<dyn Iterator<Item = (ESFNodePath, &'a NodeType)> + '_> {
+        match self {
+            Self::Record(record, path) => Box::new(record.children.iter().enumerate().flat_map(move |(group, nodes)| {
+                nodes.iter().enumerate().map(move |(index, node)| {
+                    let mut path = path.to_vec();
+                    path.push((group, index));
+                    (path, node)
+                })
+            })),
+            Self::Group(record, path, group) => Box::new(record.children[*group].iter().enumerate().map(move |(index, node)| {
+                let mut path = path.to_vec();
+                path.push((*group, index));
+                (path, node)
+            })),
+        }
+    }
+
+    /// This function returns the path of the value at the provided position, if exists.
+    fn value_path(&self, position: usize) -> Option<ESFNodePath> {
+        self.nodes().nth(position).map(|(path, _)| path)
+    }
+
+    /// This function returns the paths this cursor points to.
+    fn paths(&self) -> Vec<ESFNodePath> {
+        match self {
+            Self::Record(_, path) => vec![path.to_vec()],
+            Self::Group(..) => self.nodes().map(|(path, _)| path).collect(),
+        }
+    }
+
+    /// This function returns if this cursor has the provided value at the provided position, or at any position if no position is provided.
+    fn has_value(&self, position: Option<usize>, value: &str) -> bool {
+        let is_value = |node: &NodeType| node.value_to_string().map_or(false, |node_value| node_value == value);
+        match position {
+            Some(position) => self.nodes().nth(position).map_or(false, |(_, node)| is_value(node)),
+            None => self.nodes().any(|(_, node)| is_value(node)),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Utility functions for queries
+//---------------------------------------------------------------------------//
+
+/// This function applies the provided predicates, in order, to the provided cursors.
+fn filter_cursors<'a>(mut cursors: Vec<Cursor<'a>>, predicates: &[Predicate]) -> Vec<Cursor<'a>> {
+    for predicate in predicates {
+        cursors = match predicate {
+            Predicate::Position(position) => cursors.into_iter().nth(*position).into_iter().collect(),
+            Predicate::ValueAt(position, value) => cursors.into_iter().filter(|cursor| cursor.has_value(Some(*position), value)).collect(),
+            Predicate::AnyValue(value) => cursors.into_iter().filter(|cursor| cursor.has_value(None, value)).collect(),
+        };
+    }
+    cursors
+}
+
+/// This function parses a query into its segments.
+fn parse_query(query: &str) -> Result<Vec<Segment>> {
+    let error = |reason: &str| Error::from(ErrorKind::ESFInvalidQuery(query.to_owned(), reason.to_owned()));
+
+    // Split the segments, ignoring separators within quotes.
+    let mut raw_segments = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    for character in query.trim().trim_start_matches(SEGMENT_SEPARATOR).chars() {
+        match character {
+            '"' | '\'' => {
+                quote = match quote {
+                    Some(quote) if quote == character => None,
+                    Some(quote) => Some(quote),
+                    None => Some(character),
+                };
+                current.push(character);
+            }
+            SEGMENT_SEPARATOR if quote.is_none() => raw_segments.push(std::mem::take(&mut current)),
+            _ => current.push(character),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(error("Unclosed quotes."));
+    }
+    raw_segments.push(current);
+
+    let mut segments = Vec::with_capacity(raw_segments.len());
+    for (index, raw_segment) in raw_segments.iter().enumerate() {
+        let (selector, mut predicates) = match raw_segment.find('[') {
+            Some(position) => raw_segment.split_at(position),
+            None => (&**raw_segment, ""),
+        };
+
+        let selector = match selector.trim() {
+            "" => return Err(error("Empty segment.")),
+            "*" => Selector::Any,
+            selector if selector.starts_with('@') => match selector[1..].parse() {
+                Ok(position) if index == raw_segments.len() - 1 => Selector::Value(position),
+                Ok(_) => return Err(error("Values can only be selected in the last segment.")),
+                Err(_) => return Err(error("Invalid value position.")),
+            },
+            selector => match selector.parse() {
+                Ok(index) => Selector::Index(index),
+                Err(_) => Selector::Name(selector.to_owned()),
+            },
+        };
+
+        let mut parsed_predicates = vec![];
+        while !predicates.is_empty() {
+            let end = find_predicate_end(predicates).ok_or_else(|| error("Unclosed predicate."))?;
+            if !predicates.starts_with('[') {
+                return Err(error("Invalid predicate."));
+            }
+
+            parsed_predicates.push(parse_predicate(&predicates[1..end]).ok_or_else(|| error("Invalid predicate."))?);
+            predicates = &predicates[end + 1..];
+        }
+
+        segments.push(Segment {
+            selector,
+            predicates: parsed_predicates,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// This function returns the position of the `]` closing the predicate at the start of the provided string.
+fn find_predicate_end(predicates: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, character) in predicates.char_indices() {
+        match character {
+            '"' | '\'' => quote = match quote {
+                Some(quote) if quote == character => None,
+                Some(quote) => Some(quote),
+                None => Some(character),
+            },
+            ']' if quote.is_none() => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// This function parses the content of a predicate, without the brackets.
+fn parse_predicate(predicate: &str) -> Option<Predicate> {
+    match predicate.split_once('=') {
+        Some((key, value)) => {
+            let key = key.trim();
+            let value = value.trim();
+            let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')))
+                .unwrap_or(value)
+                .to_owned();
+
+            match key.strip_prefix('@') {
+                Some(position) => Some(Predicate::ValueAt(position.parse().ok()?, value)),
+                None if key.chars().all(|character| character.is_alphanumeric() || character == '_') => Some(Predicate::AnyValue(value)),
+                None => None,
+            }
+        }
+        None => Some(Predicate::Position(predicate.trim().parse().ok()?)),
+    }
+}
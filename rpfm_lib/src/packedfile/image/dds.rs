@@ -0,0 +1,638 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to decode and encode DDS images.
+
+DDS images have a 128 bytes header (including its signature) with their size, amount of mipmaps and pixel format.
+If their pixel format has the `DX10` FourCC, there is a second 20 bytes header with their DXGI format.
+After the headers go the images, from the biggest mipmap to the smallest one.
+
+We can decode BC1 to BC5 (DXT1 to DXT5, ATI1 and ATI2) and uncompressed formats described by bit masks.
+BC6H and BC7 are identified, but not decoded. The same formats, except BC6H and BC7, can be encoded.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::{fmt, fmt::Display};
+use std::str::FromStr;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::GetRef;
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+use super::{DDS_SIGNATURE, RgbaImage};
+
+/// Sizes of the headers of a DDS image, including the signature in the first one.
+const HEADER_SIZE: usize = 128;
+const DX10_HEADER_SIZE: usize = 20;
+
+/// Size of the pixel format struct within the header.
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+/// Flags of the header. Only the ones we write or read are here.
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+
+/// Flags of the pixel format.
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_ALPHA: u32 = 0x2;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x20000;
+
+/// Capabilities of the image.
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the header of a DDS image.
+#[derive(GetRef, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DdsHeader {
+    width: u32,
+    height: u32,
+
+    /// Only used by volume textures. 1 for any other image.
+    depth: u32,
+
+    /// Amount of mipmaps, including the full-size image.
+    mipmap_count: u32,
+    format: DdsFormat,
+
+    /// Offset of the full-size image within the file.
+    data_offset: usize,
+}
+
+/// This enum represents the pixel formats of DDS images.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum DdsFormat {
+
+    /// Also known as DXT1.
+    Bc1,
+
+    /// Also known as DXT2 and DXT3.
+    Bc2,
+
+    /// Also known as DXT4 and DXT5.
+    Bc3,
+
+    /// Also known as ATI1.
+    Bc4,
+
+    /// Also known as ATI2.
+    Bc5,
+    Bc6h,
+    Bc7,
+
+    /// Uncompressed format, with its bits per pixel and the bit masks of its red, green, blue and alpha channels.
+    Uncompressed {
+        bit_count: u32,
+        masks: [u32; 4],
+    },
+
+    /// Any other format, with its FourCC or DXGI format.
+    Unknown(String),
+}
+
+//---------------------------------------------------------------------------//
+//                           Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DdsHeader`.
+impl DdsHeader {
+
+    /// This function reads the headers of a DDS image.
+    pub fn read(data: &[u8]) -> Result<Self> {
+        if !data.starts_with(DDS_SIGNATURE) {
+            return Err(ErrorKind::ImageDecode("This is not a DDS image.".to_owned()).into());
+        }
+
+        let height = data.decode_integer_u32(12)?;
+        let width = data.decode_integer_u32(16)?;
+        let depth = data.decode_integer_u32(24)?.max(1);
+        let mipmap_count = data.decode_integer_u32(28)?.max(1);
+
+        let pixel_format_flags = data.decode_integer_u32(80)?;
+        let four_cc = data.get_bytes_checked(84, 4)?;
+        let bit_count = data.decode_integer_u32(88)?;
+        let mut masks = [0; 4];
+        for (index, mask) in masks.iter_mut().enumerate() {
+            *mask = data.decode_integer_u32(92 + index * 4)?;
+        }
+
+        let mut data_offset = HEADER_SIZE;
+        let format = if pixel_format_flags & DDPF_FOURCC != 0 {
+            match four_cc {
+                b"DXT1" => DdsFormat::Bc1,
+                b"DXT2" | b"DXT3" => DdsFormat::Bc2,
+                b"DXT4" | b"DXT5" => DdsFormat::Bc3,
+                b"ATI1" | b"BC4U" => DdsFormat::Bc4,
+                b"ATI2" | b"BC5U" => DdsFormat::Bc5,
+                b"DX10" => {
+                    data_offset += DX10_HEADER_SIZE;
+                    DdsFormat::from_dxgi_format(data.decode_integer_u32(HEADER_SIZE)?)
+                }
+                _ => DdsFormat::Unknown(String::from_utf8_lossy(four_cc).to_string()),
+            }
+        }
+
+        // Luminance images use the red mask for all the colour channels. Alpha masks are only valid with their flags.
+        else if pixel_format_flags & (DDPF_RGB | DDPF_LUMINANCE | DDPF_ALPHA) != 0 {
+            if pixel_format_flags & DDPF_LUMINANCE != 0 {
+                masks[1] = masks[0];
+                masks[2] = masks[0];
+            } else if pixel_format_flags & DDPF_RGB == 0 {
+                masks[0] = 0;
+                masks[1] = 0;
+                masks[2] = 0;
+            }
+
+            if pixel_format_flags & (DDPF_ALPHAPIXELS | DDPF_ALPHA) == 0 {
+                masks[3] = 0;
+            }
+
+            DdsFormat::Uncompressed {
+                bit_count,
+                masks,
+            }
+        }
+
+        else {
+            DdsFormat::Unknown(format!("Pixel format flags {:#X}", pixel_format_flags))
+        };
+
+        Ok(Self {
+            width,
+            height,
+            depth,
+            mipmap_count,
+            format,
+            data_offset,
+        })
+    }
+}
+
+/// Implementation of `DdsFormat`.
+impl DdsFormat {
+
+    /// This function returns the format corresponding to the provided DXGI format.
+    fn from_dxgi_format(dxgi_format: u32) -> Self {
+        match dxgi_format {
+            27..=29 => Self::Uncompressed { bit_count: 32, masks: [0xFF, 0xFF00, 0xFF_0000, 0xFF00_0000] },
+            61 => Self::Uncompressed { bit_count: 8, masks: [0xFF, 0, 0, 0] },
+            65 => Self::Uncompressed { bit_count: 8, masks: [0, 0, 0, 0xFF] },
+            70..=72 => Self::Bc1,
+            73..=75 => Self::Bc2,
+            76..=78 => Self::Bc3,
+            79 | 80 => Self::Bc4,
+            82 | 83 => Self::Bc5,
+            87 | 90 | 91 => Self::Uncompressed { bit_count: 32, masks: [0xFF_0000, 0xFF00, 0xFF, 0xFF00_0000] },
+            88 | 92 | 93 => Self::Uncompressed { bit_count: 32, masks: [0xFF_0000, 0xFF00, 0xFF, 0] },
+            94..=96 => Self::Bc6h,
+            97..=99 => Self::Bc7,
+            _ => Self::Unknown(format!("DXGI format {}", dxgi_format)),
+        }
+    }
+
+    /// This function returns the size in bytes of each block of 4x4 pixels, if the format is block-compressed.
+    fn get_block_size(&self) -> Option<usize> {
+        match self {
+            Self::Bc1 | Self::Bc4 => Some(8),
+            Self::Bc2 | Self::Bc3 | Self::Bc5 | Self::Bc6h | Self::Bc7 => Some(16),
+            Self::Uncompressed { .. } | Self::Unknown(_) => None,
+        }
+    }
+
+    /// This function returns the size in bytes of an image of the provided size in this format.
+    fn get_image_size(&self, width: u32, height: u32) -> Result<usize> {
+        match self.get_block_size() {
+            Some(block_size) => Ok(((width as usize + 3) / 4).max(1) * ((height as usize + 3) / 4).max(1) * block_size),
+            None => match self {
+                Self::Uncompressed { bit_count, .. } if *bit_count > 0 && *bit_count <= 32 && bit_count % 8 == 0 => Ok(width as usize * height as usize * (*bit_count as usize / 8)),
+                _ => Err(ErrorKind::ImageDecode(format!("Images in {} format are not supported.", self)).into()),
+            }
+        }
+    }
+}
+
+/// Implementation of `FromStr` for `DdsFormat`, to get the formats we can encode from their names.
+impl FromStr for DdsFormat {
+    type Err = rpfm_error::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match &*format.to_lowercase() {
+            "bc1" | "dxt1" => Ok(Self::Bc1),
+            "bc2" | "dxt3" => Ok(Self::Bc2),
+            "bc3" | "dxt5" => Ok(Self::Bc3),
+            "bc4" | "ati1" => Ok(Self::Bc4),
+            "bc5" | "ati2" => Ok(Self::Bc5),
+            "bgra8" => Ok(Self::Uncompressed { bit_count: 32, masks: [0xFF_0000, 0xFF00, 0xFF, 0xFF00_0000] }),
+            "rgba8" => Ok(Self::Uncompressed { bit_count: 32, masks: [0xFF, 0xFF00, 0xFF_0000, 0xFF00_0000] }),
+            _ => Err(ErrorKind::ImageDecode(format!("Unknown DDS format: {}. Valid formats are bc1, bc2, bc3, bc4, bc5, bgra8 and rgba8.", format)).into()),
+        }
+    }
+}
+
+/// Display implementation of `DdsFormat`.
+impl Display for DdsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bc1 => write!(f, "BC1 (DXT1)"),
+            Self::Bc2 => write!(f, "BC2 (DXT3)"),
+            Self::Bc3 => write!(f, "BC3 (DXT5)"),
+            Self::Bc4 => write!(f, "BC4 (ATI1)"),
+            Self::Bc5 => write!(f, "BC5 (ATI2)"),
+            Self::Bc6h => write!(f, "BC6H"),
+            Self::Bc7 => write!(f, "BC7"),
+            Self::Uncompressed { bit_count, masks } => write!(f, "Uncompressed ({} bits, masks {:#X} {:#X} {:#X} {:#X})", bit_count, masks[0], masks[1], masks[2], masks[3]),
+            Self::Unknown(format) => write!(f, "Unknown ({})", format),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Decoding functions
+//---------------------------------------------------------------------------//
+
+/// This function decodes the full-size image of a DDS image to RGBA.
+pub fn decode(data: &[u8]) -> Result<RgbaImage> {
+    let header = DdsHeader::read(data)?;
+    let size = header.format.get_image_size(header.width, header.height)?;
+    let image_data = data.get(header.data_offset..header.data_offset + size).ok_or_else(|| ErrorKind::ImageDecode(format!("The image needs {} bytes of data, but the file is too small.", size)))?;
+
+    let width = header.width as usize;
+    let height = header.height as usize;
+    let mut pixels = vec![0; width * height * 4];
+
+    match header.format {
+        DdsFormat::Uncompressed { bit_count, masks } => {
+            let pixel_size = bit_count as usize / 8;
+            for (index, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+                let mut value = 0u32;
+                for (byte_index, byte) in image_data[index * pixel_size..(index + 1) * pixel_size].iter().enumerate() {
+                    value |= (*byte as u32) << (byte_index * 8);
+                }
+
+                for channel in 0..4 {
+                    pixel[channel] = match masks[channel] {
+                        0 => if channel == 3 { 255 } else { 0 },
+                        mask => {
+                            let max = mask >> mask.trailing_zeros();
+                            ((((value & mask) >> mask.trailing_zeros()) as u64 * 255 + max as u64 / 2) / max as u64) as u8
+                        }
+                    };
+                }
+            }
+        }
+
+        _ => {
+            let block_size = header.format.get_block_size().unwrap_or_default();
+            let blocks_width = ((width + 3) / 4).max(1);
+            for (block_index, block) in image_data.chunks_exact(block_size).enumerate() {
+                let block_pixels = decode_block(block, &header.format)?;
+                let block_x = (block_index % blocks_width) * 4;
+                let block_y = (block_index / blocks_width) * 4;
+
+                // Blocks on the borders may be partially out of the image.
+                for (pixel_index, pixel) in block_pixels.iter().enumerate() {
+                    let x = block_x + pixel_index % 4;
+                    let y = block_y + pixel_index / 4;
+                    if x < width && y < height {
+                        let index = (y * width + x) * 4;
+                        pixels[index..index + 4].copy_from_slice(pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    RgbaImage::new(header.width, header.height, pixels)
+}
+
+/// This function decodes a block of 4x4 pixels of a block-compressed format.
+fn decode_block(block: &[u8], format: &DdsFormat) -> Result<[[u8; 4]; 16]> {
+    let mut pixels = [[0, 0, 0, 255]; 16];
+    match format {
+        DdsFormat::Bc1 => decode_colour_block(block, &mut pixels, true),
+        DdsFormat::Bc2 => {
+            decode_colour_block(&block[8..], &mut pixels, false);
+            for (index, pixel) in pixels.iter_mut().enumerate() {
+                pixel[3] = ((block[index / 2] >> ((index % 2) * 4)) & 0xF) * 17;
+            }
+        }
+        DdsFormat::Bc3 => {
+            decode_colour_block(&block[8..], &mut pixels, false);
+            decode_channel_block(block, &mut pixels, &[3]);
+        }
+        DdsFormat::Bc4 => decode_channel_block(block, &mut pixels, &[0, 1, 2]),
+        DdsFormat::Bc5 => {
+            decode_channel_block(block, &mut pixels, &[0]);
+            decode_channel_block(&block[8..], &mut pixels, &[1]);
+        }
+        _ => return Err(ErrorKind::ImageDecode(format!("Images in {} format are not supported.", format)).into()),
+    }
+
+    Ok(pixels)
+}
+
+/// This function decodes the colours of a BC1-3 block. Only BC1 blocks can use 1 bit alpha.
+fn decode_colour_block(block: &[u8], pixels: &mut [[u8; 4]; 16], is_bc1: bool) {
+    let colour_0 = u16::from_le_bytes([block[0], block[1]]);
+    let colour_1 = u16::from_le_bytes([block[2], block[3]]);
+    let palette = get_colour_palette(colour_0, colour_1, !is_bc1 || colour_0 > colour_1);
+
+    let indexes = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for (index, pixel) in pixels.iter_mut().enumerate() {
+        *pixel = palette[((indexes >> (index * 2)) & 0b11) as usize];
+    }
+}
+
+/// This function decodes a BC3-5 single channel block into the provided channels of the pixels.
+fn decode_channel_block(block: &[u8], pixels: &mut [[u8; 4]; 16], channels: &[usize]) {
+    let palette = get_channel_palette(block[0], block[1]);
+
+    let mut indexes = 0u64;
+    for (index, byte) in block[2..8].iter().enumerate() {
+        indexes |= (*byte as u64) << (index * 8);
+    }
+
+    for (index, pixel) in pixels.iter_mut().enumerate() {
+        let value = palette[((indexes >> (index * 3)) & 0b111) as usize];
+        channels.iter().for_each(|channel| pixel[*channel] = value);
+    }
+}
+
+/// This function returns the palette of a colour block. In 3 colour mode, the last colour is transparent black.
+fn get_colour_palette(colour_0: u16, colour_1: u16, four_colours: bool) -> [[u8; 4]; 4] {
+    let colour_0 = rgb565_to_rgba(colour_0);
+    let colour_1 = rgb565_to_rgba(colour_1);
+
+    let mut palette = [colour_0, colour_1, [0, 0, 0, 255], [0, 0, 0, 0]];
+    for channel in 0..3 {
+        let value_0 = colour_0[channel] as u32;
+        let value_1 = colour_1[channel] as u32;
+        if four_colours {
+            palette[2][channel] = ((2 * value_0 + value_1) / 3) as u8;
+            palette[3][channel] = ((value_0 + 2 * value_1) / 3) as u8;
+        } else {
+            palette[2][channel] = ((value_0 + value_1) / 2) as u8;
+        }
+    }
+
+    if four_colours {
+        palette[3][3] = 255;
+    }
+
+    palette
+}
+
+/// This function returns the palette of a single channel block.
+fn get_channel_palette(value_0: u8, value_1: u8) -> [u8; 8] {
+    let value_0_u32 = value_0 as u32;
+    let value_1_u32 = value_1 as u32;
+
+    let mut palette = [value_0, value_1, 0, 0, 0, 0, 0, 255];
+    if value_0 > value_1 {
+        for index in 1..7 {
+            palette[index + 1] = (((7 - index) as u32 * value_0_u32 + index as u32 * value_1_u32) / 7) as u8;
+        }
+    } else {
+        for index in 1..5 {
+            palette[index + 1] = (((5 - index) as u32 * value_0_u32 + index as u32 * value_1_u32) / 5) as u8;
+        }
+    }
+
+    palette
+}
+
+/// This function converts a RGB565 colour to RGBA.
+fn rgb565_to_rgba(colour: u16) -> [u8; 4] {
+    let red = ((colour >> 11) & 0x1F) as u8;
+    let green = ((colour >> 5) & 0x3F) as u8;
+    let blue = (colour & 0x1F) as u8;
+    [(red << 3) | (red >> 2), (green << 2) | (green >> 4), (blue << 3) | (blue >> 2), 255]
+}
+
+/// This function converts a RGBA colour to RGB565, rounding each channel.
+fn rgba_to_rgb565(colour: &[u8; 4]) -> u16 {
+    let red = (colour[0] as u16 * 31 + 127) / 255;
+    let green = (colour[1] as u16 * 63 + 127) / 255;
+    let blue = (colour[2] as u16 * 31 + 127) / 255;
+    (red << 11) | (green << 5) | blue
+}
+
+//---------------------------------------------------------------------------//
+//                          Encoding functions
+//---------------------------------------------------------------------------//
+
+/// This function encodes an image as DDS, in the provided format, optionally with all its mipmaps.
+pub fn encode(image: &RgbaImage, format: &DdsFormat, mipmaps: bool) -> Result<Vec<u8>> {
+
+    // Validate the format before doing anything else.
+    let top_size = format.get_image_size(image.width, image.height)?;
+    let compressed = format.get_block_size().is_some();
+    if matches!(format, DdsFormat::Bc6h | DdsFormat::Bc7) {
+        return Err(ErrorKind::ImageDecode(format!("Images cannot be encoded in {} format.", format)).into());
+    }
+
+    if image.width == 0 || image.height == 0 {
+        return Err(ErrorKind::ImageDecode("Empty images cannot be encoded.".to_owned()).into());
+    }
+
+    let mut images = vec![image.clone()];
+    if mipmaps {
+        while let Some(last) = images.last().filter(|image| image.width > 1 || image.height > 1) {
+            let next = last.get_next_mipmap();
+            images.push(next);
+        }
+    }
+
+    let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+    flags |= if compressed { DDSD_LINEARSIZE } else { DDSD_PITCH };
+    let mut caps = DDSCAPS_TEXTURE;
+    if images.len() > 1 {
+        flags |= DDSD_MIPMAPCOUNT;
+        caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+
+    let mut data = vec![];
+    data.extend_from_slice(DDS_SIGNATURE);
+    data.encode_integer_u32(HEADER_SIZE as u32 - 4);
+    data.encode_integer_u32(flags);
+    data.encode_integer_u32(image.height);
+    data.encode_integer_u32(image.width);
+    data.encode_integer_u32(if compressed { top_size as u32 } else { top_size as u32 / image.height.max(1) });
+    data.encode_integer_u32(0);
+    data.encode_integer_u32(images.len() as u32);
+    data.extend_from_slice(&[0; 44]);
+
+    // Pixel format.
+    data.encode_integer_u32(PIXEL_FORMAT_SIZE);
+    match format {
+        DdsFormat::Uncompressed { bit_count, masks } => {
+            let mut pixel_format_flags = 0;
+            if masks[..3].iter().any(|mask| *mask != 0) {
+                pixel_format_flags |= DDPF_RGB;
+            }
+
+            if masks[3] != 0 {
+                pixel_format_flags |= if pixel_format_flags == 0 { DDPF_ALPHA } else { DDPF_ALPHAPIXELS };
+            }
+
+            data.encode_integer_u32(pixel_format_flags);
+            data.extend_from_slice(&[0; 4]);
+            data.encode_integer_u32(*bit_count);
+            masks.iter().for_each(|mask| data.encode_integer_u32(*mask));
+        }
+        _ => {
+            data.encode_integer_u32(DDPF_FOURCC);
+            data.extend_from_slice(match format {
+                DdsFormat::Bc1 => b"DXT1",
+                DdsFormat::Bc2 => b"DXT3",
+                DdsFormat::Bc3 => b"DXT5",
+                DdsFormat::Bc4 => b"ATI1",
+                _ => b"ATI2",
+            });
+            data.extend_from_slice(&[0; 20]);
+        }
+    }
+
+    data.encode_integer_u32(caps);
+    data.extend_from_slice(&[0; 16]);
+
+    for image in &images {
+        match format {
+            DdsFormat::Uncompressed { bit_count, masks } => encode_uncompressed(image, &mut data, *bit_count, masks),
+            _ => encode_blocks(image, &mut data, format),
+        }
+    }
+
+    Ok(data)
+}
+
+/// This function encodes an image in an uncompressed format.
+fn encode_uncompressed(image: &RgbaImage, data: &mut Vec<u8>, bit_count: u32, masks: &[u32; 4]) {
+    for pixel in image.data.chunks_exact(4) {
+        let mut value = 0u32;
+        for (channel, mask) in masks.iter().enumerate() {
+            if *mask != 0 {
+                let max = mask >> mask.trailing_zeros();
+                value |= ((((pixel[channel] as u64 * max as u64) + 127) / 255) as u32) << mask.trailing_zeros();
+            }
+        }
+
+        data.extend_from_slice(&value.to_le_bytes()[..bit_count as usize / 8]);
+    }
+}
+
+/// This function encodes an image in a block-compressed format.
+fn encode_blocks(image: &RgbaImage, data: &mut Vec<u8>, format: &DdsFormat) {
+    for block_y in (0..image.height.max(1)).step_by(4) {
+        for block_x in (0..image.width.max(1)).step_by(4) {
+            let mut pixels = [[0; 4]; 16];
+            for (index, pixel) in pixels.iter_mut().enumerate() {
+                *pixel = image.get_pixel(block_x + index as u32 % 4, block_y + index as u32 / 4);
+            }
+
+            match format {
+                DdsFormat::Bc1 => encode_colour_block(&pixels, data, true),
+                DdsFormat::Bc2 => {
+                    let mut alpha = 0u64;
+                    for (index, pixel) in pixels.iter().enumerate() {
+                        alpha |= (((pixel[3] as u64) + 8) / 17) << (index * 4);
+                    }
+
+                    data.extend_from_slice(&alpha.to_le_bytes());
+                    encode_colour_block(&pixels, data, false);
+                }
+                DdsFormat::Bc3 => {
+                    encode_channel_block(&pixels, data, 3);
+                    encode_colour_block(&pixels, data, false);
+                }
+                DdsFormat::Bc4 => encode_channel_block(&pixels, data, 0),
+                _ => {
+                    encode_channel_block(&pixels, data, 0);
+                    encode_channel_block(&pixels, data, 1);
+                }
+            }
+        }
+    }
+}
+
+/// This function encodes the colours of a block, using the corners of their bounding box as endpoints.
+///
+/// BC1 blocks with transparent pixels (alpha under 128) use the 3 colour mode, with transparent black as fourth colour.
+fn encode_colour_block(pixels: &[[u8; 4]; 16], data: &mut Vec<u8>, is_bc1: bool) {
+    let has_alpha = is_bc1 && pixels.iter().any(|pixel| pixel[3] < 128);
+
+    let mut min = [255u8; 4];
+    let mut max = [0u8; 4];
+    for pixel in pixels.iter().filter(|pixel| !has_alpha || pixel[3] >= 128) {
+        for channel in 0..3 {
+            min[channel] = min[channel].min(pixel[channel]);
+            max[channel] = max[channel].max(pixel[channel]);
+        }
+    }
+
+    let mut colour_0 = rgba_to_rgb565(&max);
+    let mut colour_1 = rgba_to_rgb565(&min);
+
+    // The order of the colours decides the mode of the block: 4 colours if the first one is bigger, 3 otherwise.
+    if (has_alpha && colour_0 > colour_1) || (!has_alpha && colour_0 < colour_1) {
+        std::mem::swap(&mut colour_0, &mut colour_1);
+    }
+
+    let four_colours = !has_alpha && colour_0 != colour_1;
+    let palette = get_colour_palette(colour_0, colour_1, !is_bc1 || four_colours);
+    let usable_colours = if four_colours || !is_bc1 { 4 } else { 3 };
+
+    let mut indexes = 0u32;
+    for (index, pixel) in pixels.iter().enumerate() {
+        let palette_index = if has_alpha && pixel[3] < 128 { 3 } else {
+            (0..usable_colours).min_by_key(|palette_index| {
+                let colour = &palette[*palette_index];
+                (0..3).map(|channel| (colour[channel] as i32 - pixel[channel] as i32).pow(2)).sum::<i32>()
+            }).unwrap_or_default()
+        };
+
+        indexes |= (palette_index as u32) << (index * 2);
+    }
+
+    data.encode_integer_u16(colour_0);
+    data.encode_integer_u16(colour_1);
+    data.encode_integer_u32(indexes);
+}
+
+/// This function encodes a channel of a block, using its min and max values as endpoints.
+fn encode_channel_block(pixels: &[[u8; 4]; 16], data: &mut Vec<u8>, channel: usize) {
+    let max = pixels.iter().map(|pixel| pixel[channel]).max().unwrap_or_default();
+    let min = pixels.iter().map(|pixel| pixel[channel]).min().unwrap_or_default();
+    let palette = get_channel_palette(max, min);
+
+    let mut indexes = 0u64;
+    for (index, pixel) in pixels.iter().enumerate() {
+        let palette_index = (0..8).min_by_key(|palette_index| (palette[*palette_index] as i32 - pixel[channel] as i32).abs()).unwrap_or_default();
+        indexes |= (palette_index as u64) << (index * 3);
+    }
+
+    data.push(max);
+    data.push(min);
+    data.extend_from_slice(&indexes.to_le_bytes()[..6]);
+}
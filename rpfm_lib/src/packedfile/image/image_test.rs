@@ -0,0 +1,50 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Image` module.
+!*/
+
+use super::*;
+
+/// Test to make sure images survive the conversions between PNG, TGA and DDS, and their metadata is read properly.
+#[test]
+fn test_image_conversions() {
+    let pixels = (0..5 * 3).flat_map(|index| [index * 16, 255 - index * 8, index * 3, if index % 2 == 0 { 255 } else { 64 }]).collect::<Vec<u8>>();
+    let image = RgbaImage::new(5, 3, pixels).unwrap();
+
+    // PNG and TGA must keep the image exactly as it was.
+    let png = image.to_png().unwrap();
+    assert_eq!(RgbaImage::from_png(&png).unwrap(), image);
+
+    let tga = image.to_tga().unwrap();
+    assert_eq!(RgbaImage::from_tga(&tga).unwrap(), image);
+    let tga_metadata = Image::read(&tga).unwrap().get_metadata().unwrap();
+    assert_eq!(tga_metadata.get_ref_format(), &ImageFormat::Tga);
+
+    // Uncompressed DDS too, even with mipmaps.
+    let dds = image.to_dds(&"bgra8".parse::<DdsFormat>().unwrap(), true).unwrap();
+    let dds_file = Image::read(&dds).unwrap();
+    let metadata = dds_file.get_metadata().unwrap();
+    assert!(matches!(metadata.get_ref_format(), ImageFormat::Dds(DdsFormat::Uncompressed { .. })));
+    assert_eq!((*metadata.get_ref_width(), *metadata.get_ref_height(), *metadata.get_ref_mipmap_count()), (5, 3, 3));
+    assert_eq!(dds_file.to_rgba().unwrap(), image);
+    assert_eq!(RgbaImage::from_png(&dds_file.to_png().unwrap()).unwrap(), image);
+
+    // Compressed DDS are lossy, but solid colours that fit in RGB565 must survive.
+    let bc3 = image.to_dds(&DdsFormat::Bc3, false).unwrap();
+    let decoded = RgbaImage::from_dds(&bc3).unwrap();
+    assert_eq!((*decoded.get_ref_width(), *decoded.get_ref_height()), (5, 3));
+
+    let solid = RgbaImage::new(4, 4, [255, 0, 255, 128].repeat(16)).unwrap();
+    assert_eq!(RgbaImage::from_dds(&solid.to_dds(&DdsFormat::Bc3, false).unwrap()).unwrap(), solid);
+
+    assert!(Image::read(&[0, 1, 2]).unwrap().get_metadata().is_err());
+}
@@ -11,12 +11,27 @@
 /*!
 Module with all the code to interact with Image PackedFiles.
 
-Images... we really just get their that to memory. Nothing more.
+Images are kept in memory as they are, but we can read their metadata, and decode DDS, TGA and PNG images to RGBA.
+Decoded images can be encoded back as DDS, TGA or PNG, so images can be converted between these formats.
 !*/
 
 use serde_derive::{Serialize, Deserialize};
 
-use rpfm_error::Result;
+use std::{fmt, fmt::Display};
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::GetRef;
+
+use crate::common::decoder::Decoder;
+
+use self::dds::{DdsFormat, DdsHeader};
+
+pub mod dds;
+pub mod png;
+pub mod tga;
+
+#[cfg(test)]
+mod image_test;
 
 /// Extensions used by Image PackedFiles.
 pub const EXTENSIONS: [&str; 5] = [
@@ -27,6 +42,11 @@ pub const EXTENSIONS: [&str; 5] = [
     ".png",
 ];
 
+/// Signatures used to identify the format of an image. TGA images have no signature.
+const DDS_SIGNATURE: &[u8] = b"DDS ";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = &[0xFF, 0xD8];
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
@@ -39,6 +59,36 @@ pub struct Image {
     data: Vec<u8>,
 }
 
+/// This struct contains the metadata of an image.
+#[derive(GetRef, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    format: ImageFormat,
+    width: u32,
+    height: u32,
+
+    /// Amount of mipmaps, including the full-size image. Only DDS images can have more than one.
+    mipmap_count: u32,
+}
+
+/// This enum represents the formats of images we can identify.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum ImageFormat {
+    Dds(DdsFormat),
+    Tga,
+    Png,
+    Jpeg,
+}
+
+/// This struct represents an image decoded to RGBA, with 8 bits per channel.
+#[derive(GetRef, PartialEq, Clone, Debug, Default)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+
+    /// Pixels of the image, row by row from the top-left corner.
+    data: Vec<u8>,
+}
+
 //---------------------------------------------------------------------------//
 //                           Implementation of Image
 //---------------------------------------------------------------------------//
@@ -71,4 +121,183 @@ impl Image {
     pub fn get_data(&self) -> &[u8] {
         &self.data
     }
+
+    /// This function returns the metadata of the image, identifying its format from its data.
+    pub fn get_metadata(&self) -> Result<ImageMetadata> {
+        if self.data.starts_with(DDS_SIGNATURE) {
+            let header = DdsHeader::read(&self.data)?;
+            Ok(ImageMetadata {
+                format: ImageFormat::Dds(header.get_ref_format().clone()),
+                width: *header.get_ref_width(),
+                height: *header.get_ref_height(),
+                mipmap_count: *header.get_ref_mipmap_count(),
+            })
+        }
+
+        else if self.data.starts_with(PNG_SIGNATURE) {
+            let (width, height) = png::read_dimensions(&self.data)?;
+            Ok(ImageMetadata::new(ImageFormat::Png, width, height))
+        }
+
+        else if self.data.starts_with(JPEG_SIGNATURE) {
+            let (width, height) = read_jpeg_dimensions(&self.data)?;
+            Ok(ImageMetadata::new(ImageFormat::Jpeg, width, height))
+        }
+
+        else {
+            let (width, height) = tga::read_dimensions(&self.data)?;
+            Ok(ImageMetadata::new(ImageFormat::Tga, width, height))
+        }
+    }
+
+    /// This function decodes the image to RGBA. For DDS images, only the full-size image is decoded.
+    pub fn to_rgba(&self) -> Result<RgbaImage> {
+        match self.get_metadata()?.format {
+            ImageFormat::Dds(_) => RgbaImage::from_dds(&self.data),
+            ImageFormat::Tga => RgbaImage::from_tga(&self.data),
+            ImageFormat::Png => RgbaImage::from_png(&self.data),
+            ImageFormat::Jpeg => Err(ErrorKind::ImageDecode("JPEG images cannot be decoded.".to_owned()).into()),
+        }
+    }
+
+    /// This function converts the image to PNG.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        self.to_rgba()?.to_png()
+    }
+}
+
+/// Implementation of `ImageMetadata`.
+impl ImageMetadata {
+
+    /// This function creates the metadata of an image without mipmaps.
+    fn new(format: ImageFormat, width: u32, height: u32) -> Self {
+        Self {
+            format,
+            width,
+            height,
+            mipmap_count: 1,
+        }
+    }
+}
+
+/// Implementation of `RgbaImage`.
+impl RgbaImage {
+
+    /// This function creates a new `RgbaImage` from its pixels. Their size must match the size of the image.
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Result<Self> {
+        if (width as u64 * height as u64 * 4) != data.len() as u64 {
+            return Err(ErrorKind::ImageDecode(format!("{} bytes are not a valid RGBA image of {}x{} pixels.", data.len(), width, height)).into());
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// This function decodes a DDS image to RGBA. Only the full-size image is decoded.
+    pub fn from_dds(data: &[u8]) -> Result<Self> {
+        dds::decode(data)
+    }
+
+    /// This function decodes a TGA image to RGBA.
+    pub fn from_tga(data: &[u8]) -> Result<Self> {
+        tga::decode(data)
+    }
+
+    /// This function decodes a PNG image to RGBA.
+    pub fn from_png(data: &[u8]) -> Result<Self> {
+        png::decode(data)
+    }
+
+    /// This function encodes the image as DDS, in the provided format, optionally with all its mipmaps.
+    pub fn to_dds(&self, format: &DdsFormat, mipmaps: bool) -> Result<Vec<u8>> {
+        dds::encode(self, format, mipmaps)
+    }
+
+    /// This function encodes the image as an uncompressed 32 bits TGA.
+    pub fn to_tga(&self) -> Result<Vec<u8>> {
+        tga::encode(self)
+    }
+
+    /// This function encodes the image as a RGBA PNG.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        png::encode(self)
+    }
+
+    /// This function returns the RGBA values of the provided pixel. Coordinates out of the image are clamped to its borders.
+    fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let x = x.min(self.width.saturating_sub(1)) as usize;
+        let y = y.min(self.height.saturating_sub(1)) as usize;
+        let index = (y * self.width as usize + x) * 4;
+        [self.data[index], self.data[index + 1], self.data[index + 2], self.data[index + 3]]
+    }
+
+    /// This function returns the next mipmap of this image, with half its size, averaging each 2x2 block of pixels.
+    fn get_next_mipmap(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let pixels = [
+                    self.get_pixel(x * 2, y * 2),
+                    self.get_pixel(x * 2 + 1, y * 2),
+                    self.get_pixel(x * 2, y * 2 + 1),
+                    self.get_pixel(x * 2 + 1, y * 2 + 1),
+                ];
+
+                for channel in 0..4 {
+                    data.push(((pixels.iter().map(|pixel| pixel[channel] as u32).sum::<u32>() + 2) / 4) as u8);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+/// Display implementation of `ImageFormat`.
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dds(format) => write!(f, "DDS - {}", format),
+            Self::Tga => write!(f, "TGA"),
+            Self::Png => write!(f, "PNG"),
+            Self::Jpeg => write!(f, "JPEG"),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Utility functions for images
+//---------------------------------------------------------------------------//
+
+/// This function returns the width and height of a JPEG image, from its first Start Of Frame segment.
+fn read_jpeg_dimensions(data: &[u8]) -> Result<(u32, u32)> {
+    let mut index = JPEG_SIGNATURE.len();
+    while index + 4 <= data.len() {
+        if data[index] != 0xFF {
+            break;
+        }
+
+        // SOF markers are 0xC0 to 0xCF, except 0xC4 (huffman tables), 0xC8 (reserved) and 0xCC (arithmetic coding).
+        let marker = data[index + 1];
+        let size = u16::from_be_bytes([data[index + 2], data[index + 3]]) as usize;
+        if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+            let height = data.get_bytes_checked(index + 5, 2)?;
+            let width = data.get_bytes_checked(index + 7, 2)?;
+            return Ok((u16::from_be_bytes([width[0], width[1]]) as u32, u16::from_be_bytes([height[0], height[1]]) as u32));
+        }
+
+        index += 2 + size;
+    }
+
+    Err(ErrorKind::ImageDecode("The size of this JPEG image could not be found.".to_owned()).into())
 }
@@ -0,0 +1,355 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to decode and encode PNG images.
+
+PNG images are a signature followed by chunks. We only care about the header (`IHDR`), palette (`PLTE`),
+transparency (`tRNS`) and data (`IDAT`) chunks. The data is compressed with zlib, with each row filtered independently.
+
+We can decode all the standard colour types and bit depths, interlaced or not.
+Images are always encoded as non-interlaced 8 bits RGBA images.
+!*/
+
+use flate2::{Compression, Crc};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+
+use std::io::{Read, Write};
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::common::decoder::Decoder;
+
+use super::{PNG_SIGNATURE, RgbaImage};
+
+/// Size of the data of the header chunk.
+const IHDR_SIZE: usize = 13;
+
+/// Colour types.
+const COLOUR_TYPE_GRAYSCALE: u8 = 0;
+const COLOUR_TYPE_RGB: u8 = 2;
+const COLOUR_TYPE_PALETTE: u8 = 3;
+const COLOUR_TYPE_GRAYSCALE_ALPHA: u8 = 4;
+const COLOUR_TYPE_RGBA: u8 = 6;
+
+/// Starting column, starting row, column step and row step of each pass of Adam7 interlaced images.
+const ADAM7_PASSES: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// This struct contains the data of the header chunk we need to decode an image.
+struct Header {
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    colour_type: u8,
+    interlaced: bool,
+}
+
+//---------------------------------------------------------------------------//
+//                          Decoding functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the width and height of a PNG image.
+pub fn read_dimensions(data: &[u8]) -> Result<(u32, u32)> {
+    let header = read_header(&read_chunks(data)?)?;
+    Ok((header.width as u32, header.height as u32))
+}
+
+/// This function decodes a PNG image to RGBA.
+pub fn decode(data: &[u8]) -> Result<RgbaImage> {
+    let chunks = read_chunks(data)?;
+    let header = read_header(&chunks)?;
+
+    let mut palette = vec![];
+    let mut transparency = vec![];
+    let mut compressed_data = vec![];
+    for (chunk_type, chunk_data) in chunks {
+        match chunk_type {
+            b"PLTE" => palette = chunk_data.chunks_exact(3).map(|colour| [colour[0], colour[1], colour[2], 255]).collect(),
+            b"tRNS" => transparency = chunk_data.to_vec(),
+            b"IDAT" => compressed_data.extend_from_slice(chunk_data),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    if header.colour_type == COLOUR_TYPE_PALETTE {
+        transparency.iter().zip(palette.iter_mut()).for_each(|(alpha, colour)| colour[3] = *alpha);
+    }
+
+    let channels = match header.colour_type {
+        COLOUR_TYPE_GRAYSCALE | COLOUR_TYPE_PALETTE => 1,
+        COLOUR_TYPE_GRAYSCALE_ALPHA => 2,
+        COLOUR_TYPE_RGB => 3,
+        _ => 4,
+    };
+
+    // Get the size of the data of each pass, so we don't decompress more than we need.
+    let bits_per_pixel = channels * header.bit_depth as usize;
+    let passes = if header.interlaced {
+        ADAM7_PASSES.iter().map(|(x, y, step_x, step_y)| ((header.width + step_x - 1 - x) / step_x, (header.height + step_y - 1 - y) / step_y)).collect::<Vec<(usize, usize)>>()
+    } else {
+        vec![(header.width, header.height)]
+    };
+
+    let expected_size = passes.iter()
+        .filter(|(width, height)| *width > 0 && *height > 0)
+        .map(|(width, height)| (1 + (width * bits_per_pixel + 7) / 8) * height)
+        .sum::<usize>();
+
+    let mut filtered_data = vec![];
+    ZlibDecoder::new(&compressed_data[..]).take(expected_size as u64).read_to_end(&mut filtered_data)?;
+    if filtered_data.len() < expected_size {
+        return Err(ErrorKind::ImageDecode(format!("The image needs {} bytes of data, but only has {}.", expected_size, filtered_data.len())).into());
+    }
+
+    let mut pixels = vec![0; header.width * header.height * 4];
+    let mut index = 0;
+    for (pass, (pass_width, pass_height)) in passes.iter().enumerate() {
+        if *pass_width == 0 || *pass_height == 0 {
+            continue;
+        }
+
+        let row_size = (pass_width * bits_per_pixel + 7) / 8;
+        let pass_data = unfilter(&mut filtered_data[index..index + (row_size + 1) * pass_height], row_size, (bits_per_pixel + 7) / 8)?;
+        index += (row_size + 1) * pass_height;
+
+        let (start_x, start_y, step_x, step_y) = if header.interlaced { ADAM7_PASSES[pass] } else { (0, 0, 1, 1) };
+        for (row_index, row) in pass_data.chunks_exact(row_size).enumerate() {
+            for column in 0..*pass_width {
+                let pixel = decode_pixel(row, column, &header, channels, &palette, &transparency)?;
+                let x = start_x + column * step_x;
+                let y = start_y + row_index * step_y;
+                let pixel_index = (y * header.width + x) * 4;
+                pixels[pixel_index..pixel_index + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+
+    RgbaImage::new(header.width as u32, header.height as u32, pixels)
+}
+
+/// This function reads and validates the header chunk of a PNG image, which must be its first chunk.
+fn read_header(chunks: &[(&[u8], &[u8])]) -> Result<Header> {
+    let (chunk_type, chunk_data) = chunks.first().ok_or_else(|| ErrorKind::ImageDecode("This PNG image has no chunks.".to_owned()))?;
+    if *chunk_type != b"IHDR" || chunk_data.len() != IHDR_SIZE {
+        return Err(ErrorKind::ImageDecode("The first chunk of this PNG image is not a valid header.".to_owned()).into());
+    }
+
+    let header = Header {
+        width: u32::from_be_bytes([chunk_data[0], chunk_data[1], chunk_data[2], chunk_data[3]]) as usize,
+        height: u32::from_be_bytes([chunk_data[4], chunk_data[5], chunk_data[6], chunk_data[7]]) as usize,
+        bit_depth: chunk_data[8],
+        colour_type: chunk_data[9],
+        interlaced: chunk_data[12] == 1,
+    };
+
+    let valid = match header.colour_type {
+        COLOUR_TYPE_GRAYSCALE => [1, 2, 4, 8, 16].contains(&header.bit_depth),
+        COLOUR_TYPE_PALETTE => [1, 2, 4, 8].contains(&header.bit_depth),
+        COLOUR_TYPE_RGB | COLOUR_TYPE_GRAYSCALE_ALPHA | COLOUR_TYPE_RGBA => [8, 16].contains(&header.bit_depth),
+        _ => false,
+    };
+
+    if !valid || chunk_data[10] != 0 || chunk_data[11] != 0 || chunk_data[12] > 1 {
+        return Err(ErrorKind::ImageDecode(format!("PNG images with colour type {} and bit depth {} are not supported.", header.colour_type, header.bit_depth)).into());
+    }
+
+    Ok(header)
+}
+
+/// This function returns the type and data of all the chunks of a PNG image, validating their CRC.
+fn read_chunks(data: &[u8]) -> Result<Vec<(&[u8], &[u8])>> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return Err(ErrorKind::ImageDecode("This is not a PNG image.".to_owned()).into());
+    }
+
+    let mut chunks = vec![];
+    let mut index = PNG_SIGNATURE.len();
+    while index < data.len() {
+        let size_bytes = data.get_bytes_checked(index, 4)?;
+        let size = u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]]) as usize;
+        let chunk = data.get_bytes_checked(index + 4, size + 4)?;
+        let crc_bytes = data.get_bytes_checked(index + 8 + size, 4)?;
+
+        let mut crc = Crc::new();
+        crc.update(chunk);
+        if crc.sum() != u32::from_be_bytes([crc_bytes[0], crc_bytes[1], crc_bytes[2], crc_bytes[3]]) {
+            return Err(ErrorKind::ImageDecode(format!("The chunk at byte {} of this PNG image is corrupted.", index)).into());
+        }
+
+        chunks.push((&chunk[..4], &chunk[4..]));
+        index += size + 12;
+    }
+
+    Ok(chunks)
+}
+
+/// This function reverts the filters applied to each row of an image, returning the rows without their filter type byte.
+fn unfilter(data: &mut [u8], row_size: usize, pixel_size: usize) -> Result<Vec<u8>> {
+    let mut rows = Vec::with_capacity(data.len());
+    let mut previous_row = vec![0; row_size];
+    for row in data.chunks_exact_mut(row_size + 1) {
+        let filter_type = row[0];
+        let row = &mut row[1..];
+        for index in 0..row_size {
+            let left = if index >= pixel_size { row[index - pixel_size] } else { 0 };
+            let up = previous_row[index];
+            let up_left = if index >= pixel_size { previous_row[index - pixel_size] } else { 0 };
+
+            row[index] = row[index].wrapping_add(match filter_type {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => return Err(ErrorKind::ImageDecode(format!("Unknown PNG filter type: {}.", filter_type)).into()),
+            });
+        }
+
+        previous_row.copy_from_slice(row);
+        rows.extend_from_slice(row);
+    }
+
+    Ok(rows)
+}
+
+/// This function decodes the pixel in the provided column of an unfiltered row to RGBA.
+fn decode_pixel(row: &[u8], column: usize, header: &Header, channels: usize, palette: &[[u8; 4]], transparency: &[u8]) -> Result<[u8; 4]> {
+
+    // Samples are kept at their original depth, to compare them with the transparent colour.
+    let mut samples = [0u16; 4];
+    for (channel, sample) in samples.iter_mut().enumerate().take(channels) {
+        *sample = match header.bit_depth {
+            16 => {
+                let index = (column * channels + channel) * 2;
+                u16::from_be_bytes([row[index], row[index + 1]])
+            }
+            8 => row[column * channels + channel] as u16,
+            bit_depth => {
+                let bit_index = column * bit_depth as usize;
+                let shift = 8 - bit_depth as usize - bit_index % 8;
+                ((row[bit_index / 8] >> shift) & ((1 << bit_depth) - 1)) as u16
+            }
+        };
+    }
+
+    // Scale each sample to 8 bits.
+    let max = ((1u32 << header.bit_depth) - 1) as u16;
+    let scale = |sample: u16| ((sample as u32 * 255 + max as u32 / 2) / max as u32) as u8;
+    let transparent = |samples: &[u16]| transparency.len() >= samples.len() * 2 && samples.iter().enumerate().all(|(index, sample)| u16::from_be_bytes([transparency[index * 2], transparency[index * 2 + 1]]) == *sample);
+
+    Ok(match header.colour_type {
+        COLOUR_TYPE_GRAYSCALE => {
+            let value = scale(samples[0]);
+            [value, value, value, if transparent(&samples[..1]) { 0 } else { 255 }]
+        }
+        COLOUR_TYPE_RGB => [scale(samples[0]), scale(samples[1]), scale(samples[2]), if transparent(&samples[..3]) { 0 } else { 255 }],
+        COLOUR_TYPE_PALETTE => *palette.get(samples[0] as usize).ok_or_else(|| ErrorKind::ImageDecode(format!("Colour {} is not in the palette.", samples[0])))?,
+        COLOUR_TYPE_GRAYSCALE_ALPHA => {
+            let value = scale(samples[0]);
+            [value, value, value, scale(samples[1])]
+        }
+        _ => [scale(samples[0]), scale(samples[1]), scale(samples[2]), scale(samples[3])],
+    })
+}
+
+/// This function returns the prediction of the Paeth filter.
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let prediction = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (prediction - left as i16).abs();
+    let distance_up = (prediction - up as i16).abs();
+    let distance_up_left = (prediction - up_left as i16).abs();
+
+    if distance_left <= distance_up && distance_left <= distance_up_left { left }
+    else if distance_up <= distance_up_left { up }
+    else { up_left }
+}
+
+//---------------------------------------------------------------------------//
+//                          Encoding functions
+//---------------------------------------------------------------------------//
+
+/// This function encodes an image as a non-interlaced 8 bits RGBA PNG.
+///
+/// Each row uses the filter that gives the smallest sum of absolute values, as it usually compresses better.
+pub fn encode(image: &RgbaImage) -> Result<Vec<u8>> {
+    if image.width == 0 || image.height == 0 {
+        return Err(ErrorKind::ImageDecode("Empty images cannot be encoded.".to_owned()).into());
+    }
+
+    let row_size = image.width as usize * 4;
+    let mut filtered_data = Vec::with_capacity((row_size + 1) * image.height as usize);
+    let mut previous_row = vec![0; row_size];
+    for row in image.data.chunks_exact(row_size) {
+        let mut best_row = vec![];
+        let mut best_score = u64::MAX;
+        for filter_type in 0..5 {
+            let mut filtered_row = Vec::with_capacity(row_size + 1);
+            filtered_row.push(filter_type);
+            for index in 0..row_size {
+                let left = if index >= 4 { row[index - 4] } else { 0 };
+                let up = previous_row[index];
+                let up_left = if index >= 4 { previous_row[index - 4] } else { 0 };
+
+                filtered_row.push(row[index].wrapping_sub(match filter_type {
+                    0 => 0,
+                    1 => left,
+                    2 => up,
+                    3 => ((left as u16 + up as u16) / 2) as u8,
+                    _ => paeth(left, up, up_left),
+                }));
+            }
+
+            let score = filtered_row[1..].iter().map(|value| (*value as i8).unsigned_abs() as u64).sum::<u64>();
+            if score < best_score {
+                best_score = score;
+                best_row = filtered_row;
+            }
+        }
+
+        filtered_data.append(&mut best_row);
+        previous_row.copy_from_slice(row);
+    }
+
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(&filtered_data)?;
+
+    let mut header = vec![];
+    header.extend_from_slice(&image.width.to_be_bytes());
+    header.extend_from_slice(&image.height.to_be_bytes());
+    header.extend_from_slice(&[8, COLOUR_TYPE_RGBA, 0, 0, 0]);
+
+    let mut data = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut data, b"IHDR", &header);
+    write_chunk(&mut data, b"IDAT", &encoder.finish()?);
+    write_chunk(&mut data, b"IEND", &[]);
+    Ok(data)
+}
+
+/// This function writes a chunk, with its size and CRC.
+fn write_chunk(data: &mut Vec<u8>, chunk_type: &[u8], chunk_data: &[u8]) {
+    let mut crc = Crc::new();
+    crc.update(chunk_type);
+    crc.update(chunk_data);
+
+    data.extend_from_slice(&(chunk_data.len() as u32).to_be_bytes());
+    data.extend_from_slice(chunk_type);
+    data.extend_from_slice(chunk_data);
+    data.extend_from_slice(&crc.sum().to_be_bytes());
+}
@@ -0,0 +1,187 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to decode and encode TGA images.
+
+TGA images have an 18 bytes header, followed by an optional id, an optional colour map and the pixels.
+We can decode colour-mapped, true-colour and grayscale images, both uncompressed and RLE-compressed.
+Images are always encoded as uncompressed 32 bits true-colour images.
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+use super::RgbaImage;
+
+/// Size of the header of a TGA image.
+const HEADER_SIZE: usize = 18;
+
+/// Image types. RLE-compressed types are the same plus 8.
+const IMAGE_TYPE_COLOUR_MAPPED: u8 = 1;
+const IMAGE_TYPE_TRUE_COLOUR: u8 = 2;
+const IMAGE_TYPE_GRAYSCALE: u8 = 3;
+const IMAGE_TYPE_RLE: u8 = 8;
+
+/// Flags of the image descriptor, telling where the first pixel is.
+const DESCRIPTOR_RIGHT_TO_LEFT: u8 = 0x10;
+const DESCRIPTOR_TOP_TO_BOTTOM: u8 = 0x20;
+
+//---------------------------------------------------------------------------//
+//                          Decoding functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the width and height of a TGA image, validating its header.
+pub fn read_dimensions(data: &[u8]) -> Result<(u32, u32)> {
+    let image_type = data.decode_integer_u8(2)?;
+    let colour_map_type = data.decode_integer_u8(1)?;
+    let pixel_depth = data.decode_integer_u8(16)?;
+
+    let valid_depth = match image_type & !IMAGE_TYPE_RLE {
+        IMAGE_TYPE_COLOUR_MAPPED => colour_map_type == 1 && (pixel_depth == 8 || pixel_depth == 16),
+        IMAGE_TYPE_TRUE_COLOUR => colour_map_type <= 1 && [15, 16, 24, 32].contains(&pixel_depth),
+        IMAGE_TYPE_GRAYSCALE => colour_map_type <= 1 && (pixel_depth == 8 || pixel_depth == 16),
+        _ => false,
+    };
+
+    if !valid_depth || data.len() < HEADER_SIZE {
+        return Err(ErrorKind::ImageDecode("This is not a supported image.".to_owned()).into());
+    }
+
+    Ok((data.decode_integer_u16(12)? as u32, data.decode_integer_u16(14)? as u32))
+}
+
+/// This function decodes a TGA image to RGBA.
+pub fn decode(data: &[u8]) -> Result<RgbaImage> {
+    let (width, height) = read_dimensions(data)?;
+    let id_length = data.decode_integer_u8(0)? as usize;
+    let colour_map_type = data.decode_integer_u8(1)?;
+    let image_type = data.decode_integer_u8(2)?;
+    let colour_map_first = data.decode_integer_u16(3)? as usize;
+    let colour_map_length = data.decode_integer_u16(5)? as usize;
+    let colour_map_depth = data.decode_integer_u8(7)?;
+    let pixel_depth = data.decode_integer_u8(16)?;
+    let descriptor = data.decode_integer_u8(17)?;
+
+    let is_grayscale = image_type & !IMAGE_TYPE_RLE == IMAGE_TYPE_GRAYSCALE;
+    let has_alpha = descriptor & 0xF != 0;
+    let mut index = HEADER_SIZE + id_length;
+
+    // Decode the colour map. If the image is not colour-mapped, we just skip it.
+    let mut colour_map = vec![];
+    if colour_map_type == 1 {
+        let entry_size = (colour_map_depth as usize + 7) / 8;
+        let colour_map_data = data.get(index..index + colour_map_length * entry_size).ok_or_else(|| ErrorKind::ImageDecode("The colour map is bigger than the file.".to_owned()))?;
+        colour_map = colour_map_data.chunks_exact(entry_size.max(1))
+            .map(|entry| decode_pixel(entry, colour_map_depth, false, true))
+            .collect::<Result<Vec<[u8; 4]>>>()?;
+        index += colour_map_data.len();
+    }
+
+    // Each RLE packet has at most 128 pixels, so any image needing more packets than bytes left is corrupted.
+    let pixel_size = (pixel_depth as usize + 7) / 8;
+    let pixel_count = width as usize * height as usize;
+    let minimum_size = if image_type & IMAGE_TYPE_RLE != 0 { pixel_count / 128 } else { pixel_count * pixel_size };
+    if !matches!(data.len().checked_sub(index), Some(size) if size >= minimum_size) {
+        return Err(ErrorKind::ImageDecode("The image is bigger than the file.".to_owned()).into());
+    }
+
+    let mut raw_pixels = Vec::with_capacity(pixel_count);
+    if image_type & IMAGE_TYPE_RLE != 0 {
+        while raw_pixels.len() < pixel_count {
+            let packet = data.decode_integer_u8(index)?;
+            let count = (packet & 0x7F) as usize + 1;
+            index += 1;
+
+            if packet & 0x80 != 0 {
+                let pixel = data.get_bytes_checked(index, pixel_size)?;
+                (0..count).for_each(|_| raw_pixels.push(pixel));
+                index += pixel_size;
+            } else {
+                for _ in 0..count {
+                    raw_pixels.push(data.get_bytes_checked(index, pixel_size)?);
+                    index += pixel_size;
+                }
+            }
+        }
+
+        raw_pixels.truncate(pixel_count);
+    } else {
+        raw_pixels.extend(data[index..index + pixel_count * pixel_size].chunks_exact(pixel_size.max(1)));
+    }
+
+    let mut pixels = vec![0; pixel_count * 4];
+    for (pixel_index, raw_pixel) in raw_pixels.iter().enumerate() {
+        let pixel = if image_type & !IMAGE_TYPE_RLE == IMAGE_TYPE_COLOUR_MAPPED {
+            let colour_index = if pixel_depth == 8 { raw_pixel[0] as usize } else { u16::from_le_bytes([raw_pixel[0], raw_pixel[1]]) as usize };
+            *colour_map.get(colour_index.wrapping_sub(colour_map_first)).ok_or_else(|| ErrorKind::ImageDecode(format!("Colour {} is not in the colour map.", colour_index)))?
+        } else {
+            decode_pixel(raw_pixel, pixel_depth, is_grayscale, has_alpha)?
+        };
+
+        // Put the pixel in its place, as we always return the pixels from the top-left corner.
+        let mut x = pixel_index % width as usize;
+        let mut y = pixel_index / width as usize;
+        if descriptor & DESCRIPTOR_RIGHT_TO_LEFT != 0 {
+            x = width as usize - 1 - x;
+        }
+
+        if descriptor & DESCRIPTOR_TOP_TO_BOTTOM == 0 {
+            y = height as usize - 1 - y;
+        }
+
+        let index = (y * width as usize + x) * 4;
+        pixels[index..index + 4].copy_from_slice(&pixel);
+    }
+
+    RgbaImage::new(width, height, pixels)
+}
+
+/// This function decodes a single pixel, or colour map entry, to RGBA.
+fn decode_pixel(data: &[u8], depth: u8, is_grayscale: bool, has_alpha: bool) -> Result<[u8; 4]> {
+    match (depth, is_grayscale) {
+        (8, true) => Ok([data[0], data[0], data[0], 255]),
+        (16, true) => Ok([data[0], data[0], data[0], data[1]]),
+        (15, false) | (16, false) => {
+            let value = u16::from_le_bytes([data[0], data[1]]);
+            let expand = |channel: u16| ((channel << 3) | (channel >> 2)) as u8;
+            let alpha = if depth == 16 && has_alpha && value & 0x8000 == 0 { 0 } else { 255 };
+            Ok([expand((value >> 10) & 0x1F), expand((value >> 5) & 0x1F), expand(value & 0x1F), alpha])
+        }
+        (24, false) => Ok([data[2], data[1], data[0], 255]),
+        (32, false) => Ok([data[2], data[1], data[0], data[3]]),
+        _ => Err(ErrorKind::ImageDecode(format!("Pixels of {} bits are not supported.", depth)).into()),
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Encoding functions
+//---------------------------------------------------------------------------//
+
+/// This function encodes an image as an uncompressed 32 bits TGA, starting from the top-left corner.
+pub fn encode(image: &RgbaImage) -> Result<Vec<u8>> {
+    if image.width > u16::MAX as u32 || image.height > u16::MAX as u32 {
+        return Err(ErrorKind::ImageDecode(format!("TGA images cannot be bigger than {0}x{0} pixels.", u16::MAX)).into());
+    }
+
+    let mut data = Vec::with_capacity(HEADER_SIZE + image.data.len());
+    data.extend_from_slice(&[0, 0, IMAGE_TYPE_TRUE_COLOUR, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    data.encode_integer_u16(image.width as u16);
+    data.encode_integer_u16(image.height as u16);
+    data.push(32);
+    data.push(DESCRIPTOR_TOP_TO_BOTTOM | 8);
+
+    for pixel in image.data.chunks_exact(4) {
+        data.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+    }
+
+    Ok(data)
+}
@@ -24,11 +24,12 @@ use std::ops::Deref;
 use rpfm_error::{Error, ErrorKind, Result};
 
 use crate::{dependencies::Dependencies, packfile::{RESERVED_NAME_EXTRA_PACKFILE, RESERVED_NAME_NOTES}};
+use crate::packedfile::anim::Anim;
 use crate::packedfile::animpack::AnimPack;
 use crate::packedfile::ca_vp8::CaVp8;
 use crate::packedfile::esf::ESF;
 use crate::packedfile::image::Image;
-use crate::packedfile::table::{anim_fragment::AnimFragment, animtable::AnimTable, db::DB, loc::Loc, matched_combat::MatchedCombat};
+use crate::packedfile::table::{anim_fragment::AnimFragment, animtable::AnimTable, db::DB, group_formations::GroupFormations, loc::Loc, matched_combat::MatchedCombat};
 use crate::packedfile::text::{Text, TextType};
 use crate::packedfile::rigidmodel::RigidModel;
 use crate::packedfile::uic::UIC;
@@ -38,6 +39,7 @@ use crate::schema::Schema;
 use crate::SCHEMA;
 use crate::SETTINGS;
 
+pub mod anim;
 pub mod animpack;
 pub mod ca_vp8;
 pub mod esf;
@@ -57,7 +59,7 @@ pub mod unit_variant;
 /// Keep in mind that, despite we having logic to recognize them, we can't decode many of them yet.
 #[derive(PartialEq, Clone, Debug)]
 pub enum DecodedPackedFile {
-    Anim,
+    Anim(Anim),
     AnimFragment(AnimFragment),
     AnimPack(AnimPack),
     AnimTable(AnimTable),
@@ -66,7 +68,7 @@ pub enum DecodedPackedFile {
     DB(DB),
     ESF(ESF),
     Image(Image),
-    GroupFormations,
+    GroupFormations(GroupFormations),
     Loc(Loc),
     MatchedCombat(MatchedCombat),
     RigidModel(RigidModel),
@@ -120,6 +122,12 @@ impl DecodedPackedFile {
     pub fn decode(raw_packed_file: &mut RawPackedFile) -> Result<Self> {
         match PackedFileType::get_packed_file_type(raw_packed_file, true) {
 
+            PackedFileType::Anim => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = Anim::read(&data)?;
+                Ok(DecodedPackedFile::Anim(packed_file))
+            }
+
             PackedFileType::AnimFragment => {
                 let schema = SCHEMA.read().unwrap();
                 match schema.deref() {
@@ -175,6 +183,12 @@ impl DecodedPackedFile {
                 }
             }
 
+            PackedFileType::GroupFormations => {
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = GroupFormations::read(&data)?;
+                Ok(DecodedPackedFile::GroupFormations(packed_file))
+            }
+
             PackedFileType::Image => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
                 let packed_file = Image::read(&data)?;
@@ -208,7 +222,7 @@ impl DecodedPackedFile {
             #[cfg(feature = "support_rigidmodel")]
             PackedFileType::RigidModel => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
-                let packed_file = RigidModel::read(&data);
+                let packed_file = RigidModel::read_or_keep_raw(&data);
                 Ok(DecodedPackedFile::RigidModel(packed_file))
             }
 
@@ -221,15 +235,9 @@ impl DecodedPackedFile {
 
             #[cfg(feature = "support_uic")]
             PackedFileType::UIC => {
-                let schema = SCHEMA.read().unwrap();
-                match schema.deref() {
-                    Some(schema) => {
-                        let data = raw_packed_file.get_data_and_keep_it()?;
-                        let packed_file = UIC::read(&data, &schema)?;
-                        Ok(DecodedPackedFile::UIC(packed_file))
-                    }
-                    None => Err(ErrorKind::SchemaNotFound.into()),
-                }
+                let data = raw_packed_file.get_data_and_keep_it()?;
+                let packed_file = UIC::read(&data)?;
+                Ok(DecodedPackedFile::UIC(packed_file))
             }
 
             PackedFileType::UnitVariant => {
@@ -246,6 +254,8 @@ impl DecodedPackedFile {
     pub fn decode_no_locks(raw_packed_file: &mut RawPackedFile, schema: &Schema) -> Result<Self> {
         match PackedFileType::get_packed_file_type(raw_packed_file, true) {
 
+            PackedFileType::Anim => Self::decode(raw_packed_file),
+
             PackedFileType::AnimFragment => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
                 let packed_file = AnimFragment::read(&data, schema, false)?;
@@ -270,6 +280,8 @@ impl DecodedPackedFile {
                 Ok(DecodedPackedFile::DB(packed_file))
             }
 
+            PackedFileType::GroupFormations => Self::decode(raw_packed_file),
+
             PackedFileType::Image => Self::decode(raw_packed_file),
 
             PackedFileType::Loc => {
@@ -290,11 +302,7 @@ impl DecodedPackedFile {
             PackedFileType::Text(_) => Self::decode(raw_packed_file),
 
             #[cfg(feature = "support_uic")]
-            PackedFileType::UIC => {
-                let data = raw_packed_file.get_data_and_keep_it()?;
-                let packed_file = UIC::read(&data, &schema)?;
-                Ok(DecodedPackedFile::UIC(packed_file))
-            }
+            PackedFileType::UIC => Self::decode(raw_packed_file),
 
             PackedFileType::UnitVariant => {
                 let data = raw_packed_file.get_data_and_keep_it()?;
@@ -310,22 +318,24 @@ impl DecodedPackedFile {
     /// Keep in mind this should only work for PackedFiles with saving support.
     pub fn encode(&self) -> Option<Result<Vec<u8>>> {
         match self {
+            DecodedPackedFile::Anim(data) => Some(data.save()),
             DecodedPackedFile::AnimFragment(data) => Some(data.save()),
             DecodedPackedFile::AnimPack(data) => Some(Ok(data.save())),
             DecodedPackedFile::AnimTable(data) => Some(data.save()),
             DecodedPackedFile::CaVp8(data) => Some(Ok(data.save())),
             DecodedPackedFile::DB(data) => Some(data.save()),
-            DecodedPackedFile::ESF(data) => Some(Ok(data.save())),
+            DecodedPackedFile::ESF(data) => Some(data.save()),
+            DecodedPackedFile::GroupFormations(data) => Some(data.save()),
             DecodedPackedFile::Loc(data) => Some(data.save()),
             DecodedPackedFile::MatchedCombat(data) => Some(data.save()),
 
             #[cfg(feature = "support_rigidmodel")]
-            DecodedPackedFile::RigidModel(data) => Some(Ok(data.save())),
+            DecodedPackedFile::RigidModel(data) => Some(data.save()),
 
             DecodedPackedFile::Text(data) => Some(data.save()),
 
             #[cfg(feature = "support_uic")]
-            DecodedPackedFile::UIC(data) => Some(Ok(data.save())),
+            DecodedPackedFile::UIC(data) => Some(data.save()),
 
             DecodedPackedFile::UnitVariant(data) => Some(data.save()),
             _=> None,
@@ -426,6 +436,10 @@ impl PackedFileType {
                 return Self::AnimPack
             }
 
+            if packedfile_name.ends_with(anim::EXTENSION) {
+                return Self::Anim
+            }
+
             if packedfile_name.ends_with(ca_vp8::EXTENSION) {
                 return Self::CaVp8;
             }
@@ -448,6 +462,10 @@ impl PackedFileType {
 
             // If that failed, try types that need to be in a specific path.
             let path_str = path.iter().map(String::as_str).collect::<Vec<&str>>();
+            if path.len() == 1 && packedfile_name == table::group_formations::PATH {
+                return Self::GroupFormations;
+            }
+
             if path_str.starts_with(&table::matched_combat::BASE_PATH) && packedfile_name.ends_with(table::matched_combat::EXTENSION) {
                 return Self::MatchedCombat;
             }
@@ -522,6 +540,10 @@ impl PackedFileType {
             return Self::AnimPack
         }
 
+        if path.ends_with(anim::EXTENSION) {
+            return Self::Anim
+        }
+
         if path.ends_with(ca_vp8::EXTENSION) {
             return Self::CaVp8;
         }
@@ -544,6 +566,10 @@ impl PackedFileType {
 
         // If that failed, try types that need to be in a specific path.
         let path_str = path.split('/').collect::<Vec<&str>>();
+        if path.to_lowercase() == table::group_formations::PATH {
+            return Self::GroupFormations;
+        }
+
         if path.ends_with(table::matched_combat::EXTENSION) && path_str.starts_with(&table::matched_combat::BASE_PATH) {
             return Self::MatchedCombat;
         }
@@ -640,7 +666,7 @@ impl PackedFileType {
 impl From<&DecodedPackedFile> for PackedFileType {
     fn from(packed_file: &DecodedPackedFile) -> Self {
         match packed_file {
-            DecodedPackedFile::Anim => PackedFileType::Anim,
+            DecodedPackedFile::Anim(_) => PackedFileType::Anim,
             DecodedPackedFile::AnimFragment(_) => PackedFileType::AnimFragment,
             DecodedPackedFile::AnimPack(_) => PackedFileType::AnimPack,
             DecodedPackedFile::AnimTable(_) => PackedFileType::AnimTable,
@@ -648,7 +674,7 @@ impl From<&DecodedPackedFile> for PackedFileType {
             DecodedPackedFile::CEO(_) => PackedFileType::CEO,
             DecodedPackedFile::DB(_) => PackedFileType::DB,
             DecodedPackedFile::Image(_) => PackedFileType::Image,
-            DecodedPackedFile::GroupFormations => PackedFileType::GroupFormations,
+            DecodedPackedFile::GroupFormations(_) => PackedFileType::GroupFormations,
             DecodedPackedFile::Loc(_) => PackedFileType::Loc,
             DecodedPackedFile::MatchedCombat(_) => PackedFileType::MatchedCombat,
             DecodedPackedFile::RigidModel(_) => PackedFileType::RigidModel,
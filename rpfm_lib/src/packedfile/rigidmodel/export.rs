@@ -0,0 +1,211 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to export `RigidModel` files to other 3D formats.
+
+Only one LOD is exported each time, and only meshes with decoded vertices are exported.
+Supported formats are Wavefront OBJ and glTF 2.0, with its binary data in a separate buffer.
+!*/
+
+use serde_json::{json, Map, Value};
+
+use super::*;
+
+/// Constants used by glTF to identify the type of the components of an accessor and the target of a buffer view.
+const GLTF_COMPONENT_TYPE_U16: u32 = 5123;
+const GLTF_COMPONENT_TYPE_F32: u32 = 5126;
+const GLTF_TARGET_ARRAY_BUFFER: u32 = 34962;
+const GLTF_TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// glTF mode for meshes made of triangles.
+const GLTF_MODE_TRIANGLES: u32 = 4;
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `RigidModel`. Section of functions to export RigidModels.
+impl RigidModel {
+
+    /// This function exports the provided LOD of this `RigidModel` as a Wavefront OBJ file, returning its contents.
+    pub fn to_obj(&self, lod: usize) -> Result<String> {
+        let meshes = self.get_exportable_meshes(lod)?;
+        let mut obj = format!("# Exported from a RigidModel, LOD {}.\n", lod);
+
+        // OBJ indexes are global to the file, and start at 1.
+        let mut offset = 1;
+        for (name, mesh, vertices) in &meshes {
+            obj.push_str(&format!("o {}\n", name));
+            for vertex in *vertices {
+                obj.push_str(&format!("v {} {} {}\n", vertex.position[0], vertex.position[1], vertex.position[2]));
+            }
+
+            // OBJ textures have their origin at the bottom, not at the top.
+            for vertex in *vertices {
+                obj.push_str(&format!("vt {} {}\n", vertex.uv[0], 1.0 - vertex.uv[1]));
+            }
+
+            for vertex in *vertices {
+                let normal = vertex.get_normal();
+                obj.push_str(&format!("vn {} {} {}\n", normal[0], normal[1], normal[2]));
+            }
+
+            for face in mesh.indices.chunks_exact(3) {
+                let face = face.iter().map(|index| *index as usize + offset).collect::<Vec<usize>>();
+                obj.push_str(&format!("f {0}/{0}/{0} {1}/{1}/{1} {2}/{2}/{2}\n", face[0], face[1], face[2]));
+            }
+
+            offset += vertices.len();
+        }
+
+        Ok(obj)
+    }
+
+    /// This function exports the provided LOD of this `RigidModel` as a glTF file.
+    ///
+    /// It returns the JSON part of the glTF file and its binary buffer, which must be saved at the provided uri, relative to the JSON file.
+    pub fn to_gltf(&self, lod: usize, buffer_uri: &str) -> Result<(String, Vec<u8>)> {
+        let meshes = self.get_exportable_meshes(lod)?;
+
+        let mut buffer = vec![];
+        let mut buffer_views = vec![];
+        let mut accessors = vec![];
+        let mut gltf_meshes = vec![];
+        let mut nodes = vec![];
+
+        for (name, mesh, vertices) in &meshes {
+            let mut positions = vec![];
+            let mut normals = vec![];
+            let mut uvs = vec![];
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+
+            for vertex in *vertices {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(vertex.position[axis]);
+                    max[axis] = max[axis].max(vertex.position[axis]);
+                    positions.encode_float_f32(vertex.position[axis]);
+                }
+
+                // glTF requires normals to be unit vectors.
+                let normal = vertex.get_normal();
+                let length = normal.iter().map(|value| value * value).sum::<f32>().sqrt();
+                let normal = if length > 0.0 { normal.map(|value| value / length) } else { [0.0, 0.0, 1.0] };
+                normal.iter().for_each(|value| normals.encode_float_f32(*value));
+
+                vertex.uv.iter().for_each(|value| uvs.encode_float_f32(*value));
+            }
+
+            let mut indices = vec![];
+            mesh.indices.iter().for_each(|index| indices.encode_integer_u16(*index));
+
+            let count = vertices.len();
+            let position = push_accessor(&mut buffer, &mut buffer_views, &mut accessors, &positions, GLTF_TARGET_ARRAY_BUFFER, json!({
+                "componentType": GLTF_COMPONENT_TYPE_F32,
+                "count": count,
+                "type": "VEC3",
+                "min": min,
+                "max": max,
+            }));
+
+            let normal = push_accessor(&mut buffer, &mut buffer_views, &mut accessors, &normals, GLTF_TARGET_ARRAY_BUFFER, json!({
+                "componentType": GLTF_COMPONENT_TYPE_F32,
+                "count": count,
+                "type": "VEC3",
+            }));
+
+            let uv = push_accessor(&mut buffer, &mut buffer_views, &mut accessors, &uvs, GLTF_TARGET_ARRAY_BUFFER, json!({
+                "componentType": GLTF_COMPONENT_TYPE_F32,
+                "count": count,
+                "type": "VEC2",
+            }));
+
+            let mut primitive = json!({
+                "attributes": {
+                    "POSITION": position,
+                    "NORMAL": normal,
+                    "TEXCOORD_0": uv,
+                },
+                "mode": GLTF_MODE_TRIANGLES,
+            });
+
+            // Accessors cannot be empty, so meshes without indices are exported without them.
+            if !mesh.indices.is_empty() {
+                primitive["indices"] = json!(push_accessor(&mut buffer, &mut buffer_views, &mut accessors, &indices, GLTF_TARGET_ELEMENT_ARRAY_BUFFER, json!({
+                    "componentType": GLTF_COMPONENT_TYPE_U16,
+                    "count": mesh.indices.len(),
+                    "type": "SCALAR",
+                })));
+            }
+
+            nodes.push(json!({
+                "name": name,
+                "mesh": gltf_meshes.len(),
+            }));
+
+            gltf_meshes.push(json!({
+                "name": name,
+                "primitives": [primitive],
+            }));
+        }
+
+        // glTF doesn't allow empty lists, so we only add the ones with something in them.
+        let mut gltf = Map::new();
+        gltf.insert("asset".to_owned(), json!({ "version": "2.0", "generator": "RPFM" }));
+        gltf.insert("scene".to_owned(), json!(0));
+        if nodes.is_empty() {
+            gltf.insert("scenes".to_owned(), json!([{}]));
+        } else {
+            gltf.insert("scenes".to_owned(), json!([{ "nodes": (0..nodes.len()).collect::<Vec<usize>>() }]));
+            gltf.insert("nodes".to_owned(), Value::Array(nodes));
+            gltf.insert("meshes".to_owned(), Value::Array(gltf_meshes));
+            gltf.insert("accessors".to_owned(), Value::Array(accessors));
+            gltf.insert("bufferViews".to_owned(), Value::Array(buffer_views));
+            gltf.insert("buffers".to_owned(), json!([{ "uri": buffer_uri, "byteLength": buffer.len() }]));
+        }
+
+        Ok((serde_json::to_string_pretty(&gltf)?, buffer))
+    }
+
+    /// This function returns the meshes of the provided LOD we can export, with their names and vertices.
+    fn get_exportable_meshes(&self, lod: usize) -> Result<Vec<(String, &Mesh, &[Vertex])>> {
+        let lod_data = self.lods.get(lod).ok_or(ErrorKind::RigidModelLodNotFound(lod))?;
+        Ok(lod_data.meshes.iter().enumerate().filter_map(|(index, mesh)| match mesh.vertices {
+            Vertices::Decoded(ref vertices) if !vertices.is_empty() => {
+                let name = mesh.get_name().map(|name| name.to_owned()).unwrap_or_else(|| format!("mesh_{}", index));
+                Some((name, mesh, &vertices[..]))
+            }
+            _ => None,
+        }).collect())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Utility functions for exports
+//---------------------------------------------------------------------------//
+
+/// This function adds the provided data to a glTF buffer, creating its buffer view and accessor. Returns the index of the accessor.
+fn push_accessor(buffer: &mut Vec<u8>, buffer_views: &mut Vec<Value>, accessors: &mut Vec<Value>, data: &[u8], target: u32, mut accessor: Value) -> usize {
+
+    // Buffer views must be aligned to the size of their components.
+    buffer.resize((buffer.len() + 3) / 4 * 4, 0);
+    buffer_views.push(json!({
+        "buffer": 0,
+        "byteOffset": buffer.len(),
+        "byteLength": data.len(),
+        "target": target,
+    }));
+    buffer.extend_from_slice(data);
+
+    accessor["bufferView"] = json!(buffer_views.len() - 1);
+    accessors.push(accessor);
+    accessors.len() - 1
+}
@@ -11,42 +11,996 @@
 /*!
 Module with all the code to interact with RigidModel PackedFiles.
 
-This is really a dummy module, as all the logic for this is done in the view through Phazer's lib.
+RigidModels (RMV2) are the 3D models used since Napoleon. Their structure is:
+
+- Header: signature, version, amount of LODs and skeleton id (128 bytes, 0-padded).
+- LOD table: one header per LOD, with its amount of meshes, sizes of its vertex and index data, offset of its first mesh and
+  visibility distance. Since version 7 they also have their authored LOD number and quality level.
+- Meshes of each LOD, one after another. Each mesh has:
+    - Header (80 bytes): material type, size of the mesh, offsets and counts of vertices and indices, bounding box and shader.
+    - Material block: vertex format, name, texture directory, transforms, attachment points, texture paths and parameters.
+    - Vertex data, in the format specified in the material block.
+    - Index data, as u16 triangles.
+
+The layout of material blocks and vertices changes between material types, so if a material block or its vertices
+cannot be decoded, we keep them as raw data. That, and keeping the garbage some models have after the 0 in their
+fixed-size strings, ensures a decoded model is always saved back to the same bytes. Models we cannot decode at all,
+like the ones with unsupported versions, are kept entirely as raw data.
 !*/
 
 use serde_derive::{Serialize, Deserialize};
 
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::{GetRef, GetRefMut, Set};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+
+pub mod export;
+
+#[cfg(test)]
+mod rigidmodel_test;
+
 /// This represents the value that every RigidModel PackedFile has in their 0-4 bytes. A.k.a it's signature or preamble.
-#[allow(dead_code)]
 const PACKED_FILE_TYPE: &str = "RMV2";
 
 /// Extension used by RigidModel PackedFiles.
 pub const EXTENSION: &str = ".rigid_model_v2";
 
+/// Versions of RigidModels we can decode.
+const SUPPORTED_VERSIONS: [u32; 3] = [6, 7, 8];
+
+/// Size of the header of a RigidModel.
+const HEADER_SIZE: usize = 140;
+
+/// Size of the skeleton id in the header.
+const SKELETON_ID_SIZE: usize = 128;
+
+/// Size of each LOD header, for version 6 and for later versions.
+const LOD_HEADER_SIZE_V6: usize = 20;
+const LOD_HEADER_SIZE_V7: usize = 28;
+
+/// Size of the header of each mesh.
+const MESH_HEADER_SIZE: usize = 80;
+
+/// Offset of the counts of attachment points and textures within a material block.
+const MATERIAL_COUNTS_OFFSET: usize = 712;
+
+/// Size of the counts and padding after the counts of a material block, before the first attachment point.
+const MATERIAL_COUNTS_SIZE: usize = 24;
+
+/// Sizes of the fixed-size strings and padding used in meshes.
+const SHADER_NAME_SIZE: usize = 12;
+const SHADER_DATA_SIZE: usize = 20;
+const MESH_NAME_SIZE: usize = 32;
+const DIRECTORY_SIZE: usize = 256;
+const MATERIAL_PADDING_SIZE: usize = 124;
+const ATTACHMENT_POINT_NAME_SIZE: usize = 32;
+const TEXTURE_PATH_SIZE: usize = 256;
+
+/// Size of each attachment point in a material block: name, 3x4 matrix and bone id.
+const ATTACHMENT_POINT_SIZE: usize = ATTACHMENT_POINT_NAME_SIZE + 48 + 4;
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
 
 /// This struct contains a RigidModel decoded in memory.
-#[derive(Clone, Debug,PartialEq, Serialize, Deserialize)]
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct RigidModel {
-    pub data: Vec<u8>,
+    version: u32,
+    skeleton_id: String,
+    lods: Vec<Lod>,
+
+    /// Data of the model, if we couldn't decode it. If present, it's saved back as it is.
+    raw_data: Option<Vec<u8>>,
+}
+
+/// This struct represents a LOD (Level Of Detail) of a RigidModel.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lod {
+    visibility_distance: f32,
+
+    /// Only used since version 7.
+    authored_lod_number: u32,
+
+    /// Only used since version 7.
+    quality_level: u32,
+    meshes: Vec<Mesh>,
+}
+
+/// This struct represents a mesh within a LOD.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mesh {
+    material_type: u16,
+    render_flags: u16,
+
+    /// Min and max corners of the bounding box of the mesh.
+    bounding_box: [f32; 6],
+    shader_name: String,
+
+    /// Data after the shader name. The zeros at its end are not kept.
+    shader_data: Vec<u8>,
+
+    /// Decoded material block. None if we couldn't decode it.
+    material: Option<Material>,
+
+    /// Data of the material block we couldn't decode. If the material block was decoded, this is the data after it.
+    material_data: Vec<u8>,
+    vertices: Vertices,
+    indices: Vec<u16>,
+}
+
+/// This struct represents the material block of a mesh.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    vertex_format: VertexFormat,
+    name: String,
+    texture_directory: String,
+    filters: String,
+    unknown_1: u16,
+    pivot: [f32; 3],
+
+    /// Three 3x4 transformation matrices.
+    matrices: [[f32; 12]; 3],
+    matrix_index: i32,
+    parent_matrix_index: i32,
+
+    /// Padding after the counts of the lists. The zeros at its end are not kept.
+    padding: Vec<u8>,
+    attachment_points: Vec<AttachmentPoint>,
+    textures: Vec<Texture>,
+    string_params: Vec<(i32, String)>,
+    float_params: Vec<(i32, f32)>,
+    int_params: Vec<(i32, i32)>,
+    vec4_params: Vec<(i32, [f32; 4])>,
+}
+
+/// This struct represents an attachment point of a mesh.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentPoint {
+    name: String,
+
+    /// 3x4 transformation matrix.
+    matrix: [f32; 12],
+    bone_id: i32,
+}
+
+/// This struct represents a texture used by a mesh.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Texture {
+    texture_type: u32,
+    path: String,
+}
+
+/// This enum represents the vertices of a mesh.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Vertices {
+
+    /// Vertices in one of the formats we can decode.
+    Decoded(Vec<Vertex>),
+
+    /// Vertices we couldn't decode, with their count and their data.
+    Raw(u32, Vec<u8>),
+}
+
+/// This enum represents the formats vertices can have.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VertexFormat {
+
+    /// Static vertex, used by non-animated models. 32 bytes.
+    Default,
+
+    /// Vertex with up to two bones, used by animated models. 28 bytes.
+    Weighted,
+
+    /// Vertex with up to four bones, used by cinematic models. 32 bytes.
+    Cinematic,
+
+    /// Any other format. We don't know how to decode them.
+    Unknown(u16),
+}
+
+/// This struct represents a vertex. Which fields are used depends on the format of the vertices.
+///
+/// Normals, tangents and bitangents are stored as unsigned bytes, in the 0-255 range.
+#[derive(GetRef, GetRefMut, Set, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vertex {
+    position: [f32; 4],
+    uv: [f32; 2],
+
+    /// Only used by `Default` vertices.
+    uv_2: [f32; 2],
+    normal: [u8; 4],
+    tangent: [u8; 4],
+    bitangent: [u8; 4],
+
+    /// Only used by `Default` vertices.
+    colour: [u8; 4],
+
+    /// Only used by `Weighted` (first two) and `Cinematic` vertices.
+    bone_indices: [u8; 4],
+
+    /// Only used by `Weighted` (first two) and `Cinematic` vertices.
+    bone_weights: [u8; 4],
 }
 
 //---------------------------------------------------------------------------//
 //                              Implementations
 //---------------------------------------------------------------------------//
 
-/// Implementation of RigidModel.
+/// Implementation of `RigidModel`.
 impl RigidModel {
 
-    pub fn read(packed_file_data: &[u8]) -> Self {
-        Self {
-            data: packed_file_data.to_vec(),
+    /// This function checks if the provided data is a RigidModel.
+    pub fn is_rigid_model(packed_file_data: &[u8]) -> bool {
+        if let Ok(signature) = packed_file_data.decode_string_u8(0, PACKED_FILE_TYPE.len()) {
+            signature == PACKED_FILE_TYPE
+        } else { false }
+    }
+
+    /// This function creates a `RigidModel` from a `&[u8]`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        if !Self::is_rigid_model(packed_file_data) {
+            return Err(ErrorKind::RigidModelNotSupportedFile.into());
+        }
+
+        let version = packed_file_data.decode_integer_u32(4)?;
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            return Err(ErrorKind::RigidModelNotSupportedType.into());
+        }
+
+        let lods_count = packed_file_data.decode_integer_u32(8)?;
+        let skeleton_id = decode_padded_string(packed_file_data, 12, SKELETON_ID_SIZE)?;
+
+        // Read the LOD table. The sizes of its vertex and index data are recalculated when saving.
+        let mut index = HEADER_SIZE;
+        let mut lod_headers = vec![];
+        for _ in 0..lods_count {
+            let meshes_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let _vertices_data_size = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let _indices_data_size = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+            let start_offset = packed_file_data.decode_packedfile_integer_u32(index, &mut index)? as usize;
+            let visibility_distance = packed_file_data.decode_packedfile_float_f32(index, &mut index)?;
+
+            let (authored_lod_number, quality_level) = if version >= 7 {
+                (packed_file_data.decode_packedfile_integer_u32(index, &mut index)?, packed_file_data.decode_packedfile_integer_u32(index, &mut index)?)
+            } else { (0, 0) };
+
+            lod_headers.push((meshes_count, start_offset, Lod {
+                visibility_distance,
+                authored_lod_number,
+                quality_level,
+                meshes: vec![],
+            }));
+        }
+
+        // Meshes are one after another, so we only accept LODs that start where the previous one ended.
+        let mut lods = vec![];
+        for (meshes_count, start_offset, mut lod) in lod_headers {
+            if start_offset != index {
+                return Err(ErrorKind::RigidModelDecode(format!("LOD {} starts at byte {}, but it was expected to start at byte {}.", lods.len(), start_offset, index)).into());
+            }
+
+            for _ in 0..meshes_count {
+                let mesh_size = packed_file_data.decode_integer_u32(index + 4)? as usize;
+                let mesh_data = packed_file_data.get(index..index + mesh_size).ok_or_else(|| ErrorKind::RigidModelDecode(format!("Mesh at byte {} is bigger than the file.", index)))?;
+                lod.meshes.push(Mesh::read(mesh_data)?);
+                index += mesh_size;
+            }
+
+            lods.push(lod);
+        }
+
+        // Trigger an error if there's left data on the source.
+        if index != packed_file_data.len() {
+            return Err(ErrorKind::PackedFileIncompleteDecoding.into())
+        }
+
+        Ok(Self {
+            version,
+            skeleton_id,
+            lods,
+            raw_data: None,
+        })
+    }
+
+    /// This function creates a `RigidModel` from a `&[u8]`, keeping its data raw if it cannot be decoded.
+    pub fn read_or_keep_raw(packed_file_data: &[u8]) -> Self {
+        Self::read(packed_file_data).unwrap_or_else(|_| Self {
+            raw_data: Some(packed_file_data.to_vec()),
+            ..Default::default()
+        })
+    }
+
+    /// This function reads only the paths of the textures used by a RigidModel, with the LOD and mesh using them.
+    ///
+    /// It's meant for when we only need the textures, as it skips the vertices and indices of the meshes.
+    pub fn read_texture_paths(packed_file_data: &[u8]) -> Result<Vec<(usize, usize, String)>> {
+        if !Self::is_rigid_model(packed_file_data) {
+            return Err(ErrorKind::RigidModelNotSupportedFile.into());
+        }
+
+        let version = packed_file_data.decode_integer_u32(4)?;
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            return Err(ErrorKind::RigidModelNotSupportedType.into());
         }
+
+        let lods_count = packed_file_data.decode_integer_u32(8)? as usize;
+        let lod_header_size = if version >= 7 { LOD_HEADER_SIZE_V7 } else { LOD_HEADER_SIZE_V6 };
+
+        let mut texture_paths = vec![];
+        for lod in 0..lods_count {
+            let lod_header_offset = HEADER_SIZE + lod * lod_header_size;
+            let meshes_count = packed_file_data.decode_integer_u32(lod_header_offset)? as usize;
+            let mut index = packed_file_data.decode_integer_u32(lod_header_offset + 12)? as usize;
+
+            for mesh in 0..meshes_count {
+                let mesh_size = packed_file_data.decode_integer_u32(index + 4)? as usize;
+                let vertices_offset = packed_file_data.decode_integer_u32(index + 8)? as usize;
+                let material_block = packed_file_data.get(index + MESH_HEADER_SIZE..index + vertices_offset)
+                    .ok_or_else(|| ErrorKind::RigidModelDecode(format!("Invalid material block in the mesh at byte {}.", index)))?;
+
+                // Like when decoding them, material blocks we cannot read are treated as blocks without textures.
+                if let Ok(paths) = Material::read_texture_paths(material_block) {
+                    texture_paths.extend(paths.into_iter().map(|path| (lod, mesh, path)));
+                }
+                index += mesh_size;
+            }
+        }
+
+        Ok(texture_paths)
+    }
+
+    /// This function returns if the `RigidModel` was decoded, or we kept it as raw data.
+    pub fn is_decoded(&self) -> bool {
+        self.raw_data.is_none()
     }
 
-    pub fn save(&self) -> Vec<u8> {
-        self.data.to_vec()
+    /// This function takes a `RigidModel` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+        if let Some(ref raw_data) = self.raw_data {
+            return Ok(raw_data.clone());
+        }
+
+        let lod_header_size = if self.version >= 7 { LOD_HEADER_SIZE_V7 } else { LOD_HEADER_SIZE_V6 };
+
+        let mut data = vec![];
+        data.encode_string_u8(PACKED_FILE_TYPE);
+        data.encode_integer_u32(self.version);
+        data.encode_integer_u32(self.lods.len() as u32);
+        encode_padded_string(&mut data, &self.skeleton_id, SKELETON_ID_SIZE)?;
+
+        let mut meshes_data = vec![];
+        let mut start_offset = HEADER_SIZE + lod_header_size * self.lods.len();
+        for lod in &self.lods {
+            let mut lod_data = vec![];
+            let mut vertices_data_size = 0;
+            for mesh in &lod.meshes {
+                let (mut mesh_data, mesh_vertices_data_size) = mesh.save()?;
+                lod_data.append(&mut mesh_data);
+                vertices_data_size += mesh_vertices_data_size;
+            }
+
+            data.encode_integer_u32(lod.meshes.len() as u32);
+            data.encode_integer_u32(vertices_data_size as u32);
+            data.encode_integer_u32(lod.meshes.iter().map(|mesh| mesh.indices.len() * 2).sum::<usize>() as u32);
+            data.encode_integer_u32(start_offset as u32);
+            data.encode_float_f32(lod.visibility_distance);
+
+            if self.version >= 7 {
+                data.encode_integer_u32(lod.authored_lod_number);
+                data.encode_integer_u32(lod.quality_level);
+            }
+
+            start_offset += lod_data.len();
+            meshes_data.append(&mut lod_data);
+        }
+
+        data.append(&mut meshes_data);
+        Ok(data)
+    }
+
+    /// This function returns the paths of all the textures used by this model, with the LOD and mesh using them.
+    pub fn get_texture_paths(&self) -> Vec<(usize, usize, &str)> {
+        self.lods.iter().enumerate().flat_map(|(lod_index, lod)| {
+            lod.meshes.iter().enumerate().flat_map(move |(mesh_index, mesh)| {
+                mesh.material.iter().flat_map(|material| &material.textures)
+                    .map(move |texture| (lod_index, mesh_index, texture.get_path()))
+                    .filter(|(_, _, path)| !path.is_empty())
+            })
+        }).collect()
+    }
+}
+
+/// Implementation of `Mesh`.
+impl Mesh {
+
+    /// This function creates a `Mesh` from the data of a single mesh.
+    fn read(data: &[u8]) -> Result<Self> {
+        let material_type = data.decode_integer_u16(0)?;
+        let render_flags = data.decode_integer_u16(2)?;
+        let vertices_offset = data.decode_integer_u32(8)? as usize;
+        let vertices_count = data.decode_integer_u32(12)?;
+        let indices_offset = data.decode_integer_u32(16)? as usize;
+        let indices_count = data.decode_integer_u32(20)? as usize;
+
+        let mut bounding_box = [0.0; 6];
+        for (index, value) in bounding_box.iter_mut().enumerate() {
+            *value = data.decode_float_f32(24 + index * 4)?;
+        }
+
+        let shader_name = decode_padded_string(data, 48, SHADER_NAME_SIZE)?;
+        let shader_data = decode_padded_data(data, 48 + SHADER_NAME_SIZE, SHADER_DATA_SIZE)?;
+
+        if vertices_offset < MESH_HEADER_SIZE || indices_offset < vertices_offset || indices_offset + indices_count * 2 != data.len() {
+            return Err(ErrorKind::RigidModelDecode(format!("Invalid mesh offsets: vertices at {}, indices at {} ({} indices), in a mesh of {} bytes.", vertices_offset, indices_offset, indices_count, data.len())).into());
+        }
+
+        // Only keep the decoded material if it encodes back to the same data. Otherwise, we keep it raw.
+        let material_block = &data[MESH_HEADER_SIZE..vertices_offset];
+        let (material, material_data) = match Material::read(material_block) {
+            Ok((material, size)) if material.save().ok().as_deref() == Some(&material_block[..size]) => (Some(material), material_block[size..].to_vec()),
+            _ => (None, material_block.to_vec()),
+        };
+
+        let vertex_format = material.as_ref().map_or(VertexFormat::Unknown(u16::MAX), |material| material.vertex_format);
+        let vertices = Vertices::read(&data[vertices_offset..indices_offset], vertices_count, vertex_format);
+
+        let mut indices = Vec::with_capacity(indices_count);
+        for index in 0..indices_count {
+            indices.push(data.decode_integer_u16(indices_offset + index * 2)?);
+        }
+
+        Ok(Self {
+            material_type,
+            render_flags,
+            bounding_box,
+            shader_name,
+            shader_data,
+            material,
+            material_data,
+            vertices,
+            indices,
+        })
+    }
+
+    /// This function encodes a `Mesh` to `Vec<u8>`, returning it with the size of its vertex data.
+    fn save(&self) -> Result<(Vec<u8>, usize)> {
+        let mut material_data = match self.material {
+            Some(ref material) => material.save()?,
+            None => vec![],
+        };
+        material_data.extend_from_slice(&self.material_data);
+
+        let mut vertices_data = self.vertices.save(self.material.as_ref().map_or(VertexFormat::Unknown(u16::MAX), |material| material.vertex_format))?;
+
+        let vertices_data_size = vertices_data.len();
+        let vertices_offset = MESH_HEADER_SIZE + material_data.len();
+        let indices_offset = vertices_offset + vertices_data_size;
+        let mesh_size = indices_offset + self.indices.len() * 2;
+
+        let mut data = Vec::with_capacity(mesh_size);
+        data.encode_integer_u16(self.material_type);
+        data.encode_integer_u16(self.render_flags);
+        data.encode_integer_u32(mesh_size as u32);
+        data.encode_integer_u32(vertices_offset as u32);
+        data.encode_integer_u32(self.vertices.len() as u32);
+        data.encode_integer_u32(indices_offset as u32);
+        data.encode_integer_u32(self.indices.len() as u32);
+        self.bounding_box.iter().for_each(|value| data.encode_float_f32(*value));
+        encode_padded_string(&mut data, &self.shader_name, SHADER_NAME_SIZE)?;
+        encode_padded_data(&mut data, &self.shader_data, SHADER_DATA_SIZE)?;
+
+        data.append(&mut material_data);
+        data.append(&mut vertices_data);
+        self.indices.iter().for_each(|index| data.encode_integer_u16(*index));
+
+        Ok((data, vertices_data_size))
+    }
+
+    /// This function returns the name of the mesh, if it has one.
+    pub fn get_name(&self) -> Option<&str> {
+        self.material.as_ref().map(|material| trim_padded_string(&material.name)).filter(|name| !name.is_empty())
+    }
+}
+
+/// Implementation of `Material`.
+impl Material {
+
+    /// This function tries to decode a material block, returning it and the amount of bytes decoded.
+    fn read(data: &[u8]) -> Result<(Self, usize)> {
+        let mut index = 0;
+        let vertex_format = VertexFormat::from(data.decode_packedfile_integer_u16(index, &mut index)?);
+
+        let name = decode_padded_string(data, index, MESH_NAME_SIZE)?;
+        index += MESH_NAME_SIZE;
+        let texture_directory = decode_padded_string(data, index, DIRECTORY_SIZE)?;
+        index += DIRECTORY_SIZE;
+        let filters = decode_padded_string(data, index, DIRECTORY_SIZE)?;
+        index += DIRECTORY_SIZE;
+
+        let unknown_1 = data.decode_packedfile_integer_u16(index, &mut index)?;
+
+        let mut pivot = [0.0; 3];
+        for value in &mut pivot {
+            *value = data.decode_packedfile_float_f32(index, &mut index)?;
+        }
+
+        let mut matrices = [[0.0; 12]; 3];
+        for value in matrices.iter_mut().flatten() {
+            *value = data.decode_packedfile_float_f32(index, &mut index)?;
+        }
+
+        let matrix_index = data.decode_packedfile_integer_i32(index, &mut index)?;
+        let parent_matrix_index = data.decode_packedfile_integer_i32(index, &mut index)?;
+        let attachment_points_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let textures_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let string_params_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let float_params_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let int_params_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+        let vec4_params_count = data.decode_packedfile_integer_u32(index, &mut index)?;
+
+        let padding = decode_padded_data(data, index, MATERIAL_PADDING_SIZE)?;
+        index += MATERIAL_PADDING_SIZE;
+
+        let mut attachment_points = vec![];
+        for _ in 0..attachment_points_count {
+            let name = decode_padded_string(data, index, ATTACHMENT_POINT_NAME_SIZE)?;
+            index += ATTACHMENT_POINT_NAME_SIZE;
+
+            let mut matrix = [0.0; 12];
+            for value in &mut matrix {
+                *value = data.decode_packedfile_float_f32(index, &mut index)?;
+            }
+
+            let bone_id = data.decode_packedfile_integer_i32(index, &mut index)?;
+            attachment_points.push(AttachmentPoint {
+                name,
+                matrix,
+                bone_id,
+            });
+        }
+
+        let mut textures = vec![];
+        for _ in 0..textures_count {
+            let texture_type = data.decode_packedfile_integer_u32(index, &mut index)?;
+            let path = decode_padded_string(data, index, TEXTURE_PATH_SIZE)?;
+            index += TEXTURE_PATH_SIZE;
+
+            textures.push(Texture {
+                texture_type,
+                path,
+            });
+        }
+
+        let mut string_params = vec![];
+        for _ in 0..string_params_count {
+            string_params.push((data.decode_packedfile_integer_i32(index, &mut index)?, data.decode_packedfile_string_u8(index, &mut index)?));
+        }
+
+        let mut float_params = vec![];
+        for _ in 0..float_params_count {
+            float_params.push((data.decode_packedfile_integer_i32(index, &mut index)?, data.decode_packedfile_float_f32(index, &mut index)?));
+        }
+
+        let mut int_params = vec![];
+        for _ in 0..int_params_count {
+            int_params.push((data.decode_packedfile_integer_i32(index, &mut index)?, data.decode_packedfile_integer_i32(index, &mut index)?));
+        }
+
+        let mut vec4_params = vec![];
+        for _ in 0..vec4_params_count {
+            let param_index = data.decode_packedfile_integer_i32(index, &mut index)?;
+            let mut value = [0.0; 4];
+            for value in &mut value {
+                *value = data.decode_packedfile_float_f32(index, &mut index)?;
+            }
+
+            vec4_params.push((param_index, value));
+        }
+
+        Ok((Self {
+            vertex_format,
+            name,
+            texture_directory,
+            filters,
+            unknown_1,
+            pivot,
+            matrices,
+            matrix_index,
+            parent_matrix_index,
+            padding,
+            attachment_points,
+            textures,
+            string_params,
+            float_params,
+            int_params,
+            vec4_params,
+        }, index))
+    }
+
+    /// This function reads only the paths of the textures of a material block, ignoring the empty ones.
+    fn read_texture_paths(data: &[u8]) -> Result<Vec<String>> {
+        let attachment_points_count = data.decode_integer_u32(MATERIAL_COUNTS_OFFSET)? as usize;
+        let textures_count = data.decode_integer_u32(MATERIAL_COUNTS_OFFSET + 4)? as usize;
+
+        let mut index = MATERIAL_COUNTS_OFFSET + MATERIAL_COUNTS_SIZE + MATERIAL_PADDING_SIZE + attachment_points_count * ATTACHMENT_POINT_SIZE;
+        let mut paths = vec![];
+        for _ in 0..textures_count {
+            let path = decode_padded_string(data, index + 4, TEXTURE_PATH_SIZE)?;
+            index += 4 + TEXTURE_PATH_SIZE;
+
+            let path = trim_padded_string(&path);
+            if !path.is_empty() {
+                paths.push(path.to_owned());
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// This function encodes a `Material` to `Vec<u8>`.
+    fn save(&self) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        data.encode_integer_u16(self.vertex_format.into());
+        encode_padded_string(&mut data, &self.name, MESH_NAME_SIZE)?;
+        encode_padded_string(&mut data, &self.texture_directory, DIRECTORY_SIZE)?;
+        encode_padded_string(&mut data, &self.filters, DIRECTORY_SIZE)?;
+        data.encode_integer_u16(self.unknown_1);
+
+        self.pivot.iter().for_each(|value| data.encode_float_f32(*value));
+        self.matrices.iter().flatten().for_each(|value| data.encode_float_f32(*value));
+
+        data.encode_integer_i32(self.matrix_index);
+        data.encode_integer_i32(self.parent_matrix_index);
+        data.encode_integer_u32(self.attachment_points.len() as u32);
+        data.encode_integer_u32(self.textures.len() as u32);
+        data.encode_integer_u32(self.string_params.len() as u32);
+        data.encode_integer_u32(self.float_params.len() as u32);
+        data.encode_integer_u32(self.int_params.len() as u32);
+        data.encode_integer_u32(self.vec4_params.len() as u32);
+        encode_padded_data(&mut data, &self.padding, MATERIAL_PADDING_SIZE)?;
+
+        for attachment_point in &self.attachment_points {
+            encode_padded_string(&mut data, &attachment_point.name, ATTACHMENT_POINT_NAME_SIZE)?;
+            attachment_point.matrix.iter().for_each(|value| data.encode_float_f32(*value));
+            data.encode_integer_i32(attachment_point.bone_id);
+        }
+
+        for texture in &self.textures {
+            data.encode_integer_u32(texture.texture_type);
+            encode_padded_string(&mut data, &texture.path, TEXTURE_PATH_SIZE)?;
+        }
+
+        for (index, value) in &self.string_params {
+            data.encode_integer_i32(*index);
+            data.encode_packedfile_string_u8(value);
+        }
+
+        for (index, value) in &self.float_params {
+            data.encode_integer_i32(*index);
+            data.encode_float_f32(*value);
+        }
+
+        for (index, value) in &self.int_params {
+            data.encode_integer_i32(*index);
+            data.encode_integer_i32(*value);
+        }
+
+        for (index, value) in &self.vec4_params {
+            data.encode_integer_i32(*index);
+            value.iter().for_each(|value| data.encode_float_f32(*value));
+        }
+
+        Ok(data)
+    }
+}
+
+/// Implementation of `Texture`.
+impl Texture {
+
+    /// This function returns the path of the texture, without the garbage some models have after it.
+    pub fn get_path(&self) -> &str {
+        trim_padded_string(&self.path)
+    }
+}
+
+/// Implementation of `Vertices`.
+impl Vertices {
+
+    /// This function decodes the vertices of a mesh. If they're not in a format we can decode, they're kept raw.
+    fn read(data: &[u8], count: u32, format: VertexFormat) -> Self {
+        if let Some(size) = format.get_size() {
+            if data.len() == size * count as usize {
+                let vertices = data.chunks_exact(size).map(|vertex| Vertex::read(vertex, format)).collect::<Vec<Vertex>>();
+
+                // Half floats may not survive the trip, so only keep them decoded if they encode back to the same data.
+                let vertices = Self::Decoded(vertices);
+                if vertices.save(format).ok().as_deref() == Some(data) {
+                    return vertices;
+                }
+            }
+        }
+
+        Self::Raw(count, data.to_vec())
+    }
+
+    /// This function encodes the vertices of a mesh to `Vec<u8>`.
+    fn save(&self, format: VertexFormat) -> Result<Vec<u8>> {
+        match self {
+            Self::Decoded(vertices) => {
+                if vertices.is_empty() {
+                    return Ok(vec![]);
+                }
+
+                match format.get_size() {
+                    Some(size) => {
+                        let mut data = Vec::with_capacity(size * vertices.len());
+                        vertices.iter().for_each(|vertex| vertex.save(&mut data, format));
+                        Ok(data)
+                    }
+                    None => Err(ErrorKind::RigidModelNotSupportedType.into()),
+                }
+            },
+            Self::Raw(_, data) => Ok(data.to_vec()),
+        }
+    }
+
+    /// This function returns the amount of vertices.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Decoded(vertices) => vertices.len(),
+            Self::Raw(count, _) => *count as usize,
+        }
+    }
+
+    /// This function returns if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Implementation of `Vertex`.
+impl Vertex {
+
+    /// This function decodes a single vertex. The data must be of the size of the format.
+    fn read(data: &[u8], format: VertexFormat) -> Self {
+        let read_f16 = |offset: usize| f16_to_f32(u16::from_le_bytes([data[offset], data[offset + 1]]));
+        let read_u8_4 = |offset: usize| [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]];
+
+        let mut vertex = Self {
+            position: [read_f16(0), read_f16(2), read_f16(4), read_f16(6)],
+            ..Self::default()
+        };
+
+        match format {
+            VertexFormat::Default => {
+                vertex.uv = [read_f16(8), read_f16(10)];
+                vertex.uv_2 = [read_f16(12), read_f16(14)];
+                vertex.normal = read_u8_4(16);
+                vertex.tangent = read_u8_4(20);
+                vertex.bitangent = read_u8_4(24);
+                vertex.colour = read_u8_4(28);
+            }
+            VertexFormat::Weighted => {
+                vertex.bone_indices = [data[8], data[9], 0, 0];
+                vertex.bone_weights = [data[10], data[11], 0, 0];
+                vertex.normal = read_u8_4(12);
+                vertex.uv = [read_f16(16), read_f16(18)];
+                vertex.tangent = read_u8_4(20);
+                vertex.bitangent = read_u8_4(24);
+            }
+            VertexFormat::Cinematic => {
+                vertex.bone_indices = read_u8_4(8);
+                vertex.bone_weights = read_u8_4(12);
+                vertex.normal = read_u8_4(16);
+                vertex.uv = [read_f16(20), read_f16(22)];
+                vertex.tangent = read_u8_4(24);
+                vertex.bitangent = read_u8_4(28);
+            }
+            VertexFormat::Unknown(_) => {}
+        }
+
+        vertex
+    }
+
+    /// This function encodes a single vertex in the provided format.
+    fn save(&self, data: &mut Vec<u8>, format: VertexFormat) {
+        self.position.iter().for_each(|value| data.encode_integer_u16(f32_to_f16(*value)));
+
+        match format {
+            VertexFormat::Default => {
+                self.uv.iter().chain(self.uv_2.iter()).for_each(|value| data.encode_integer_u16(f32_to_f16(*value)));
+                data.extend_from_slice(&self.normal);
+                data.extend_from_slice(&self.tangent);
+                data.extend_from_slice(&self.bitangent);
+                data.extend_from_slice(&self.colour);
+            }
+            VertexFormat::Weighted => {
+                data.extend_from_slice(&self.bone_indices[..2]);
+                data.extend_from_slice(&self.bone_weights[..2]);
+                data.extend_from_slice(&self.normal);
+                self.uv.iter().for_each(|value| data.encode_integer_u16(f32_to_f16(*value)));
+                data.extend_from_slice(&self.tangent);
+                data.extend_from_slice(&self.bitangent);
+            }
+            VertexFormat::Cinematic => {
+                data.extend_from_slice(&self.bone_indices);
+                data.extend_from_slice(&self.bone_weights);
+                data.extend_from_slice(&self.normal);
+                self.uv.iter().for_each(|value| data.encode_integer_u16(f32_to_f16(*value)));
+                data.extend_from_slice(&self.tangent);
+                data.extend_from_slice(&self.bitangent);
+            }
+            VertexFormat::Unknown(_) => {}
+        }
+    }
+
+    /// This function returns the normal of the vertex, in the -1 to 1 range.
+    pub fn get_normal(&self) -> [f32; 3] {
+        [
+            self.normal[0] as f32 / 127.5 - 1.0,
+            self.normal[1] as f32 / 127.5 - 1.0,
+            self.normal[2] as f32 / 127.5 - 1.0,
+        ]
+    }
+}
+
+/// Implementation of `VertexFormat`.
+impl VertexFormat {
+
+    /// This function returns the size of a vertex in this format, if we know it.
+    pub fn get_size(&self) -> Option<usize> {
+        match self {
+            Self::Default => Some(32),
+            Self::Weighted => Some(28),
+            Self::Cinematic => Some(32),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+/// Implementation of `Default` for `Vertices`.
+impl Default for Vertices {
+    fn default() -> Self {
+        Self::Decoded(vec![])
+    }
+}
+
+/// Implementation of `Default` for `VertexFormat`.
+impl Default for VertexFormat {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+/// Implementation to create a `VertexFormat` from an u16.
+impl From<u16> for VertexFormat {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => Self::Default,
+            3 => Self::Weighted,
+            4 => Self::Cinematic,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// Implementation to create an u16 from a `VertexFormat`.
+impl From<VertexFormat> for u16 {
+    fn from(value: VertexFormat) -> Self {
+        match value {
+            VertexFormat::Default => 0,
+            VertexFormat::Weighted => 3,
+            VertexFormat::Cinematic => 4,
+            VertexFormat::Unknown(value) => value,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Utility functions for RigidModels
+//---------------------------------------------------------------------------//
+
+/// This function decodes a fixed-size string. Only the zeros at the end are removed, so any garbage after the first zero is kept.
+fn decode_padded_string(data: &[u8], offset: usize, size: usize) -> Result<String> {
+    let bytes = decode_padded_data(data, offset, size)?;
+    data.decode_string_u8_iso_8859_1(offset, bytes.len())
+}
+
+/// This function decodes a fixed-size block of data, removing the zeros at its end.
+fn decode_padded_data(data: &[u8], offset: usize, size: usize) -> Result<Vec<u8>> {
+    let bytes = data.get(offset..offset + size).ok_or(ErrorKind::RigidModelParseError)?;
+    let size_no_zeros = bytes.iter().rposition(|byte| *byte != 0).map_or(0, |position| position + 1);
+    Ok(bytes[..size_no_zeros].to_vec())
+}
+
+/// This function encodes a fixed-size string decoded with `decode_padded_string`.
+fn encode_padded_string(data: &mut Vec<u8>, string: &str, size: usize) -> Result<()> {
+    let mut bytes = vec![];
+    bytes.encode_string_u8_iso_8859_1(string);
+    encode_padded_data(data, &bytes, size)
+}
+
+/// This function encodes a block of data, filling it with zeros until it reaches the provided size.
+fn encode_padded_data(data: &mut Vec<u8>, bytes: &[u8], size: usize) -> Result<()> {
+    if bytes.len() > size {
+        return Err(ErrorKind::RigidModelDecode(format!("\"{}\" is longer than {} bytes.", String::from_utf8_lossy(bytes), size)).into());
+    }
+
+    data.extend_from_slice(bytes);
+    data.resize(data.len() + size - bytes.len(), 0);
+    Ok(())
+}
+
+/// This function returns a fixed-size string without the garbage after its first zero.
+fn trim_padded_string(string: &str) -> &str {
+    string.split('\0').next().unwrap_or_default()
+}
+
+/// This function converts a half-precision float to a f32.
+fn f16_to_f32(value: u16) -> f32 {
+    let sign = ((value as u32) & 0x8000) << 16;
+    let exponent = ((value >> 10) & 0x1f) as u32;
+    let mantissa = (value & 0x3ff) as u32;
+
+    match exponent {
+
+        // Zeros and subnormals.
+        0 => {
+            let value = mantissa as f32 * 2f32.powi(-24);
+            if sign != 0 { -value } else { value }
+        }
+
+        // Infinities and NaNs.
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+/// This function converts a f32 to a half-precision float, rounding to the nearest value.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    // Infinities and NaNs. NaNs must keep at least one bit of their mantissa.
+    if exponent == 0xff {
+        let half_mantissa = (mantissa >> 13) as u16;
+        return sign | 0x7c00 | half_mantissa | if mantissa != 0 && half_mantissa == 0 { 1 } else { 0 };
+    }
+
+    let half_exponent = exponent - 112;
+    if half_exponent >= 0x1f {
+        sign | 0x7c00
+    }
+
+    // Subnormals, or zeros if the value is too small.
+    else if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let mut half_mantissa = mantissa >> shift;
+        let remainder = mantissa & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        if remainder > halfway || (remainder == halfway && half_mantissa & 1 == 1) {
+            half_mantissa += 1;
+        }
+
+        sign | half_mantissa as u16
+    }
+
+    else {
+        let mut half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+        let remainder = mantissa & 0x1fff;
+        if remainder > 0x1000 || (remainder == 0x1000 && half & 1 == 1) {
+            half += 1;
+        }
+
+        sign | half as u16
     }
 }
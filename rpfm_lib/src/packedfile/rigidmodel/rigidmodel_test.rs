@@ -0,0 +1,94 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `RigidModel` module.
+!*/
+
+use super::*;
+
+/// Test to make sure RigidModels are saved back to the same bytes, keeping as raw data the parts we cannot decode, and only export the decoded meshes.
+#[test]
+fn test_rigid_model_roundtrip() {
+    let vertex = |position: [f32; 4], uv: [f32; 2]| {
+        let mut vertex = Vertex::default();
+        vertex.set_position(position);
+        vertex.set_uv(uv);
+        vertex.set_normal([127, 255, 127, 0]);
+        vertex.set_bone_indices([1, 2, 0, 0]);
+        vertex.set_bone_weights([192, 63, 0, 0]);
+        vertex
+    };
+
+    // Texture paths may have garbage after their end, which must be kept.
+    let mut texture = Texture::default();
+    texture.set_path("variantmeshes/wh_variantmodels/hu1/emp/tex/body_base_colour.dds\0garbage".to_owned());
+
+    let mut material = Material::default();
+    material.set_vertex_format(VertexFormat::Weighted);
+    material.set_name("body".to_owned());
+    let mut attachment_point = AttachmentPoint::default();
+    attachment_point.set_name("weapon_1".to_owned());
+    attachment_point.set_bone_id(3);
+
+    material.set_attachment_points(vec![attachment_point]);
+    material.set_textures(vec![texture, Texture::default()]);
+    material.set_string_params(vec![(0, "param".to_owned())]);
+
+    let mut mesh = Mesh::default();
+    mesh.set_shader_name("default_dry".to_owned());
+    mesh.set_material(Some(material));
+    mesh.set_vertices(Vertices::Decoded(vec![vertex([0.0, 0.0, 0.0, 1.0], [0.0, 0.0]), vertex([1.0, 0.0, 0.0, 1.0], [1.0, 0.0]), vertex([0.0, 1.5, -2.0, 1.0], [0.0, 0.25])]));
+    mesh.set_indices(vec![0, 1, 2]);
+
+    // Meshes we cannot decode must be kept raw.
+    let mut raw_mesh = Mesh::default();
+    raw_mesh.set_material_data(vec![1; 10]);
+    raw_mesh.set_vertices(Vertices::Raw(2, vec![7; 24]));
+    raw_mesh.set_indices(vec![0, 1, 1]);
+
+    let mut lod = Lod::default();
+    lod.set_visibility_distance(100.0);
+    lod.set_meshes(vec![mesh, raw_mesh]);
+
+    let mut rigid_model = RigidModel::default();
+    rigid_model.set_version(7);
+    rigid_model.set_skeleton_id("humanoid01".to_owned());
+    rigid_model.set_lods(vec![lod, Lod::default()]);
+
+    let data = rigid_model.save().unwrap();
+    let decoded = RigidModel::read(&data).unwrap();
+    assert_eq!(decoded, rigid_model);
+    assert_eq!(decoded.save().unwrap(), data);
+    assert_eq!(decoded.get_texture_paths(), vec![(0, 0, "variantmeshes/wh_variantmodels/hu1/emp/tex/body_base_colour.dds")]);
+    assert_eq!(RigidModel::read_texture_paths(&data).unwrap(), vec![(0, 0, "variantmeshes/wh_variantmodels/hu1/emp/tex/body_base_colour.dds".to_owned())]);
+    assert!(RigidModel::read_or_keep_raw(&data).is_decoded());
+
+    // Models with versions we cannot decode must be kept raw, and saved back unchanged.
+    let mut unsupported_data = data.to_vec();
+    unsupported_data[4] = 9;
+    let unsupported = RigidModel::read_or_keep_raw(&unsupported_data);
+    assert!(RigidModel::read(&unsupported_data).is_err());
+    assert!(!unsupported.is_decoded());
+    assert!(unsupported.get_texture_paths().is_empty());
+    assert_eq!(unsupported.save().unwrap(), unsupported_data);
+
+    // Only the decoded mesh can be exported.
+    let obj = decoded.to_obj(0).unwrap();
+    assert!(obj.contains("o body\n"));
+    assert!(obj.contains("v 0 1.5 -2\n"));
+    assert!(obj.contains("f 1/1/1 2/2/2 3/3/3\n"));
+    assert!(decoded.to_obj(2).is_err());
+
+    let (gltf, buffer) = decoded.to_gltf(0, "model.bin").unwrap();
+    let gltf: serde_json::Value = serde_json::from_str(&gltf).unwrap();
+    assert_eq!(gltf["meshes"].as_array().unwrap().len(), 1);
+    assert_eq!(gltf["buffers"][0]["byteLength"], buffer.len());
+}
@@ -283,6 +283,69 @@ impl DB {
         })
     }
 
+    /// This function returns the values of the bitwise fields of a raw `DB` that have bits set outside their declared flags.
+    ///
+    /// These bits are lost when decoding the table, so we need to decode the raw data again, without splitting the bitwise fields.
+    /// Each entry is the row, the index of the field in the definition, and the undeclared bits.
+    pub fn get_undeclared_bitwise_bits(packed_file_data: &[u8], definition: &Definition) -> Result<Vec<(usize, usize, i64)>> {
+        let mut raw_definition = definition.clone();
+        raw_definition.get_ref_mut_fields().iter_mut().for_each(|field| field.set_is_bitwise(0));
+
+        // Colour fields are merged when decoding, so we need to find the decoded column of each bitwise field by its name.
+        // Fields with all their bits declared cannot have undeclared bits, so we skip them.
+        let raw_fields_processed = raw_definition.get_fields_processed();
+        let bitwise_fields = definition.get_ref_fields().iter()
+            .enumerate()
+            .filter(|(_, field)| field.get_is_bitwise() > 1)
+            .filter_map(|(index, field)| {
+                let bit_width = match field.get_ref_field_type() {
+                    FieldType::I16 => 16,
+                    FieldType::I32 => 32,
+                    FieldType::I64 => 64,
+                    _ => return None,
+                };
+
+                if field.get_is_bitwise() >= bit_width {
+                    return None;
+                }
+
+                raw_fields_processed.iter()
+                    .position(|raw_field| raw_field.get_name() == field.get_name())
+                    .map(|column| (index, column, u64::MAX << field.get_is_bitwise()))
+            })
+            .collect::<Vec<(usize, usize, u64)>>();
+
+        if bitwise_fields.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (_, _, _, entry_count, mut index) = Self::read_header(packed_file_data)?;
+
+        let mut table = Table::new(&raw_definition);
+        table.decode(packed_file_data, entry_count, &mut index, false)?;
+
+        let mut undeclared_bits = vec![];
+        for (row, cells) in table.get_ref_table_data().iter().enumerate() {
+            for (field_index, column, undeclared_mask) in &bitwise_fields {
+
+                // Cast through the unsigned type of the same width, so negative values don't set bits beyond the width of the field.
+                let value = match cells.get(*column) {
+                    Some(DecodedData::I16(value)) => *value as u16 as u64,
+                    Some(DecodedData::I32(value)) => *value as u32 as u64,
+                    Some(DecodedData::I64(value)) => *value as u64,
+                    _ => continue,
+                };
+
+                let bits = value & undeclared_mask;
+                if bits != 0 {
+                    undeclared_bits.push((row, *field_index, bits as i64));
+                }
+            }
+        }
+
+        Ok(undeclared_bits)
+    }
+
     /// This function takes a `DB` and encodes it to `Vec<u8>`.
     pub fn save(&self) -> Result<Vec<u8>> {
         let mut packed_file: Vec<u8> = vec![];
@@ -0,0 +1,468 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to interact with GroupFormations.
+
+GroupFormations files contain the formations the AI uses to deploy its units in battle.
+They have no version, just a formation count followed by the formations. Their lines are variant records,
+where the fields after the line type depend on said type, so they cannot be decoded with a schema definition.
+Instead, we decode them with a dedicated decoder into a table with a fixed definition, where the fields
+a line type doesn't use are left with their default value and ignored when encoding.
+
+The layout of each formation is:
+- `name`: StringU16.
+- `ai_priority`: F32.
+- `ai_purpose`: U32, with the purposes as flags.
+- `min_unit_category_percentages`: U32 count, then a U32 unit category and a U32 percentage per entry.
+- `ai_supported_subcultures`: U32 count, then a StringU16 per entry.
+- `ai_supported_factions`: U32 count, then a StringU16 per entry.
+- `lines`: U32 count, then the lines.
+
+And the layout of each line is:
+- `line_id`: U32.
+- `line_type`: U32. `0` for absolute lines, `1` for lines relative to another line, and `3` for lines spanning other lines.
+- `priority`: F32.
+- For relative lines, `relative_to`: U32, with the id of the line it's relative to.
+- For absolute and relative lines, `shape` (U32), `spacing` (F32), `crescent_y_offset` (F32), `x` (F32), `y` (F32),
+  `min_threshold` (U32), `max_threshold` (U32) and `entity_preferences` (U32 count, then a F32 priority and a U32 entity per entry).
+- For spanning lines, `spanned_lines`: U32 count, then the U32 id of each line spanned.
+
+!*/
+
+use serde_json::to_string_pretty;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::common::{decoder::Decoder, encoder::Encoder};
+use super::DecodedData;
+use super::Table;
+
+use crate::schema::*;
+
+/// Path of the GroupFormations file. There is only one per game.
+pub const PATH: &str = "groupformations.bin";
+
+/// This is the name used in TSV-exported GroupFormations, to identify it as a GroupFormations PackedFile.
+pub const TSV_NAME_GROUP_FORMATIONS: &str = "GroupFormations PackedFile";
+
+/// Version of the definition used to decode GroupFormations. These files are not versioned, so this is the only one.
+pub const VERSION: i32 = 0;
+
+/// Line type of lines placed at an absolute position.
+pub const LINE_TYPE_ABSOLUTE: i32 = 0;
+
+/// Line type of lines placed relative to another line.
+pub const LINE_TYPE_RELATIVE: i32 = 1;
+
+/// Line type of lines spanning other lines.
+pub const LINE_TYPE_SPANNING: i32 = 3;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This stores the data of a decoded GroupFormations PackedFile in memory.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct GroupFormations {
+
+    /// The table's data, containing all the stuff needed to decode/encode it.
+    table: Table,
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of GroupFormations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `GroupFormations`.
+impl GroupFormations {
+
+    /// This function creates a new empty `GroupFormations`.
+    pub fn new() -> Self {
+        Self {
+            table: Table::new(&Self::get_definition_for_version(VERSION).unwrap()),
+        }
+    }
+
+    /// This function returns the definition used to decode GroupFormations with the provided version.
+    ///
+    /// As these files are not versioned, this fails for any version except `VERSION`.
+    pub fn get_definition_for_version(version: i32) -> Result<Definition> {
+        if version != VERSION {
+            return Err(ErrorKind::SchemaDefinitionNotFound.into());
+        }
+
+        let mut definition_percentage = Definition::new(-1);
+        definition_percentage.get_ref_mut_fields().push(Self::new_field("unit_category", FieldType::I32, 0));
+        definition_percentage.get_ref_mut_fields().push(Self::new_field("percentage", FieldType::I32, 1));
+
+        let mut definition_subculture = Definition::new(-1);
+        definition_subculture.get_ref_mut_fields().push(Self::new_field("subculture", FieldType::StringU16, 0));
+
+        let mut definition_faction = Definition::new(-1);
+        definition_faction.get_ref_mut_fields().push(Self::new_field("faction", FieldType::StringU16, 0));
+
+        let mut definition_entity_preference = Definition::new(-1);
+        definition_entity_preference.get_ref_mut_fields().push(Self::new_field("priority", FieldType::F32, 0));
+        definition_entity_preference.get_ref_mut_fields().push(Self::new_field("entity", FieldType::I32, 1));
+
+        let mut definition_spanned_line = Definition::new(-1);
+        definition_spanned_line.get_ref_mut_fields().push(Self::new_field("line_id", FieldType::I32, 0));
+
+        let mut definition_line = Definition::new(-1);
+        definition_line.get_ref_mut_fields().push(Self::new_field("line_id", FieldType::I32, 0));
+        definition_line.get_ref_mut_fields().push(Self::new_field("line_type", FieldType::I32, 1));
+        definition_line.get_ref_mut_fields().push(Self::new_field("priority", FieldType::F32, 2));
+        definition_line.get_ref_mut_fields().push(Self::new_field("relative_to", FieldType::I32, 3));
+        definition_line.get_ref_mut_fields().push(Self::new_field("shape", FieldType::I32, 4));
+        definition_line.get_ref_mut_fields().push(Self::new_field("spacing", FieldType::F32, 5));
+        definition_line.get_ref_mut_fields().push(Self::new_field("crescent_y_offset", FieldType::F32, 6));
+        definition_line.get_ref_mut_fields().push(Self::new_field("x", FieldType::F32, 7));
+        definition_line.get_ref_mut_fields().push(Self::new_field("y", FieldType::F32, 8));
+        definition_line.get_ref_mut_fields().push(Self::new_field("min_threshold", FieldType::I32, 9));
+        definition_line.get_ref_mut_fields().push(Self::new_field("max_threshold", FieldType::I32, 10));
+        definition_line.get_ref_mut_fields().push(Self::new_field("entity_preferences", FieldType::SequenceU32(Box::new(definition_entity_preference)), 11));
+        definition_line.get_ref_mut_fields().push(Self::new_field("spanned_lines", FieldType::SequenceU32(Box::new(definition_spanned_line)), 12));
+
+        let mut definition = Definition::new(VERSION);
+        definition.get_ref_mut_fields().push(Self::new_field("name", FieldType::StringU16, 0));
+        definition.get_ref_mut_fields().push(Self::new_field("ai_priority", FieldType::F32, 1));
+        definition.get_ref_mut_fields().push(Self::new_field("ai_purpose", FieldType::I32, 2));
+        definition.get_ref_mut_fields().push(Self::new_field("min_unit_category_percentages", FieldType::SequenceU32(Box::new(definition_percentage)), 3));
+        definition.get_ref_mut_fields().push(Self::new_field("ai_supported_subcultures", FieldType::SequenceU32(Box::new(definition_subculture)), 4));
+        definition.get_ref_mut_fields().push(Self::new_field("ai_supported_factions", FieldType::SequenceU32(Box::new(definition_faction)), 5));
+        definition.get_ref_mut_fields().push(Self::new_field("lines", FieldType::SequenceU32(Box::new(definition_line)), 6));
+        Ok(definition)
+    }
+
+    /// This function returns a copy of the definition of this GroupFormations.
+    pub fn get_definition(&self) -> Definition {
+        self.table.get_definition()
+    }
+
+    /// This function returns a reference to the definition of this GroupFormations Table.
+    pub fn get_ref_definition(&self) -> &Definition {
+        self.table.get_ref_definition()
+    }
+
+    /// This function returns a copy of the entries of this GroupFormations Table.
+    pub fn get_table_data(&self) -> Vec<Vec<DecodedData>> {
+        self.table.get_table_data()
+    }
+
+    /// This function returns a reference to the entries of this GroupFormations Table.
+    pub fn get_ref_table_data(&self) -> &[Vec<DecodedData>] {
+        self.table.get_ref_table_data()
+    }
+
+    /// This function returns the amount of entries in this GroupFormations Table.
+    pub fn get_entry_count(&self) -> usize {
+        self.table.get_entry_count()
+    }
+
+    /// This function replaces the data of this table with the one provided.
+    ///
+    /// This can (and will) fail if the data is not of the format defined by the definition of the table.
+    pub fn set_table_data(&mut self, data: &[Vec<DecodedData>]) -> Result<()> {
+        self.table.set_table_data(data)
+    }
+
+    /// This function creates a new `GroupFormations` from a `Vec<u8>`.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
+        let definition = Self::get_definition_for_version(VERSION)?;
+        let mut index = 0;
+
+        let entry_count = packed_file_data.decode_packedfile_integer_u32(index, &mut index)?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            entries.push(Self::decode_formation(packed_file_data, &definition, &mut index)?);
+        }
+
+        // If we are not in the last byte, it means we didn't parse the entire file, which means this file is corrupt.
+        if index != packed_file_data.len() { return Err(ErrorKind::PackedFileSizeIsNotWhatWeExpect(packed_file_data.len(), index).into()) }
+
+        let mut table = Table::new(&definition);
+        table.set_table_data(&entries)?;
+        Ok(Self {
+            table,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        to_string_pretty(&self).unwrap()
+    }
+
+    /// This function imports a TSV file into a decoded GroupFormations.
+    pub fn import_tsv(
+        schema: &Schema,
+        path: &Path,
+    ) -> Result<(Self, Option<Vec<String>>)> {
+        let (table, file_path) = Table::import_tsv(schema, path)?;
+        let group_formations = GroupFormations::from(table);
+        Ok((group_formations, file_path))
+    }
+
+    /// This function exports the provided data to a TSV file.
+    pub fn export_tsv(
+        &self,
+        path: &Path,
+        file_path: &[String],
+    ) -> Result<()> {
+        self.table.export_tsv(path, TSV_NAME_GROUP_FORMATIONS, file_path)
+    }
+
+    /// This function takes a `GroupFormations` and encodes it to `Vec<u8>`.
+    pub fn save(&self) -> Result<Vec<u8>> {
+
+        // Create the vector to hold them all.
+        let mut packed_file: Vec<u8> = vec![];
+        packed_file.encode_integer_u32(self.table.entries.len() as u32);
+        for formation in &self.table.entries {
+            Self::encode_formation(&mut packed_file, formation)?;
+        }
+
+        // Return the encoded `PackedFile`.
+        Ok(packed_file)
+    }
+
+    /// This function creates a field for the GroupFormations definition.
+    fn new_field(name: &str, field_type: FieldType, ca_order: i16) -> Field {
+        Field::new(name.to_owned(), field_type, false, None, false, None, None, None, String::new(), ca_order, 0, BTreeMap::new(), None)
+    }
+
+    /// This function returns the definition of the provided sequence field of a definition.
+    fn get_sequence_definition(definition: &Definition, column: usize) -> Result<&Definition> {
+        match definition.get_ref_fields().get(column).map(|field| field.get_ref_field_type()) {
+            Some(FieldType::SequenceU32(definition)) => Ok(definition),
+            _ => Err(ErrorKind::GroupFormationsDecode(format!("Column {} is not a sequence.", column)).into()),
+        }
+    }
+
+    /// This function creates a sequence cell with the provided definition and rows.
+    fn new_sequence(definition: &Definition, rows: &[Vec<DecodedData>]) -> Result<DecodedData> {
+        let mut table = Table::new(definition);
+        table.set_table_data(rows)?;
+        Ok(DecodedData::SequenceU32(Box::new(table)))
+    }
+
+    /// This function decodes a sequence of single-column rows, using the provided function to decode each value.
+    fn decode_sequence<F>(packed_file_data: &[u8], definition: &Definition, index: &mut usize, decode_row: F) -> Result<DecodedData>
+        where F: Fn(&[u8], &mut usize) -> Result<Vec<DecodedData>> {
+        let entry_count = packed_file_data.decode_packedfile_integer_u32(*index, index)?;
+        let mut rows = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            rows.push(decode_row(packed_file_data, index)?);
+        }
+
+        Self::new_sequence(definition, &rows)
+    }
+
+    /// This function decodes a formation, returning it as a row of the GroupFormations table.
+    fn decode_formation(packed_file_data: &[u8], definition: &Definition, index: &mut usize) -> Result<Vec<DecodedData>> {
+        let mut row = Vec::with_capacity(7);
+        row.push(DecodedData::StringU16(packed_file_data.decode_packedfile_string_u16(*index, index)?));
+        row.push(DecodedData::F32(packed_file_data.decode_packedfile_float_f32(*index, index)?));
+        row.push(DecodedData::I32(packed_file_data.decode_packedfile_integer_i32(*index, index)?));
+
+        row.push(Self::decode_sequence(packed_file_data, Self::get_sequence_definition(definition, 3)?, index, |data, index| Ok(vec![
+            DecodedData::I32(data.decode_packedfile_integer_i32(*index, index)?),
+            DecodedData::I32(data.decode_packedfile_integer_i32(*index, index)?),
+        ]))?);
+
+        for column in 4..=5 {
+            row.push(Self::decode_sequence(packed_file_data, Self::get_sequence_definition(definition, column)?, index, |data, index| Ok(vec![
+                DecodedData::StringU16(data.decode_packedfile_string_u16(*index, index)?),
+            ]))?);
+        }
+
+        let definition_line = Self::get_sequence_definition(definition, 6)?;
+        row.push(Self::decode_sequence(packed_file_data, definition_line, index, |data, index| Self::decode_line(data, definition_line, index))?);
+        Ok(row)
+    }
+
+    /// This function decodes a line of a formation, returning it as a row of the lines sequence.
+    fn decode_line(packed_file_data: &[u8], definition: &Definition, index: &mut usize) -> Result<Vec<DecodedData>> {
+        let line_id = packed_file_data.decode_packedfile_integer_i32(*index, index)?;
+        let line_type = packed_file_data.decode_packedfile_integer_i32(*index, index)?;
+        let priority = packed_file_data.decode_packedfile_float_f32(*index, index)?;
+
+        let definition_entity_preference = Self::get_sequence_definition(definition, 11)?;
+        let definition_spanned_line = Self::get_sequence_definition(definition, 12)?;
+
+        let mut row = vec![DecodedData::I32(line_id), DecodedData::I32(line_type), DecodedData::F32(priority)];
+        match line_type {
+            LINE_TYPE_ABSOLUTE | LINE_TYPE_RELATIVE => {
+                let relative_to = if line_type == LINE_TYPE_RELATIVE { packed_file_data.decode_packedfile_integer_i32(*index, index)? } else { 0 };
+                row.push(DecodedData::I32(relative_to));
+                row.push(DecodedData::I32(packed_file_data.decode_packedfile_integer_i32(*index, index)?));
+                for _ in 0..4 {
+                    row.push(DecodedData::F32(packed_file_data.decode_packedfile_float_f32(*index, index)?));
+                }
+                for _ in 0..2 {
+                    row.push(DecodedData::I32(packed_file_data.decode_packedfile_integer_i32(*index, index)?));
+                }
+
+                row.push(Self::decode_sequence(packed_file_data, definition_entity_preference, index, |data, index| Ok(vec![
+                    DecodedData::F32(data.decode_packedfile_float_f32(*index, index)?),
+                    DecodedData::I32(data.decode_packedfile_integer_i32(*index, index)?),
+                ]))?);
+                row.push(Self::new_sequence(definition_spanned_line, &[])?);
+            }
+
+            LINE_TYPE_SPANNING => {
+                row.extend_from_slice(&[DecodedData::I32(0), DecodedData::I32(0)]);
+                row.extend_from_slice(&[DecodedData::F32(0.0), DecodedData::F32(0.0), DecodedData::F32(0.0), DecodedData::F32(0.0)]);
+                row.extend_from_slice(&[DecodedData::I32(0), DecodedData::I32(0)]);
+                row.push(Self::new_sequence(definition_entity_preference, &[])?);
+                row.push(Self::decode_sequence(packed_file_data, definition_spanned_line, index, |data, index| Ok(vec![
+                    DecodedData::I32(data.decode_packedfile_integer_i32(*index, index)?),
+                ]))?);
+            }
+
+            _ => return Err(ErrorKind::GroupFormationsDecode(format!("Unknown type {} in line {}.", line_type, line_id)).into()),
+        }
+
+        Ok(row)
+    }
+
+    /// This function encodes a formation from a row of the GroupFormations table.
+    fn encode_formation(packed_file: &mut Vec<u8>, row: &[DecodedData]) -> Result<()> {
+        packed_file.encode_packedfile_string_u16(Self::get_string(row, 0)?);
+        packed_file.encode_float_f32(Self::get_f32(row, 1)?);
+        packed_file.encode_integer_i32(Self::get_i32(row, 2)?);
+
+        let percentages = Self::get_sequence(row, 3)?;
+        packed_file.encode_integer_u32(percentages.len() as u32);
+        for percentage in percentages {
+            packed_file.encode_integer_i32(Self::get_i32(percentage, 0)?);
+            packed_file.encode_integer_i32(Self::get_i32(percentage, 1)?);
+        }
+
+        for column in 4..=5 {
+            let values = Self::get_sequence(row, column)?;
+            packed_file.encode_integer_u32(values.len() as u32);
+            for value in values {
+                packed_file.encode_packedfile_string_u16(Self::get_string(value, 0)?);
+            }
+        }
+
+        let lines = Self::get_sequence(row, 6)?;
+        packed_file.encode_integer_u32(lines.len() as u32);
+        for line in lines {
+            Self::encode_line(packed_file, line)?;
+        }
+
+        Ok(())
+    }
+
+    /// This function encodes a line of a formation from a row of the lines sequence, using only the fields its line type uses.
+    fn encode_line(packed_file: &mut Vec<u8>, row: &[DecodedData]) -> Result<()> {
+        let line_id = Self::get_i32(row, 0)?;
+        let line_type = Self::get_i32(row, 1)?;
+        packed_file.encode_integer_i32(line_id);
+        packed_file.encode_integer_i32(line_type);
+        packed_file.encode_float_f32(Self::get_f32(row, 2)?);
+
+        match line_type {
+            LINE_TYPE_ABSOLUTE | LINE_TYPE_RELATIVE => {
+                if line_type == LINE_TYPE_RELATIVE {
+                    packed_file.encode_integer_i32(Self::get_i32(row, 3)?);
+                }
+
+                packed_file.encode_integer_i32(Self::get_i32(row, 4)?);
+                for column in 5..=8 {
+                    packed_file.encode_float_f32(Self::get_f32(row, column)?);
+                }
+                for column in 9..=10 {
+                    packed_file.encode_integer_i32(Self::get_i32(row, column)?);
+                }
+
+                let entity_preferences = Self::get_sequence(row, 11)?;
+                packed_file.encode_integer_u32(entity_preferences.len() as u32);
+                for entity_preference in entity_preferences {
+                    packed_file.encode_float_f32(Self::get_f32(entity_preference, 0)?);
+                    packed_file.encode_integer_i32(Self::get_i32(entity_preference, 1)?);
+                }
+            }
+
+            LINE_TYPE_SPANNING => {
+                let spanned_lines = Self::get_sequence(row, 12)?;
+                packed_file.encode_integer_u32(spanned_lines.len() as u32);
+                for spanned_line in spanned_lines {
+                    packed_file.encode_integer_i32(Self::get_i32(spanned_line, 0)?);
+                }
+            }
+
+            _ => return Err(ErrorKind::GroupFormationsDecode(format!("Unknown type {} in line {}.", line_type, line_id)).into()),
+        }
+
+        Ok(())
+    }
+
+    /// This function returns the error for a cell of the wrong type, or a missing one.
+    fn get_cell_error(row: &[DecodedData], column: usize, field_type: &str) -> Error {
+        match row.get(column) {
+            Some(data) => ErrorKind::TableWrongFieldType(format!("{}", data), field_type.to_owned()).into(),
+            None => ErrorKind::TableRowWrongFieldCount(column as u32 + 1, row.len() as u32).into(),
+        }
+    }
+
+    /// This function returns the value of an I32 cell.
+    fn get_i32(row: &[DecodedData], column: usize) -> Result<i32> {
+        match row.get(column) {
+            Some(DecodedData::I32(data)) => Ok(*data),
+            _ => Err(Self::get_cell_error(row, column, "I32")),
+        }
+    }
+
+    /// This function returns the value of a F32 cell.
+    fn get_f32(row: &[DecodedData], column: usize) -> Result<f32> {
+        match row.get(column) {
+            Some(DecodedData::F32(data)) => Ok(*data),
+            _ => Err(Self::get_cell_error(row, column, "F32")),
+        }
+    }
+
+    /// This function returns the value of a StringU16 cell.
+    fn get_string(row: &[DecodedData], column: usize) -> Result<&str> {
+        match row.get(column) {
+            Some(DecodedData::StringU16(data)) => Ok(data),
+            _ => Err(Self::get_cell_error(row, column, "StringU16")),
+        }
+    }
+
+    /// This function returns the rows of a SequenceU32 cell.
+    fn get_sequence(row: &[DecodedData], column: usize) -> Result<&[Vec<DecodedData>]> {
+        match row.get(column) {
+            Some(DecodedData::SequenceU32(data)) => Ok(data.get_ref_table_data()),
+            _ => Err(Self::get_cell_error(row, column, "SequenceU32")),
+        }
+    }
+}
+
+/// Implementation of `Default` for `GroupFormations`.
+impl Default for GroupFormations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implementation to create a `GroupFormations` from a `Table`.
+impl From<Table> for GroupFormations {
+    fn from(table: Table) -> Self {
+        Self {
+            table,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `GroupFormations` module.
+!*/
+
+use std::path::PathBuf;
+
+use crate::packedfile::PackedFileType;
+use crate::packfile::packedfile::RawPackedFile;
+
+use super::*;
+use super::db::DB;
+use super::group_formations::*;
+
+/// This function returns the bytes of a GroupFormations file with a formation using each line type, and an empty formation.
+fn get_test_data() -> Vec<u8> {
+    let mut data = vec![];
+    let string = |data: &mut Vec<u8>, string: &str| {
+        data.extend_from_slice(&(string.len() as u16).to_le_bytes());
+        string.encode_utf16().for_each(|character| data.extend_from_slice(&character.to_le_bytes()));
+    };
+
+    data.extend_from_slice(&2u32.to_le_bytes());
+
+    // Formation with an absolute line, a line relative to it, and a line spanning both.
+    string(&mut data, "wedge");
+    data.extend_from_slice(&1.5f32.to_le_bytes());
+    data.extend_from_slice(&0x11u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&30u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    string(&mut data, "wh_main_sc_emp_empire");
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&1.0f32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&2.0f32.to_le_bytes());
+    data.extend_from_slice(&0.5f32.to_le_bytes());
+    data.extend_from_slice(&0.0f32.to_le_bytes());
+    data.extend_from_slice(&(-10.0f32).to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&4u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&0.8f32.to_le_bytes());
+    data.extend_from_slice(&7u32.to_le_bytes());
+
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&0.5f32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&3.0f32.to_le_bytes());
+    data.extend_from_slice(&0.0f32.to_le_bytes());
+    data.extend_from_slice(&5.0f32.to_le_bytes());
+    data.extend_from_slice(&(-4.0f32).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&u32::MAX.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&0.25f32.to_le_bytes());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+
+    // Formation without lines or restrictions.
+    string(&mut data, "empty");
+    data.extend_from_slice(&0.0f32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&[0; 16]);
+    data
+}
+
+/// Test to make sure GroupFormations lines are decoded depending on their type, and saved back to the same bytes.
+#[test]
+fn test_group_formations_read_save() {
+    let data = get_test_data();
+    let group_formations = GroupFormations::read(&data).unwrap();
+    assert_eq!(group_formations.get_entry_count(), 2);
+    assert_eq!(group_formations.save().unwrap(), data);
+
+    let formation = &group_formations.get_ref_table_data()[0];
+    assert_eq!(formation[0], DecodedData::StringU16("wedge".to_owned()));
+    assert_eq!(formation[2], DecodedData::I32(0x11));
+    let lines = match &formation[6] {
+        DecodedData::SequenceU32(lines) => lines.get_table_data(),
+        _ => panic!("Lines are not a sequence."),
+    };
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0][1], DecodedData::I32(LINE_TYPE_ABSOLUTE));
+    assert_eq!(lines[0][7], DecodedData::F32(0.0));
+    assert_eq!(lines[0][8], DecodedData::F32(-10.0));
+    assert_eq!(lines[1][1], DecodedData::I32(LINE_TYPE_RELATIVE));
+    assert_eq!(lines[1][3], DecodedData::I32(0));
+    assert_eq!(lines[1][10], DecodedData::I32(-1));
+    assert_eq!(lines[2][1], DecodedData::I32(LINE_TYPE_SPANNING));
+    match &lines[2][12] {
+        DecodedData::SequenceU32(spanned_lines) => assert_eq!(spanned_lines.get_table_data(), vec![vec![DecodedData::I32(0)], vec![DecodedData::I32(1)]]),
+        _ => panic!("Spanned lines are not a sequence."),
+    }
+
+    // Leftover or missing bytes, and unknown line types, mean the file is not what we expect.
+    let mut data_extra = data.to_vec();
+    data_extra.push(0);
+    assert!(GroupFormations::read(&data_extra).is_err());
+    assert!(GroupFormations::read(&data[..data.len() - 2]).is_err());
+
+    let line_type_offset = data.windows(8).position(|x| x == [2, 0, 0, 0, 3, 0, 0, 0]).unwrap() + 4;
+    let mut data_unknown_type = data.to_vec();
+    data_unknown_type[line_type_offset] = 2;
+    assert!(GroupFormations::read(&data_unknown_type).is_err());
+
+    // The file is detected by its path.
+    let raw_packed_file = RawPackedFile::read_from_vec(vec![PATH.to_owned()], String::new(), 0, false, data.to_vec());
+    assert_eq!(PackedFileType::get_packed_file_type(&raw_packed_file, false), PackedFileType::GroupFormations);
+}
+
+/// Test to make sure GroupFormations survive being exported to TSV and imported back, including the binary file variants.
+#[test]
+fn test_group_formations_tsv() {
+    let data = get_test_data();
+    let group_formations = GroupFormations::read(&data).unwrap();
+    let schema = Schema::default();
+
+    let path = PathBuf::from("../test_files/group_formations_test.tsv");
+    group_formations.export_tsv(&path, &[PATH.to_owned()]).unwrap();
+    let (imported, file_path) = GroupFormations::import_tsv(&schema, &path).unwrap();
+    assert_eq!(file_path, Some(vec![PATH.to_owned()]));
+    assert_eq!(imported.save().unwrap(), data);
+
+    // The binary file variants must keep the file name.
+    let binary_path = PathBuf::from("../test_files/group_formations_test").join(PATH);
+    std::fs::create_dir_all(binary_path.parent().unwrap()).unwrap();
+    std::fs::write(&binary_path, &data).unwrap();
+    DB::export_tsv_from_binary_file(&schema, std::slice::from_ref(&binary_path)).unwrap();
+    std::fs::remove_file(&binary_path).unwrap();
+    DB::import_tsv_to_binary_file(&schema, &[binary_path.with_extension("tsv")]).unwrap();
+    assert_eq!(std::fs::read(&binary_path).unwrap(), data);
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_dir_all(binary_path.parent().unwrap()).unwrap();
+}
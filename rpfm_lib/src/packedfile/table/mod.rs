@@ -19,7 +19,8 @@ use csv::{QuoteStyle, ReaderBuilder, WriterBuilder};
 use serde_derive::{Serialize, Deserialize};
 
 use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
 use std::{fmt, fmt::Display};
 use std::fs::{DirBuilder, File};
 use std::io::{BufReader, BufWriter, Read, Write};
@@ -34,9 +35,16 @@ use crate::schema::*;
 pub mod animtable;
 pub mod anim_fragment;
 pub mod db;
+pub mod group_formations;
 pub mod loc;
 pub mod matched_combat;
 
+#[cfg(test)]
+mod group_formations_test;
+
+#[cfg(test)]
+pub(crate) mod table_test;
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
@@ -89,6 +97,54 @@ pub struct DependencyData {
     pub data: HashMap<String, String>,
 }
 
+/// This struct represents a conflict found while doing a three-way merge of a table.
+///
+/// A conflict happens when both sides changed the same cell to different values, or when one side deleted a row the other side changed.
+/// The merged table keeps our version of the row, so the conflict can be resolved later by replacing that row.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct TableMergeConflict {
+
+    /// Values of the key columns of the conflicting row.
+    pub key: Vec<String>,
+
+    /// Position of the conflicting row in the merged table. None if the row is not in the merged table because we deleted it.
+    pub merged_row: Option<usize>,
+
+    /// Names of the columns in conflict. Empty if the conflict is about one side deleting the row.
+    pub columns: Vec<String>,
+
+    /// The row in the base table, if it was there.
+    pub base: Option<Vec<DecodedData>>,
+
+    /// The row in our table, if it is there.
+    pub ours: Option<Vec<DecodedData>>,
+
+    /// The row in their table, if it is there.
+    pub theirs: Option<Vec<DecodedData>>,
+}
+
+/// This struct represents the effective contents of a table once every source of it has been loaded, as the game sees it.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct MergedTable {
+
+    /// The effective table. It uses the newest definition found between all its sources.
+    table: Table,
+
+    /// The source of each row of the table, in the same order as the rows.
+    provenance: Vec<RowProvenance>,
+}
+
+/// This struct represents the source of a row in a `MergedTable`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct RowProvenance {
+
+    /// Name of the PackFile the row comes from.
+    pub pack: String,
+
+    /// Path of the PackedFile the row comes from.
+    pub path: Vec<String>,
+}
+
 //----------------------------------------------------------------//
 // Implementations for `DecodedData`.
 //----------------------------------------------------------------//
@@ -504,6 +560,108 @@ impl Table {
         Ok(())
     }
 
+    /// This function does a three-way merge of the provided tables, returning the merged table and the conflicts found.
+    ///
+    /// Rows are matched by the key fields of the newest definition (or by all the fields, if there are no keys), and all tables are
+    /// updated to that definition before merging. Changes done only by one side are applied automatically. Conflicts keep our value.
+    pub fn merge(base: &Self, ours: &Self, theirs: &Self) -> (Self, Vec<TableMergeConflict>) {
+        let definition = [base, ours, theirs].iter().map(|x| x.get_ref_definition()).max_by_key(|x| x.get_version()).unwrap().clone();
+        let [base, ours, theirs] = [base, ours, theirs].map(|table| {
+            let mut table = table.clone();
+            if table.get_ref_definition() != &definition {
+                table.set_definition(&definition);
+            }
+            table
+        });
+
+        let fields = definition.get_fields_processed();
+        let mut key_columns = fields.iter().enumerate().filter(|(_, field)| field.get_is_key()).map(|(index, _)| index).collect::<Vec<usize>>();
+        if key_columns.is_empty() {
+            key_columns = (0..fields.len()).collect();
+        }
+
+        // Rows are identified by their key and by how many times that key appeared before, so duplicated keys are matched in order.
+        let get_rows_by_key = |table: &Self| {
+            let mut rows = vec![];
+            let mut occurrences: HashMap<Vec<String>, usize> = HashMap::new();
+            for row in table.get_ref_table_data() {
+                let key = key_columns.iter().map(|index| row[*index].data_to_string()).collect::<Vec<String>>();
+                let occurrence = occurrences.entry(key.to_vec()).or_default();
+                rows.push(((key, *occurrence), row.to_vec()));
+                *occurrence += 1;
+            }
+            rows
+        };
+
+        let base_rows = get_rows_by_key(&base).into_iter().collect::<HashMap<(Vec<String>, usize), Vec<DecodedData>>>();
+        let ours_rows = get_rows_by_key(&ours);
+        let theirs_rows = get_rows_by_key(&theirs);
+        let ours_keys = ours_rows.iter().map(|(key, _)| key.clone()).collect::<HashSet<(Vec<String>, usize)>>();
+        let theirs_map = theirs_rows.iter().cloned().collect::<HashMap<(Vec<String>, usize), Vec<DecodedData>>>();
+
+        // Our rows go first, in our order. Then, whatever rows only they have, in their order.
+        let mut keys = ours_rows.iter().map(|(key, _)| key.clone()).collect::<Vec<(Vec<String>, usize)>>();
+        keys.extend(theirs_rows.iter().map(|(key, _)| key.clone()).filter(|key| !ours_keys.contains(key)));
+        let ours_map = ours_rows.into_iter().collect::<HashMap<(Vec<String>, usize), Vec<DecodedData>>>();
+
+        let mut entries = vec![];
+        let mut conflicts = vec![];
+        for key in keys {
+            let base_row = base_rows.get(&key);
+            let ours_row = ours_map.get(&key);
+            let theirs_row = theirs_map.get(&key);
+
+            let mut conflict = |merged_row: Option<usize>, columns: Vec<String>| conflicts.push(TableMergeConflict {
+                key: key.0.to_vec(),
+                merged_row,
+                columns,
+                base: base_row.cloned(),
+                ours: ours_row.cloned(),
+                theirs: theirs_row.cloned(),
+            });
+
+            match (ours_row, theirs_row) {
+                (Some(ours_row), Some(theirs_row)) => {
+                    let mut merged_row = ours_row.to_vec();
+                    let mut columns = vec![];
+                    for (index, (ours_cell, theirs_cell)) in ours_row.iter().zip(theirs_row.iter()).enumerate() {
+                        if ours_cell == theirs_cell { continue; }
+                        match base_row.map(|row| &row[index]) {
+                            Some(base_cell) if base_cell == ours_cell => merged_row[index] = theirs_cell.clone(),
+                            Some(base_cell) if base_cell == theirs_cell => {},
+                            _ => columns.push(fields[index].get_name().to_owned()),
+                        }
+                    }
+
+                    if !columns.is_empty() {
+                        conflict(Some(entries.len()), columns);
+                    }
+                    entries.push(merged_row);
+                }
+
+                // If one side deleted the row, we only delete it if the other side didn't change it.
+                (Some(ours_row), None) => match base_row {
+                    Some(base_row) if base_row == ours_row => {},
+                    Some(_) => {
+                        conflict(Some(entries.len()), vec![]);
+                        entries.push(ours_row.to_vec());
+                    }
+                    None => entries.push(ours_row.to_vec()),
+                },
+                (None, Some(theirs_row)) => match base_row {
+                    Some(base_row) if base_row == theirs_row => {},
+                    Some(_) => conflict(None, vec![]),
+                    None => entries.push(theirs_row.to_vec()),
+                },
+                (None, None) => {},
+            }
+        }
+
+        let mut table = Self::new(&definition);
+        table.entries = entries;
+        (table, conflicts)
+    }
+
     /// This function decodes all the fields of a table from raw bytes.
     ///
     /// If return_incomplete == true, this function will return an error with the incompletely decoded table when it fails.
@@ -1140,6 +1298,7 @@ impl Table {
                     file_path = record_data.get(2).map(|x| x.split('/').map(|x| x.to_string()).collect::<Vec<String>>());
 
                     definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_versioned_file_loc()?.get_version(table_version)?.clone() }
+                    else if table_type == group_formations::TSV_NAME_GROUP_FORMATIONS { group_formations::GroupFormations::get_definition_for_version(table_version)? }
                     else { schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?.clone() };
                     fields_processed = definition.get_fields_processed();
                 }
@@ -1171,9 +1330,9 @@ impl Table {
                                     FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(field.to_owned()),
                                     FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(field.to_owned()),
 
-                                    // For now fail on Sequences. These are a bit special and I don't know if the're even possible in TSV.
-                                    FieldType::SequenceU16(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into()),
-                                    FieldType::SequenceU32(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into())
+                                    // Sequences are stored as inline JSON, with one array per row of the sequence.
+                                    FieldType::SequenceU16(definition) => DecodedData::SequenceU16(Box::new(Self::sequence_from_json(definition, field).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
+                                    FieldType::SequenceU32(definition) => DecodedData::SequenceU32(Box::new(Self::sequence_from_json(definition, field).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
                                 }
                             }
                         }
@@ -1246,6 +1405,7 @@ impl Table {
                     let table_version = if let Some(table_version) = record_data.get(1) { table_version.parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))? } else { return Err(ErrorKind::ImportTSVInvalidVersion.into()) };
 
                     definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_versioned_file_loc()?.get_version(table_version)?.clone() }
+                    else if table_type == group_formations::TSV_NAME_GROUP_FORMATIONS { group_formations::GroupFormations::get_definition_for_version(table_version)? }
                     else { schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?.clone() };
                     fields_processed = definition.get_fields_processed();
                 }
@@ -1277,9 +1437,9 @@ impl Table {
                                     FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(field.to_owned()),
                                     FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(field.to_owned()),
 
-                                    // For now fail on Sequences. These are a bit special and I don't know if the're even possible in TSV.
-                                    FieldType::SequenceU16(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into()),
-                                    FieldType::SequenceU32(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into())
+                                    // Sequences are stored as inline JSON, with one array per row of the sequence.
+                                    FieldType::SequenceU16(definition) => DecodedData::SequenceU16(Box::new(Self::sequence_from_json(definition, field).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
+                                    FieldType::SequenceU32(definition) => DecodedData::SequenceU32(Box::new(Self::sequence_from_json(definition, field).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
                                 }
                             }
                         }
@@ -1297,12 +1457,20 @@ impl Table {
             file.set_table_data(&entries)?;
             file.save()
         }
+        else if table_type == group_formations::TSV_NAME_GROUP_FORMATIONS {
+            let mut file = group_formations::GroupFormations::new();
+            file.set_table_data(&entries)?;
+            file.save()
+        }
         else {
             let mut file = db::DB::new(&table_type, None, &definition);
             file.set_table_data(&entries)?;
             file.save()
         }?;
 
+        // GroupFormations need their extension back, or we will not recognize them later.
+        let destination_path = if table_type == group_formations::TSV_NAME_GROUP_FORMATIONS { destination_path.with_extension("bin") } else { destination_path.to_path_buf() };
+
         // Then, we try to write it on disk. If there is an error, report it.
         let mut file = BufWriter::new(File::create(&destination_path)?);
         file.write_all(&data)?;
@@ -1347,7 +1515,12 @@ impl Table {
         for entry in &self.entries {
             let sorted_entry = sorted_indexes.iter()
                 .map(|index| &entry[*index])
-                .map(|data| if let DecodedData::ColourRGB(_) = data { DecodedData::StringU8(data.data_to_string()) } else { data.clone() })
+                .map(|data| match data {
+                    DecodedData::ColourRGB(_) => DecodedData::StringU8(data.data_to_string()),
+                    DecodedData::SequenceU16(sequence) |
+                    DecodedData::SequenceU32(sequence) => DecodedData::StringU8(sequence.sequence_to_json()),
+                    _ => data.clone(),
+                })
                 .collect::<Vec<DecodedData>>();
             writer.serialize(&sorted_entry)?;
         }
@@ -1377,9 +1550,14 @@ impl Table {
         let mut data = vec![];
         file.read_to_end(&mut data)?;
 
+        let is_group_formations = source_path.file_name().and_then(|x| x.to_str()).map(|x| x.to_lowercase() == group_formations::PATH).unwrap_or(false);
         let (table_type, version, entries) = if let Ok(data) = loc::Loc::read(&data, schema, false) {
             (loc::TSV_NAME_LOC, data.get_definition().get_version(), data.get_table_data())
         }
+        else if is_group_formations {
+            if let Ok(data) = group_formations::GroupFormations::read(&data) { (group_formations::TSV_NAME_GROUP_FORMATIONS, data.get_definition().get_version(), data.get_table_data()) }
+            else { return Err(ErrorKind::ImportTSVWrongTypeTable.into()) }
+        }
         else {
             let table_type = source_path.parent().unwrap().file_name().unwrap().to_str().unwrap();
             if let Ok(data) = db::DB::read(&data, table_type, schema, false) { (table_type, data.get_definition().get_version(), data.get_table_data()) }
@@ -1387,6 +1565,7 @@ impl Table {
         };
 
         let definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_versioned_file_loc()?.get_version(version)?.clone() }
+        else if table_type == group_formations::TSV_NAME_GROUP_FORMATIONS { group_formations::GroupFormations::get_definition_for_version(version)? }
         else { schema.get_ref_versioned_file_db(table_type)?.get_version(version)?.clone() };
 
         let fields_sorted = definition.get_fields_sorted();
@@ -1403,7 +1582,12 @@ impl Table {
         for entry in entries {
             let sorted_entry = sorted_indexes.iter()
                 .map(|index| &entry[*index])
-                .map(|data| if let DecodedData::ColourRGB(_) = data { DecodedData::StringU8(data.data_to_string()) } else { data.clone() })
+                .map(|data| match data {
+                    DecodedData::ColourRGB(_) => DecodedData::StringU8(data.data_to_string()),
+                    DecodedData::SequenceU16(sequence) |
+                    DecodedData::SequenceU32(sequence) => DecodedData::StringU8(sequence.sequence_to_json()),
+                    _ => data.clone(),
+                })
                 .collect::<Vec<DecodedData>>();
             writer.serialize(&sorted_entry)?;
         }
@@ -1411,6 +1595,94 @@ impl Table {
         writer.flush().map_err(From::from)
     }
 
+    /// This function encodes the rows of this table as an inline JSON array, so a sequence can be stored in a single TSV cell.
+    ///
+    /// Each row is an array with its values in the same order as the processed fields of the definition. Nested sequences are nested arrays.
+    fn sequence_to_json(&self) -> String {
+        let rows = self.entries.iter().map(|row| {
+            let cells = row.iter().map(|cell| match cell {
+                DecodedData::Boolean(data) => data.to_string(),
+
+                // JSON has no representation for NaN and infinite values, so those are stored as strings.
+                DecodedData::F32(data) => if data.is_finite() { serde_json::to_string(data).unwrap_or_default() } else { serde_json::Value::String(data.to_string()).to_string() },
+                DecodedData::F64(data) => if data.is_finite() { serde_json::to_string(data).unwrap_or_default() } else { serde_json::Value::String(data.to_string()).to_string() },
+                DecodedData::I16(data) => data.to_string(),
+                DecodedData::I32(data) => data.to_string(),
+                DecodedData::I64(data) => data.to_string(),
+                DecodedData::ColourRGB(_) |
+                DecodedData::StringU8(_) |
+                DecodedData::StringU16(_) |
+                DecodedData::OptionalStringU8(_) |
+                DecodedData::OptionalStringU16(_) => serde_json::Value::String(cell.data_to_string()).to_string(),
+                DecodedData::SequenceU16(data) |
+                DecodedData::SequenceU32(data) => data.sequence_to_json(),
+            }).collect::<Vec<String>>();
+
+            format!("[{}]", cells.join(","))
+        }).collect::<Vec<String>>();
+
+        format!("[{}]", rows.join(","))
+    }
+
+    /// This function decodes a sequence encoded with `sequence_to_json` into a new Table with the provided definition.
+    ///
+    /// An empty string is considered an empty sequence.
+    fn sequence_from_json(definition: &Definition, data: &str) -> Result<Self> {
+        if data.is_empty() {
+            return Ok(Self::new(definition));
+        }
+
+        let value: serde_json::Value = serde_json::from_str(data)?;
+        Self::sequence_from_json_value(definition, &value)
+    }
+
+    /// This function decodes an already parsed JSON sequence into a new Table with the provided definition.
+    fn sequence_from_json_value(definition: &Definition, value: &serde_json::Value) -> Result<Self> {
+        let fields = definition.get_fields_processed();
+        let rows = value.as_array().ok_or_else(|| Error::from(ErrorKind::Generic))?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let cells = row.as_array().ok_or_else(|| Error::from(ErrorKind::Generic))?;
+            if cells.len() != fields.len() {
+                return Err(ErrorKind::Generic.into());
+            }
+
+            let entry = fields.iter().zip(cells.iter()).map(|(field, cell)| {
+                let string = || cell.as_str().map(|x| x.to_owned()).ok_or_else(|| Error::from(ErrorKind::Generic));
+                let integer = || cell.as_i64().ok_or_else(|| Error::from(ErrorKind::Generic));
+
+                // Floats are parsed from their text representation, so we don't lose precision going through an f64.
+                // Non-finite floats are stored as strings.
+                let float = || match cell {
+                    serde_json::Value::Number(_) => Ok(cell.to_string()),
+                    serde_json::Value::String(text) if matches!(text.as_str(), "NaN" | "inf" | "-inf") => Ok(text.to_owned()),
+                    _ => Err(Error::from(ErrorKind::Generic)),
+                };
+                Ok(match field.get_ref_field_type() {
+                    FieldType::Boolean => DecodedData::Boolean(cell.as_bool().ok_or_else(|| Error::from(ErrorKind::Generic))?),
+                    FieldType::F32 => DecodedData::F32(float()?.parse::<f32>()?),
+                    FieldType::F64 => DecodedData::F64(float()?.parse::<f64>()?),
+                    FieldType::I16 => DecodedData::I16(i16::try_from(integer()?).map_err(|_| Error::from(ErrorKind::Generic))?),
+                    FieldType::I32 => DecodedData::I32(i32::try_from(integer()?).map_err(|_| Error::from(ErrorKind::Generic))?),
+                    FieldType::I64 => DecodedData::I64(integer()?),
+                    FieldType::ColourRGB => DecodedData::ColourRGB(u32::from_str_radix(&string()?, 16)?),
+                    FieldType::StringU8 => DecodedData::StringU8(string()?),
+                    FieldType::StringU16 => DecodedData::StringU16(string()?),
+                    FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(string()?),
+                    FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(string()?),
+                    FieldType::SequenceU16(definition) => DecodedData::SequenceU16(Box::new(Self::sequence_from_json_value(definition, cell)?)),
+                    FieldType::SequenceU32(definition) => DecodedData::SequenceU32(Box::new(Self::sequence_from_json_value(definition, cell)?)),
+                })
+            }).collect::<Result<Vec<DecodedData>>>()?;
+            entries.push(entry);
+        }
+
+        let mut table = Self::new(definition);
+        table.set_table_data(&entries)?;
+        Ok(table)
+    }
+
     /// This function escapes certain characters of the provided string.
     fn escape_special_chars(data: &str)-> String {
          let mut output = Vec::with_capacity(data.len() + 10);
@@ -1430,6 +1702,78 @@ impl Table {
     }
 }
 
+//----------------------------------------------------------------//
+// Implementations for `MergedTable`.
+//----------------------------------------------------------------//
+
+/// Implementation of `MergedTable`.
+impl MergedTable {
+
+    /// This function builds a `MergedTable` from the provided sources, which must not contain files overridden by other files with the same path.
+    ///
+    /// Rows are identified by their key fields. The sources are read in the order the game reads them, and if a row has the same key
+    /// as one read before it, it's ignored, as the game only keeps the first one. Rows of tables without key fields cannot override others,
+    /// so all of them are kept.
+    pub fn new(sources: &[(RowProvenance, &Table)]) -> Option<Self> {
+        let definition = sources.iter().map(|(_, table)| table.get_ref_definition()).max_by_key(|x| x.get_version())?.clone();
+        let key_columns = definition.get_fields_processed().iter()
+            .enumerate()
+            .filter_map(|(index, field)| if field.get_is_key() { Some(index) } else { None })
+            .collect::<Vec<usize>>();
+
+        let mut sources = sources.iter().collect::<Vec<&(RowProvenance, &Table)>>();
+        sources.sort_by(|(x, _), (y, _)| Self::cmp_by_load_order(&x.path, &y.path));
+
+        let mut entries: Vec<Vec<DecodedData>> = vec![];
+        let mut provenance = vec![];
+        let mut keys: HashSet<Vec<String>> = HashSet::new();
+        for (source, table) in sources {
+            let mut table = (*table).clone();
+            if table.get_ref_definition() != &definition {
+                table.set_definition(&definition);
+            }
+
+            for row in table.entries {
+                if !key_columns.is_empty() {
+                    let key = key_columns.iter().map(|index| row[*index].data_to_string()).collect::<Vec<String>>();
+                    if !keys.insert(key) {
+                        continue;
+                    }
+                }
+
+                entries.push(row);
+                provenance.push(source.clone());
+            }
+        }
+
+        Some(Self {
+            table: Table {
+                definition,
+                entries,
+            },
+            provenance,
+        })
+    }
+
+    /// This function returns a reference to the effective table.
+    pub fn get_ref_table(&self) -> &Table {
+        &self.table
+    }
+
+    /// This function returns a reference to the source of each row of the table.
+    pub fn get_ref_provenance(&self) -> &[RowProvenance] {
+        &self.provenance
+    }
+
+    /// This function compares the paths of two table files by the order the game reads their rows.
+    ///
+    /// The game reads the files of a table in path order (meaning file name order, as they're all in the same folder),
+    /// and keeps the first row it finds for each key. So rows in files that sort first override the ones in files that sort later.
+    pub fn cmp_by_load_order(path: &[String], other: &[String]) -> Ordering {
+        path.cmp(other)
+    }
+}
+
 /// Implementation of `From<&RawTable>` for `Table`.
 impl From<&RawTable> for Table {
     fn from(raw_table: &RawTable) -> Self {
@@ -0,0 +1,166 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Table` module.
+!*/
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::*;
+use super::db::DB;
+
+/// Field with the provided name, type, key status and order, and the default values for everything else.
+pub(crate) fn get_test_field(name: &str, field_type: FieldType, is_key: bool, ca_order: i16) -> Field {
+    Field::new(name.to_owned(), field_type, is_key, None, false, None, None, None, String::new(), ca_order, 0, BTreeMap::new(), None)
+}
+
+/// Definition with a key column and a value column, for the table tests.
+pub(crate) fn get_test_definition() -> Definition {
+    let mut definition = Definition::new(1);
+    definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    definition.get_ref_mut_fields().push(get_test_field("value", FieldType::I32, false, 1));
+    definition
+}
+
+/// Row for a table with the test definition.
+pub(crate) fn get_test_row(key: &str, value: i32) -> Vec<DecodedData> {
+    vec![DecodedData::StringU8(key.to_owned()), DecodedData::I32(value)]
+}
+
+/// Table with the test definition and the provided rows.
+pub(crate) fn get_test_table(rows: &[Vec<DecodedData>]) -> Table {
+    let mut table = Table::new(&get_test_definition());
+    table.set_table_data(rows).unwrap();
+    table
+}
+
+/// Test to make sure the three-way merge of tables matches the rows by key, and reports the rows changed on both sides as conflicts.
+#[test]
+fn test_merge_table_three_way() {
+    let row = get_test_row;
+    let table = get_test_table;
+
+    let base = table(&[row("a", 1), row("b", 1), row("c", 1), row("d", 1), row("e", 1)]);
+    let ours = table(&[row("a", 2), row("b", 1), row("c", 2), row("e", 2), row("ours", 1)]);
+    let theirs = table(&[row("a", 1), row("b", 3), row("c", 3), row("d", 1), row("theirs", 1)]);
+
+    // a: changed by us. b: changed by them. c: changed by both. d: deleted by us. e: changed by us, deleted by them.
+    let (merged, conflicts) = Table::merge(&base, &ours, &theirs);
+    assert_eq!(merged.get_ref_table_data(), &[row("a", 2), row("b", 3), row("c", 2), row("e", 2), row("ours", 1), row("theirs", 1)]);
+
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts[0].key, vec!["c".to_owned()]);
+    assert_eq!(conflicts[0].merged_row, Some(2));
+    assert_eq!(conflicts[0].columns, vec!["value".to_owned()]);
+    assert_eq!(conflicts[0].theirs, Some(row("c", 3)));
+    assert_eq!(conflicts[1].key, vec!["e".to_owned()]);
+    assert!(conflicts[1].columns.is_empty());
+    assert_eq!(conflicts[1].theirs, None);
+}
+
+/// Test to make sure the merged table keeps the rows of all the sources, with the first table file read by the game winning the rows with the same key.
+#[test]
+fn test_merged_table() {
+    let row = get_test_row;
+    let table = get_test_table;
+    let provenance = |pack: &str, file: &str| RowProvenance { pack: pack.to_owned(), path: vec!["db".to_owned(), "units_tables".to_owned(), file.to_owned()] };
+
+    // Sources are passed in PackFile load order, but the game reads them by file name, so "!my_mod" goes before "data__", and "data__" before "zz_my_mod".
+    let vanilla = table(&[row("a", 1), row("b", 1), row("c", 1)]);
+    let modded_first = table(&[row("b", 2), row("d", 2), row("d", 3)]);
+    let modded_last = table(&[row("c", 4), row("e", 4)]);
+    let merged = MergedTable::new(&[
+        (provenance("data.pack", "data__"), &vanilla),
+        (provenance("my_mod.pack", "!my_mod"), &modded_first),
+        (provenance("my_mod.pack", "zz_my_mod"), &modded_last),
+    ]).unwrap();
+
+    assert_eq!(merged.get_ref_table().get_ref_table_data(), &[row("b", 2), row("d", 2), row("a", 1), row("c", 1), row("e", 4)]);
+    let files = merged.get_ref_provenance().iter().map(|x| x.path[2].as_str()).collect::<Vec<&str>>();
+    assert_eq!(files, vec!["!my_mod", "!my_mod", "data__", "data__", "zz_my_mod"]);
+    assert_eq!(merged.get_ref_provenance()[2].pack, "data.pack");
+}
+
+/// Test to make sure tables with sequences survive being exported to TSV and imported back, including the mass import/export of binary files.
+#[test]
+fn test_tsv_sequences_roundtrip() {
+    let table_name = "sequence_test_tables";
+
+    let mut definition_nested = Definition::new(-1);
+    definition_nested.get_ref_mut_fields().push(get_test_field("value", FieldType::I16, false, 0));
+
+    let mut definition_sequence = Definition::new(-1);
+    definition_sequence.get_ref_mut_fields().push(get_test_field("name", FieldType::StringU8, false, 0));
+    definition_sequence.get_ref_mut_fields().push(get_test_field("weight", FieldType::F32, false, 1));
+    definition_sequence.get_ref_mut_fields().push(get_test_field("colour", FieldType::ColourRGB, false, 2));
+    definition_sequence.get_ref_mut_fields().push(get_test_field("nested", FieldType::SequenceU16(Box::new(definition_nested.clone())), false, 3));
+
+    let mut definition = Definition::new(1);
+    definition.get_ref_mut_fields().push(get_test_field("key", FieldType::StringU8, true, 0));
+    definition.get_ref_mut_fields().push(get_test_field("sequence", FieldType::SequenceU32(Box::new(definition_sequence.clone())), false, 1));
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB(table_name.to_owned(), vec![definition.clone()]));
+
+    let sequence = |definition: &Definition, rows: Vec<Vec<DecodedData>>| {
+        let mut table = Table::new(definition);
+        table.set_table_data(&rows).unwrap();
+        Box::new(table)
+    };
+    let nested = sequence(&definition_nested, vec![vec![DecodedData::I16(-3)], vec![DecodedData::I16(7)]]);
+    let data = vec![
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::SequenceU32(sequence(&definition_sequence, vec![
+            vec![DecodedData::StringU8("tab\\t \"quoted\", [brackets]".to_owned()), DecodedData::F32(0.1), DecodedData::ColourRGB(0xFF8000), DecodedData::SequenceU16(nested)],
+            vec![DecodedData::StringU8(String::new()), DecodedData::F32(-2.5), DecodedData::ColourRGB(0), DecodedData::SequenceU16(sequence(&definition_nested, vec![]))],
+            vec![DecodedData::StringU8("inf".to_owned()), DecodedData::F32(f32::NEG_INFINITY), DecodedData::ColourRGB(0), DecodedData::SequenceU16(sequence(&definition_nested, vec![]))],
+        ]))],
+        vec![DecodedData::StringU8("b".to_owned()), DecodedData::SequenceU32(sequence(&definition_sequence, vec![]))],
+    ];
+
+    // The binary data must survive the decoder, so we know the data we export is the one the game sees.
+    let mut db = DB::new(table_name, None, &definition);
+    db.set_table_data(&data).unwrap();
+    let db = DB::read(&db.save().unwrap(), table_name, &schema, false).unwrap();
+    assert_eq!(db.get_ref_table_data(), &data);
+
+    let path = PathBuf::from("../test_files/sequence_test.tsv");
+    db.export_tsv(&path, table_name, &["db".to_owned(), table_name.to_owned(), "data".to_owned()]).unwrap();
+    let (db_imported, _) = DB::import_tsv(&schema, &path).unwrap();
+    assert_eq!(db_imported.get_ref_table_data(), &data);
+
+    // Sequences are only written as JSON in TSV files. Other places showing the data as text keep using the type name.
+    assert_eq!(data[0][1].data_to_string(), "SequenceU32");
+
+    // NaN is not equal to itself, so we check it separately.
+    let mut db_nan = DB::new(table_name, None, &definition);
+    db_nan.set_table_data(&[vec![DecodedData::StringU8("nan".to_owned()), DecodedData::SequenceU32(sequence(&definition_sequence, vec![
+        vec![DecodedData::StringU8(String::new()), DecodedData::F32(f32::NAN), DecodedData::ColourRGB(0), DecodedData::SequenceU16(sequence(&definition_nested, vec![]))],
+    ]))]]).unwrap();
+    db_nan.export_tsv(&path, table_name, &["db".to_owned(), table_name.to_owned(), "data".to_owned()]).unwrap();
+    let (db_imported, _) = DB::import_tsv(&schema, &path).unwrap();
+    match &db_imported.get_ref_table_data()[0][1] {
+        DecodedData::SequenceU32(sequence) => assert!(matches!(sequence.get_ref_table_data()[0][1], DecodedData::F32(value) if value.is_nan())),
+        _ => panic!("Imported cell is not a sequence."),
+    }
+
+    // Same thing, but going through the binary file variants used by the mass import/export.
+    let binary_path = PathBuf::from(format!("../test_files/sequence_test/{}/data", table_name));
+    std::fs::create_dir_all(binary_path.parent().unwrap()).unwrap();
+    std::fs::write(&binary_path, db.save().unwrap()).unwrap();
+    DB::export_tsv_from_binary_file(&schema, std::slice::from_ref(&binary_path)).unwrap();
+    DB::import_tsv_to_binary_file(&schema, &[binary_path.with_extension("tsv")]).unwrap();
+    let db_imported = DB::read(&std::fs::read(&binary_path).unwrap(), table_name, &schema, false).unwrap();
+    assert_eq!(db_imported.get_ref_table_data(), &data);
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_dir_all("../test_files/sequence_test").unwrap();
+}
@@ -12,7 +12,10 @@
 Module with all the code to interact with Text PackedFiles.
 
 Text PackedFiles are any kind of plain text packedfile, like lua, xml, txt,...
-The only thing to take into account is that this only work for UTF-8 encoded files.
+
+They can be encoded in UTF-8, UTF-16 (LE and BE), ISO-8859-1 or ISO-8859-15, with or without BOM.
+If there is no BOM, the encoding is guessed from the data. The encoding, BOM and line endings of the
+file are kept, so saving an unedited file gives back the exact same bytes.
 !*/
 
 use serde_derive::{Serialize, Deserialize};
@@ -21,15 +24,21 @@ use rpfm_error::{ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
 
+#[cfg(test)]
+mod text_test;
+
 /// UTF-8 BOM (Byte Order Mark).
 const BOM_UTF_8: [u8;3] = [0xEF,0xBB,0xBF];
 
-// UTF-16 BOM (Byte Order Mark), Big Endian.
-//const BOM_UTF_16_BE: [u8;2] = [0xFE,0xFF];
+/// UTF-16 BOM (Byte Order Mark), Big Endian.
+const BOM_UTF_16_BE: [u8;2] = [0xFE,0xFF];
 
 /// UTF-16 BOM (Byte Order Mark), Little Endian.
 const BOM_UTF_16_LE: [u8;2] = [0xFF,0xFE];
 
+/// Characters that change between ISO-8859-1 and ISO-8859-15. Only the first one (`¤` to `€`) is not a letter in ISO-8859-15.
+const ISO_8859_15_CHANGED_BYTES: [u8; 8] = [0xA4, 0xA6, 0xA8, 0xB4, 0xB8, 0xBC, 0xBD, 0xBE];
+
 /// List of extensions for files this lib can decode as Text PackedFiles, with their respective type.
 pub const EXTENSIONS: [(&str, TextType); 23] = [
     (".inl", TextType::Cpp),
@@ -68,6 +77,12 @@ pub struct Text {
     /// The encoding used by the text of the PackedFile.
     encoding: SupportedEncodings,
 
+    /// If the PackedFile starts with a BOM.
+    bom: bool,
+
+    /// The line endings used by the PackedFile. The contents always use `\n`.
+    line_ending: LineEnding,
+
     /// Type of text this PackedFile has.
     text_type: TextType,
 
@@ -79,10 +94,19 @@ pub struct Text {
 #[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum SupportedEncodings {
     Utf8,
-    //Utf16Be,
+    Utf16Be,
     Utf16Le,
     Iso8859_1,
-    //Iso8859_15,
+    Iso8859_15,
+}
+
+/// This enum contains the line endings RPFM can keep when saving a Text PackedFile.
+///
+/// Files with mixed line endings are treated as `Lf`, so their `\r` are kept in the contents.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
 }
 
 /// This enum contains the list of text types RPFM supports.
@@ -108,6 +132,8 @@ impl Default for Text {
     fn default() -> Self {
         Self {
             encoding: SupportedEncodings::Utf8,
+            bom: false,
+            line_ending: LineEnding::Lf,
             text_type: TextType::Plain,
             contents: String::new(),
         }
@@ -125,31 +151,25 @@ impl Text {
     /// This function creates a `Text` from a `Vec<u8>`.
     pub fn read(packed_file_data: &[u8]) -> Result<Self> {
 
-        // First, check for BOMs. 2 bytes for UTF-16 BOMs, 3 for UTF-8. If no BOM is found, we assume UTF-8 or ISO5589-1.
-        let (packed_file_data, guessed_encoding) = if packed_file_data.is_empty() { (packed_file_data, SupportedEncodings::Utf8) }
-        else if packed_file_data.len() > 2 && packed_file_data[0..3] == BOM_UTF_8 { (&packed_file_data[3..], SupportedEncodings::Utf8) }
-        //else if packed_file_data.len() > 1 && packed_file_data[0..2] == BOM_UTF_16_BE { (&packed_file_data[2..], SupportedEncodings::UTF16_BE) }
-        else if packed_file_data.len() > 1 && packed_file_data[0..2] == BOM_UTF_16_LE { (&packed_file_data[2..], SupportedEncodings::Utf16Le) }
-        else { (packed_file_data, SupportedEncodings::Utf8) };
-
-        // This is simple: we try to decode it depending on what the guesser gave us. If all fails, return error.
-        let (encoding, contents) = match guessed_encoding {
-            SupportedEncodings::Utf8 | SupportedEncodings::Iso8859_1 => {
-                match packed_file_data.decode_string_u8(0, packed_file_data.len()) {
-                    Ok(string) => (SupportedEncodings::Utf8, string),
-                    Err(_) => match packed_file_data.decode_string_u8_iso_8859_1(0, packed_file_data.len()) {
-                        Ok(string) => (SupportedEncodings::Iso8859_1, string),
-                        Err(_) => return Err(ErrorKind::TextDecodeWrongEncodingOrNotATextFile.into()),
-                    }
-                }
-            }
-
-            SupportedEncodings::Utf16Le => {
-                match packed_file_data.decode_string_u16(0, packed_file_data.len()) {
-                    Ok(string) => (SupportedEncodings::Utf16Le, string),
-                    Err(_) => return Err(ErrorKind::TextDecodeWrongEncodingOrNotATextFile.into()),
-                }
-            }
+        // First, check for BOMs. 2 bytes for UTF-16 BOMs, 3 for UTF-8. If no BOM is found, we have to guess the encoding.
+        let (data, encoding, bom) = if packed_file_data.starts_with(&BOM_UTF_8) { (&packed_file_data[3..], SupportedEncodings::Utf8, true) }
+        else if packed_file_data.starts_with(&BOM_UTF_16_BE) { (&packed_file_data[2..], SupportedEncodings::Utf16Be, true) }
+        else if packed_file_data.starts_with(&BOM_UTF_16_LE) { (&packed_file_data[2..], SupportedEncodings::Utf16Le, true) }
+        else { (packed_file_data, Self::guess_encoding(packed_file_data), false) };
+
+        let contents = match encoding {
+            SupportedEncodings::Utf8 => data.decode_string_u8(0, data.len()),
+            SupportedEncodings::Utf16Be => data.decode_string_u16_be(0, data.len()),
+            SupportedEncodings::Utf16Le => data.decode_string_u16(0, data.len()),
+            SupportedEncodings::Iso8859_1 => data.decode_string_u8_iso_8859_1(0, data.len()),
+            SupportedEncodings::Iso8859_15 => data.decode_string_u8_iso_8859_15(0, data.len()),
+        }.map_err(|_| ErrorKind::TextDecodeWrongEncodingOrNotATextFile)?;
+
+        // We only use CRLF if all the lines use it. Otherwise, we leave the contents as they are.
+        let line_ending = if contents.contains("\r\n") && contents.matches('\n').count() == contents.matches("\r\n").count() { LineEnding::CrLf } else { LineEnding::Lf };
+        let contents = match line_ending {
+            LineEnding::CrLf => contents.replace("\r\n", "\n"),
+            LineEnding::Lf => contents,
         };
 
         // Without the path we can't know the text type, so we left it as plain, and overwrite it later.
@@ -157,25 +177,78 @@ impl Text {
 
         Ok(Self {
             encoding,
+            bom,
+            line_ending,
             text_type,
             contents,
         })
     }
 
+    /// This function tries to guess the encoding of a text file without BOM.
+    ///
+    /// UTF-16 is detected by the zeros of ASCII characters, then we try UTF-8. If it's not valid UTF-8, it's one of the ISO-8859 variants.
+    fn guess_encoding(data: &[u8]) -> SupportedEncodings {
+        if data.len() >= 2 && data.len() % 2 == 0 {
+            let zeros_even = data.iter().step_by(2).filter(|x| **x == 0).count();
+            let zeros_odd = data.iter().skip(1).step_by(2).filter(|x| **x == 0).count();
+            let chars = data.len() / 2;
+
+            if zeros_odd * 2 > chars && zeros_even * 10 < zeros_odd && data.decode_string_u16(0, data.len()).is_ok() {
+                return SupportedEncodings::Utf16Le;
+            }
+
+            if zeros_even * 2 > chars && zeros_odd * 10 < zeros_even && data.decode_string_u16_be(0, data.len()).is_ok() {
+                return SupportedEncodings::Utf16Be;
+            }
+        }
+
+        if std::str::from_utf8(data).is_ok() {
+            return SupportedEncodings::Utf8;
+        }
+
+        // Both ISO-8859 variants can decode anything, so we check the bytes that change between them.
+        // If they look like letters (they're next to other letters) or euro signs (next to numbers), it's ISO-8859-15.
+        let is_letter = |byte: Option<&u8>| matches!(byte, Some(byte) if byte.is_ascii_alphabetic() || *byte >= 0xC0);
+        let is_number = |byte: Option<&u8>| matches!(byte, Some(byte) if byte.is_ascii_digit() || *byte == b' ');
+        let (mut score_1, mut score_15) = (0, 0);
+        for (index, byte) in data.iter().enumerate() {
+            if ISO_8859_15_CHANGED_BYTES.contains(byte) {
+                let previous = index.checked_sub(1).and_then(|index| data.get(index));
+                let next = data.get(index + 1);
+                let looks_like_iso_8859_15 = if *byte == ISO_8859_15_CHANGED_BYTES[0] { is_number(previous) || is_number(next) } else { is_letter(previous) || is_letter(next) };
+
+                if looks_like_iso_8859_15 { score_15 += 1; } else { score_1 += 1; }
+            }
+        }
+
+        if score_15 > score_1 { SupportedEncodings::Iso8859_15 } else { SupportedEncodings::Iso8859_1 }
+    }
+
     /// This function takes a `Text` and encodes it to `Vec<u8>`.
     ///
-    /// TODO: Make this save other than UTF-8.
+    /// The text is saved with the encoding, BOM and line endings it has.
     pub fn save(&self) -> Result<Vec<u8>> {
+        let contents = match self.line_ending {
+            LineEnding::CrLf => self.contents.replace('\n', "\r\n"),
+            LineEnding::Lf => self.contents.to_owned(),
+        };
+
         let mut data = vec![];
+        if self.bom {
+            match self.encoding {
+                SupportedEncodings::Utf8 => data.extend_from_slice(&BOM_UTF_8),
+                SupportedEncodings::Utf16Be => data.extend_from_slice(&BOM_UTF_16_BE),
+                SupportedEncodings::Utf16Le => data.extend_from_slice(&BOM_UTF_16_LE),
+                SupportedEncodings::Iso8859_1 | SupportedEncodings::Iso8859_15 => {},
+            }
+        }
+
         match self.encoding {
-            SupportedEncodings::Utf8 => data.encode_string_u8(&self.contents),
-            SupportedEncodings::Iso8859_1 => data.encode_string_u8_iso_8859_1(&self.contents),
-
-            // For UTF-16 we always have to add the BOM. Otherwise we have no way to easily tell what this file is.
-            SupportedEncodings::Utf16Le => {
-                data.append(&mut BOM_UTF_16_LE.to_vec());
-                data.encode_string_u16(&self.contents)
-            },
+            SupportedEncodings::Utf8 => data.encode_string_u8(&contents),
+            SupportedEncodings::Utf16Be => data.encode_string_u16_be(&contents),
+            SupportedEncodings::Utf16Le => data.encode_string_u16(&contents),
+            SupportedEncodings::Iso8859_1 => data.encode_string_u8_iso_8859_1(&contents),
+            SupportedEncodings::Iso8859_15 => data.encode_string_u8_iso_8859_15(&contents),
         }
 
         Ok(data)
@@ -197,8 +270,31 @@ impl Text {
     }
 
     /// This function sets the encoding used to save the text file.
+    ///
+    /// UTF-16 files get a BOM, as otherwise we have no way to easily tell what this file is. The rest don't.
     pub fn set_encoding(&mut self, encoding: SupportedEncodings) {
         self.encoding = encoding;
+        self.bom = matches!(encoding, SupportedEncodings::Utf16Be | SupportedEncodings::Utf16Le);
+    }
+
+    /// This function returns if the text file starts with a BOM.
+    pub fn get_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// This function sets if the text file starts with a BOM when saved.
+    pub fn set_bom(&mut self, bom: bool) {
+        self.bom = bom;
+    }
+
+    /// This function returns the line endings used by the text file.
+    pub fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// This function sets the line endings used to save the text file.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
     }
 
     /// This function returns a reference to the contents of the text file.
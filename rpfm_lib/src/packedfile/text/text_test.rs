@@ -0,0 +1,57 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Text` module.
+!*/
+
+use super::*;
+
+/// Test to make sure the encoding, BOM and line endings of text files are detected properly, and kept when saving.
+#[test]
+fn test_text_encodings() {
+    let check = |data: &[u8], encoding: SupportedEncodings, bom: bool, line_ending: LineEnding, contents: &str| {
+        let text = Text::read(data).unwrap();
+        assert_eq!(text.get_encoding(), encoding);
+        assert_eq!(text.get_bom(), bom);
+        assert_eq!(text.get_line_ending(), line_ending);
+        assert_eq!(text.get_ref_contents(), contents);
+        assert_eq!(text.save().unwrap(), data);
+    };
+
+    let utf16 = |text: &str, big_endian: bool| text.encode_utf16().flat_map(|x| if big_endian { x.to_be_bytes() } else { x.to_le_bytes() }).collect::<Vec<u8>>();
+
+    // UTF-8, with and without BOM.
+    check("local a = 1\r\nlocal b = 'ñ'\r\n".as_bytes(), SupportedEncodings::Utf8, false, LineEnding::CrLf, "local a = 1\nlocal b = 'ñ'\n");
+    check(&[&[0xEF, 0xBB, 0xBF][..], b"a\nb"].concat(), SupportedEncodings::Utf8, true, LineEnding::Lf, "a\nb");
+
+    // Mixed line endings are kept as they are.
+    check(b"a\r\nb\nc\r", SupportedEncodings::Utf8, false, LineEnding::Lf, "a\r\nb\nc\r");
+    check(b"a\r\r\nb\r\n", SupportedEncodings::Utf8, false, LineEnding::CrLf, "a\r\nb\n");
+
+    // UTF-16, with and without BOM.
+    check(&[&[0xFF, 0xFE][..], &utf16("hello\r\nworld", false)].concat(), SupportedEncodings::Utf16Le, true, LineEnding::CrLf, "hello\nworld");
+    check(&[&[0xFE, 0xFF][..], &utf16("hello", true)].concat(), SupportedEncodings::Utf16Be, true, LineEnding::Lf, "hello");
+    check(&utf16("<root>ñ</root>", false), SupportedEncodings::Utf16Le, false, LineEnding::Lf, "<root>ñ</root>");
+    check(&utf16("<root>ñ</root>\n", true), SupportedEncodings::Utf16Be, false, LineEnding::Lf, "<root>ñ</root>\n");
+
+    // ISO-8859-1 and ISO-8859-15. The later is used if the differing bytes look like euro signs or letters.
+    check(b"caf\xE9 \xBD kg", SupportedEncodings::Iso8859_1, false, LineEnding::Lf, "café ½ kg");
+    check(b"10 \xA4\r\nc\xBDur", SupportedEncodings::Iso8859_15, false, LineEnding::CrLf, "10 €\ncœur");
+
+    // New files in UTF-16 get a BOM.
+    let mut text = Text::new();
+    text.set_contents("a");
+    text.set_encoding(SupportedEncodings::Utf16Be);
+    assert_eq!(text.save().unwrap(), vec![0xFE, 0xFF, 0, b'a']);
+
+    // Broken UTF-16 must fail, not panic.
+    assert!(Text::read(&[0xFF, 0xFE, 0x00]).is_err());
+}
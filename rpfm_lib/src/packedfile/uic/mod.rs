@@ -13,14 +13,34 @@ Module with all the code to interact with UI Components.
 
 UI Components are binary files that form the ui of TW Games.
 They have no extension (mostly), and I heard they're a pain in the ass to work with.
+
+Their structure is a header (`Version` followed by the version as 3 ascii digits) and the root component of the layout.
+Each component has, in this order:
+
+- Its id, name, events, position, visibility, priority, tooltip and docking.
+- Its default state and the images it uses.
+- Its mask image and its states. Each state has its own size, text, font, shader, image uses and mouse state transitions.
+- Its properties, as key-value pairs.
+- Since version 110, its event callbacks.
+- Its children. Since version 120, each child is preceded by a tag telling if it's a component or a template.
+
+Strings are u16-prefixed. Strings players can see (texts and tooltips) are UTF-16, the rest are UTF-8.
+
+There are a lot of small differences between the versions used in each game, so if we cannot decode the layout
+of a file, or it doesn't encode back to the same bytes, we keep it as raw data, so it can still be saved back.
 !*/
 
 use serde_derive::{Serialize, Deserialize};
 
-use rpfm_error::Result;
+use rpfm_error::{ErrorKind, Result};
+use rpfm_macros::{GetRef, GetRefMut, Set};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
-use crate::Schema;
+
+pub mod xml;
+
+#[cfg(test)]
+mod uic_test;
 
 const SIGNATURE: &str = "Version";
 const VERSION_SIZE: usize = 3;
@@ -30,23 +50,177 @@ pub const HEADER_SIZE: usize = 10;
 
 pub const EXTENSION: &str = ".cml";
 
+/// Range of versions we can decode the layout of.
+const MIN_SUPPORTED_VERSION: u32 = 100;
+const MAX_SUPPORTED_VERSION: u32 = 130;
+
+/// First version with event callbacks in their components.
+const VERSION_EVENT_CALLBACKS: u32 = 110;
+
+/// First version with templates as children.
+const VERSION_TEMPLATES: u32 = 120;
+
+/// Tags used since version 120 to identify the type of each child.
+const CHILD_TAG_COMPONENT: u16 = 0;
+const CHILD_TAG_TEMPLATE: u16 = 1;
+
+/// Max depth of the component tree we decode. Anything deeper is considered corrupted.
+const MAX_DEPTH: usize = 256;
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
 
 /// This holds an entire UI Component decoded in memory.
-#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct UIC {
     version: u32,
+    content: UICContent,
+}
+
+/// This enum represents the layout of an UI Component.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum UICContent {
+
+    /// Layout we could decode, with its root component.
+    Decoded(Component),
+
+    /// Data after the header we couldn't decode.
+    Raw(Vec<u8>),
+}
+
+/// This struct represents a component of the layout, with all its children.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Component {
+    uid: u32,
+    name: String,
+    events: String,
+    offset: [i32; 2],
+    visible: bool,
+    priority: u8,
+    tooltip_text: String,
+    tooltip_id: String,
+    docking: u32,
+    dock_offset: [i32; 2],
+
+    /// Uid of the state the component starts in.
+    default_state: u32,
+    images: Vec<Image>,
+
+    /// Uid of the image used as mask. 0 if there is none.
+    mask_image: u32,
+    states: Vec<State>,
+    properties: Vec<(String, String)>,
+
+    /// Only used since version 110.
+    event_callbacks: Vec<EventCallback>,
+    children: Vec<Child>,
+}
+
+/// This struct represents an image used by a component.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Image {
+    uid: u32,
+    path: String,
+    width: u32,
+    height: u32,
+}
+
+/// This struct represents a state of a component.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    uid: u32,
+    name: String,
+    width: u32,
+    height: u32,
+    text: String,
+    tooltip_text: String,
+    text_bounds: [u32; 2],
+
+    /// Horizontal and vertical alignment of the text.
+    text_alignment: [u32; 2],
+
+    /// Left, right, top and bottom offsets of the text.
+    text_offset: [i32; 4],
+    font_name: String,
+    font_size: u32,
+
+    /// Colour of the font, as RGBA.
+    font_colour: u32,
+    font_category: String,
+    shader_name: String,
+    shader_variables: [f32; 4],
+    image_uses: Vec<ImageUse>,
+    mouse_states: Vec<MouseState>,
+}
+
+/// This struct represents how a state uses one of the images of its component.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImageUse {
+
+    /// Uid of the image used.
+    image_uid: u32,
+    offset: [i32; 2],
+    size: [u32; 2],
+
+    /// Colour the image is tinted with, as RGBA.
+    colour: u32,
+    tiled: bool,
+    x_flipped: bool,
+    y_flipped: bool,
+    docking: u32,
+    rotation: f32,
+    pivot: [f32; 2],
+    shader_name: String,
+}
+
+/// This struct represents a transition from a state to another when the mouse interacts with the component.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MouseState {
+    mouse_state: u32,
+
+    /// Uid of the state the component changes to.
+    target_state: u32,
+    events: Vec<String>,
+}
+
+/// This struct represents an event callback of a component.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventCallback {
+    callback_id: String,
+    context_object_id: String,
+    context_function_id: String,
+    properties: Vec<(String, String)>,
+}
+
+/// This enum represents a child of a component.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum Child {
+    Component(Component),
+
+    /// Only used since version 120.
+    Template(Template),
+}
+
+/// This struct represents a template used as child of a component. The template's layout is in another file.
+#[derive(GetRef, GetRefMut, Set, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Template {
+    uid: u32,
+    name: String,
+    template_name: String,
+
+    /// Properties of the template's components overwritten by this instance.
+    properties: Vec<(String, String)>,
 }
 
 //---------------------------------------------------------------------------//
-//                           Implementation of Text
+//                           Implementation of UIC
 //---------------------------------------------------------------------------//
 
 /// Implementation of `UIC`.
 impl UIC {
 
+    /// This function checks if the provided data is an UI Component.
     pub fn is_ui_component(data: &[u8]) -> bool {
         match data.decode_string_u8(0, 7) {
             Ok(signature) => signature == SIGNATURE,
@@ -59,29 +233,381 @@ impl UIC {
     }
 
     /// This function creates a `UIC` from a `&[u8]`.
-    pub fn read(packed_file_data: &[u8], _schema: &Schema) -> Result<Self> {
+    ///
+    /// If the layout of the file cannot be decoded, it's kept as raw data.
+    pub fn read(packed_file_data: &[u8]) -> Result<Self> {
         let version = Self::read_header(packed_file_data)?;
+        let data = &packed_file_data[HEADER_SIZE..];
+
+        // Only keep the decoded layout if it encodes back to the same data.
+        let content = if (MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&version) {
+            let mut index = 0;
+            match Component::read(data, &mut index, version, 0) {
+                Ok(component) if index == data.len() && component.save(version).ok().as_deref() == Some(data) => UICContent::Decoded(component),
+                _ => UICContent::Raw(data.to_vec()),
+            }
+        } else {
+            UICContent::Raw(data.to_vec())
+        };
 
         // If we've reached this, we've successfully decoded the entire UI.
         Ok(Self {
             version,
+            content,
         })
     }
 
     /// This function tries to read the header of an UIC PackedFile from raw data.
     pub fn read_header(packed_file_data: &[u8]) -> Result<u32> {
-        let _signature = packed_file_data.decode_string_u8(0, SIGNATURE.len())?;
+        if !Self::is_ui_component(packed_file_data) {
+            return Err(ErrorKind::UICDecode("This is not an UI Component.".to_owned()).into());
+        }
+
         let version = packed_file_data.decode_string_u8(SIGNATURE.len(), VERSION_SIZE)?.parse::<u32>()?;
 
         Ok(version)
     }
 
     /// This function takes an `UIC` and encodes it to `Vec<u8>`.
-    pub fn save(&self) -> Vec<u8> {
+    pub fn save(&self) -> Result<Vec<u8>> {
+        if self.version > 999 {
+            return Err(ErrorKind::UICEncode(format!("Version {} cannot be written with {} digits.", self.version, VERSION_SIZE)).into());
+        }
+
         let mut data = vec![];
         data.encode_string_u8(SIGNATURE);
-        data.encode_integer_u32(self.version);
+        data.encode_string_u8(&format!("{:03}", self.version));
+
+        match self.content {
+            UICContent::Decoded(ref component) => data.append(&mut component.save(self.version)?),
+            UICContent::Raw(ref raw_data) => data.extend_from_slice(raw_data),
+        }
+
+        Ok(data)
+    }
+
+    /// This function returns if the layout of this `UIC` has been decoded.
+    pub fn is_decoded(&self) -> bool {
+        matches!(self.content, UICContent::Decoded(_))
+    }
+}
+
+/// Default implementation of `UICContent`.
+impl Default for UICContent {
+    fn default() -> Self {
+        Self::Raw(vec![])
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of Components
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Component`.
+impl Component {
+
+    /// This function decodes a component and all its children, moving the provided index to the byte where the next data starts.
+    fn read(data: &[u8], index: &mut usize, version: u32, depth: usize) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(ErrorKind::UICDecode(format!("Component at byte {} is nested more than {} levels deep.", index, MAX_DEPTH)).into());
+        }
+
+        let uid = data.decode_packedfile_integer_u32(*index, index)?;
+        let name = data.decode_packedfile_string_u8(*index, index)?;
+        let events = data.decode_packedfile_string_u8(*index, index)?;
+        let offset = [data.decode_packedfile_integer_i32(*index, index)?, data.decode_packedfile_integer_i32(*index, index)?];
+        let visible = data.decode_packedfile_bool(*index, index)?;
+        let priority = data.decode_packedfile_integer_u8(*index, index)?;
+        let tooltip_text = data.decode_packedfile_string_u16(*index, index)?;
+        let tooltip_id = data.decode_packedfile_string_u8(*index, index)?;
+        let docking = data.decode_packedfile_integer_u32(*index, index)?;
+        let dock_offset = [data.decode_packedfile_integer_i32(*index, index)?, data.decode_packedfile_integer_i32(*index, index)?];
+        let default_state = data.decode_packedfile_integer_u32(*index, index)?;
+
+        let mut images = vec![];
+        for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+            images.push(Image {
+                uid: data.decode_packedfile_integer_u32(*index, index)?,
+                path: data.decode_packedfile_string_u8(*index, index)?,
+                width: data.decode_packedfile_integer_u32(*index, index)?,
+                height: data.decode_packedfile_integer_u32(*index, index)?,
+            });
+        }
+
+        let mask_image = data.decode_packedfile_integer_u32(*index, index)?;
+
+        let mut states = vec![];
+        for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+            states.push(State::read(data, index)?);
+        }
+
+        let properties = read_properties(data, index)?;
+
+        let mut event_callbacks = vec![];
+        if version >= VERSION_EVENT_CALLBACKS {
+            for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+                event_callbacks.push(EventCallback {
+                    callback_id: data.decode_packedfile_string_u8(*index, index)?,
+                    context_object_id: data.decode_packedfile_string_u8(*index, index)?,
+                    context_function_id: data.decode_packedfile_string_u8(*index, index)?,
+                    properties: read_properties(data, index)?,
+                });
+            }
+        }
+
+        let mut children = vec![];
+        for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+            let tag = if version >= VERSION_TEMPLATES { data.decode_packedfile_integer_u16(*index, index)? } else { CHILD_TAG_COMPONENT };
+            match tag {
+                CHILD_TAG_COMPONENT => children.push(Child::Component(Self::read(data, index, version, depth + 1)?)),
+                CHILD_TAG_TEMPLATE => children.push(Child::Template(Template {
+                    uid: data.decode_packedfile_integer_u32(*index, index)?,
+                    name: data.decode_packedfile_string_u8(*index, index)?,
+                    template_name: data.decode_packedfile_string_u8(*index, index)?,
+                    properties: read_properties(data, index)?,
+                })),
+                _ => return Err(ErrorKind::UICDecode(format!("Unknown child type {} at byte {}.", tag, *index - 2)).into()),
+            }
+        }
+
+        Ok(Self {
+            uid,
+            name,
+            events,
+            offset,
+            visible,
+            priority,
+            tooltip_text,
+            tooltip_id,
+            docking,
+            dock_offset,
+            default_state,
+            images,
+            mask_image,
+            states,
+            properties,
+            event_callbacks,
+            children,
+        })
+    }
+
+    /// This function encodes a component and all its children to `Vec<u8>`, using the format of the provided version.
+    fn save(&self, version: u32) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        data.encode_integer_u32(self.uid);
+        data.encode_packedfile_string_u8(&self.name);
+        data.encode_packedfile_string_u8(&self.events);
+        self.offset.iter().for_each(|value| data.encode_integer_i32(*value));
+        data.encode_bool(self.visible);
+        data.push(self.priority);
+        data.encode_packedfile_string_u16(&self.tooltip_text);
+        data.encode_packedfile_string_u8(&self.tooltip_id);
+        data.encode_integer_u32(self.docking);
+        self.dock_offset.iter().for_each(|value| data.encode_integer_i32(*value));
+        data.encode_integer_u32(self.default_state);
+
+        data.encode_integer_u32(self.images.len() as u32);
+        for image in &self.images {
+            data.encode_integer_u32(image.uid);
+            data.encode_packedfile_string_u8(&image.path);
+            data.encode_integer_u32(image.width);
+            data.encode_integer_u32(image.height);
+        }
+
+        data.encode_integer_u32(self.mask_image);
+
+        data.encode_integer_u32(self.states.len() as u32);
+        self.states.iter().for_each(|state| state.save(&mut data));
+
+        save_properties(&mut data, &self.properties);
+
+        if version >= VERSION_EVENT_CALLBACKS {
+            data.encode_integer_u32(self.event_callbacks.len() as u32);
+            for callback in &self.event_callbacks {
+                data.encode_packedfile_string_u8(&callback.callback_id);
+                data.encode_packedfile_string_u8(&callback.context_object_id);
+                data.encode_packedfile_string_u8(&callback.context_function_id);
+                save_properties(&mut data, &callback.properties);
+            }
+        } else if !self.event_callbacks.is_empty() {
+            return Err(ErrorKind::UICEncode(format!("Component \"{}\" has event callbacks, but they're not supported by version {}.", self.name, version)).into());
+        }
+
+        data.encode_integer_u32(self.children.len() as u32);
+        for child in &self.children {
+            match child {
+                Child::Component(component) => {
+                    if version >= VERSION_TEMPLATES {
+                        data.encode_integer_u16(CHILD_TAG_COMPONENT);
+                    }
+
+                    data.append(&mut component.save(version)?);
+                }
+                Child::Template(template) => {
+                    if version < VERSION_TEMPLATES {
+                        return Err(ErrorKind::UICEncode(format!("Component \"{}\" has templates as children, but they're not supported by version {}.", self.name, version)).into());
+                    }
+
+                    data.encode_integer_u16(CHILD_TAG_TEMPLATE);
+                    data.encode_integer_u32(template.uid);
+                    data.encode_packedfile_string_u8(&template.name);
+                    data.encode_packedfile_string_u8(&template.template_name);
+                    save_properties(&mut data, &template.properties);
+                }
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+/// Implementation of `State`.
+impl State {
+
+    /// This function decodes a state, moving the provided index to the byte where the next data starts.
+    fn read(data: &[u8], index: &mut usize) -> Result<Self> {
+        let uid = data.decode_packedfile_integer_u32(*index, index)?;
+        let name = data.decode_packedfile_string_u8(*index, index)?;
+        let width = data.decode_packedfile_integer_u32(*index, index)?;
+        let height = data.decode_packedfile_integer_u32(*index, index)?;
+        let text = data.decode_packedfile_string_u16(*index, index)?;
+        let tooltip_text = data.decode_packedfile_string_u16(*index, index)?;
+        let text_bounds = [data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_integer_u32(*index, index)?];
+        let text_alignment = [data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_integer_u32(*index, index)?];
+
+        let mut text_offset = [0; 4];
+        for value in &mut text_offset {
+            *value = data.decode_packedfile_integer_i32(*index, index)?;
+        }
+
+        let font_name = data.decode_packedfile_string_u8(*index, index)?;
+        let font_size = data.decode_packedfile_integer_u32(*index, index)?;
+        let font_colour = data.decode_packedfile_integer_u32(*index, index)?;
+        let font_category = data.decode_packedfile_string_u8(*index, index)?;
+        let shader_name = data.decode_packedfile_string_u8(*index, index)?;
+
+        let mut shader_variables = [0.0; 4];
+        for value in &mut shader_variables {
+            *value = data.decode_packedfile_float_f32(*index, index)?;
+        }
+
+        let mut image_uses = vec![];
+        for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+            image_uses.push(ImageUse {
+                image_uid: data.decode_packedfile_integer_u32(*index, index)?,
+                offset: [data.decode_packedfile_integer_i32(*index, index)?, data.decode_packedfile_integer_i32(*index, index)?],
+                size: [data.decode_packedfile_integer_u32(*index, index)?, data.decode_packedfile_integer_u32(*index, index)?],
+                colour: data.decode_packedfile_integer_u32(*index, index)?,
+                tiled: data.decode_packedfile_bool(*index, index)?,
+                x_flipped: data.decode_packedfile_bool(*index, index)?,
+                y_flipped: data.decode_packedfile_bool(*index, index)?,
+                docking: data.decode_packedfile_integer_u32(*index, index)?,
+                rotation: data.decode_packedfile_float_f32(*index, index)?,
+                pivot: [data.decode_packedfile_float_f32(*index, index)?, data.decode_packedfile_float_f32(*index, index)?],
+                shader_name: data.decode_packedfile_string_u8(*index, index)?,
+            });
+        }
+
+        let mut mouse_states = vec![];
+        for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+            let mouse_state = data.decode_packedfile_integer_u32(*index, index)?;
+            let target_state = data.decode_packedfile_integer_u32(*index, index)?;
+
+            let mut events = vec![];
+            for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+                events.push(data.decode_packedfile_string_u8(*index, index)?);
+            }
+
+            mouse_states.push(MouseState {
+                mouse_state,
+                target_state,
+                events,
+            });
+        }
+
+        Ok(Self {
+            uid,
+            name,
+            width,
+            height,
+            text,
+            tooltip_text,
+            text_bounds,
+            text_alignment,
+            text_offset,
+            font_name,
+            font_size,
+            font_colour,
+            font_category,
+            shader_name,
+            shader_variables,
+            image_uses,
+            mouse_states,
+        })
+    }
+
+    /// This function encodes a state to the provided `Vec<u8>`.
+    fn save(&self, data: &mut Vec<u8>) {
+        data.encode_integer_u32(self.uid);
+        data.encode_packedfile_string_u8(&self.name);
+        data.encode_integer_u32(self.width);
+        data.encode_integer_u32(self.height);
+        data.encode_packedfile_string_u16(&self.text);
+        data.encode_packedfile_string_u16(&self.tooltip_text);
+        self.text_bounds.iter().for_each(|value| data.encode_integer_u32(*value));
+        self.text_alignment.iter().for_each(|value| data.encode_integer_u32(*value));
+        self.text_offset.iter().for_each(|value| data.encode_integer_i32(*value));
+        data.encode_packedfile_string_u8(&self.font_name);
+        data.encode_integer_u32(self.font_size);
+        data.encode_integer_u32(self.font_colour);
+        data.encode_packedfile_string_u8(&self.font_category);
+        data.encode_packedfile_string_u8(&self.shader_name);
+        self.shader_variables.iter().for_each(|value| data.encode_float_f32(*value));
+
+        data.encode_integer_u32(self.image_uses.len() as u32);
+        for image_use in &self.image_uses {
+            data.encode_integer_u32(image_use.image_uid);
+            image_use.offset.iter().for_each(|value| data.encode_integer_i32(*value));
+            image_use.size.iter().for_each(|value| data.encode_integer_u32(*value));
+            data.encode_integer_u32(image_use.colour);
+            data.encode_bool(image_use.tiled);
+            data.encode_bool(image_use.x_flipped);
+            data.encode_bool(image_use.y_flipped);
+            data.encode_integer_u32(image_use.docking);
+            data.encode_float_f32(image_use.rotation);
+            image_use.pivot.iter().for_each(|value| data.encode_float_f32(*value));
+            data.encode_packedfile_string_u8(&image_use.shader_name);
+        }
+
+        data.encode_integer_u32(self.mouse_states.len() as u32);
+        for mouse_state in &self.mouse_states {
+            data.encode_integer_u32(mouse_state.mouse_state);
+            data.encode_integer_u32(mouse_state.target_state);
+            data.encode_integer_u32(mouse_state.events.len() as u32);
+            mouse_state.events.iter().for_each(|event| data.encode_packedfile_string_u8(event));
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Utility functions for UIC
+//---------------------------------------------------------------------------//
+
+/// This function decodes a list of key-value properties, moving the provided index to the byte where the next data starts.
+fn read_properties(data: &[u8], index: &mut usize) -> Result<Vec<(String, String)>> {
+    let mut properties = vec![];
+    for _ in 0..data.decode_packedfile_integer_u32(*index, index)? {
+        properties.push((data.decode_packedfile_string_u8(*index, index)?, data.decode_packedfile_string_u8(*index, index)?));
+    }
+
+    Ok(properties)
+}
 
-        data
+/// This function encodes a list of key-value properties to the provided `Vec<u8>`.
+fn save_properties(data: &mut Vec<u8>, properties: &[(String, String)]) {
+    data.encode_integer_u32(properties.len() as u32);
+    for (key, value) in properties {
+        data.encode_packedfile_string_u8(key);
+        data.encode_packedfile_string_u8(value);
     }
 }
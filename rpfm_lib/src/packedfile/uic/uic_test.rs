@@ -0,0 +1,99 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `UIC` module.
+!*/
+
+use super::*;
+
+/// Test to make sure UI Components are saved back to the same bytes and exported to XML, keeping as raw data the layouts we cannot decode.
+#[test]
+fn test_uic_roundtrip() {
+    let mut image = Image::default();
+    image.set_uid(7);
+    image.set_path("ui/skins/default/button_basic.png".to_owned());
+    image.set_width(64);
+    image.set_height(32);
+
+    let mut image_use = ImageUse::default();
+    image_use.set_image_uid(7);
+    image_use.set_size([64, 32]);
+    image_use.set_colour(0xFFFF_FFFF);
+    image_use.set_tiled(true);
+
+    let mut mouse_state = MouseState::default();
+    mouse_state.set_mouse_state(1);
+    mouse_state.set_target_state(2);
+    mouse_state.set_events(vec!["button_hover".to_owned()]);
+
+    let mut state = State::default();
+    state.set_uid(1);
+    state.set_name("active".to_owned());
+    state.set_text("Ready & Able".to_owned());
+    state.set_font_name("la_gioconda".to_owned());
+    state.set_shader_variables([0.5, 0.0, 1.0, 0.0]);
+    state.set_image_uses(vec![image_use]);
+    state.set_mouse_states(vec![mouse_state]);
+
+    let mut callback = EventCallback::default();
+    callback.set_callback_id("ContextCommandLeftClick".to_owned());
+    callback.set_properties(vec![("command".to_owned(), "EndTurn".to_owned())]);
+
+    let mut template = Template::default();
+    template.set_uid(3);
+    template.set_name("close_button".to_owned());
+    template.set_template_name("round_small_button".to_owned());
+
+    let mut button = Component::default();
+    button.set_uid(2);
+    button.set_name("button_end_turn".to_owned());
+    button.set_visible(true);
+    button.set_default_state(1);
+    button.set_images(vec![image]);
+    button.set_states(vec![state]);
+    button.set_event_callbacks(vec![callback]);
+
+    let mut root = Component::default();
+    root.set_uid(1);
+    root.set_name("root".to_owned());
+    root.set_properties(vec![("priority".to_owned(), "100".to_owned())]);
+    root.set_children(vec![Child::Component(button), Child::Template(template)]);
+
+    let mut uic = UIC::new();
+    uic.set_version(130);
+    uic.set_content(UICContent::Decoded(root));
+
+    let data = uic.save().unwrap();
+    assert_eq!(&data[..10], b"Version130");
+
+    let decoded = UIC::read(&data).unwrap();
+    assert!(decoded.is_decoded());
+    assert_eq!(decoded, uic);
+    assert_eq!(decoded.save().unwrap(), data);
+
+    let xml = decoded.to_xml().unwrap();
+    assert!(xml.contains("<ui_component version=\"130\">"));
+    assert!(xml.contains("text=\"Ready &amp; Able\""));
+    assert!(xml.contains("<event name=\"button_hover\"/>"));
+    assert!(xml.contains("<template uid=\"3\" name=\"close_button\" template_name=\"round_small_button\">"));
+
+    // Versions without templates cannot save them.
+    uic.set_version(100);
+    assert!(uic.save().is_err());
+
+    // Layouts we cannot decode must be kept as they are.
+    let mut data = b"Version130".to_vec();
+    data.extend_from_slice(&[1, 2, 3, 4, 5]);
+    let decoded = UIC::read(&data).unwrap();
+    assert!(!decoded.is_decoded());
+    assert_eq!(decoded.save().unwrap(), data);
+    assert!(decoded.to_xml().unwrap().contains("<raw>0102030405</raw>"));
+}
@@ -0,0 +1,249 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to export `UIC` files to XML.
+
+The XML follows the component tree, with one attribute per field and one element per list. Empty lists are omitted.
+Layouts we couldn't decode are exported as a hex string, so two versions of the same file can still be compared.
+!*/
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+
+use std::fmt::Display;
+use std::io::Cursor;
+
+use super::*;
+
+/// Type of the writer used to export to XML.
+type XMLWriter = Writer<Cursor<Vec<u8>>>;
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `UIC`. Section of functions to export UI Components.
+impl UIC {
+
+    /// This function exports this `UIC` as XML, returning its contents.
+    pub fn to_xml(&self) -> Result<String> {
+        let mut writer = Writer::new_with_indent(Cursor::new(vec![]), b' ', 4);
+        start_element(&mut writer, "ui_component", &[("version", self.version.to_string())])?;
+
+        match self.content {
+            UICContent::Decoded(ref component) => component.to_xml(&mut writer)?,
+            UICContent::Raw(ref data) => {
+                start_element(&mut writer, "raw", &[])?;
+                let data = data.iter().map(|byte| format!("{:02X}", byte)).collect::<String>();
+                writer.write_event(Event::Text(BytesText::from_plain_str(&data)))?;
+                end_element(&mut writer, "raw")?;
+            }
+        }
+
+        end_element(&mut writer, "ui_component")?;
+        Ok(String::from_utf8(writer.into_inner().into_inner())?)
+    }
+}
+
+/// Implementation of `Component`. Section of functions to export components.
+impl Component {
+
+    /// This function writes this component and all its children as XML.
+    fn to_xml(&self, writer: &mut XMLWriter) -> Result<()> {
+        start_element(writer, "component", &[
+            ("uid", self.uid.to_string()),
+            ("name", self.name.to_owned()),
+            ("events", self.events.to_owned()),
+            ("offset", join(&self.offset)),
+            ("visible", self.visible.to_string()),
+            ("priority", self.priority.to_string()),
+            ("tooltip_text", self.tooltip_text.to_owned()),
+            ("tooltip_id", self.tooltip_id.to_owned()),
+            ("docking", self.docking.to_string()),
+            ("dock_offset", join(&self.dock_offset)),
+            ("default_state", self.default_state.to_string()),
+            ("mask_image", self.mask_image.to_string()),
+        ])?;
+
+        if !self.images.is_empty() {
+            start_element(writer, "images", &[])?;
+            for image in &self.images {
+                empty_element(writer, "image", &[
+                    ("uid", image.uid.to_string()),
+                    ("path", image.path.to_owned()),
+                    ("width", image.width.to_string()),
+                    ("height", image.height.to_string()),
+                ])?;
+            }
+            end_element(writer, "images")?;
+        }
+
+        if !self.states.is_empty() {
+            start_element(writer, "states", &[])?;
+            for state in &self.states {
+                state.to_xml(writer)?;
+            }
+            end_element(writer, "states")?;
+        }
+
+        properties_to_xml(writer, &self.properties)?;
+
+        if !self.event_callbacks.is_empty() {
+            start_element(writer, "event_callbacks", &[])?;
+            for callback in &self.event_callbacks {
+                start_element(writer, "event_callback", &[
+                    ("callback_id", callback.callback_id.to_owned()),
+                    ("context_object_id", callback.context_object_id.to_owned()),
+                    ("context_function_id", callback.context_function_id.to_owned()),
+                ])?;
+                properties_to_xml(writer, &callback.properties)?;
+                end_element(writer, "event_callback")?;
+            }
+            end_element(writer, "event_callbacks")?;
+        }
+
+        if !self.children.is_empty() {
+            start_element(writer, "children", &[])?;
+            for child in &self.children {
+                match child {
+                    Child::Component(component) => component.to_xml(writer)?,
+                    Child::Template(template) => {
+                        start_element(writer, "template", &[
+                            ("uid", template.uid.to_string()),
+                            ("name", template.name.to_owned()),
+                            ("template_name", template.template_name.to_owned()),
+                        ])?;
+                        properties_to_xml(writer, &template.properties)?;
+                        end_element(writer, "template")?;
+                    }
+                }
+            }
+            end_element(writer, "children")?;
+        }
+
+        end_element(writer, "component")
+    }
+}
+
+/// Implementation of `State`. Section of functions to export states.
+impl State {
+
+    /// This function writes this state as XML.
+    fn to_xml(&self, writer: &mut XMLWriter) -> Result<()> {
+        start_element(writer, "state", &[
+            ("uid", self.uid.to_string()),
+            ("name", self.name.to_owned()),
+            ("width", self.width.to_string()),
+            ("height", self.height.to_string()),
+            ("text", self.text.to_owned()),
+            ("tooltip_text", self.tooltip_text.to_owned()),
+            ("text_bounds", join(&self.text_bounds)),
+            ("text_alignment", join(&self.text_alignment)),
+            ("text_offset", join(&self.text_offset)),
+            ("font_name", self.font_name.to_owned()),
+            ("font_size", self.font_size.to_string()),
+            ("font_colour", format!("{:08X}", self.font_colour)),
+            ("font_category", self.font_category.to_owned()),
+            ("shader_name", self.shader_name.to_owned()),
+            ("shader_variables", join(&self.shader_variables)),
+        ])?;
+
+        if !self.image_uses.is_empty() {
+            start_element(writer, "image_uses", &[])?;
+            for image_use in &self.image_uses {
+                empty_element(writer, "image_use", &[
+                    ("image_uid", image_use.image_uid.to_string()),
+                    ("offset", join(&image_use.offset)),
+                    ("size", join(&image_use.size)),
+                    ("colour", format!("{:08X}", image_use.colour)),
+                    ("tiled", image_use.tiled.to_string()),
+                    ("x_flipped", image_use.x_flipped.to_string()),
+                    ("y_flipped", image_use.y_flipped.to_string()),
+                    ("docking", image_use.docking.to_string()),
+                    ("rotation", image_use.rotation.to_string()),
+                    ("pivot", join(&image_use.pivot)),
+                    ("shader_name", image_use.shader_name.to_owned()),
+                ])?;
+            }
+            end_element(writer, "image_uses")?;
+        }
+
+        if !self.mouse_states.is_empty() {
+            start_element(writer, "mouse_states", &[])?;
+            for mouse_state in &self.mouse_states {
+                let attributes = [
+                    ("mouse_state", mouse_state.mouse_state.to_string()),
+                    ("target_state", mouse_state.target_state.to_string()),
+                ];
+
+                if mouse_state.events.is_empty() {
+                    empty_element(writer, "mouse_state", &attributes)?;
+                } else {
+                    start_element(writer, "mouse_state", &attributes)?;
+                    for event in &mouse_state.events {
+                        empty_element(writer, "event", &[("name", event.to_owned())])?;
+                    }
+                    end_element(writer, "mouse_state")?;
+                }
+            }
+            end_element(writer, "mouse_states")?;
+        }
+
+        end_element(writer, "state")
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Utility functions for XML
+//---------------------------------------------------------------------------//
+
+/// This function writes the opening tag of an element, with the provided attributes.
+fn start_element(writer: &mut XMLWriter, name: &str, attributes: &[(&str, String)]) -> Result<()> {
+    writer.write_event(Event::Start(new_element(name, attributes)))?;
+    Ok(())
+}
+
+/// This function writes the closing tag of an element.
+fn end_element(writer: &mut XMLWriter, name: &str) -> Result<()> {
+    writer.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?;
+    Ok(())
+}
+
+/// This function writes an element without children, with the provided attributes.
+fn empty_element(writer: &mut XMLWriter, name: &str, attributes: &[(&str, String)]) -> Result<()> {
+    writer.write_event(Event::Empty(new_element(name, attributes)))?;
+    Ok(())
+}
+
+/// This function creates the tag of an element with the provided attributes. Values are escaped.
+fn new_element<'a>(name: &'a str, attributes: &'a [(&str, String)]) -> BytesStart<'a> {
+    let mut element = BytesStart::borrowed_name(name.as_bytes());
+    attributes.iter().for_each(|(key, value)| element.push_attribute((*key, value.as_str())));
+    element
+}
+
+/// This function writes a list of key-value properties, if it's not empty.
+fn properties_to_xml(writer: &mut XMLWriter, properties: &[(String, String)]) -> Result<()> {
+    if !properties.is_empty() {
+        start_element(writer, "properties", &[])?;
+        for (key, value) in properties {
+            empty_element(writer, "property", &[("key", key.to_owned()), ("value", value.to_owned())])?;
+        }
+        end_element(writer, "properties")?;
+    }
+
+    Ok(())
+}
+
+/// This function joins a list of values with commas.
+fn join<T: Display>(values: &[T]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(",")
+}
@@ -10,98 +10,157 @@
 
 // Here should go all the functions related to the compression/decompression of PackedFiles.
 
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
 use xz2::read::XzDecoder;
 use xz2::stream::Stream;
 
-use std::env::temp_dir;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::{BufReader, Read, SeekFrom};
-
-use std::process::Command;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
 use std::u64;
 
 use rpfm_error::{Error, ErrorKind, Result};
-use crate::common::encoder::Encoder;
-use crate::common::decoder::Decoder;
-use crate::SETTINGS;
-use crate::settings::ZIP_PATH;
+use crate::common::{decoder::Decoder, encoder::Encoder};
+use crate::packfile::CompressionFormat;
+use crate::packfile::lzma::{encode_raw, DICTIONARY_SIZE, PROPERTIES};
+
+/// Magic numbers of the LZ4 and Zstd frames. If the data after the uncompressed size doesn't start with one of these, it's LZMA1.
+const MAGIC_NUMBER_LZ4: u32 = 0x184D_2204;
+const MAGIC_NUMBER_ZSTD: u32 = 0xFD2F_B528;
+
+/// Compression level used for Zstd. It's the default one of the Zstd lib.
+const ZSTD_LEVEL: i32 = 3;
+
+/// This function returns the format the provided compressed data was compressed with.
+///
+/// All formats start with the size of the uncompressed data as an u32, so we detect them by what comes after it.
+pub fn get_compression_format(data: &[u8]) -> CompressionFormat {
+    match data.decode_integer_u32(4) {
+        Ok(MAGIC_NUMBER_LZ4) => CompressionFormat::Lz4,
+        Ok(MAGIC_NUMBER_ZSTD) => CompressionFormat::Zstd,
+        _ => CompressionFormat::Lzma1,
+    }
+}
 
 /// This function decompress the data of a PackedFile, returning the decompressed data.
+///
+/// The compression format is detected from the data itself.
 pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
     if !data.is_empty() {
         if data.len() >= 9 {
-
-            // CA Tweaks their headers to remove 4 bytes per PackedFile, while losing +4GB File Compression Support.
-            // We need to fix their headers so the normal LZMA lib can read them.
-            let mut fixed_data: Vec<u8> = vec![];
-            fixed_data.extend_from_slice(&data[4..8]);
-            fixed_data.push(0);
-            fixed_data.extend_from_slice(&data[0..4]);
-            fixed_data.extend_from_slice(&[0; 4]);
-            fixed_data.extend_from_slice(&data[9..]);
-
-            // Vanilla compressed files are LZMA Alone (or legacy) level 3 compressed files, reproducible by compressing them
-            // with default settings with 7-Zip. This should do the trick to get them decoded.
-            let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
-            let mut encoder = XzDecoder::new_stream(&*fixed_data, stream);
-            let mut compress_data = vec![];
-            match encoder.read_to_end(&mut compress_data) {
-                Ok(_) => Ok(compress_data),
-                Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into())
-            }
+            let size = data.decode_integer_u32(0)? as usize;
+            let decompressed_data = match get_compression_format(data) {
+                CompressionFormat::Lzma1 => decompress_data_lzma1(data)?,
+                CompressionFormat::Lz4 => {
+                    let mut decompressed_data = Vec::with_capacity(size);
+                    FrameDecoder::new(&data[4..]).read_to_end(&mut decompressed_data).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+                    decompressed_data
+                }
+                CompressionFormat::Zstd => {
+                    let mut decompressed_data = Vec::with_capacity(size);
+                    zstd::stream::copy_decode(&data[4..], &mut decompressed_data).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+                    decompressed_data
+                }
+            };
+
+            // Make sure we got the data the header says we should have got.
+            if decompressed_data.len() == size { Ok(decompressed_data) }
+            else { Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into()) }
         }
         else { Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into()) }
     }
     else { Ok(vec![]) }
 }
 
-/// This function compress the data of a PackedFile, returning the compressed data.
+/// This function decompress LZMA1-compressed data, returning the decompressed data.
+fn decompress_data_lzma1(data: &[u8]) -> Result<Vec<u8>> {
+
+    // CA Tweaks their headers to remove 4 bytes per PackedFile, while losing +4GB File Compression Support.
+    // We need to fix their headers so the normal LZMA lib can read them.
+    let mut fixed_data: Vec<u8> = vec![];
+    fixed_data.extend_from_slice(&data[4..8]);
+    fixed_data.push(0);
+    fixed_data.extend_from_slice(&data[0..4]);
+    fixed_data.extend_from_slice(&[0; 4]);
+    fixed_data.extend_from_slice(&data[9..]);
+
+    // Vanilla compressed files are LZMA Alone (or legacy) level 3 compressed files, reproducible by compressing them
+    // with default settings with 7-Zip. This should do the trick to get them decoded.
+    let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+    let mut encoder = XzDecoder::new_stream(&*fixed_data, stream);
+    let mut compress_data = vec![];
+    match encoder.read_to_end(&mut compress_data) {
+        Ok(_) => Ok(compress_data),
+        Err(_) => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into())
+    }
+}
+
+/// This function decompress a raw LZMA1 stream without CA's header, like the ones inside compressed ESF nodes.
 ///
-/// Now, some explanation: CA uses Non-Streamed LZMA1 (or LZMA Alone) compressed files.
+/// The properties are the 5 bytes of LZMA1 properties (properties byte and dictionary size) the stream was compressed with.
+pub fn decompress_data_lzma1_raw(data: &[u8], properties: &[u8], size: u32) -> Result<Vec<u8>> {
+    if properties.len() != 5 {
+        return Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into());
+    }
+
+    // Turn it into a LZMA Alone file with a known size, so the normal LZMA lib can read it.
+    let mut fixed_data: Vec<u8> = Vec::with_capacity(data.len() + 13);
+    fixed_data.extend_from_slice(properties);
+    fixed_data.encode_integer_u64(u64::from(size));
+    fixed_data.extend_from_slice(data);
+
+    let stream = Stream::new_lzma_decoder(u64::MAX).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeDecompressed))?;
+    let mut decoder = XzDecoder::new_stream(&*fixed_data, stream);
+    let mut decompressed_data = Vec::with_capacity(size as usize);
+    match decoder.read_to_end(&mut decompressed_data) {
+        Ok(_) if decompressed_data.len() == size as usize => Ok(decompressed_data),
+        _ => Err(ErrorKind::PackedFileDataCouldNotBeDecompressed.into())
+    }
+}
+
+/// This function compress the data of a PackedFile with the provided format, returning the compressed data.
+///
+/// All formats use CA's header, which starts with the size of the uncompressed data as an u32, followed by:
+/// - LZMA1: the LZMA1 properties byte and the dictionary size, then the raw LZMA1 stream.
+/// - LZ4: a full LZ4 frame.
+/// - Zstd: a full Zstd frame.
+///
+/// Now, some explanation about LZMA1: CA uses Non-Streamed LZMA1 (or LZMA Alone) compressed files.
 /// Xz, the `standard` linux lib to deal with LZMA files has a fucking exception for
 /// Non-Streamed LZMA1 files. So we can decode from it, but not encode to it.
-/// So we do it the hard way: write the uncompressed file to disk, call 7z, compress it
-/// to 7z LZMA1 Level 3 format, read the compressed file, and remove the 7z part.
-/// Sadly, this means we have to ship 7z with RPFM. But hey, we're not the ones doing a
-/// fucking exception to a known format because we don't want to support the original format.
-pub fn compress_data(data: &[u8]) -> Result<Vec<u8>> {
-
-    match SETTINGS.read().unwrap().paths.get(ZIP_PATH) {
-        Some(Some(zip_path)) => {
-
-            // Prepare both paths, uncompressed and compressed.
-            let mut uncompressed_path = temp_dir();
-            let mut compressed_path = temp_dir();
-            uncompressed_path.push("frodo_best_waifu");
-            compressed_path.push("frodo_bestest_waifu.7z");
-
-            // Get the data into the uncompressed file, and launch 7z.
-            File::create(&uncompressed_path)?.write_all(data)?;
-            Command::new(zip_path).arg("a").arg("-m0=lzma").arg("-mx=3").arg(&compressed_path).arg(&uncompressed_path).output()?;
-
-            // Get the compressed LZMA data (and only that data) from the compressed file. To get it, we know:
-            // - The header of a 7z file is 32 bytes.
-            // - The bytes 12-16 are the offset of the footer from the end of the header.
-            // - We have just one file, so the offset is the exact length of that file.
-            // - Then we read the offset from the end of the header. And done.
-            let mut reader = BufReader::new(File::open(&compressed_path)?);
-            let mut footer_offset = vec![0; 4];
-            reader.seek(SeekFrom::Start(12))?;
-            reader.read_exact(&mut footer_offset)?;
-            let compressed_data_length = footer_offset.decode_integer_u32(0)?;
-
-            let mut compressed_data = vec![0; compressed_data_length as usize];
-            reader.seek(SeekFrom::Start(32))?;
-            reader.read_exact(&mut compressed_data)?;
-
-            let mut fixed_data = vec![];
-            fixed_data.encode_integer_i32(data.len() as i32);
-            fixed_data.extend_from_slice(&[0x5D, 0x00, 0x00, 0x40, 0x00]);
-            fixed_data.append(&mut compressed_data);
-
-            Ok(fixed_data)
+/// So we do it the hard way: we encode the raw LZMA1 stream with our own encoder, equivalent
+/// to 7-Zip's LZMA1 Level 3, and then we put CA's header before it.
+pub fn compress_data(data: &[u8], format: CompressionFormat) -> Result<Vec<u8>> {
+    let mut fixed_data = vec![];
+    fixed_data.encode_integer_u32(u32::try_from(data.len()).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?);
+
+    match format {
+        CompressionFormat::Lzma1 => {
+            fixed_data.push(PROPERTIES);
+            fixed_data.encode_integer_u32(DICTIONARY_SIZE);
+            fixed_data.append(&mut encode_raw(data));
+        }
+        CompressionFormat::Lz4 => {
+            let mut encoder = FrameEncoder::new(fixed_data);
+            encoder.write_all(data).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+            fixed_data = encoder.finish().map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+        }
+        CompressionFormat::Zstd => {
+            let mut encoder = zstd::Encoder::new(fixed_data, ZSTD_LEVEL).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+            encoder.include_checksum(true).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+            encoder.set_pledged_src_size(Some(data.len() as u64)).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+            encoder.write_all(data).map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
+            fixed_data = encoder.finish().map_err(|_| Error::from(ErrorKind::PackedFileDataCouldNotBeCompressed))?;
         }
-        _ => Err(ErrorKind::ZipFolderNotFound.into())
     }
+
+    Ok(fixed_data)
+}
+
+/// This function compress the provided data into a raw LZMA1 stream without CA's header, like the ones inside compressed ESF nodes.
+///
+/// It returns the stream and the 5 bytes of LZMA1 properties (properties byte and dictionary size) needed to decompress it.
+pub fn compress_data_lzma1_raw(data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let mut properties = vec![PROPERTIES];
+    properties.encode_integer_u32(DICTIONARY_SIZE);
+    (encode_raw(data), properties)
 }
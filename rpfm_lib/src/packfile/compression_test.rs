@@ -0,0 +1,102 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Compression` module.
+!*/
+
+use super::*;
+
+/// Text-like data, binary data with repeated structures, incompressible data, data repeating at every distance and the corner cases.
+fn get_compression_samples() -> Vec<Vec<u8>> {
+    let text = "wh_main_emp_empire;wh_main_emp_inf_swordsmen;wh_main_emp_inf_halberdiers;\r\n".repeat(500).into_bytes();
+    let binary = (0..200_000u32).flat_map(|x| (x % 1024).to_le_bytes()).collect::<Vec<u8>>();
+    let noise = (0..100_000u64).map(|x| (x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407) >> 56) as u8).collect::<Vec<u8>>();
+    let periods = (1..300usize).flat_map(|period| (0..period * 4).map(move |x| ((x % period) * 31 + period) as u8)).collect::<Vec<u8>>();
+    vec![vec![0], vec![1, 2], vec![7; 300], text, binary, noise, periods]
+}
+
+/// Test to make sure the LZMA1 encoder produces data the decoder can read back, with the header the game expects.
+#[test]
+fn test_compression_lzma_roundtrip() {
+    for sample in &get_compression_samples() {
+        let compressed = compress_data(sample, CompressionFormat::Lzma1).unwrap();
+        assert_eq!(&compressed[4..9], &[0x5D, 0x00, 0x00, 0x40, 0x00]);
+        assert_eq!(get_compression_format(&compressed), CompressionFormat::Lzma1);
+        assert_eq!(decompress_data(&compressed).unwrap(), *sample);
+    }
+}
+
+/// Test to make sure a compressed PackFile, once saved and reloaded, decompresses its PackedFiles to the same data.
+#[test]
+fn test_compression_lzma_packfile() {
+    let mut pack_file_base = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    pack_file_base.set_file_path(&PathBuf::from("../test_files/PFH5_test_compression.pack")).unwrap();
+    let mut pack_file_new = pack_file_base.clone();
+    pack_file_new.toggle_compression(true);
+    pack_file_new.save(Some(PathBuf::from("../test_files/PFH5_test_compression.pack"))).unwrap();
+
+    let mut pack_file_new = PackFile::read(&PathBuf::from("../test_files/PFH5_test_compression.pack"), false).unwrap();
+    for packed_file in pack_file_base.get_ref_mut_packed_files_all() {
+        let path = packed_file.get_path().to_vec();
+        let data = packed_file.get_raw_data().unwrap();
+        assert_eq!(pack_file_new.get_ref_mut_packed_file_by_path(&path).unwrap().get_raw_data().unwrap(), data);
+    }
+}
+
+/// Test to make sure the compression rules of a PackFile are parsed properly, and decide which PackedFiles get compressed when saving.
+#[test]
+fn test_compression_rules() {
+
+    // Rules must parse, print back as they were written, and reject garbage.
+    let rule = CompressionRule::from_line("skip;db/**_tables").unwrap();
+    assert_eq!(rule.to_string(), "skip;db/**_tables");
+    assert!(rule.is_match(&["db".to_owned(), "land_units_tables".to_owned(), "mod".to_owned()]));
+    assert!(!rule.is_match(&["text".to_owned(), "db".to_owned(), "mod.loc".to_owned()]));
+    assert!(CompressionRule::from_line("compress;*.LOC").unwrap().is_match(&["text".to_owned(), "db".to_owned(), "mod.loc".to_owned()]));
+    assert!(CompressionRule::from_line("maybe;*.loc").is_err());
+    assert!(CompressionRule::from_line("compress;").is_err());
+
+    // Compress everything but the first PackedFile, and check only that one ends up uncompressed.
+    let mut pack_file = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    let paths = pack_file.get_ref_packed_files_all().iter().map(|x| x.get_path().join("/")).collect::<Vec<String>>();
+    let mut settings = pack_file.get_settings().clone();
+    settings.settings_text.insert("compression_rules".to_owned(), format!("# Comment\ncompress;**\n\nskip;{}", paths[0]));
+    pack_file.set_settings(&settings);
+    pack_file.save(Some(PathBuf::from("../test_files/PFH5_test_compression_rules.pack"))).unwrap();
+
+    let pack_file = PackFile::read(&PathBuf::from("../test_files/PFH5_test_compression_rules.pack"), false).unwrap();
+    assert_eq!(pack_file.get_settings().get_compression_rules().unwrap().len(), 2);
+    for packed_file in pack_file.get_ref_packed_files_all() {
+        assert_eq!(packed_file.get_ref_raw().get_compression_state(), packed_file.get_path().join("/") != paths[0]);
+    }
+}
+
+/// Test to make sure LZ4 data round-trips, keeping the uncompressed size at the start of the data.
+#[test]
+fn test_compression_lz4_roundtrip() {
+    for sample in &get_compression_samples() {
+        let compressed = compress_data(sample, CompressionFormat::Lz4).unwrap();
+        assert_eq!(&compressed[0..4], &(sample.len() as u32).to_le_bytes());
+        assert_eq!(get_compression_format(&compressed), CompressionFormat::Lz4);
+        assert_eq!(decompress_data(&compressed).unwrap(), *sample);
+    }
+}
+
+/// Test to make sure Zstd data round-trips, keeping the uncompressed size at the start of the data.
+#[test]
+fn test_compression_zstd_roundtrip() {
+    for sample in &get_compression_samples() {
+        let compressed = compress_data(sample, CompressionFormat::Zstd).unwrap();
+        assert_eq!(&compressed[0..4], &(sample.len() as u32).to_le_bytes());
+        assert_eq!(get_compression_format(&compressed), CompressionFormat::Zstd);
+        assert_eq!(decompress_data(&compressed).unwrap(), *sample);
+    }
+}
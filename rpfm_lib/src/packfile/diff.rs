@@ -0,0 +1,427 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to compare two `PackFiles`.
+
+This module contains the structs used to represent the differences between two `PackFiles`,
+going down to the row level for Table-like `PackedFiles`.
+!*/
+
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{HashMap, HashSet};
+use std::{fmt, fmt::Display};
+use std::mem::discriminant;
+
+use rpfm_error::Result;
+
+use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::table::DecodedData;
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::Definition;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the differences between two `PackFiles`.
+///
+/// All the changes are from the point of view of the first (old) `PackFile`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackFileDiff {
+
+    /// Paths of the PackedFiles only present in the new `PackFile`.
+    added: Vec<Vec<String>>,
+
+    /// Paths of the PackedFiles only present in the old `PackFile`.
+    removed: Vec<Vec<String>>,
+
+    /// PackedFiles present in both `PackFiles`, but with different data.
+    modified: Vec<PackedFileDiff>,
+}
+
+/// This struct contains the differences between two versions of the same `PackedFile`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PackedFileDiff {
+
+    /// Path of the PackedFile.
+    path: Vec<String>,
+
+    /// Row-level differences, if both versions of the PackedFile could be decoded as tables.
+    table: Option<TableDiff>,
+}
+
+/// This struct contains the row-level differences between two versions of the same table.
+///
+/// Rows are matched by the key fields of the new definition. If the table has no key fields, all the columns are used as key.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableDiff {
+
+    /// Name of the columns used to match rows.
+    key_columns: Vec<String>,
+
+    /// Name of the columns only present in the new version of the table.
+    added_columns: Vec<String>,
+
+    /// Name of the columns only present in the old version of the table.
+    removed_columns: Vec<String>,
+
+    /// Rows only present in the new version of the table.
+    added_rows: Vec<RowDiff>,
+
+    /// Rows only present in the old version of the table.
+    removed_rows: Vec<RowDiff>,
+
+    /// Rows present in both versions of the table, but with different data.
+    modified_rows: Vec<RowDiff>,
+}
+
+/// This struct contains the differences of a row between two versions of the same table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RowDiff {
+
+    /// Values of the key columns of the row.
+    key: Vec<String>,
+
+    /// Changed cells. For added or removed rows, this contains all the cells of the row.
+    cells: Vec<CellDiff>,
+}
+
+/// This struct contains the change of a single cell of a row.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CellDiff {
+
+    /// Name of the column the cell belongs to.
+    column: String,
+
+    /// Old value of the cell. None if the row was added.
+    old_value: Option<String>,
+
+    /// New value of the cell. None if the row was removed.
+    new_value: Option<String>,
+}
+
+//---------------------------------------------------------------------------//
+//                              Implementations
+//---------------------------------------------------------------------------//
+
+/// Implementation of `PackFileDiff`.
+impl PackFileDiff {
+
+    /// This function compares two `PackFiles`, returning their differences.
+    pub fn new(old: &PackFile, new: &PackFile) -> Result<Self> {
+        let old_paths = old.get_ref_packed_files_all_paths().into_iter().collect::<HashSet<&[String]>>();
+        let new_paths = new.get_ref_packed_files_all_paths().into_iter().collect::<HashSet<&[String]>>();
+
+        let mut added = new_paths.difference(&old_paths).map(|path| path.to_vec()).collect::<Vec<Vec<String>>>();
+        let mut removed = old_paths.difference(&new_paths).map(|path| path.to_vec()).collect::<Vec<Vec<String>>>();
+        added.sort();
+        removed.sort();
+
+        let mut modified = old_paths.intersection(&new_paths)
+            .collect::<Vec<&&[String]>>()
+            .par_iter()
+            .filter_map(|path| {
+                let old_packed_file = old.get_ref_packed_file_by_path(path)?;
+                let new_packed_file = new.get_ref_packed_file_by_path(path)?;
+                PackedFileDiff::new(old_packed_file, new_packed_file).transpose()
+            })
+            .collect::<Result<Vec<PackedFileDiff>>>()?;
+        modified.sort_by(|x, y| x.path.cmp(&y.path));
+
+        Ok(Self {
+            added,
+            removed,
+            modified,
+        })
+    }
+
+    /// This function returns the paths of the PackedFiles only present in the new `PackFile`.
+    pub fn get_ref_added(&self) -> &[Vec<String>] {
+        &self.added
+    }
+
+    /// This function returns the paths of the PackedFiles only present in the old `PackFile`.
+    pub fn get_ref_removed(&self) -> &[Vec<String>] {
+        &self.removed
+    }
+
+    /// This function returns the differences of the PackedFiles present in both `PackFiles`.
+    pub fn get_ref_modified(&self) -> &[PackedFileDiff] {
+        &self.modified
+    }
+
+    /// This function returns if both `PackFiles` are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Implementation of `PackedFileDiff`.
+impl PackedFileDiff {
+
+    /// This function compares two versions of the same `PackedFile`, returning their differences, or None if their data is the same.
+    ///
+    /// If both versions can be decoded as tables, this also gets the row-level differences between them.
+    pub fn new(old: &PackedFile, new: &PackedFile) -> Result<Option<Self>> {
+        if old.get_ref_raw().get_data()? == new.get_ref_raw().get_data()? {
+            return Ok(None);
+        }
+
+        // If we cannot decode any of them (no schema, broken tables,...) we just report them as modified.
+        let table = match (old.decode_return_ref_no_cache(), new.decode_return_ref_no_cache()) {
+            (Ok(old_decoded), Ok(new_decoded)) => match (get_table_data(&old_decoded), get_table_data(&new_decoded)) {
+                (Some((old_definition, old_data)), Some((new_definition, new_data))) => Some(TableDiff::new(old_definition, old_data, new_definition, new_data)),
+                _ => None,
+            }
+            _ => None,
+        };
+
+        Ok(Some(Self {
+            path: old.get_path().to_vec(),
+            table,
+        }))
+    }
+
+    /// This function returns the path of the PackedFile.
+    pub fn get_ref_path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// This function returns the row-level differences of the PackedFile, if it's a table.
+    pub fn get_ref_table(&self) -> &Option<TableDiff> {
+        &self.table
+    }
+}
+
+/// Implementation of `TableDiff`.
+impl TableDiff {
+
+    /// This function compares two versions of the same table, returning their differences.
+    pub fn new(old_definition: &Definition, old_data: &[Vec<DecodedData>], new_definition: &Definition, new_data: &[Vec<DecodedData>]) -> Self {
+        let old_fields = old_definition.get_fields_processed();
+        let new_fields = new_definition.get_fields_processed();
+
+        // Columns are matched by name, so we can compare different versions of the same table.
+        let common_columns = new_fields.iter().enumerate()
+            .filter_map(|(new_index, new_field)| old_fields.iter()
+                .position(|old_field| old_field.get_name() == new_field.get_name())
+                .map(|old_index| (new_field.get_name().to_owned(), old_index, new_index)))
+            .collect::<Vec<(String, usize, usize)>>();
+
+        let added_columns = new_fields.iter().map(|x| x.get_name().to_owned()).filter(|x| common_columns.iter().all(|(name, _, _)| name != x)).collect();
+        let removed_columns = old_fields.iter().map(|x| x.get_name().to_owned()).filter(|x| common_columns.iter().all(|(name, _, _)| name != x)).collect();
+
+        let mut key_columns = common_columns.iter().filter(|(_, _, new_index)| new_fields[*new_index].get_is_key()).cloned().collect::<Vec<(String, usize, usize)>>();
+        if key_columns.is_empty() {
+            key_columns = common_columns.to_vec();
+        }
+
+        // Group the old rows by key, keeping their order, so we can match duplicated keys in order too.
+        let mut old_rows_by_key: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+        for (index, row) in old_data.iter().enumerate() {
+            let key = key_columns.iter().map(|(_, old_index, _)| row[*old_index].data_to_string()).collect();
+            old_rows_by_key.entry(key).or_default().push(index);
+        }
+        old_rows_by_key.values_mut().for_each(|indexes| indexes.reverse());
+
+        let mut added_rows = vec![];
+        let mut modified_rows = vec![];
+        for row in new_data {
+            let key = key_columns.iter().map(|(_, _, new_index)| row[*new_index].data_to_string()).collect::<Vec<String>>();
+            match old_rows_by_key.get_mut(&key).and_then(|indexes| indexes.pop()) {
+                Some(old_index) => {
+                    let old_row = &old_data[old_index];
+                    let cells = common_columns.iter()
+                        .filter(|(_, old_index, new_index)| !is_cell_equal(&old_row[*old_index], &row[*new_index]))
+                        .map(|(name, old_index, new_index)| CellDiff {
+                            column: name.to_owned(),
+                            old_value: Some(old_row[*old_index].data_to_string()),
+                            new_value: Some(row[*new_index].data_to_string()),
+                        })
+                        .collect::<Vec<CellDiff>>();
+
+                    if !cells.is_empty() {
+                        modified_rows.push(RowDiff { key, cells });
+                    }
+                }
+                None => {
+                    let cells = new_fields.iter().zip(row.iter())
+                        .map(|(field, value)| CellDiff {
+                            column: field.get_name().to_owned(),
+                            old_value: None,
+                            new_value: Some(value.data_to_string()),
+                        })
+                        .collect();
+
+                    added_rows.push(RowDiff { key, cells });
+                }
+            }
+        }
+
+        // Whatever old rows were not matched, have been removed.
+        let mut removed_indexes = old_rows_by_key.into_values().flatten().collect::<Vec<usize>>();
+        removed_indexes.sort_unstable();
+        let removed_rows = removed_indexes.iter().map(|index| {
+            let row = &old_data[*index];
+            RowDiff {
+                key: key_columns.iter().map(|(_, old_index, _)| row[*old_index].data_to_string()).collect(),
+                cells: old_fields.iter().zip(row.iter())
+                    .map(|(field, value)| CellDiff {
+                        column: field.get_name().to_owned(),
+                        old_value: Some(value.data_to_string()),
+                        new_value: None,
+                    })
+                    .collect(),
+            }
+        }).collect();
+
+        Self {
+            key_columns: key_columns.into_iter().map(|(name, _, _)| name).collect(),
+            added_columns,
+            removed_columns,
+            added_rows,
+            removed_rows,
+            modified_rows,
+        }
+    }
+
+    /// This function returns the name of the columns used to match rows.
+    pub fn get_ref_key_columns(&self) -> &[String] {
+        &self.key_columns
+    }
+
+    /// This function returns the name of the columns only present in the new version of the table.
+    pub fn get_ref_added_columns(&self) -> &[String] {
+        &self.added_columns
+    }
+
+    /// This function returns the name of the columns only present in the old version of the table.
+    pub fn get_ref_removed_columns(&self) -> &[String] {
+        &self.removed_columns
+    }
+
+    /// This function returns the rows only present in the new version of the table.
+    pub fn get_ref_added_rows(&self) -> &[RowDiff] {
+        &self.added_rows
+    }
+
+    /// This function returns the rows only present in the old version of the table.
+    pub fn get_ref_removed_rows(&self) -> &[RowDiff] {
+        &self.removed_rows
+    }
+
+    /// This function returns the rows present in both versions of the table, but with different data.
+    pub fn get_ref_modified_rows(&self) -> &[RowDiff] {
+        &self.modified_rows
+    }
+}
+
+/// Implementation of `RowDiff`.
+impl RowDiff {
+
+    /// This function returns the values of the key columns of the row.
+    pub fn get_ref_key(&self) -> &[String] {
+        &self.key
+    }
+
+    /// This function returns the changed cells of the row.
+    pub fn get_ref_cells(&self) -> &[CellDiff] {
+        &self.cells
+    }
+}
+
+/// Implementation of `CellDiff`.
+impl CellDiff {
+
+    /// This function returns the name of the column the cell belongs to.
+    pub fn get_ref_column(&self) -> &str {
+        &self.column
+    }
+
+    /// This function returns the old value of the cell, if any.
+    pub fn get_ref_old_value(&self) -> &Option<String> {
+        &self.old_value
+    }
+
+    /// This function returns the new value of the cell, if any.
+    pub fn get_ref_new_value(&self) -> &Option<String> {
+        &self.new_value
+    }
+}
+
+/// Display implementation of `PackFileDiff`, to get a human-readable report of the differences.
+impl Display for PackFileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for path in &self.added {
+            writeln!(f, "+ {}", path.join("/"))?;
+        }
+
+        for path in &self.removed {
+            writeln!(f, "- {}", path.join("/"))?;
+        }
+
+        for packed_file in &self.modified {
+            writeln!(f, "~ {}", packed_file.path.join("/"))?;
+
+            if let Some(ref table) = packed_file.table {
+                if !table.added_columns.is_empty() {
+                    writeln!(f, "    Columns added: {}", table.added_columns.join(", "))?;
+                }
+
+                if !table.removed_columns.is_empty() {
+                    writeln!(f, "    Columns removed: {}", table.removed_columns.join(", "))?;
+                }
+
+                for row in &table.added_rows {
+                    writeln!(f, "    + [{}] {}", row.key.join(", "), row.cells.iter().map(|cell| format!("{} = {:?}", cell.column, cell.new_value.as_deref().unwrap_or_default())).collect::<Vec<String>>().join(", "))?;
+                }
+
+                for row in &table.removed_rows {
+                    writeln!(f, "    - [{}]", row.key.join(", "))?;
+                }
+
+                for row in &table.modified_rows {
+                    writeln!(f, "    ~ [{}] {}", row.key.join(", "), row.cells.iter().map(|cell| format!("{}: {:?} => {:?}", cell.column, cell.old_value.as_deref().unwrap_or_default(), cell.new_value.as_deref().unwrap_or_default())).collect::<Vec<String>>().join(", "))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Util functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the definition and data of a decoded PackedFile, if it's Table-like.
+fn get_table_data(decoded: &DecodedPackedFile) -> Option<(&Definition, &[Vec<DecodedData>])> {
+    match decoded {
+        DecodedPackedFile::AnimFragment(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        DecodedPackedFile::AnimTable(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        DecodedPackedFile::DB(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        DecodedPackedFile::GroupFormations(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        DecodedPackedFile::Loc(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        DecodedPackedFile::MatchedCombat(data) => Some((data.get_ref_definition(), data.get_ref_table_data())),
+        _ => None,
+    }
+}
+
+/// This function checks if two cells contain the same value.
+///
+/// If the type of the column changed between versions, the values are compared as text.
+fn is_cell_equal(old: &DecodedData, new: &DecodedData) -> bool {
+    if discriminant(old) == discriminant(new) { old == new }
+    else { old.data_to_string() == new.data_to_string() }
+}
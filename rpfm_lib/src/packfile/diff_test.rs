@@ -0,0 +1,84 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Diff` module.
+!*/
+
+use std::path::PathBuf;
+
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::table_test::{get_test_definition, get_test_field, get_test_row};
+use crate::schema::FieldType;
+
+use super::*;
+use super::diff::TableDiff;
+use super::packedfile::RawPackedFile;
+
+/// Test to make sure the diff of two PackFiles finds the added, removed and modified PackedFiles.
+#[test]
+fn test_diff_packfile() {
+    let pack_file_old = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+    assert!(pack_file_old.diff(&pack_file_old).unwrap().is_empty());
+
+    // Remove a PackedFile, modify another one and add a new one.
+    let mut pack_file_new = pack_file_old.clone();
+    let paths = pack_file_old.get_packed_files_all_paths();
+    pack_file_new.remove_packed_file_by_path(&paths[0]);
+
+    let mut modified = pack_file_new.get_ref_packed_file_by_path(&paths[1]).unwrap().clone();
+    let mut data = modified.get_raw_data().unwrap();
+    data.push(0);
+    modified.get_ref_mut_raw().set_data(&data);
+    pack_file_new.add_packed_file(&modified, true).unwrap();
+
+    let added_path = vec!["text".to_owned(), "diff_test.txt".to_owned()];
+    let raw = RawPackedFile::read_from_vec(added_path.to_vec(), "test".to_owned(), 0, false, b"test".to_vec());
+    pack_file_new.add_packed_file(&PackedFile::new_from_raw(&raw), true).unwrap();
+
+    let diff = pack_file_old.diff(&pack_file_new).unwrap();
+    assert_eq!(diff.get_ref_added(), &[added_path]);
+    assert_eq!(diff.get_ref_removed(), &[paths[0].to_vec()]);
+    assert_eq!(diff.get_ref_modified().len(), 1);
+    assert_eq!(diff.get_ref_modified()[0].get_ref_path(), &paths[1][..]);
+}
+
+/// Test to make sure the diff of two tables matches the rows by key, and reports the changed columns and cells.
+#[test]
+fn test_diff_table() {
+    let mut definition_old = get_test_definition();
+    definition_old.get_ref_mut_fields().push(get_test_field("old_column", FieldType::Boolean, false, 2));
+
+    let mut definition_new = definition_old.clone();
+    definition_new.get_ref_mut_fields().pop();
+
+    let row = get_test_row;
+    let data_old = vec![row("unchanged", 1), row("modified", 2), row("removed", 3)].into_iter()
+        .map(|mut x| { x.push(DecodedData::Boolean(false)); x })
+        .collect::<Vec<Vec<DecodedData>>>();
+    let data_new = vec![row("added", 4), row("modified", 5), row("unchanged", 1)];
+
+    let diff = TableDiff::new(&definition_old, &data_old, &definition_new, &data_new);
+    assert_eq!(diff.get_ref_key_columns(), &["key".to_owned()]);
+    assert_eq!(diff.get_ref_removed_columns(), &["old_column".to_owned()]);
+    assert!(diff.get_ref_added_columns().is_empty());
+
+    assert_eq!(diff.get_ref_added_rows().len(), 1);
+    assert_eq!(diff.get_ref_added_rows()[0].get_ref_key(), &["added".to_owned()]);
+    assert_eq!(diff.get_ref_removed_rows().len(), 1);
+    assert_eq!(diff.get_ref_removed_rows()[0].get_ref_key(), &["removed".to_owned()]);
+
+    assert_eq!(diff.get_ref_modified_rows().len(), 1);
+    let cells = diff.get_ref_modified_rows()[0].get_ref_cells();
+    assert_eq!(cells.len(), 1);
+    assert_eq!(cells[0].get_ref_column(), "value");
+    assert_eq!(cells[0].get_ref_old_value(), &Some("2".to_owned()));
+    assert_eq!(cells[0].get_ref_new_value(), &Some("5".to_owned()));
+}
@@ -0,0 +1,525 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing a native encoder for LZMA1 raw streams.
+
+CA uses Non-Streamed LZMA1 (LZMA Alone) for compressed PackedFiles, with the properties `lc = 3`, `lp = 0`,
+`pb = 2` and a 4MB dictionary, which is what 7-Zip uses by default on level 3. The xz lib we use to decompress
+them cannot write that format without an end marker, so this module implements the encoder side by hand.
+
+The encoder is a simple one: a hash chain match finder, greedy parsing with one step of lazy matching,
+and support for repeated distances. It doesn't try to find the optimal parse like 7-Zip does, so the
+compression ratio is a bit lower, but the output is a standard LZMA1 stream any decoder can read.
+
+This module only generates the compressed payload. The header is written by the `compression` module.
+!*/
+
+/// Literal context bits (lc). Hardcoded, as it's what CA uses.
+const LITERAL_CONTEXT_BITS: usize = 3;
+
+/// Literal position bits (lp). Hardcoded, as it's what CA uses.
+const LITERAL_POS_BITS: usize = 0;
+
+/// Position bits (pb). Hardcoded, as it's what CA uses.
+const POS_BITS: usize = 2;
+
+/// Properties byte corresponding to the lc/lp/pb values above (0x5D).
+pub const PROPERTIES: u8 = ((POS_BITS * 5 + LITERAL_POS_BITS) * 9 + LITERAL_CONTEXT_BITS) as u8;
+
+/// Dictionary size used by CA's files (4MB, 7-Zip's default for level 3).
+pub const DICTIONARY_SIZE: u32 = 0x0040_0000;
+
+const POS_STATES: usize = 1 << POS_BITS;
+const POS_MASK: usize = POS_STATES - 1;
+const LITERAL_POS_MASK: usize = (1 << LITERAL_POS_BITS) - 1;
+
+const NUM_STATES: usize = 12;
+const NUM_LITERAL_STATES: usize = 7;
+
+const MATCH_LEN_MIN: usize = 2;
+const MATCH_LEN_MAX: usize = 273;
+
+const LEN_LOW_BITS: u32 = 3;
+const LEN_MID_BITS: u32 = 3;
+const LEN_HIGH_BITS: u32 = 8;
+const LEN_LOW_SYMBOLS: usize = 1 << LEN_LOW_BITS;
+const LEN_MID_SYMBOLS: usize = 1 << LEN_MID_BITS;
+
+const DIST_STATES: usize = 4;
+const DIST_SLOT_BITS: u32 = 6;
+const DIST_MODEL_START: usize = 4;
+const DIST_MODEL_END: usize = 14;
+const FULL_DISTANCES: usize = 1 << (DIST_MODEL_END / 2);
+const ALIGN_BITS: u32 = 4;
+const ALIGN_MASK: usize = (1 << ALIGN_BITS) - 1;
+
+const BIT_MODEL_TOTAL_BITS: u32 = 11;
+const BIT_MODEL_TOTAL: u16 = 1 << BIT_MODEL_TOTAL_BITS;
+const PROB_INIT: u16 = BIT_MODEL_TOTAL / 2;
+const MOVE_BITS: u32 = 5;
+const TOP_VALUE: u32 = 1 << 24;
+
+/// Amount of bits used for the hash table of the match finder.
+const HASH_BITS: u32 = 16;
+
+/// Max amount of positions we check in a hash chain before giving up. Higher is slower, but compresses better.
+const MAX_CHAIN_DEPTH: usize = 48;
+
+/// If we find a match this long, we stop searching and take it.
+const NICE_LEN: usize = 64;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the range encoder that writes the compressed bits.
+struct RangeEncoder {
+    low: u64,
+    range: u32,
+    cache: u8,
+    cache_size: u64,
+    output: Vec<u8>,
+}
+
+/// This struct contains the probabilities used to encode match lengths.
+struct LengthEncoder {
+    choice: u16,
+    choice_2: u16,
+    low: [[u16; LEN_LOW_SYMBOLS]; POS_STATES],
+    mid: [[u16; LEN_MID_SYMBOLS]; POS_STATES],
+    high: [u16; 1 << LEN_HIGH_BITS],
+}
+
+/// This struct contains the state of the encoder while it's compressing the data.
+struct LzmaEncoder<'a> {
+    data: &'a [u8],
+    rc: RangeEncoder,
+
+    /// Current state of the state machine (0-11).
+    state: usize,
+
+    /// Last four distances used. These are 0-based (distance - 1), like in the stream.
+    reps: [usize; 4],
+
+    is_match: [[u16; POS_STATES]; NUM_STATES],
+    is_rep: [u16; NUM_STATES],
+    is_rep_g0: [u16; NUM_STATES],
+    is_rep_g1: [u16; NUM_STATES],
+    is_rep_g2: [u16; NUM_STATES],
+    is_rep0_long: [[u16; POS_STATES]; NUM_STATES],
+    literal: Vec<u16>,
+    dist_slot: [[u16; 1 << DIST_SLOT_BITS]; DIST_STATES],
+    dist_special: [u16; FULL_DISTANCES - DIST_MODEL_END],
+    dist_align: [u16; 1 << ALIGN_BITS],
+    match_len: LengthEncoder,
+    rep_len: LengthEncoder,
+
+    /// Head of the hash chains. -1 means empty.
+    hash_head: Vec<i32>,
+
+    /// Previous position with the same hash for each position. -1 means none.
+    hash_prev: Vec<i32>,
+
+    /// Position up to which (not included) we have added positions to the hash chains.
+    hash_pos: usize,
+}
+
+//---------------------------------------------------------------------------//
+//                          Public functions
+//---------------------------------------------------------------------------//
+
+/// This function compresses the provided data into a raw LZMA1 stream, without header nor end marker.
+///
+/// As there is no end marker, the decoder needs to know the size of the uncompressed data to decode it.
+pub fn encode_raw(data: &[u8]) -> Vec<u8> {
+    let mut encoder = LzmaEncoder::new(data);
+    encoder.encode();
+    encoder.rc.finish()
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementations
+//---------------------------------------------------------------------------//
+
+impl RangeEncoder {
+
+    /// This function creates a new empty `RangeEncoder`.
+    fn new(capacity: usize) -> Self {
+        Self {
+            low: 0,
+            range: u32::MAX,
+            cache: 0,
+            cache_size: 1,
+            output: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// This function moves the top byte of `low` to the output, propagating the carry if needed.
+    fn shift_low(&mut self) {
+        if self.low < 0xFF00_0000 || self.low > 0xFFFF_FFFF {
+            let carry = (self.low >> 32) as u8;
+            let mut temp = self.cache;
+            loop {
+                self.output.push(temp.wrapping_add(carry));
+                temp = 0xFF;
+                self.cache_size -= 1;
+                if self.cache_size == 0 {
+                    break;
+                }
+            }
+            self.cache = (self.low >> 24) as u8;
+        }
+
+        self.cache_size += 1;
+        self.low = (self.low & 0x00FF_FFFF) << 8;
+    }
+
+    /// This function encodes a bit using the provided probability, and updates it.
+    fn encode_bit(&mut self, prob: &mut u16, bit: u32) {
+        let bound = (self.range >> BIT_MODEL_TOTAL_BITS) * *prob as u32;
+        if bit == 0 {
+            self.range = bound;
+            *prob += (BIT_MODEL_TOTAL - *prob) >> MOVE_BITS;
+        } else {
+            self.low += bound as u64;
+            self.range -= bound;
+            *prob -= *prob >> MOVE_BITS;
+        }
+
+        while self.range < TOP_VALUE {
+            self.range <<= 8;
+            self.shift_low();
+        }
+    }
+
+    /// This function encodes the lower `count` bits of `value` with fixed 50% probabilities, from the highest to the lowest.
+    fn encode_direct_bits(&mut self, value: u32, count: u32) {
+        for bit in (0..count).rev() {
+            self.range >>= 1;
+            if (value >> bit) & 1 == 1 {
+                self.low += self.range as u64;
+            }
+
+            while self.range < TOP_VALUE {
+                self.range <<= 8;
+                self.shift_low();
+            }
+        }
+    }
+
+    /// This function encodes the lower `bits` bits of `value` using a bit tree, from the highest bit to the lowest.
+    fn encode_tree(&mut self, probs: &mut [u16], bits: u32, value: u32) {
+        let mut index = 1;
+        for bit in (0..bits).rev() {
+            let bit = (value >> bit) & 1;
+            self.encode_bit(&mut probs[index], bit);
+            index = (index << 1) | bit as usize;
+        }
+    }
+
+    /// This function encodes the lower `bits` bits of `value` using a bit tree, from the lowest bit to the highest.
+    ///
+    /// Unlike in `encode_tree`, the first probability used is the one at index 0, so the special distances can share one array.
+    fn encode_reverse_tree(&mut self, probs: &mut [u16], bits: u32, mut value: u32) {
+        let mut index = 1;
+        for _ in 0..bits {
+            let bit = value & 1;
+            value >>= 1;
+            self.encode_bit(&mut probs[index - 1], bit);
+            index = (index << 1) | bit as usize;
+        }
+    }
+
+    /// This function flushes the pending bytes and returns the encoded data.
+    fn finish(mut self) -> Vec<u8> {
+        for _ in 0..5 {
+            self.shift_low();
+        }
+        self.output
+    }
+}
+
+impl LengthEncoder {
+
+    /// This function creates a new `LengthEncoder` with all its probabilities initialized.
+    fn new() -> Self {
+        Self {
+            choice: PROB_INIT,
+            choice_2: PROB_INIT,
+            low: [[PROB_INIT; LEN_LOW_SYMBOLS]; POS_STATES],
+            mid: [[PROB_INIT; LEN_MID_SYMBOLS]; POS_STATES],
+            high: [PROB_INIT; 1 << LEN_HIGH_BITS],
+        }
+    }
+
+    /// This function encodes a match length.
+    fn encode(&mut self, rc: &mut RangeEncoder, len: usize, pos_state: usize) {
+        let len = (len - MATCH_LEN_MIN) as u32;
+        if len < LEN_LOW_SYMBOLS as u32 {
+            rc.encode_bit(&mut self.choice, 0);
+            rc.encode_tree(&mut self.low[pos_state], LEN_LOW_BITS, len);
+        } else if len < (LEN_LOW_SYMBOLS + LEN_MID_SYMBOLS) as u32 {
+            rc.encode_bit(&mut self.choice, 1);
+            rc.encode_bit(&mut self.choice_2, 0);
+            rc.encode_tree(&mut self.mid[pos_state], LEN_MID_BITS, len - LEN_LOW_SYMBOLS as u32);
+        } else {
+            rc.encode_bit(&mut self.choice, 1);
+            rc.encode_bit(&mut self.choice_2, 1);
+            rc.encode_tree(&mut self.high, LEN_HIGH_BITS, len - (LEN_LOW_SYMBOLS + LEN_MID_SYMBOLS) as u32);
+        }
+    }
+}
+
+impl<'a> LzmaEncoder<'a> {
+
+    /// This function creates a new `LzmaEncoder` for the provided data.
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            rc: RangeEncoder::new(data.len() / 2 + 16),
+            state: 0,
+            reps: [0; 4],
+            is_match: [[PROB_INIT; POS_STATES]; NUM_STATES],
+            is_rep: [PROB_INIT; NUM_STATES],
+            is_rep_g0: [PROB_INIT; NUM_STATES],
+            is_rep_g1: [PROB_INIT; NUM_STATES],
+            is_rep_g2: [PROB_INIT; NUM_STATES],
+            is_rep0_long: [[PROB_INIT; POS_STATES]; NUM_STATES],
+            literal: vec![PROB_INIT; 0x300 << (LITERAL_CONTEXT_BITS + LITERAL_POS_BITS)],
+            dist_slot: [[PROB_INIT; 1 << DIST_SLOT_BITS]; DIST_STATES],
+            dist_special: [PROB_INIT; FULL_DISTANCES - DIST_MODEL_END],
+            dist_align: [PROB_INIT; 1 << ALIGN_BITS],
+            match_len: LengthEncoder::new(),
+            rep_len: LengthEncoder::new(),
+            hash_head: vec![-1; 1 << HASH_BITS],
+            hash_prev: vec![-1; data.len()],
+            hash_pos: 0,
+        }
+    }
+
+    /// This function encodes the entire data, choosing between literals, matches and repeated matches on each position.
+    fn encode(&mut self) {
+        let mut pos = 0;
+        while pos < self.data.len() {
+
+            // The first byte can only be a literal, as there is nothing before it to reference.
+            if pos == 0 {
+                self.encode_literal(pos);
+                pos += 1;
+                continue;
+            }
+
+            let (rep_len, rep_index) = self.find_rep_match(pos);
+            let (main_len, main_dist) = self.find_match(pos);
+
+            // Repeated distances are way cheaper to encode, so we prefer them even if they're a bit shorter.
+            if rep_len >= MATCH_LEN_MIN && rep_len + 1 >= main_len {
+                self.encode_rep_match(pos, rep_index, rep_len);
+                pos += rep_len;
+            }
+
+            // Short matches with big distances take more space than the literals they replace, so we skip them.
+            else if main_len >= 3 || (main_len == MATCH_LEN_MIN && main_dist < 0x80) {
+
+                // Lazy matching: if the next position has a clearly better match, use a literal here and take that one instead.
+                if main_len < NICE_LEN && pos + 1 < self.data.len() {
+                    let (next_len, _) = self.find_match(pos + 1);
+                    if next_len > main_len + 1 {
+                        self.encode_literal(pos);
+                        pos += 1;
+                        continue;
+                    }
+                }
+
+                self.encode_match(pos, main_dist, main_len);
+                pos += main_len;
+            }
+
+            else {
+                self.encode_literal(pos);
+                pos += 1;
+            }
+        }
+    }
+
+    /// This function returns the length of the match at the provided 0-based distance.
+    fn match_len_at(&self, pos: usize, dist: usize, max_len: usize) -> usize {
+        let source = pos - dist - 1;
+        self.data[source..source + max_len].iter()
+            .zip(self.data[pos..pos + max_len].iter())
+            .take_while(|(x, y)| x == y)
+            .count()
+    }
+
+    /// This function returns the length and index of the longest match using one of the repeated distances.
+    fn find_rep_match(&self, pos: usize) -> (usize, usize) {
+        let max_len = MATCH_LEN_MAX.min(self.data.len() - pos);
+        let mut best = (0, 0);
+        for (index, dist) in self.reps.iter().enumerate() {
+            if *dist < pos {
+                let len = self.match_len_at(pos, *dist, max_len);
+                if len > best.0 {
+                    best = (len, index);
+                }
+            }
+        }
+        best
+    }
+
+    /// This function returns the hash of the three bytes starting at the provided position.
+    fn hash(&self, pos: usize) -> usize {
+        let value = self.data[pos] as u32 | (self.data[pos + 1] as u32) << 8 | (self.data[pos + 2] as u32) << 16;
+        (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    /// This function adds all the positions before the provided one to the hash chains.
+    fn update_hash_chains(&mut self, pos: usize) {
+        while self.hash_pos < pos && self.hash_pos + 2 < self.data.len() {
+            let hash = self.hash(self.hash_pos);
+            self.hash_prev[self.hash_pos] = self.hash_head[hash];
+            self.hash_head[hash] = self.hash_pos as i32;
+            self.hash_pos += 1;
+        }
+    }
+
+    /// This function returns the length and 0-based distance of the longest match found for the provided position.
+    fn find_match(&mut self, pos: usize) -> (usize, usize) {
+        if pos + 2 >= self.data.len() {
+            return (0, 0);
+        }
+
+        self.update_hash_chains(pos);
+
+        let max_len = MATCH_LEN_MAX.min(self.data.len() - pos);
+        let mut best = (0, 0);
+        let mut candidate = self.hash_head[self.hash(pos)];
+        let mut depth = 0;
+
+        while candidate >= 0 && depth < MAX_CHAIN_DEPTH {
+            let dist = pos - candidate as usize - 1;
+            if dist >= DICTIONARY_SIZE as usize {
+                break;
+            }
+
+            // Quick check to discard candidates that cannot beat the current best.
+            if self.data[candidate as usize + best.0.min(max_len - 1)] == self.data[pos + best.0.min(max_len - 1)] {
+                let len = self.match_len_at(pos, dist, max_len);
+                if len > best.0 {
+                    best = (len, dist);
+                    if len >= NICE_LEN || len == max_len {
+                        break;
+                    }
+                }
+            }
+
+            candidate = self.hash_prev[candidate as usize];
+            depth += 1;
+        }
+
+        best
+    }
+
+    /// This function encodes the byte at the provided position as a literal.
+    fn encode_literal(&mut self, pos: usize) {
+        let pos_state = pos & POS_MASK;
+        self.rc.encode_bit(&mut self.is_match[self.state][pos_state], 0);
+
+        let prev_byte = if pos > 0 { self.data[pos - 1] as usize } else { 0 };
+        let base = 0x300 * (((pos & LITERAL_POS_MASK) << LITERAL_CONTEXT_BITS) + (prev_byte >> (8 - LITERAL_CONTEXT_BITS)));
+        let probs = &mut self.literal[base..base + 0x300];
+        let mut symbol = self.data[pos] as usize | 0x100;
+
+        // After a literal we encode the byte as-is. After a match, we encode it relative to the byte at the last distance used.
+        if self.state < NUM_LITERAL_STATES {
+            while symbol < 0x10000 {
+                self.rc.encode_bit(&mut probs[symbol >> 8], ((symbol >> 7) & 1) as u32);
+                symbol <<= 1;
+            }
+        } else {
+            let mut match_byte = self.data[pos - self.reps[0] - 1] as usize;
+            let mut offset = 0x100;
+            while symbol < 0x10000 {
+                match_byte <<= 1;
+                self.rc.encode_bit(&mut probs[offset + (match_byte & offset) + (symbol >> 8)], ((symbol >> 7) & 1) as u32);
+                symbol <<= 1;
+                offset &= !(match_byte ^ symbol);
+            }
+        }
+
+        self.state = if self.state < 4 { 0 } else if self.state < 10 { self.state - 3 } else { self.state - 6 };
+    }
+
+    /// This function encodes a match with a new distance.
+    fn encode_match(&mut self, pos: usize, dist: usize, len: usize) {
+        let pos_state = pos & POS_MASK;
+        self.rc.encode_bit(&mut self.is_match[self.state][pos_state], 1);
+        self.rc.encode_bit(&mut self.is_rep[self.state], 0);
+        self.match_len.encode(&mut self.rc, len, pos_state);
+
+        let dist_state = (len - MATCH_LEN_MIN).min(DIST_STATES - 1);
+        let dist_slot = get_dist_slot(dist);
+        self.rc.encode_tree(&mut self.dist_slot[dist_state], DIST_SLOT_BITS, dist_slot as u32);
+
+        if dist_slot >= DIST_MODEL_START {
+            let footer_bits = ((dist_slot >> 1) - 1) as u32;
+            let base = (2 | (dist_slot & 1)) << footer_bits;
+            let dist_reduced = (dist - base) as u32;
+
+            if dist_slot < DIST_MODEL_END {
+                self.rc.encode_reverse_tree(&mut self.dist_special[base - dist_slot..], footer_bits, dist_reduced);
+            } else {
+                self.rc.encode_direct_bits(dist_reduced >> ALIGN_BITS, footer_bits - ALIGN_BITS);
+                self.rc.encode_reverse_tree(&mut self.dist_align, ALIGN_BITS, dist_reduced & ALIGN_MASK as u32);
+            }
+        }
+
+        self.reps = [dist, self.reps[0], self.reps[1], self.reps[2]];
+        self.state = if self.state < NUM_LITERAL_STATES { 7 } else { 10 };
+    }
+
+    /// This function encodes a match using one of the last four distances.
+    fn encode_rep_match(&mut self, pos: usize, rep_index: usize, len: usize) {
+        let pos_state = pos & POS_MASK;
+        self.rc.encode_bit(&mut self.is_match[self.state][pos_state], 1);
+        self.rc.encode_bit(&mut self.is_rep[self.state], 1);
+
+        if rep_index == 0 {
+            self.rc.encode_bit(&mut self.is_rep_g0[self.state], 0);
+            self.rc.encode_bit(&mut self.is_rep0_long[self.state][pos_state], 1);
+        } else {
+            self.rc.encode_bit(&mut self.is_rep_g0[self.state], 1);
+            if rep_index == 1 {
+                self.rc.encode_bit(&mut self.is_rep_g1[self.state], 0);
+            } else {
+                self.rc.encode_bit(&mut self.is_rep_g1[self.state], 1);
+                self.rc.encode_bit(&mut self.is_rep_g2[self.state], rep_index as u32 - 2);
+            }
+
+            // The used distance goes to the front. The rest keep their order.
+            let dist = self.reps[rep_index];
+            self.reps.copy_within(0..rep_index, 1);
+            self.reps[0] = dist;
+        }
+
+        self.rep_len.encode(&mut self.rc, len, pos_state);
+        self.state = if self.state < NUM_LITERAL_STATES { 8 } else { 11 };
+    }
+}
+
+/// This function returns the distance slot of the provided 0-based distance.
+fn get_dist_slot(dist: usize) -> usize {
+    if dist < DIST_MODEL_START {
+        dist
+    } else {
+        let high_bit = (usize::BITS - 1 - dist.leading_zeros()) as usize;
+        (high_bit << 1) | ((dist >> (high_bit - 1)) & 1)
+    }
+}
@@ -25,8 +25,10 @@ use itertools::{Itertools, Either};
 use serde_derive::{Serialize, Deserialize};
 use serde_json::{from_slice, to_string_pretty};
 use rayon::prelude::*;
+use regex::Regex;
 use unicase::UniCase;
 
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::{fmt, fmt::Display};
 use std::fs::{DirBuilder, File};
@@ -42,20 +44,31 @@ use crate::SCHEMA;
 use crate::SETTINGS;
 use crate::common::{*, decoder::Decoder, encoder::Encoder};
 use crate::dependencies::Dependencies;
+use crate::diagnostics::rules::DiagnosticsRules;
 use crate::packfile::compression::*;
 use crate::packfile::crypto::*;
+use crate::packfile::diff::PackFileDiff;
 use crate::packfile::packedfile::*;
 use crate::packedfile::{DecodedPackedFile, PackedFileType};
-use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::{DecodedData, MergedTable, RowProvenance, Table, TableMergeConflict};
 use crate::packedfile::table::db::DB;
+use crate::packedfile::table::group_formations::{self, GroupFormations, TSV_NAME_GROUP_FORMATIONS};
 use crate::packedfile::table::loc::{Loc, TSV_NAME_LOC};
 use crate::packedfile::text::TextType;
 use crate::schema::Schema;
 
-mod compression;
+pub(crate) mod compression;
 mod crypto;
+pub mod diff;
+mod lzma;
 pub mod packedfile;
 
+#[cfg(test)]
+mod compression_test;
+
+#[cfg(test)]
+mod diff_test;
+
 #[cfg(test)]
 mod packfile_test;
 
@@ -69,6 +82,11 @@ const PFH0_PREAMBLE: &str = "PFH0"; // PFH0
 
 const MFH_PREAMBLE: &str = "MFH"; // Weird format of some packs downloaded from Steam.
 
+/// These are the names of the different compression formats, as used in the PackFile settings.
+const COMPRESSION_FORMAT_LZMA1: &str = "lzma1";
+const COMPRESSION_FORMAT_LZ4: &str = "lz4";
+const COMPRESSION_FORMAT_ZSTD: &str = "zstd";
+
 /// This one is the path in a `PackFile` where all the maps generated by Terry end up.
 const TERRY_MAP_PATH: [&str; 4] = ["terrain", "tiles", "battle", "_assembly_kit"];
 
@@ -248,7 +266,7 @@ pub enum PFHVersion {
 ///
 /// The types here are sorted in the same order they'll load when the game starts.
 /// The number in their docs is their numeric value when read from a PackFile.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PFHFileType {
 
     /// **(0)**: Used in CA PackFiles, not useful for modding.
@@ -292,7 +310,7 @@ pub enum PathType {
 
 /// This enum indicates the current state of the compression in the current PackFile.
 ///
-/// Despite compression being per-packedfile, we only support applying it to the full PackFile for now.
+/// Compression is per-packedfile, and which PackedFiles get compressed is decided on save by the `CompressionRule`s of the PackFile.
 /// Also, compression is only supported by `PFHVersion::PFH5` PackFiles.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CompressionState {
@@ -307,6 +325,23 @@ pub enum CompressionState {
     Disabled,
 }
 
+/// This enum represents the different formats a PackedFile can be compressed with.
+///
+/// When reading, the format of each PackedFile is detected from its data. When saving, all the PackedFiles that need
+/// to be compressed use the format set in the settings of the PackFile, as long as the `GameSelected` supports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionFormat {
+
+    /// LZMA1 (or LZMA Alone) compression. Supported by all games that support compression.
+    Lzma1,
+
+    /// LZ4 compression, using the LZ4 frame format. Only supported by recent games.
+    Lz4,
+
+    /// Zstd compression. Only supported by recent games.
+    Zstd,
+}
+
 /// This struct hold PackFile-specific settings.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PackFileSettings {
@@ -320,6 +355,23 @@ pub struct PackFileSettings {
     pub settings_number: BTreeMap<String, i32>,
 }
 
+/// This struct represents a rule to decide if the PackedFiles matching it should be compressed or not.
+///
+/// Rules are stored in the `compression_rules` setting of the PackFile, one per line, with the format `compress;pattern` or `skip;pattern`.
+/// The syntax of the pattern is explained in `get_path_pattern_regex`. If more than one rule matches a PackedFile, the last one wins.
+#[derive(Clone, Debug)]
+pub struct CompressionRule {
+
+    /// If the PackedFiles matching this rule should be compressed or not.
+    compress: bool,
+
+    /// The pattern of the rule, as the user wrote it.
+    pattern: String,
+
+    /// The regex built from the pattern, to match paths against it.
+    regex: Regex,
+}
+
 //---------------------------------------------------------------------------//
 //                             Enum Implementations
 //---------------------------------------------------------------------------//
@@ -395,6 +447,40 @@ impl PFHVersion {
     }
 }
 
+/// Implementation of `CompressionFormat`.
+impl CompressionFormat {
+
+    /// This function returns the name of the `CompressionFormat`, as used in the PackFile settings.
+    pub fn get_value(&self) -> &str {
+        match *self {
+            CompressionFormat::Lzma1 => COMPRESSION_FORMAT_LZMA1,
+            CompressionFormat::Lz4 => COMPRESSION_FORMAT_LZ4,
+            CompressionFormat::Zstd => COMPRESSION_FORMAT_ZSTD,
+        }
+    }
+
+    /// This function returns the `CompressionFormat` corresponding to the provided name, or an error if the provided name is not a valid `CompressionFormat`.
+    pub fn get_format(value: &str) -> Result<Self> {
+        match &*value.trim().to_lowercase() {
+            COMPRESSION_FORMAT_LZMA1 => Ok(CompressionFormat::Lzma1),
+            COMPRESSION_FORMAT_LZ4 => Ok(CompressionFormat::Lz4),
+            COMPRESSION_FORMAT_ZSTD => Ok(CompressionFormat::Zstd),
+            _ => Err(ErrorKind::InvalidCompressionFormat(value.to_owned()).into()),
+        }
+    }
+}
+
+/// Display implementation of `CompressionFormat`.
+impl Display for CompressionFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompressionFormat::Lzma1 => write!(f, "LZMA1"),
+            CompressionFormat::Lz4 => write!(f, "LZ4"),
+            CompressionFormat::Zstd => write!(f, "Zstd"),
+        }
+    }
+}
+
 /// Display implementation of `PFHVersion`.
 impl Display for PFHVersion {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -740,6 +826,12 @@ impl PackFile {
                                         }
                                         *path_as_packed_file.last_mut().unwrap() = packed_file_name.to_owned() + ".loc";
                                         RawPackedFile::read_from_vec(path_as_packed_file, packed_file_name, 0, false, raw_data)
+                                    } else if table_type == TSV_NAME_GROUP_FORMATIONS {
+                                        let (table, _) = GroupFormations::import_tsv(schema, path_as_file)?;
+                                        let raw_data = table.save()?;
+                                        let packed_file_name = group_formations::PATH.to_owned();
+                                        *path_as_packed_file.last_mut().unwrap() = packed_file_name.to_owned();
+                                        RawPackedFile::read_from_vec(path_as_packed_file, packed_file_name, 0, false, raw_data)
                                     } else {
                                         let (table, _) = DB::import_tsv(schema, path_as_file)?;
                                         let raw_data = table.save()?;
@@ -909,6 +1001,12 @@ impl PackFile {
                                                         }
                                                         *new_path.last_mut().unwrap() = packed_file_name.to_owned() + ".loc";
                                                         RawPackedFile::read_from_vec(new_path, packed_file_name, 0, false, raw_data)
+                                                    } else if table_type == TSV_NAME_GROUP_FORMATIONS {
+                                                        let (table, _) = GroupFormations::import_tsv(schema, file_path)?;
+                                                        let raw_data = table.save()?;
+                                                        let packed_file_name = group_formations::PATH.to_owned();
+                                                        *new_path.last_mut().unwrap() = packed_file_name.to_owned();
+                                                        RawPackedFile::read_from_vec(new_path, packed_file_name, 0, false, raw_data)
                                                     } else {
                                                         let (table, _) = DB::import_tsv(schema, file_path)?;
                                                         let raw_data = table.save()?;
@@ -1055,6 +1153,13 @@ impl PackFile {
         &self.file_path
     }
 
+    /// This function compares two PackFiles by the order the game loads them: first by `PFHFileType`, then by file name.
+    ///
+    /// In case of conflict, files from the PackFile that loads later override the ones from the PackFile that loads earlier.
+    pub fn cmp_by_load_order(&self, other: &Self) -> Ordering {
+        self.pfh_file_type.cmp(&other.pfh_file_type).then_with(|| self.get_file_name().cmp(&other.get_file_name()))
+    }
+
     /// This function changes the path of the PackFile.
     ///
     /// This can fail if you pass it an empty path.
@@ -1351,6 +1456,13 @@ impl PackFile {
         self.packed_files.par_iter().map(|x| x.get_path()).collect()
     }
 
+    /// This function compares this `PackFile` with the provided one, returning the differences between them.
+    ///
+    /// This `PackFile` is considered the old one. For Table-like `PackedFiles`, the differences are reported row by row.
+    pub fn diff(&self, other: &Self) -> Result<PackFileDiff> {
+        PackFileDiff::new(self, other)
+    }
+
     /// This function returns a copy of the paths of all the `PackedFiles` in the provided `PackFile` as Strings.
     pub fn get_packed_files_all_paths_as_string(&self) -> HashSet<UniCase<String>> {
         self.packed_files.par_iter().map(|x| UniCase::new(x.get_path().join("/"))).collect()
@@ -1728,11 +1840,36 @@ impl PackFile {
         Ok(files_extracted)
     }
 
-    /// This function enables/disables compression in all `PackedFiles` inside the `PackFile`. Partial compression is not supported.
+    /// This function enables/disables compression in all `PackedFiles` inside the `PackFile`.
+    ///
+    /// Keep in mind that the compression rules of the `PackFile` take priority over this when saving.
     pub fn toggle_compression(&mut self, enable: bool) {
         self.packed_files.par_iter_mut().for_each(|x| x.get_ref_mut_raw().set_should_be_compressed(enable));
     }
 
+    /// This function marks the `PackedFiles` that should be compressed on save, according to the compression rules of the `PackFile`.
+    ///
+    /// PackedFiles not matching any rule keep their current state, except tables, which are never compressed unless a rule says so.
+    /// If the `PackFile` doesn't support compression, this disables compression for all its `PackedFiles`.
+    pub fn apply_compression_rules(&mut self) -> Result<()> {
+        let rules = self.settings.get_compression_rules()?;
+        let is_compression_supported = self.pfh_version == PFHVersion::PFH5;
+
+        self.packed_files.par_iter_mut().for_each(|packed_file| {
+            let should_be_compressed = is_compression_supported && match rules.iter().rev().find(|rule| rule.is_match(packed_file.get_path())) {
+                Some(rule) => rule.get_compress(),
+                None => {
+                    let is_table = matches!(PackedFileType::get_packed_file_type(packed_file.get_ref_raw(), false), PackedFileType::DB | PackedFileType::Loc);
+                    !is_table && packed_file.get_ref_raw().get_should_be_compressed()
+                }
+            };
+
+            packed_file.get_ref_mut_raw().set_should_be_compressed(should_be_compressed);
+        });
+
+        Ok(())
+    }
+
     /// This function returns the notes contained within the provided `PackFile`.
     pub fn get_notes(&self) -> &Option<String> {
         &self.notes
@@ -2083,6 +2220,117 @@ impl PackFile {
         self.add_packed_file(&packed_file, true)
     }
 
+    /// This function returns the effective version of the provided table once vanilla, parent and this `PackFile` files are loaded, with the source of each row.
+    ///
+    /// This `PackFile` is loaded last, so its tables overwrite dependency tables with the same path. Rows with the same keys are resolved
+    /// like the game does, keeping the one from the table file that sorts first.
+    /// Table name must end in _tables.
+    pub fn get_merged_db_table(&self, table_name: &str, dependencies: &Dependencies) -> Result<MergedTable> {
+        let packed_files = self.get_ref_packed_files_by_path_start(&["db".to_owned(), table_name.to_owned()]);
+
+        let schema = SCHEMA.read().unwrap();
+        let mut tables = packed_files.iter().filter_map(|packed_file| {
+            let decoded = match *schema {
+                Some(ref schema) => packed_file.decode_return_ref_no_cache_no_locks(schema).ok(),
+                None => packed_file.get_decoded_from_memory().ok().cloned(),
+            };
+
+            if let Some(DecodedPackedFile::DB(db)) = decoded {
+                Some((packed_file.get_path().join("/"), self.get_file_name(), db))
+            } else { None }
+        }).collect::<Vec<(String, String, DB)>>();
+
+        // Dependency tables with the same path as one of ours are never loaded by the game.
+        let mut dependency_tables = dependencies.get_db_tables_with_path_from_cache(table_name, true, true)?;
+        dependency_tables.retain(|(path, _, _)| !tables.iter().any(|(local_path, _, _)| local_path == path));
+        dependency_tables.append(&mut tables);
+
+        let sources = dependency_tables.iter()
+            .map(|(path, pack, db)| (RowProvenance {
+                pack: pack.to_owned(),
+                path: path.split('/').map(|x| x.to_owned()).collect(),
+            }, db.get_ref_table()))
+            .collect::<Vec<(RowProvenance, &Table)>>();
+
+        MergedTable::new(&sources).ok_or_else(|| ErrorKind::MergedTableNotFound(table_name.to_owned()).into())
+    }
+
+    /// This function does a three-way merge of the DB and Loc tables of this `PackFile` (ours) with the ones of another `PackFile` (theirs),
+    /// using a third `PackFile` (base), like a vanilla PackFile or a previous release of the mod, as their common ancestor.
+    ///
+    /// Tables missing in any of the `PackFiles` are considered empty. Changes done only by one side are applied automatically.
+    /// Conflicting rows keep our version, and are returned grouped by the path of their table, so they can be resolved manually.
+    pub fn merge_tables_three_way(&mut self, base: &Self, theirs: &Self) -> Result<Vec<(Vec<String>, Vec<TableMergeConflict>)>> {
+
+        // Get the schema, as we'll need it unlocked to decode all the files fast.
+        let schema = SCHEMA.read().unwrap();
+        let schema = if let Some(ref schema) = *schema { schema } else { return Err(ErrorKind::SchemaNotFound.into()) };
+
+        let packed_file_types = [PackedFileType::DB, PackedFileType::Loc];
+        let mut paths = self.get_ref_packed_files_by_types(&packed_file_types, false).iter().map(|x| x.get_path().to_vec()).collect::<Vec<Vec<String>>>();
+        for path in theirs.get_ref_packed_files_by_types(&packed_file_types, false).iter().map(|x| x.get_path()) {
+            if !paths.iter().any(|x| x == path) {
+                paths.push(path.to_vec());
+            }
+        }
+
+        let mut conflicts = vec![];
+        for path in &paths {
+            let ours_decoded = match self.get_ref_mut_packed_file_by_path(path) {
+                Some(packed_file) => Some(packed_file.decode_return_ref_no_locks(schema)?.clone()),
+                None => None,
+            };
+            let base_decoded = match base.get_ref_packed_file_by_path(path) {
+                Some(packed_file) => Some(packed_file.decode_return_ref_no_cache_no_locks(schema)?),
+                None => None,
+            };
+            let theirs_decoded = match theirs.get_ref_packed_file_by_path(path) {
+                Some(packed_file) => Some(packed_file.decode_return_ref_no_cache_no_locks(schema)?),
+                None => None,
+            };
+
+            // Missing tables are empty tables, so we can detect rows deleted by removing the full table.
+            let mut tables = vec![];
+            for decoded in [&base_decoded, &ours_decoded, &theirs_decoded] {
+                tables.push(match decoded {
+                    Some(DecodedPackedFile::DB(table)) => Some(table.get_ref_table().clone()),
+                    Some(DecodedPackedFile::Loc(table)) => Some(table.get_ref_table().clone()),
+                    Some(_) => return Err(ErrorKind::InvalidFilesForMerging.into()),
+                    None => None,
+                });
+            }
+
+            let definition = tables.iter().flatten().next().unwrap().get_definition();
+            let tables = tables.into_iter().map(|x| x.unwrap_or_else(|| Table::new(&definition))).collect::<Vec<Table>>();
+            let (merged_table, table_conflicts) = Table::merge(&tables[0], &tables[1], &tables[2]);
+
+            if ours_decoded.is_none() && merged_table.get_entry_count() == 0 {
+                continue;
+            }
+
+            let template = ours_decoded.as_ref().or(theirs_decoded.as_ref()).or(base_decoded.as_ref()).unwrap();
+            let merged_decoded = match template {
+                DecodedPackedFile::DB(table) => {
+                    let mut db = DB::new(&table.name, Some(&table.get_uuid()), merged_table.get_ref_definition());
+                    db.set_table_data(merged_table.get_ref_table_data())?;
+                    DecodedPackedFile::DB(db)
+                }
+                _ => {
+                    let mut loc = Loc::new(merged_table.get_ref_definition());
+                    loc.set_table_data(merged_table.get_ref_table_data())?;
+                    DecodedPackedFile::Loc(loc)
+                }
+            };
+
+            self.add_packed_file(&PackedFile::new_from_decoded(&merged_decoded, path), true)?;
+            if !table_conflicts.is_empty() {
+                conflicts.push((path.to_vec(), table_conflicts));
+            }
+        }
+
+        Ok(conflicts)
+    }
+
     /// This function is used to optimize a `PackFile` by removing extra useless data from it.
     ///
     /// Currently, this function does the following:
@@ -2440,6 +2688,20 @@ impl PackFile {
                                 }
                                 else { error_files.push(path.to_string_lossy().to_string()); }
                             }
+                            TSV_NAME_GROUP_FORMATIONS => {
+                                if let Ok((table, file_path)) = GroupFormations::import_tsv(schema, path) {
+
+                                    // There is only one GroupFormations file per game, so we always replace the existing one.
+                                    let path = file_path.unwrap_or_else(|| vec![group_formations::PATH.to_owned()]);
+                                    if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
+                                    packed_files.retain(|x| x.get_path() != &*path);
+
+                                    let mut packed_file = PackedFile::new(path, self.get_file_name());
+                                    packed_file.set_decoded(&DecodedPackedFile::GroupFormations(table));
+                                    packed_files.push(packed_file);
+                                }
+                                else { error_files.push(path.to_string_lossy().to_string()); }
+                            }
                             _ => {
                                 if let Ok((table, file_name)) = DB::import_tsv(schema, path) {
 
@@ -2569,6 +2831,22 @@ impl PackFile {
                                     Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
                                 }
                             }
+                            DecodedPackedFile::GroupFormations(data) => {
+                                let mut name = format!("{}.tsv", path.last().unwrap());
+                                let mut export_path = export_path.to_path_buf();
+
+                                let mut index = 1;
+                                while exported_files.contains(&name) {
+                                    name = format!("{}_{}.tsv", path.last().unwrap(), index);
+                                    index += 1;
+                                }
+
+                                export_path.push(&name);
+                                match data.export_tsv(&export_path, &path) {
+                                    Ok(_) => exported_files.push(name),
+                                    Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
+                                }
+                            }
 
                             // Ignore any other PackedFiles.
                             _ => {}
@@ -3019,6 +3297,12 @@ impl PackFile {
     /// If no path is passed, the `PackFile` will be saved in his current path.
     /// If a path is passed as `new_path` the `PackFile` will be saved in that path.
     pub fn save(&mut self, new_path: Option<PathBuf>) -> Result<()> {
+        let compression_formats_supported = GAME_SELECTED.read().unwrap().get_compression_formats_supported().to_vec();
+        self.save_with_compression_formats(new_path, &compression_formats_supported)
+    }
+
+    /// This function tries to save a `PackFile` to a file in the filesystem, failing if it has to compress data with a format not in the provided list.
+    pub(crate) fn save_with_compression_formats(&mut self, new_path: Option<PathBuf>, compression_formats_supported: &[CompressionFormat]) -> Result<()> {
 
         // If any of the problematic masks in the header is set or is one of CA's, return an error.
         if !self.is_editable(*SETTINGS.read().unwrap().settings_bool.get("allow_editing_of_ca_packfiles").unwrap()) { return Err(ErrorKind::PackFileIsNonEditable.into()) }
@@ -3027,6 +3311,12 @@ impl PackFile {
         if let Some(path) = new_path { self.set_file_path(&path)?; }
         else if !self.get_file_path().is_file() { return Err(ErrorKind::PackFileIsNotAFile.into()) }
 
+        // Decide what needs to be compressed before touching the data. Data already compressed in the right format is kept as it is,
+        // so we only need the game to support the format if we have to compress something with it.
+        self.apply_compression_rules()?;
+        let compression_format = self.settings.get_compression_format()?;
+        let compression_format_supported = compression_formats_supported.contains(&compression_format);
+
         // We ensure that all the data is loaded and in his right form (compressed/encrypted) before attempting to save.
         // We need to do this here because we need later on their compressed size.
         for packed_file in &mut self.packed_files {
@@ -3035,29 +3325,35 @@ impl PackFile {
             packed_file.encode()?;
 
             // Remember: first compress (only PFH5), then encrypt.
-            let is_compressible = !matches!(PackedFileType::get_packed_file_type(packed_file.get_ref_raw(), false), PackedFileType::DB | PackedFileType::Loc);
             let (_, data, is_compressed, is_encrypted, should_be_compressed, should_be_encrypted) = packed_file.get_ref_mut_raw().get_data_and_info_from_memory()?;
 
-            // If, in any moment, we enabled/disabled the PackFile compression, compress/decompress the PackedFile. EXCEPT FOR TABLES. NEVER COMPRESS TABLES.
-            if !is_compressible {
-                *should_be_compressed = false;
+            // Encryption is not yet supported. Decrypt everything. This needs to be done first, as encrypted data may also be compressed.
+            if is_encrypted.is_some() {
+                *data = decrypt_packed_file(data);
+                *is_encrypted = None;
+                *should_be_encrypted = None;
             }
 
-            if *should_be_compressed && !*is_compressed {
-                *data = compress_data(data)?;
+            // If, in any moment, we enabled/disabled the compression of the PackedFile, or we changed the format to use, compress/decompress it.
+            if *should_be_compressed && *is_compressed && get_compression_format(data) != compression_format {
+                if !compression_format_supported {
+                    return Err(ErrorKind::CompressionFormatNotSupported(compression_format.to_string()).into());
+                }
+
+                *data = compress_data(&decompress_data(data)?, compression_format)?;
+            }
+            else if *should_be_compressed && !*is_compressed {
+                if !compression_format_supported {
+                    return Err(ErrorKind::CompressionFormatNotSupported(compression_format.to_string()).into());
+                }
+
+                *data = compress_data(data, compression_format)?;
                 *is_compressed = true;
             }
             else if !*should_be_compressed && *is_compressed {
                 *data = decompress_data(data)?;
                 *is_compressed = false;
             }
-
-            // Encryption is not yet supported. Decrypt everything.
-            if is_encrypted.is_some() {
-                *data = decrypt_packed_file(data);
-                *is_encrypted = None;
-                *should_be_encrypted = None;
-            }
         }
 
         // Only do this in non-vanilla files.
@@ -3306,12 +3602,15 @@ impl Default for PackFileSettings {
 
     fn default() -> Self {
         let mut settings_text = BTreeMap::new();
-        let settings_string = BTreeMap::new();
+        let mut settings_string = BTreeMap::new();
         let mut settings_bool = BTreeMap::new();
         let settings_number = BTreeMap::new();
 
         settings_text.insert("diagnostics_files_to_ignore".to_owned(), "".to_owned());
         settings_text.insert("import_files_to_ignore".to_owned(), "".to_owned());
+        settings_text.insert("compression_rules".to_owned(), "".to_owned());
+        settings_text.insert("diagnostics_rules".to_owned(), "".to_owned());
+        settings_string.insert("compression_format".to_owned(), COMPRESSION_FORMAT_LZMA1.to_owned());
         settings_bool.insert("disable_autosaves".to_owned(), false);
 
         Self {
@@ -3360,6 +3659,48 @@ impl PackFileSettings {
         Ok(settings)
     }
 
+    /// This function returns the compression rules of the PackFile, in the order they're defined.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. If any of the other lines is not a valid rule, this returns an error.
+    pub fn get_compression_rules(&self) -> Result<Vec<CompressionRule>> {
+        match self.settings_text.get("compression_rules") {
+            Some(rules) => rules.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(CompressionRule::from_line)
+                .collect(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// This function returns the format the PackedFiles of the PackFile should be compressed with.
+    pub fn get_compression_format(&self) -> Result<CompressionFormat> {
+        match self.settings_string.get("compression_format") {
+            Some(format) => CompressionFormat::get_format(format),
+            None => Ok(CompressionFormat::Lzma1),
+        }
+    }
+
+    /// This function sets the format the PackedFiles of the PackFile should be compressed with.
+    pub fn set_compression_format(&mut self, format: CompressionFormat) {
+        self.settings_string.insert("compression_format".to_owned(), format.get_value().to_owned());
+    }
+
+    /// This function replaces the compression rules of the PackFile with the provided ones.
+    pub fn set_compression_rules(&mut self, rules: &[CompressionRule]) {
+        self.settings_text.insert("compression_rules".to_owned(), rules.iter().map(|rule| rule.to_string()).join("\n"));
+    }
+
+    /// This function returns the diagnostics rules stored in the PackFile.
+    ///
+    /// If the rules are not valid TOML, or any of their path patterns is invalid, this returns an error.
+    pub fn get_diagnostics_rules(&self) -> Result<DiagnosticsRules> {
+        match self.settings_text.get("diagnostics_rules") {
+            Some(rules) if !rules.trim().is_empty() => DiagnosticsRules::from_toml(rules),
+            _ => Ok(DiagnosticsRules::default()),
+        }
+    }
+
     pub fn get_diagnostics_files_to_ignore(&self) -> Option<Vec<(Vec<String>, Vec<String>, Vec<String>)>> {
         self.settings_text.get("diagnostics_files_to_ignore").map(|files_to_ignore| {
             let files = files_to_ignore.split('\n').collect::<Vec<&str>>();
@@ -3385,6 +3726,53 @@ impl PackFileSettings {
     }
 }
 
+/// Implementation of CompressionRule.
+impl CompressionRule {
+
+    /// This function creates a new `CompressionRule` from its pattern.
+    pub fn new(compress: bool, pattern: &str) -> Result<Self> {
+        Ok(Self {
+            compress,
+            pattern: pattern.trim().to_owned(),
+            regex: get_path_pattern_regex(pattern)?,
+        })
+    }
+
+    /// This function parses a rule from its text form, which is `compress;pattern` or `skip;pattern`.
+    pub fn from_line(line: &str) -> Result<Self> {
+        match line.trim().split_once(';') {
+            Some((action, pattern)) if !pattern.trim().is_empty() => match action.trim().to_lowercase().as_ref() {
+                "compress" => Self::new(true, pattern),
+                "skip" => Self::new(false, pattern),
+                _ => Err(ErrorKind::InvalidCompressionRule(line.to_owned()).into()),
+            }
+            _ => Err(ErrorKind::InvalidCompressionRule(line.to_owned()).into()),
+        }
+    }
+
+    /// This function returns if the PackedFiles matching this rule should be compressed or not.
+    pub fn get_compress(&self) -> bool {
+        self.compress
+    }
+
+    /// This function returns the pattern of this rule.
+    pub fn get_ref_pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// This function checks if the provided PackedFile path matches this rule.
+    pub fn is_match(&self, path: &[String]) -> bool {
+        self.regex.is_match(&path.join("/"))
+    }
+}
+
+/// Display implementation of `CompressionRule`, returning the same text form it's parsed from.
+impl Display for CompressionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{};{}", if self.compress { "compress" } else { "skip" }, self.pattern)
+    }
+}
+
 /// Implementation of trait `Default` for `PFHFlags`.
 impl Default for PFHFlags {
     fn default() -> Self {
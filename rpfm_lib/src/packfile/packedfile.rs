@@ -30,6 +30,7 @@ use std::sync::{Arc, Mutex};
 use rpfm_macros::*;
 use rpfm_error::Error;
 
+use crate::common::decoder::Decoder;
 use crate::packedfile::animpack::AnimPacked;
 use crate::packfile::*;
 use crate::packfile::compression::decompress_data;
@@ -484,6 +485,7 @@ impl PackedFile {
 
             match self.get_packed_file_type(false) {
                 PackedFileType::DB |
+                PackedFileType::GroupFormations |
                 PackedFileType::Loc => {
                     match self.get_ref_decoded() {
                         DecodedPackedFile::DB(table) => {
@@ -494,6 +496,10 @@ impl PackedFile {
                             current_path.set_extension("tsv");
                             table.export_tsv(&current_path, TSV_NAME_LOC, self.get_path())
                         },
+                        DecodedPackedFile::GroupFormations(table) => {
+                            current_path.set_extension("tsv");
+                            table.export_tsv(&current_path, self.get_path())
+                        },
 
                         _ => self.encode_and_save_to_disk(&current_path),
                     }
@@ -720,6 +726,19 @@ impl RawPackedFile {
         }
     }
 
+    /// This function returns the size the data of the provided `RawPackedFile` has once decompressed.
+    ///
+    /// For compressed data, this reads the size from the compression header, so there is no need to decompress it.
+    pub fn get_uncompressed_size(&self) -> Result<u32> {
+        if self.get_compression_state() {
+            let mut data = self.get_raw_data()?;
+            if self.get_encryption_state() { data = decrypt_packed_file(&data); }
+            data.decode_integer_u32(0)
+        } else {
+            Ok(self.get_size())
+        }
+    }
+
     /// This function returns the current compression state of the provided `RawPackedFile`.
     pub fn get_compression_state(&self) -> bool {
         match self.data {
@@ -14,7 +14,10 @@ Module containing test for the `PackFile` module, just to make sure we don't bre
 
 use std::path::PathBuf;
 
-use super::PackFile;
+use rpfm_error::ErrorKind;
+
+use super::{CompressionFormat, PackFile};
+use super::compression::get_compression_format;
 
 #[test]
 fn test_decode_pfh6() {
@@ -142,3 +145,64 @@ fn test_encode_pfh0() {
 
 	assert_eq!(pack_file_base, pack_file_new);
 }
+
+/// This function compresses a PackFile with the provided format, saves it, and checks the reloaded PackedFiles use that format and decompress to the same data.
+fn check_compression_format_packfile(compression_format: CompressionFormat, path: &str) {
+	let mut pack_file_base = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+	let mut pack_file_new = pack_file_base.clone();
+	let mut settings = pack_file_new.get_settings().clone();
+	settings.set_compression_format(compression_format);
+	pack_file_new.set_settings(&settings);
+	pack_file_new.toggle_compression(true);
+	pack_file_new.save_with_compression_formats(Some(PathBuf::from(path)), &[CompressionFormat::Lzma1, CompressionFormat::Lz4, CompressionFormat::Zstd]).unwrap();
+
+	let mut pack_file_new = PackFile::read(&PathBuf::from(path), false).unwrap();
+	assert_eq!(pack_file_new.get_settings().get_compression_format().unwrap(), compression_format);
+
+	// Tables are not compressed unless a rule says so, so only the rest of the PackedFiles must use the format.
+	let mut compressed = 0;
+	for packed_file in pack_file_base.get_ref_mut_packed_files_all() {
+		let path = packed_file.get_path().to_vec();
+		let data = packed_file.get_raw_data().unwrap();
+		let packed_file_new = pack_file_new.get_ref_mut_packed_file_by_path(&path).unwrap();
+		if packed_file_new.get_ref_raw().get_compression_state() {
+			assert_eq!(get_compression_format(&packed_file_new.get_ref_raw().get_raw_data().unwrap()), compression_format);
+			compressed += 1;
+		}
+		assert_eq!(packed_file_new.get_raw_data().unwrap(), data);
+	}
+	assert!(compressed > 0);
+}
+
+#[test]
+fn test_compression_format_lzma1_packfile() {
+	check_compression_format_packfile(CompressionFormat::Lzma1, "../test_files/PFH5_test_compression_lzma1.pack");
+}
+
+#[test]
+fn test_compression_format_lz4_packfile() {
+	check_compression_format_packfile(CompressionFormat::Lz4, "../test_files/PFH5_test_compression_lz4.pack");
+}
+
+#[test]
+fn test_compression_format_zstd_packfile() {
+	check_compression_format_packfile(CompressionFormat::Zstd, "../test_files/PFH5_test_compression_zstd.pack");
+}
+
+#[test]
+fn test_compression_format_not_supported() {
+
+	// Compressing data with a format the game doesn't support must fail, but data already compressed with it can be kept.
+	let mut pack_file = PackFile::read(&PathBuf::from("../test_files/PFH5_test.pack"), false).unwrap();
+	let mut settings = pack_file.get_settings().clone();
+	settings.set_compression_format(CompressionFormat::Zstd);
+	pack_file.set_settings(&settings);
+	pack_file.toggle_compression(true);
+
+	let path = PathBuf::from("../test_files/PFH5_test_compression_not_supported.pack");
+	assert!(matches!(pack_file.save_with_compression_formats(Some(path.to_path_buf()), &[CompressionFormat::Lzma1]).unwrap_err().kind(), ErrorKind::CompressionFormatNotSupported(_)));
+
+	pack_file.save_with_compression_formats(Some(path.to_path_buf()), &[CompressionFormat::Zstd]).unwrap();
+	let mut pack_file = PackFile::read(&path, false).unwrap();
+	pack_file.save_with_compression_formats(None, &[CompressionFormat::Lzma1]).unwrap();
+}
@@ -1394,6 +1394,11 @@ impl Field {
         self.is_bitwise
     }
 
+    /// Setter for the `is_bitwise` field.
+    pub fn set_is_bitwise(&mut self, is_bitwise: i32) {
+        self.is_bitwise = is_bitwise;
+    }
+
     /// Getter for the `enum_values` field.
     pub fn get_enum_values(&self) -> &BTreeMap<i32, String> {
         &self.enum_values
@@ -1427,6 +1432,30 @@ impl Field {
         false
     }
 
+    /// Getter for the `min_value` field for schema patches.
+    pub fn get_min_value(&self, table_name: Option<&str>) -> Option<f64> {
+        if let Some(table_name) = table_name {
+            let game = GAME_SELECTED.read().unwrap().get_game_key_name();
+            if let Some(min_value) = SCHEMA_PATCHES.read().unwrap().get_data(&game, table_name, self.get_name(), "min_value") {
+                return min_value.parse::<f64>().ok();
+            }
+        }
+
+        None
+    }
+
+    /// Getter for the `max_value` field for schema patches.
+    pub fn get_max_value(&self, table_name: Option<&str>) -> Option<f64> {
+        if let Some(table_name) = table_name {
+            let game = GAME_SELECTED.read().unwrap().get_game_key_name();
+            if let Some(max_value) = SCHEMA_PATCHES.read().unwrap().get_data(&game, table_name, self.get_name(), "max_value") {
+                return max_value.parse::<f64>().ok();
+            }
+        }
+
+        None
+    }
+
     /// Getter for the `explanation` field for schema patches.
     pub fn get_schema_patch_explanation(&self, table_name: Option<&str>) -> String {
         if let Some(table_name) = table_name {
@@ -45,9 +45,6 @@ const PROGRAM_NAME: &str = "rpfm";
 /// Name of the settings file.
 const SETTINGS_FILE: &str = "settings.ron";
 
-/// Key of the 7Zip path in the settings";
-pub const ZIP_PATH: &str = "7zip_path";
-
 /// Key of the MyMod path in the settings";
 pub const MYMOD_BASE_PATH: &str = "mymods_base_path";
 
@@ -78,7 +75,6 @@ impl Settings {
         let mut settings_string = BTreeMap::new();
         let mut settings_bool = BTreeMap::new();
         paths.insert(MYMOD_BASE_PATH.to_owned(), None);
-        paths.insert(ZIP_PATH.to_owned(), None);
         for game in &SUPPORTED_GAMES.get_games() {
             let game_key = game.get_game_key_name();
             paths.insert(game_key.to_owned(), None);
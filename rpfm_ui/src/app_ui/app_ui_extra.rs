@@ -74,7 +74,7 @@ use crate::global_search_ui::GlobalSearchUI;
 use crate::locale::{qtr, qtre, tre};
 use crate::pack_tree::{BuildData, icons::IconType, new_pack_file_tooltip, PackTree, TreePathType, TreeViewOperation};
 use crate::packedfile_views::dependencies_manager::DependenciesManagerView;
-use crate::packedfile_views::{anim_fragment::*, animpack::*, ca_vp8::*, DataSource, decoder::*, esf::*, external::*, image::*, PackedFileView, packfile::PackFileExtraView, packfile_settings::*, table::*, text::*, unit_variant::*};
+use crate::packedfile_views::{anim::*, anim_fragment::*, animpack::*, ca_vp8::*, DataSource, decoder::*, esf::*, external::*, image::*, PackedFileView, packfile::PackFileExtraView, packfile_settings::*, table::*, text::*, unit_variant::*};
 use crate::packfile_contents_ui::PackFileContentsUI;
 use crate::QString;
 use crate::QT_PROGRAM;
@@ -1572,6 +1572,29 @@ impl AppUI {
                             }
                         }
 
+                        // If the file is an Anim PackedFile...
+                        PackedFileType::Anim => {
+                            match PackedFileAnimView::new_view(&mut tab) {
+                                Ok(packed_file_info) => {
+
+                                    // Add the file to the 'Currently open' list and make it visible.
+                                    app_ui.tab_bar_packed_file.add_tab_3a(tab.get_mut_widget(), icon, &QString::from_std_str(""));
+                                    app_ui.tab_bar_packed_file.set_current_widget(tab.get_mut_widget());
+
+                                    // Fix the tips view.
+                                    let layout = tab.get_mut_widget().layout().static_downcast::<QGridLayout>();
+                                    layout.add_widget_5a(tab.get_tips_widget(), 0, 99, layout.row_count(), 1);
+
+                                    let mut open_list = UI_STATE.set_open_packedfiles();
+                                    open_list.push(tab);
+                                    if data_source == DataSource::PackFile {
+                                        pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::UpdateTooltip(vec![packed_file_info;1]), DataSource::PackFile);
+                                    }
+                                },
+                                Err(error) => return show_dialog(&app_ui.main_window, ErrorKind::AnimDecode(format!("{}", error)), false),
+                            }
+                        }
+
                         // If the file is an AnimPack PackedFile...
                         PackedFileType::AnimPack => {
                             match PackedFileAnimPackView::new_view(&mut tab, app_ui, pack_file_contents_ui) {
@@ -1705,6 +1728,31 @@ impl AppUI {
                             }
                         }
 
+                        // If the file is a GroupFormations PackedFile...
+                        PackedFileType::GroupFormations => {
+                            match PackedFileTableView::new_view(&mut tab, app_ui, global_search_ui, pack_file_contents_ui, diagnostics_ui, dependencies_ui, references_ui) {
+                                Ok(packed_file_info) => {
+
+                                    // Add the file to the 'Currently open' list and make it visible.
+                                    app_ui.tab_bar_packed_file.add_tab_3a(tab.get_mut_widget(), icon, &QString::from_std_str(""));
+                                    app_ui.tab_bar_packed_file.set_current_widget(tab.get_mut_widget());
+
+                                    // Fix the tips view.
+                                    let layout = tab.get_mut_widget().layout().static_downcast::<QGridLayout>();
+                                    layout.add_widget_5a(tab.get_tips_widget(), 0, 99, layout.row_count(), 1);
+
+                                    let mut open_list = UI_STATE.set_open_packedfiles();
+                                    open_list.push(tab);
+                                    if let Some(packed_file_info) = packed_file_info {
+                                        if data_source == DataSource::PackFile {
+                                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::UpdateTooltip(vec![packed_file_info;1]), data_source);
+                                        }
+                                    }
+                                },
+                                Err(error) => return show_dialog(&app_ui.main_window, ErrorKind::GroupFormationsDecode(format!("{}", error)), false),
+                            }
+                        }
+
                         // If the file is a MatchedCombat PackedFile...
                         PackedFileType::MatchedCombat => {
                             match PackedFileTableView::new_view(&mut tab, app_ui, global_search_ui, pack_file_contents_ui, diagnostics_ui, dependencies_ui, references_ui) {
@@ -40,6 +40,7 @@ use rpfm_lib::packfile::PFHFileType;
 use rpfm_lib::packedfile::*;
 use rpfm_lib::packedfile::animpack::AnimPack;
 use rpfm_lib::packedfile::table::db::DB;
+use rpfm_lib::packedfile::table::group_formations::GroupFormations;
 use rpfm_lib::packedfile::table::loc::{Loc, TSV_NAME_LOC};
 use rpfm_lib::packedfile::text::{Text, TextType};
 use rpfm_lib::packfile::{PackFile, PackFileInfo, packedfile::{PackedFile, PackedFileInfo, RawPackedFile}, PathType, PFHFlags, RESERVED_NAME_NOTES};
@@ -575,12 +576,14 @@ pub fn background_loop() {
                                     match packed_file.decode_return_ref() {
                                         Ok(packed_file_data) => {
                                             match packed_file_data {
+                                                DecodedPackedFile::Anim(data) => CentralCommand::send_back(&sender, Response::AnimPackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::AnimFragment(data) => CentralCommand::send_back(&sender, Response::AnimFragmentPackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::AnimPack(data) => CentralCommand::send_back(&sender, Response::AnimPackPackedFileInfo((data.get_as_pack_file_info(&path), From::from(&**packed_file)))),
                                                 DecodedPackedFile::AnimTable(data) => CentralCommand::send_back(&sender, Response::AnimTablePackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::CaVp8(data) => CentralCommand::send_back(&sender, Response::CaVp8PackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::ESF(data) => CentralCommand::send_back(&sender, Response::ESFPackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::DB(table) => CentralCommand::send_back(&sender, Response::DBPackedFileInfo((table.clone(), From::from(&**packed_file)))),
+                                                DecodedPackedFile::GroupFormations(data) => CentralCommand::send_back(&sender, Response::GroupFormationsPackedFileInfo((data.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::Image(image) => CentralCommand::send_back(&sender, Response::ImagePackedFileInfo((image.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::Loc(table) => CentralCommand::send_back(&sender, Response::LocPackedFileInfo((table.clone(), From::from(&**packed_file)))),
                                                 DecodedPackedFile::MatchedCombat(data) => CentralCommand::send_back(&sender, Response::MatchedCombatPackedFileInfo((data.clone(), From::from(&**packed_file)))),
@@ -606,12 +609,14 @@ pub fn background_loop() {
                                 match packed_file.decode_return_ref() {
                                     Ok(packed_file_data) => {
                                         match packed_file_data {
+                                            DecodedPackedFile::Anim(data) => CentralCommand::send_back(&sender, Response::AnimPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimFragment(data) => CentralCommand::send_back(&sender, Response::AnimFragmentPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimPack(data) => CentralCommand::send_back(&sender, Response::AnimPackPackedFileInfo((data.get_as_pack_file_info(&path), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimTable(data) => CentralCommand::send_back(&sender, Response::AnimTablePackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::CaVp8(data) => CentralCommand::send_back(&sender, Response::CaVp8PackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::ESF(data) => CentralCommand::send_back(&sender, Response::ESFPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::DB(table) => CentralCommand::send_back(&sender, Response::DBPackedFileInfo((table.clone(), From::from(&packed_file)))),
+                                            DecodedPackedFile::GroupFormations(data) => CentralCommand::send_back(&sender, Response::GroupFormationsPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::Image(image) => CentralCommand::send_back(&sender, Response::ImagePackedFileInfo((image.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::Loc(table) => CentralCommand::send_back(&sender, Response::LocPackedFileInfo((table.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::MatchedCombat(data) => CentralCommand::send_back(&sender, Response::MatchedCombatPackedFileInfo((data.clone(), From::from(&packed_file)))),
@@ -635,12 +640,14 @@ pub fn background_loop() {
                                 match packed_file.decode_return_ref() {
                                     Ok(packed_file_data) => {
                                         match packed_file_data {
+                                            DecodedPackedFile::Anim(data) => CentralCommand::send_back(&sender, Response::AnimPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimFragment(data) => CentralCommand::send_back(&sender, Response::AnimFragmentPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimPack(data) => CentralCommand::send_back(&sender, Response::AnimPackPackedFileInfo((data.get_as_pack_file_info(&path), From::from(&packed_file)))),
                                             DecodedPackedFile::AnimTable(data) => CentralCommand::send_back(&sender, Response::AnimTablePackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::CaVp8(data) => CentralCommand::send_back(&sender, Response::CaVp8PackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::ESF(data) => CentralCommand::send_back(&sender, Response::ESFPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::DB(table) => CentralCommand::send_back(&sender, Response::DBPackedFileInfo((table.clone(), From::from(&packed_file)))),
+                                            DecodedPackedFile::GroupFormations(data) => CentralCommand::send_back(&sender, Response::GroupFormationsPackedFileInfo((data.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::Image(image) => CentralCommand::send_back(&sender, Response::ImagePackedFileInfo((image.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::Loc(table) => CentralCommand::send_back(&sender, Response::LocPackedFileInfo((table.clone(), From::from(&packed_file)))),
                                             DecodedPackedFile::MatchedCombat(data) => CentralCommand::send_back(&sender, Response::MatchedCombatPackedFileInfo((data.clone(), From::from(&packed_file)))),
@@ -884,6 +891,10 @@ pub fn background_loop() {
                             Ok(_) => CentralCommand::send_back(&sender, Response::Success),
                             Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
                         },
+                        DecodedPackedFile::GroupFormations(data) => match data.export_tsv(&external_path, &packed_file.get_path()) {
+                            Ok(_) => CentralCommand::send_back(&sender, Response::Success),
+                            Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
+                        },
                         /*
                         DecodedPackedFile::DependencyPackFileList(data) => match data.export_tsv(&[external_path]) {
                             Ok(_) => CentralCommand::send_back(&sender, Response::Success),
@@ -909,6 +920,10 @@ pub fn background_loop() {
                                     Ok((data, _)) => CentralCommand::send_back(&sender, Response::TableType(TableType::Loc(data))),
                                     Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
                                 },
+                                PackedFileType::GroupFormations => match GroupFormations::import_tsv(&schema, &external_path) {
+                                    Ok((data, _)) => CentralCommand::send_back(&sender, Response::TableType(TableType::GroupFormations(data))),
+                                    Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
+                                },
                                 _ => unimplemented!()
                             }
                             None => CentralCommand::send_back(&sender, Response::Error(ErrorKind::PackedFileNotFound.into())),
@@ -980,6 +995,24 @@ pub fn background_loop() {
                                 }
                             },
 
+                            PackedFileType::GroupFormations => {
+                                match packed_file.decode_return_clean_cache() {
+                                    Ok(data) => {
+                                        if let DecodedPackedFile::GroupFormations(data) = data {
+                                            temporal_file_path.set_extension("tsv");
+                                            match data.export_tsv(&temporal_file_path, &packed_file.get_path()) {
+                                                Ok(_) => {
+                                                    that_in_background(&temporal_file_path);
+                                                    CentralCommand::send_back(&sender, Response::PathBuf(temporal_file_path));
+                                                }
+                                                Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
+                                            }
+                                        }
+                                    },
+                                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                                }
+                            },
+
                             // The rest of the files, we extract them as we have them.
                             _ => {
                                 match packed_file.get_raw_data_and_clean_cache() {
@@ -1013,7 +1046,7 @@ pub fn background_loop() {
                         match packed_file.get_packed_file_type(false) {
 
                             // Tables we extract them as TSV.
-                            PackedFileType::DB | PackedFileType::Loc => {
+                            PackedFileType::DB | PackedFileType::GroupFormations | PackedFileType::Loc => {
                                 match *SCHEMA.read().unwrap() {
                                     Some(ref schema) => {
                                         match packed_file.decode_return_ref_mut() {
@@ -1043,6 +1076,18 @@ pub fn background_loop() {
                                                             Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
                                                         }
                                                     }
+                                                    DecodedPackedFile::GroupFormations(ref mut data) => {
+                                                        match GroupFormations::import_tsv(&schema, &external_path) {
+                                                            Ok((new_data, _)) => {
+                                                                *data = new_data;
+                                                                match packed_file.encode_and_clean_cache() {
+                                                                    Ok(_) => CentralCommand::send_back(&sender, Response::Success),
+                                                                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                                                                }
+                                                            }
+                                                            Err(error) =>  CentralCommand::send_back(&sender, Response::Error(error)),
+                                                        }
+                                                    }
                                                     _ => unimplemented!(),
                                                 }
                                             },
@@ -1172,6 +1217,14 @@ pub fn background_loop() {
                 CentralCommand::send_back(&sender, Response::DiagnosticsVecPackedFileInfo(diagnostics, packed_files_info));
             }
 
+            // In case we want to fix the fixable results of a diagnostics check...
+            Command::DiagnosticsApplyFixes(diagnostics) => {
+                match diagnostics.apply_fixes(&mut pack_file_decoded, &dependencies, None) {
+                    Ok(edited_paths) => CentralCommand::send_back(&sender, Response::VecVecStringVecVecString(edited_paths.into_iter().unzip())),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
+            }
+
             // In case we want to get the open PackFile's Settings...
             Command::GetPackFileSettings(is_autosave) => {
                 if is_autosave {
@@ -1297,6 +1350,13 @@ pub fn background_loop() {
                 }
             },
 
+            Command::GetMergedDBTable(table_name) => {
+                match pack_file_decoded.get_merged_db_table(&table_name, &dependencies) {
+                    Ok(merged_table) => CentralCommand::send_back(&sender, Response::MergedTable(merged_table)),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
+            },
+
             Command::SearchReferences(reference_map, value) => {
                 let paths = reference_map.keys().map(|x| PathType::Folder(vec!["db".to_owned(), x.to_owned()])).collect::<Vec<PathType>>();
                 let packed_files = pack_file_decoded.get_ref_packed_files_by_path_type_unicased(&paths);
@@ -1323,7 +1383,7 @@ pub fn background_loop() {
                 // Pass for parent tables.
                 for (table_name, columns) in &reference_map {
                     if let Ok(tables) = dependencies.get_db_tables_with_path_from_cache(table_name, false, true) {
-                        references.append(&mut tables.par_iter().map(|(path, table)| {
+                        references.append(&mut tables.par_iter().map(|(path, _, table)| {
                             let mut references = vec![];
                             for column_name in columns {
                                 if let Some((column_index, row_indexes)) = table.get_ref_table().get_location_of_reference_data(column_name, &value) {
@@ -1342,7 +1402,7 @@ pub fn background_loop() {
                 // Pass for vanilla tables.
                 for (table_name, columns) in &reference_map {
                     if let Ok(tables) = dependencies.get_db_tables_with_path_from_cache(table_name, true, false) {
-                        references.append(&mut tables.par_iter().map(|(path, table)| {
+                        references.append(&mut tables.par_iter().map(|(path, _, table)| {
                             let mut references = vec![];
                             for column_name in columns {
                                 if let Some((column_index, row_indexes)) = table.get_ref_table().get_location_of_reference_data(column_name, &value) {
@@ -27,11 +27,12 @@ use rpfm_lib::diagnostics::Diagnostics;
 use rpfm_lib::git_integration::GitResponse;
 use rpfm_lib::global_search::GlobalSearch;
 use rpfm_lib::global_search::MatchHolder;
+use rpfm_lib::packedfile::anim::Anim;
 use rpfm_lib::packedfile::ca_vp8::{CaVp8, SupportedFormats};
 use rpfm_lib::packedfile::{DecodedPackedFile, PackedFileType};
 use rpfm_lib::packedfile::esf::ESF;
 use rpfm_lib::packedfile::image::Image;
-use rpfm_lib::packedfile::table::{DependencyData, anim_fragment::AnimFragment, animtable::AnimTable, db::{DB, CascadeEdition}, loc::Loc, matched_combat::MatchedCombat};
+use rpfm_lib::packedfile::table::{DependencyData, MergedTable, anim_fragment::AnimFragment, animtable::AnimTable, db::{DB, CascadeEdition}, group_formations::GroupFormations, loc::Loc, matched_combat::MatchedCombat};
 use rpfm_lib::packedfile::text::Text;
 use rpfm_lib::packedfile::rigidmodel::RigidModel;
 use rpfm_lib::packedfile::uic::UIC;
@@ -296,6 +297,9 @@ pub enum Command {
     /// This command is used to trigger a partial diagnostics check over the open PackFile.
     DiagnosticsUpdate((Diagnostics, Vec<PathType>)),
 
+    /// This command is used to apply the available fixes of the provided diagnostics to the open PackFile.
+    DiagnosticsApplyFixes(Diagnostics),
+
     /// This command is used to get the settings of the currently open PackFile. True if the message is for the autosave.
     GetPackFileSettings(bool),
 
@@ -323,6 +327,9 @@ pub enum Command {
     /// This command is used for the Find References feature. Contains list of table/columns to search, and value to search.
     SearchReferences(HashMap<String, Vec<String>>, String),
 
+    /// This command is used to get the effective version of a DB table once vanilla, parent and our files are loaded. Contains the table name.
+    GetMergedDBTable(String),
+
     /// This command is used to get the type of a PackedFile.
     GetPackedFileType(Vec<String>),
 
@@ -438,6 +445,9 @@ pub enum Response {
     /// Response to return `APIResponseTips`.
     APIResponseTips(APIResponseTips),
 
+    /// Response to return `(Anim, PackedFileInfo)`.
+    AnimPackedFileInfo((Anim, PackedFileInfo)),
+
     /// Response to return `(AnimFragment, PackedFileInfo)`.
     AnimFragmentPackedFileInfo((AnimFragment, PackedFileInfo)),
 
@@ -462,6 +472,9 @@ pub enum Response {
     /// Response to return `(DB, PackedFileInfo)`.
     DBPackedFileInfo((DB, PackedFileInfo)),
 
+    /// Response to return `(GroupFormations, PackedFileInfo)`.
+    GroupFormationsPackedFileInfo((GroupFormations, PackedFileInfo)),
+
     /// Response to return `(Loc, PackedFileInfo)`.
     LocPackedFileInfo((Loc, PackedFileInfo)),
 
@@ -515,6 +528,9 @@ pub enum Response {
     /// Response to return `Vec<(DataSource, Vec<String>, String, usize, usize)>`.
     VecDataSourceVecStringStringUsizeUsize(Vec<(DataSource, Vec<String>, String, usize, usize)>),
 
+    /// Response to return `MergedTable`.
+    MergedTable(MergedTable),
+
     /// Response to return `Option<(String, String, String)>`.
     OptionStringStringString(Option<(String, String, String)>),
 
@@ -25,6 +25,7 @@ pub unsafe fn set_connections(ui: &DiagnosticsUI, slots: &DiagnosticsUISlots) {
 
     ui.diagnostics_button_check_packfile.released().connect(&slots.diagnostics_check_packfile);
     ui.diagnostics_button_check_current_packed_file.released().connect(&slots.diagnostics_check_currently_open_packed_file);
+    ui.diagnostics_button_fix_all.released().connect(&slots.diagnostics_fix_all);
 
     ui.diagnostics_button_info.toggled().connect(&slots.toggle_filters);
     ui.diagnostics_button_warning.toggled().connect(&slots.toggle_filters);
@@ -58,4 +59,9 @@ pub unsafe fn set_connections(ui: &DiagnosticsUI, slots: &DiagnosticsUISlots) {
     ui.checkbox_incorrect_game_path.toggled().connect(&slots.toggle_filters);
     ui.checkbox_banned_table.toggled().connect(&slots.toggle_filters);
     ui.checkbox_value_cannot_be_empty.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_missing_loc_data.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_orphan_loc_key.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_invalid_enum_value.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_invalid_bitwise_value.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_value_out_of_range.toggled().connect(&slots.toggle_filters);
 }
@@ -48,7 +48,7 @@ use cpp_core::Ptr;
 
 use std::rc::Rc;
 
-use rpfm_lib::diagnostics::{*, anim_fragment::*, config::*, table::*, dependency_manager::*, packfile::*};
+use rpfm_lib::diagnostics::{*, anim_fragment::*, config::*, table::*, dependency_manager::*, load_order::*, lua::*, packfile::*, rigid_model::*};
 use rpfm_lib::GAME_SELECTED;
 use rpfm_lib::games::supported_games::*;
 use rpfm_lib::packfile::PathType;
@@ -63,12 +63,12 @@ use crate::dependencies_ui::DependenciesUI;
 use crate::ffi::{new_tableview_filter_safe, trigger_tableview_filter_safe};
 use crate::global_search_ui::GlobalSearchUI;
 use crate::locale::{qtr, qtre, tr};
-use crate::pack_tree::{PackTree, get_color_info, get_color_warning, get_color_error, get_color_info_pressed, get_color_warning_pressed, get_color_error_pressed, TreeViewOperation};
+use crate::pack_tree::{PackTree, TreePathType, get_color_info, get_color_warning, get_color_error, get_color_info_pressed, get_color_warning_pressed, get_color_error_pressed, TreeViewOperation};
 use crate::packedfile_views::{DataSource, PackedFileView, View, ViewType};
 use crate::packfile_contents_ui::PackFileContentsUI;
 use crate::UI_STATE;
 use crate::references_ui::ReferencesUI;
-use crate::utils::create_grid_layout;
+use crate::utils::{create_grid_layout, show_dialog};
 use crate::views::table::{ITEM_HAS_ERROR, ITEM_HAS_WARNING, ITEM_HAS_INFO};
 
 pub mod connections;
@@ -95,6 +95,7 @@ pub struct DiagnosticsUI {
     //-------------------------------------------------------------------------------//
     diagnostics_button_check_packfile: QBox<QPushButton>,
     diagnostics_button_check_current_packed_file: QBox<QPushButton>,
+    diagnostics_button_fix_all: QBox<QPushButton>,
     diagnostics_button_error: QBox<QPushButton>,
     diagnostics_button_warning: QBox<QPushButton>,
     diagnostics_button_info: QBox<QPushButton>,
@@ -127,6 +128,11 @@ pub struct DiagnosticsUI {
     checkbox_incorrect_game_path: QBox<QCheckBox>,
     checkbox_banned_table: QBox<QCheckBox>,
     checkbox_value_cannot_be_empty: QBox<QCheckBox>,
+    checkbox_missing_loc_data: QBox<QCheckBox>,
+    checkbox_orphan_loc_key: QBox<QCheckBox>,
+    checkbox_invalid_enum_value: QBox<QCheckBox>,
+    checkbox_invalid_bitwise_value: QBox<QCheckBox>,
+    checkbox_value_out_of_range: QBox<QCheckBox>,
 }
 
 //-------------------------------------------------------------------------------//
@@ -157,6 +163,7 @@ impl DiagnosticsUI {
 
         let diagnostics_button_check_packfile = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_check_packfile"), &filter_frame);
         let diagnostics_button_check_current_packed_file = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_check_current_packed_file"), &filter_frame);
+        let diagnostics_button_fix_all = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_fix_all"), &filter_frame);
         let diagnostics_button_error = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_error"), &filter_frame);
         let diagnostics_button_warning = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_warning"), &filter_frame);
         let diagnostics_button_info = QPushButton::from_q_string_q_widget(&qtr("diagnostics_button_info"), &filter_frame);
@@ -167,6 +174,7 @@ impl DiagnosticsUI {
         line_separator.set_frame_shape(Shape::VLine);
         line_separator.set_frame_shadow(Shadow::Sunken);
 
+        diagnostics_button_fix_all.set_enabled(false);
         diagnostics_button_error.set_checkable(true);
         diagnostics_button_warning.set_checkable(true);
         diagnostics_button_info.set_checkable(true);
@@ -200,12 +208,13 @@ impl DiagnosticsUI {
 
         filter_grid.add_widget_5a(&diagnostics_button_check_packfile, 0, 0, 1, 1);
         filter_grid.add_widget_5a(&diagnostics_button_check_current_packed_file, 0, 1, 1, 1);
-        filter_grid.add_widget_5a(&line_separator, 0, 2, 1, 1);
-        filter_grid.add_widget_5a(&diagnostics_button_error, 0, 3, 1, 1);
-        filter_grid.add_widget_5a(&diagnostics_button_warning, 0, 4, 1, 1);
-        filter_grid.add_widget_5a(&diagnostics_button_info, 0, 5, 1, 1);
-        filter_grid.add_widget_5a(&diagnostics_button_only_current_packed_file, 0, 6, 1, 1);
-        filter_grid.add_widget_5a(&diagnostics_button_show_more_filters, 0, 7, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_fix_all, 0, 2, 1, 1);
+        filter_grid.add_widget_5a(&line_separator, 0, 3, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_error, 0, 4, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_warning, 0, 5, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_info, 0, 6, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_only_current_packed_file, 0, 7, 1, 1);
+        filter_grid.add_widget_5a(&diagnostics_button_show_more_filters, 0, 8, 1, 1);
 
         let diagnostics_table_view = QTableView::new_1a(&diagnostics_dock_inner_widget);
         let diagnostics_table_filter = new_tableview_filter_safe(diagnostics_dock_inner_widget.static_upcast());
@@ -275,6 +284,11 @@ impl DiagnosticsUI {
         let label_incorrect_game_path = QLabel::from_q_string_q_widget(&qtr("label_incorrect_game_path"), &sidebar_scroll_area);
         let label_banned_table = QLabel::from_q_string_q_widget(&qtr("label_banned_table"), &sidebar_scroll_area);
         let label_value_cannot_be_empty = QLabel::from_q_string_q_widget(&qtr("label_value_cannot_be_empty"), &sidebar_scroll_area);
+        let label_missing_loc_data = QLabel::from_q_string_q_widget(&qtr("label_missing_loc_data"), &sidebar_scroll_area);
+        let label_orphan_loc_key = QLabel::from_q_string_q_widget(&qtr("label_orphan_loc_key"), &sidebar_scroll_area);
+        let label_invalid_enum_value = QLabel::from_q_string_q_widget(&qtr("label_invalid_enum_value"), &sidebar_scroll_area);
+        let label_invalid_bitwise_value = QLabel::from_q_string_q_widget(&qtr("label_invalid_bitwise_value"), &sidebar_scroll_area);
+        let label_value_out_of_range = QLabel::from_q_string_q_widget(&qtr("label_value_out_of_range"), &sidebar_scroll_area);
 
         let checkbox_all = QCheckBox::from_q_widget(&sidebar_scroll_area);
         let checkbox_outdated_table = QCheckBox::from_q_widget(&sidebar_scroll_area);
@@ -301,6 +315,11 @@ impl DiagnosticsUI {
         let checkbox_incorrect_game_path = QCheckBox::from_q_widget(&sidebar_scroll_area);
         let checkbox_banned_table = QCheckBox::from_q_widget(&sidebar_scroll_area);
         let checkbox_value_cannot_be_empty = QCheckBox::from_q_widget(&sidebar_scroll_area);
+        let checkbox_missing_loc_data = QCheckBox::from_q_widget(&sidebar_scroll_area);
+        let checkbox_orphan_loc_key = QCheckBox::from_q_widget(&sidebar_scroll_area);
+        let checkbox_invalid_enum_value = QCheckBox::from_q_widget(&sidebar_scroll_area);
+        let checkbox_invalid_bitwise_value = QCheckBox::from_q_widget(&sidebar_scroll_area);
+        let checkbox_value_out_of_range = QCheckBox::from_q_widget(&sidebar_scroll_area);
 
         checkbox_all.set_checked(true);
         checkbox_outdated_table.set_checked(true);
@@ -327,6 +346,11 @@ impl DiagnosticsUI {
         checkbox_incorrect_game_path.set_checked(true);
         checkbox_banned_table.set_checked(true);
         checkbox_value_cannot_be_empty.set_checked(true);
+        checkbox_missing_loc_data.set_checked(true);
+        checkbox_orphan_loc_key.set_checked(true);
+        checkbox_invalid_enum_value.set_checked(true);
+        checkbox_invalid_bitwise_value.set_checked(true);
+        checkbox_value_out_of_range.set_checked(true);
 
         sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_all, QFlags::from(AlignmentFlag::AlignHCenter));
         sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_outdated_table, QFlags::from(AlignmentFlag::AlignHCenter));
@@ -353,6 +377,11 @@ impl DiagnosticsUI {
         sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_incorrect_game_path, QFlags::from(AlignmentFlag::AlignHCenter));
         sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_banned_table, QFlags::from(AlignmentFlag::AlignHCenter));
         sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_value_cannot_be_empty, QFlags::from(AlignmentFlag::AlignHCenter));
+        sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_missing_loc_data, QFlags::from(AlignmentFlag::AlignHCenter));
+        sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_orphan_loc_key, QFlags::from(AlignmentFlag::AlignHCenter));
+        sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_invalid_enum_value, QFlags::from(AlignmentFlag::AlignHCenter));
+        sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_invalid_bitwise_value, QFlags::from(AlignmentFlag::AlignHCenter));
+        sidebar_grid.set_alignment_q_widget_q_flags_alignment_flag(&checkbox_value_out_of_range, QFlags::from(AlignmentFlag::AlignHCenter));
 
         sidebar_grid.add_widget_5a(&label_all, 1, 0, 1, 1);
         sidebar_grid.add_widget_5a(&label_outdated_table, 2, 0, 1, 1);
@@ -379,6 +408,11 @@ impl DiagnosticsUI {
         sidebar_grid.add_widget_5a(&label_incorrect_game_path, 23, 0, 1, 1);
         sidebar_grid.add_widget_5a(&label_banned_table, 24, 0, 1, 1);
         sidebar_grid.add_widget_5a(&label_value_cannot_be_empty, 25, 0, 1, 1);
+        sidebar_grid.add_widget_5a(&label_missing_loc_data, 26, 0, 1, 1);
+        sidebar_grid.add_widget_5a(&label_orphan_loc_key, 27, 0, 1, 1);
+        sidebar_grid.add_widget_5a(&label_invalid_enum_value, 28, 0, 1, 1);
+        sidebar_grid.add_widget_5a(&label_invalid_bitwise_value, 29, 0, 1, 1);
+        sidebar_grid.add_widget_5a(&label_value_out_of_range, 30, 0, 1, 1);
 
         sidebar_grid.add_widget_5a(&checkbox_all, 1, 1, 1, 1);
         sidebar_grid.add_widget_5a(&checkbox_outdated_table, 2, 1, 1, 1);
@@ -405,6 +439,11 @@ impl DiagnosticsUI {
         sidebar_grid.add_widget_5a(&checkbox_incorrect_game_path, 23, 1, 1, 1);
         sidebar_grid.add_widget_5a(&checkbox_banned_table, 24, 1, 1, 1);
         sidebar_grid.add_widget_5a(&checkbox_value_cannot_be_empty, 25, 1, 1, 1);
+        sidebar_grid.add_widget_5a(&checkbox_missing_loc_data, 26, 1, 1, 1);
+        sidebar_grid.add_widget_5a(&checkbox_orphan_loc_key, 27, 1, 1, 1);
+        sidebar_grid.add_widget_5a(&checkbox_invalid_enum_value, 28, 1, 1, 1);
+        sidebar_grid.add_widget_5a(&checkbox_invalid_bitwise_value, 29, 1, 1, 1);
+        sidebar_grid.add_widget_5a(&checkbox_value_out_of_range, 30, 1, 1, 1);
 
         // Add all the stuff to the main grid and hide the search widget.
         diagnostics_dock_layout.add_widget_5a(&sidebar_scroll_area, 0, 1, 2, 1);
@@ -426,6 +465,7 @@ impl DiagnosticsUI {
             //-------------------------------------------------------------------------------//
             diagnostics_button_check_packfile,
             diagnostics_button_check_current_packed_file,
+            diagnostics_button_fix_all,
             diagnostics_button_error,
             diagnostics_button_warning,
             diagnostics_button_info,
@@ -457,7 +497,12 @@ impl DiagnosticsUI {
             checkbox_field_with_path_not_found,
             checkbox_incorrect_game_path,
             checkbox_banned_table,
-            checkbox_value_cannot_be_empty
+            checkbox_value_cannot_be_empty,
+            checkbox_missing_loc_data,
+            checkbox_orphan_loc_key,
+            checkbox_invalid_enum_value,
+            checkbox_invalid_bitwise_value,
+            checkbox_value_out_of_range
         }
     }
 
@@ -479,6 +524,7 @@ impl DiagnosticsUI {
                 Self::load_diagnostics_to_ui(app_ui, diagnostics_ui, diagnostics.get_ref_diagnostics());
                 Self::filter(app_ui, diagnostics_ui);
                 Self::update_level_counts(diagnostics_ui, diagnostics.get_ref_diagnostics());
+                diagnostics_ui.diagnostics_button_fix_all.set_enabled(diagnostics.has_fixes());
                 UI_STATE.set_diagnostics(&diagnostics);
             }
             _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
@@ -506,12 +552,56 @@ impl DiagnosticsUI {
 
                 Self::filter(app_ui, diagnostics_ui);
                 Self::update_level_counts(diagnostics_ui, diagnostics.get_ref_diagnostics());
+                diagnostics_ui.diagnostics_button_fix_all.set_enabled(diagnostics.has_fixes());
                 UI_STATE.set_diagnostics(&diagnostics);
             }
             _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
         }
     }
 
+    /// This function takes care of applying all the available fixes of the last diagnostics check to the open PackFile.
+    pub unsafe fn fix_all(app_ui: &Rc<AppUI>, pack_file_contents_ui: &Rc<PackFileContentsUI>, diagnostics_ui: &Rc<Self>) {
+
+        // Make sure the backend has the latest data of the open PackedFiles before fixing them.
+        if let Err(error) = AppUI::back_to_back_end_all(app_ui, pack_file_contents_ui) {
+            show_dialog(&app_ui.main_window, error, false);
+            return;
+        }
+
+        let diagnostics = UI_STATE.get_diagnostics();
+        let receiver = CENTRAL_COMMAND.send_background(Command::DiagnosticsApplyFixes(diagnostics));
+        let response = CentralCommand::recv(&receiver);
+        match response {
+            Response::VecVecStringVecVecString((paths_before, paths_after)) => {
+
+                // The fixed PackedFiles are edited in the backend, so we need to close their views to not overwrite the fixes.
+                for path in &paths_before {
+                    let _ = AppUI::purge_that_one_specifically(app_ui, pack_file_contents_ui, path, DataSource::PackFile, false);
+                }
+
+                let renamed_items = paths_before.iter().zip(paths_after.iter())
+                    .filter(|(path_before, path_after)| path_before != path_after)
+                    .map(|(path_before, path_after)| (TreePathType::File(path_before.to_vec()), path_after.to_vec()))
+                    .collect::<Vec<(TreePathType, Vec<String>)>>();
+
+                if !renamed_items.is_empty() {
+                    pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Move(renamed_items), DataSource::PackFile);
+                }
+
+                let edited_items = paths_after.iter().map(|path| TreePathType::File(path.to_vec())).collect::<Vec<TreePathType>>();
+                if !edited_items.is_empty() {
+                    pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::MarkAlwaysModified(edited_items), DataSource::PackFile);
+                    UI_STATE.set_is_modified(true, app_ui, pack_file_contents_ui);
+                }
+            }
+            Response::Error(error) => show_dialog(&app_ui.main_window, error, false),
+            _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+        }
+
+        // Check the PackFile again, so the fixed results disappear from the list.
+        Self::check(app_ui, diagnostics_ui);
+    }
+
     /// This function takes care of loading the results of a diagnostic check into the table.
     unsafe fn load_diagnostics_to_ui(app_ui: &Rc<AppUI>, diagnostics_ui: &Rc<Self>, diagnostics: &[DiagnosticType]) {
 
@@ -762,6 +852,149 @@ impl DiagnosticsUI {
                             diagnostics_ui.diagnostics_table_model.append_row_q_list_of_q_standard_item(qlist_boi.as_ref());
                         }
                     }
+
+                    DiagnosticType::LoadOrder(ref diagnostic) => {
+                        for result in diagnostic.get_ref_result() {
+                            let qlist_boi = QListOfQStandardItem::new();
+
+                            // Create an empty row.
+                            let level = QStandardItem::new();
+                            let diag_type = QStandardItem::new();
+                            let fill1 = QStandardItem::new();
+                            let path = QStandardItem::new();
+                            let message = QStandardItem::new();
+                            let report_type = QStandardItem::new();
+                            let (result_type, color) = match result.level {
+                                DiagnosticLevel::Info => ("Info".to_owned(), get_color_info()),
+                                DiagnosticLevel::Warning => ("Warning".to_owned(), get_color_warning()),
+                                DiagnosticLevel::Error => ("Error".to_owned(), get_color_error()),
+                            };
+
+                            level.set_background(&QBrush::from_q_color(&QColor::from_q_string(&QString::from_std_str(color))));
+                            level.set_text(&QString::from_std_str(result_type));
+                            diag_type.set_text(&QString::from_std_str(&format!("{}", diagnostic_type)));
+                            path.set_text(&QString::from_std_str(&result.path.join("/")));
+                            message.set_text(&QString::from_std_str(&result.message));
+                            report_type.set_text(&QString::from_std_str(&format!("{}", result.report_type)));
+
+                            level.set_editable(false);
+                            diag_type.set_editable(false);
+                            fill1.set_editable(false);
+                            path.set_editable(false);
+                            message.set_editable(false);
+                            report_type.set_editable(false);
+
+                            // Set the tooltips to the diag type and description columns.
+                            Self::set_tooltips_load_order(&[&level, &path, &message], &result.report_type);
+
+                            // Add an empty row to the list.
+                            qlist_boi.append_q_standard_item(&level.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&diag_type.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&fill1.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&path.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&message.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&report_type.into_ptr().as_mut_raw_ptr());
+
+                            // Append the new row.
+                            diagnostics_ui.diagnostics_table_model.append_row_q_list_of_q_standard_item(qlist_boi.as_ref());
+                        }
+                    }
+
+                    DiagnosticType::RigidModel(ref diagnostic) => {
+                        for result in diagnostic.get_ref_result() {
+                            let qlist_boi = QListOfQStandardItem::new();
+
+                            // Create an empty row.
+                            let level = QStandardItem::new();
+                            let diag_type = QStandardItem::new();
+                            let meshes_affected = QStandardItem::new();
+                            let path = QStandardItem::new();
+                            let message = QStandardItem::new();
+                            let report_type = QStandardItem::new();
+                            let (result_type, color) = match result.level {
+                                DiagnosticLevel::Info => ("Info".to_owned(), get_color_info()),
+                                DiagnosticLevel::Warning => ("Warning".to_owned(), get_color_warning()),
+                                DiagnosticLevel::Error => ("Error".to_owned(), get_color_error()),
+                            };
+
+                            level.set_background(&QBrush::from_q_color(&QColor::from_q_string(&QString::from_std_str(color))));
+                            level.set_text(&QString::from_std_str(result_type));
+                            diag_type.set_text(&QString::from_std_str(&format!("{}", diagnostic_type)));
+                            meshes_affected.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(serde_json::to_string(&result.meshes_affected).unwrap())), 2);
+                            path.set_text(&QString::from_std_str(&diagnostic.get_path().join("/")));
+                            message.set_text(&QString::from_std_str(&result.message));
+                            report_type.set_text(&QString::from_std_str(&format!("{}", result.report_type)));
+
+                            level.set_editable(false);
+                            diag_type.set_editable(false);
+                            meshes_affected.set_editable(false);
+                            path.set_editable(false);
+                            message.set_editable(false);
+                            report_type.set_editable(false);
+
+                            // Set the tooltips to the diag type and description columns.
+                            Self::set_tooltips_rigid_model(&[&level, &path, &message], &result.report_type);
+
+                            // Add an empty row to the list.
+                            qlist_boi.append_q_standard_item(&level.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&diag_type.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&meshes_affected.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&path.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&message.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&report_type.into_ptr().as_mut_raw_ptr());
+
+                            // Append the new row.
+                            diagnostics_ui.diagnostics_table_model.append_row_q_list_of_q_standard_item(qlist_boi.as_ref());
+                        }
+                    }
+
+                    DiagnosticType::Lua(ref diagnostic) => {
+                        for result in diagnostic.get_ref_result() {
+                            let qlist_boi = QListOfQStandardItem::new();
+
+                            // Create an empty row.
+                            let level = QStandardItem::new();
+                            let diag_type = QStandardItem::new();
+                            let position = QStandardItem::new();
+                            let path = QStandardItem::new();
+                            let message = QStandardItem::new();
+                            let report_type = QStandardItem::new();
+                            let (result_type, color) = match result.level {
+                                DiagnosticLevel::Info => ("Info".to_owned(), get_color_info()),
+                                DiagnosticLevel::Warning => ("Warning".to_owned(), get_color_warning()),
+                                DiagnosticLevel::Error => ("Error".to_owned(), get_color_error()),
+                            };
+
+                            level.set_background(&QBrush::from_q_color(&QColor::from_q_string(&QString::from_std_str(color))));
+                            level.set_text(&QString::from_std_str(result_type));
+                            diag_type.set_text(&QString::from_std_str(&format!("{}", diagnostic_type)));
+                            position.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(serde_json::to_string(&result.position).unwrap())), 2);
+                            path.set_text(&QString::from_std_str(&diagnostic.get_path().join("/")));
+                            message.set_text(&QString::from_std_str(&result.message));
+                            report_type.set_text(&QString::from_std_str(&format!("{}", result.report_type)));
+
+                            level.set_editable(false);
+                            diag_type.set_editable(false);
+                            position.set_editable(false);
+                            path.set_editable(false);
+                            message.set_editable(false);
+                            report_type.set_editable(false);
+
+                            // Set the tooltips to the diag type and description columns.
+                            Self::set_tooltips_lua(&[&level, &path, &message], &result.report_type);
+
+                            // Add an empty row to the list.
+                            qlist_boi.append_q_standard_item(&level.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&diag_type.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&position.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&path.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&message.into_ptr().as_mut_raw_ptr());
+                            qlist_boi.append_q_standard_item(&report_type.into_ptr().as_mut_raw_ptr());
+
+                            // Append the new row.
+                            diagnostics_ui.diagnostics_table_model.append_row_q_list_of_q_standard_item(qlist_boi.as_ref());
+                        }
+                    }
                 }
 
                 // After that, check if the table is open, and paint the results into it.
@@ -1303,6 +1536,7 @@ impl DiagnosticsUI {
         }
         if diagnostics_ui.checkbox_field_with_path_not_found.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::FieldWithPathNotFound));
+            diagnostic_type_pattern.push_str(&format!("{}|", RigidModelDiagnosticReportType::TexturePathNotFound));
         }
         if diagnostics_ui.checkbox_banned_table.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::BannedTable));
@@ -1310,6 +1544,21 @@ impl DiagnosticsUI {
         if diagnostics_ui.checkbox_value_cannot_be_empty.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::ValueCannotBeEmpty));
         }
+        if diagnostics_ui.checkbox_missing_loc_data.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::MissingLocData));
+        }
+        if diagnostics_ui.checkbox_orphan_loc_key.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::OrphanLocKey));
+        }
+        if diagnostics_ui.checkbox_invalid_enum_value.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::InvalidEnumValue));
+        }
+        if diagnostics_ui.checkbox_invalid_bitwise_value.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::InvalidBitwiseValue));
+        }
+        if diagnostics_ui.checkbox_value_out_of_range.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::ValueOutOfRange));
+        }
 
 
         if diagnostics_ui.checkbox_invalid_dependency_packfile.is_checked() {
@@ -1331,8 +1580,22 @@ impl DiagnosticsUI {
 
         if diagnostics_ui.checkbox_invalid_packfile_name.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", PackFileDiagnosticReportType::InvalidPackFileName));
+            diagnostic_type_pattern.push_str(&format!("{}|", PackFileDiagnosticReportType::InvalidDiagnosticsRules));
         }
 
+        // Load order diagnostics have no filters, so they're always shown.
+        diagnostic_type_pattern.push_str(&format!("{}|", LoadOrderDiagnosticReportType::FileOverridden));
+        diagnostic_type_pattern.push_str(&format!("{}|", LoadOrderDiagnosticReportType::DuplicatedDBKeyAcrossPacks));
+        diagnostic_type_pattern.push_str(&format!("{}|", LoadOrderDiagnosticReportType::DuplicatedLocKeyAcrossPacks));
+
+        // Same with anim skeleton diagnostics.
+        diagnostic_type_pattern.push_str(&format!("{}|", AnimFragmentDiagnosticReportType::AnimSkeletonMismatch));
+
+        // And with lua diagnostics.
+        diagnostic_type_pattern.push_str(&format!("{}|", LuaDiagnosticReportType::SyntaxError));
+        diagnostic_type_pattern.push_str(&format!("{}|", LuaDiagnosticReportType::UnknownApiFunction));
+        diagnostic_type_pattern.push_str(&format!("{}|", LuaDiagnosticReportType::MissingDBKey));
+
         diagnostic_type_pattern.pop();
 
         if diagnostic_type_pattern.is_empty() {
@@ -1370,6 +1633,18 @@ impl DiagnosticsUI {
                     .filter(|y| matches!(y.level, DiagnosticLevel::Info))
                     .count(),
                  DiagnosticType::Config(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Info))
+                    .count(),
+                DiagnosticType::LoadOrder(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Info))
+                    .count(),
+                DiagnosticType::RigidModel(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Info))
+                    .count(),
+                DiagnosticType::Lua(ref diag) => diag.get_ref_result()
                     .iter()
                     .filter(|y| matches!(y.level, DiagnosticLevel::Info))
                     .count()
@@ -1395,6 +1670,18 @@ impl DiagnosticsUI {
                     .filter(|y| matches!(y.level, DiagnosticLevel::Warning))
                     .count(),
                 DiagnosticType::Config(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Warning))
+                    .count(),
+                DiagnosticType::LoadOrder(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Warning))
+                    .count(),
+                DiagnosticType::RigidModel(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Warning))
+                    .count(),
+                DiagnosticType::Lua(ref diag) => diag.get_ref_result()
                     .iter()
                     .filter(|y| matches!(y.level, DiagnosticLevel::Warning))
                     .count()
@@ -1421,6 +1708,18 @@ impl DiagnosticsUI {
                     .filter(|y| matches!(y.level, DiagnosticLevel::Error))
                     .count(),
                 DiagnosticType::Config(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Error))
+                    .count(),
+                DiagnosticType::LoadOrder(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Error))
+                    .count(),
+                DiagnosticType::RigidModel(ref diag) => diag.get_ref_result()
+                    .iter()
+                    .filter(|y| matches!(y.level, DiagnosticLevel::Error))
+                    .count(),
+                DiagnosticType::Lua(ref diag) => diag.get_ref_result()
                     .iter()
                     .filter(|y| matches!(y.level, DiagnosticLevel::Error))
                     .count()
@@ -1434,6 +1733,7 @@ impl DiagnosticsUI {
     pub unsafe fn set_tooltips_anim_fragment(items: &[&CppBox<QStandardItem>], report_type: &AnimFragmentDiagnosticReportType) {
         let tool_tip = match report_type {
             AnimFragmentDiagnosticReportType::FieldWithPathNotFound => qtr("field_with_path_not_found_explanation"),
+            AnimFragmentDiagnosticReportType::AnimSkeletonMismatch => qtr("anim_skeleton_mismatch_explanation"),
         };
 
         for item in items {
@@ -1461,6 +1761,11 @@ impl DiagnosticsUI {
             TableDiagnosticReportType::FieldWithPathNotFound => qtr("field_with_path_not_found_explanation"),
             TableDiagnosticReportType::BannedTable => qtr("banned_table_explanation"),
             TableDiagnosticReportType::ValueCannotBeEmpty => qtr("value_cannot_be_empty_explanation"),
+            TableDiagnosticReportType::MissingLocData => qtr("missing_loc_data_explanation"),
+            TableDiagnosticReportType::OrphanLocKey => qtr("orphan_loc_key_explanation"),
+            TableDiagnosticReportType::InvalidEnumValue => qtr("invalid_enum_value_explanation"),
+            TableDiagnosticReportType::InvalidBitwiseValue => qtr("invalid_bitwise_value_explanation"),
+            TableDiagnosticReportType::ValueOutOfRange => qtr("value_out_of_range_explanation"),
         };
 
         for item in items {
@@ -1494,6 +1799,41 @@ impl DiagnosticsUI {
     pub unsafe fn set_tooltips_packfile(items: &[&CppBox<QStandardItem>], report_type: &PackFileDiagnosticReportType) {
         let tool_tip = match report_type {
             PackFileDiagnosticReportType::InvalidPackFileName => qtr("invalid_packfile_name_explanation"),
+            PackFileDiagnosticReportType::InvalidDiagnosticsRules => qtr("invalid_diagnostics_rules_explanation"),
+        };
+
+        for item in items {
+            item.set_tool_tip(&tool_tip);
+        }
+    }
+
+    pub unsafe fn set_tooltips_rigid_model(items: &[&CppBox<QStandardItem>], report_type: &RigidModelDiagnosticReportType) {
+        let tool_tip = match report_type {
+            RigidModelDiagnosticReportType::TexturePathNotFound => qtr("texture_path_not_found_explanation"),
+        };
+
+        for item in items {
+            item.set_tool_tip(&tool_tip);
+        }
+    }
+
+    pub unsafe fn set_tooltips_lua(items: &[&CppBox<QStandardItem>], report_type: &LuaDiagnosticReportType) {
+        let tool_tip = match report_type {
+            LuaDiagnosticReportType::SyntaxError => qtr("lua_syntax_error_explanation"),
+            LuaDiagnosticReportType::UnknownApiFunction => qtr("unknown_api_function_explanation"),
+            LuaDiagnosticReportType::MissingDBKey => qtr("missing_db_key_explanation"),
+        };
+
+        for item in items {
+            item.set_tool_tip(&tool_tip);
+        }
+    }
+
+    pub unsafe fn set_tooltips_load_order(items: &[&CppBox<QStandardItem>], report_type: &LoadOrderDiagnosticReportType) {
+        let tool_tip = match report_type {
+            LoadOrderDiagnosticReportType::FileOverridden => qtr("file_overridden_explanation"),
+            LoadOrderDiagnosticReportType::DuplicatedDBKeyAcrossPacks => qtr("duplicated_db_key_across_packs_explanation"),
+            LoadOrderDiagnosticReportType::DuplicatedLocKeyAcrossPacks => qtr("duplicated_loc_key_across_packs_explanation"),
         };
 
         for item in items {
@@ -40,6 +40,7 @@ use crate::UI_STATE;
 pub struct DiagnosticsUISlots {
     pub diagnostics_check_packfile: QBox<SlotNoArgs>,
     pub diagnostics_check_currently_open_packed_file: QBox<SlotNoArgs>,
+    pub diagnostics_fix_all: QBox<SlotNoArgs>,
     pub diagnostics_open_result: QBox<SlotOfQModelIndex>,
     pub show_hide_extra_filters: QBox<SlotOfBool>,
     pub toggle_filters: QBox<SlotNoArgs>,
@@ -89,6 +90,19 @@ impl DiagnosticsUISlots {
             }
         ));
 
+        // What happens when we try to fix all the fixable results.
+        let diagnostics_fix_all = SlotNoArgs::new(&diagnostics_ui.diagnostics_dock_widget, clone!(
+            app_ui,
+            pack_file_contents_ui,
+            diagnostics_ui => move || {
+                info!("Triggering `Fix All (Diag)` By Slot");
+
+                app_ui.main_window.set_disabled(true);
+                DiagnosticsUI::fix_all(&app_ui, &pack_file_contents_ui, &diagnostics_ui);
+                app_ui.main_window.set_disabled(false);
+            }
+        ));
+
         // What happens when we try to open the file corresponding to one of the matches.
         let diagnostics_open_result = SlotOfQModelIndex::new(&diagnostics_ui.diagnostics_dock_widget, clone!(
             app_ui,
@@ -144,6 +158,11 @@ impl DiagnosticsUISlots {
                 let _blocker_21 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_incorrect_game_path.static_upcast::<QObject>());
                 let _blocker_22 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_banned_table.static_upcast::<QObject>());
                 let _blocker_23 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_value_cannot_be_empty.static_upcast::<QObject>());
+                let _blocker_24 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_missing_loc_data.static_upcast::<QObject>());
+                let _blocker_25 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_orphan_loc_key.static_upcast::<QObject>());
+                let _blocker_26 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_invalid_enum_value.static_upcast::<QObject>());
+                let _blocker_27 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_invalid_bitwise_value.static_upcast::<QObject>());
+                let _blocker_28 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_value_out_of_range.static_upcast::<QObject>());
 
                 diagnostics_ui.checkbox_outdated_table.toggle();
                 diagnostics_ui.checkbox_invalid_reference.toggle();
@@ -169,6 +188,11 @@ impl DiagnosticsUISlots {
                 diagnostics_ui.checkbox_incorrect_game_path.toggle();
                 diagnostics_ui.checkbox_banned_table.toggle();
                 diagnostics_ui.checkbox_value_cannot_be_empty.toggle();
+                diagnostics_ui.checkbox_missing_loc_data.toggle();
+                diagnostics_ui.checkbox_orphan_loc_key.toggle();
+                diagnostics_ui.checkbox_invalid_enum_value.toggle();
+                diagnostics_ui.checkbox_invalid_bitwise_value.toggle();
+                diagnostics_ui.checkbox_value_out_of_range.toggle();
 
                 DiagnosticsUI::filter(&app_ui, &diagnostics_ui);
             }
@@ -178,6 +202,7 @@ impl DiagnosticsUISlots {
         Self {
             diagnostics_check_packfile,
             diagnostics_check_currently_open_packed_file,
+            diagnostics_fix_all,
             diagnostics_open_result,
             show_hide_extra_filters,
             toggle_filters,
@@ -0,0 +1,146 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2022 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code for managing the view for Anim PackedFiles.
+
+For now this view is read-only, and only shows the metadata and the bone table of the Anim.
+!*/
+
+use qt_widgets::QGridLayout;
+use qt_widgets::QLabel;
+use qt_widgets::QListWidget;
+use qt_widgets::QWidget;
+
+use qt_core::QBox;
+use qt_core::QString;
+use qt_core::QPtr;
+
+use std::sync::Arc;
+
+use rpfm_error::{Result, ErrorKind};
+use rpfm_lib::packedfile::PackedFileType;
+use rpfm_lib::packedfile::anim::Anim;
+use rpfm_lib::packfile::packedfile::PackedFileInfo;
+
+use crate::CENTRAL_COMMAND;
+use crate::communications::*;
+use crate::locale::qtr;
+use crate::packedfile_views::{PackedFileView, View, ViewType};
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct contains the view of an Anim PackedFile.
+pub struct PackedFileAnimView {
+    version_data_label: QBox<QLabel>,
+    skeleton_data_label: QBox<QLabel>,
+    framerate_data_label: QBox<QLabel>,
+    end_time_data_label: QBox<QLabel>,
+    num_frames_data_label: QBox<QLabel>,
+    bones_list: QBox<QListWidget>,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+/// Implementation for `PackedFileAnimView`.
+impl PackedFileAnimView {
+
+    /// This function creates a new Anim View.
+    pub unsafe fn new_view(
+        packed_file_view: &mut PackedFileView,
+    ) -> Result<PackedFileInfo> {
+
+        let receiver = CENTRAL_COMMAND.send_background(Command::DecodePackedFile(packed_file_view.get_path(), packed_file_view.get_data_source()));
+        let response = CentralCommand::recv(&receiver);
+        let (data, packed_file_info) = match response {
+            Response::AnimPackedFileInfo((data, packed_file_info)) => (data, packed_file_info),
+            Response::Error(error) => return Err(error),
+            Response::Unknown => return Err(ErrorKind::PackedFileTypeUnknown.into()),
+            _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+        };
+
+        let layout: QPtr<QGridLayout> = packed_file_view.get_mut_widget().layout().static_downcast();
+        layout.set_contents_margins_4a(4, 4, 4, 4);
+        layout.set_spacing(2);
+
+        let version_label = QLabel::from_q_string_q_widget(&qtr("version"), packed_file_view.get_mut_widget());
+        let skeleton_label = QLabel::from_q_string_q_widget(&qtr("anim_skeleton"), packed_file_view.get_mut_widget());
+        let framerate_label = QLabel::from_q_string_q_widget(&qtr("framerate"), packed_file_view.get_mut_widget());
+        let end_time_label = QLabel::from_q_string_q_widget(&qtr("anim_end_time"), packed_file_view.get_mut_widget());
+        let num_frames_label = QLabel::from_q_string_q_widget(&qtr("num_frames"), packed_file_view.get_mut_widget());
+        let bones_label = QLabel::from_q_string_q_widget(&qtr("anim_bones"), packed_file_view.get_mut_widget());
+
+        let version_data_label = QLabel::from_q_widget(packed_file_view.get_mut_widget());
+        let skeleton_data_label = QLabel::from_q_widget(packed_file_view.get_mut_widget());
+        let framerate_data_label = QLabel::from_q_widget(packed_file_view.get_mut_widget());
+        let end_time_data_label = QLabel::from_q_widget(packed_file_view.get_mut_widget());
+        let num_frames_data_label = QLabel::from_q_widget(packed_file_view.get_mut_widget());
+        let bones_list = QListWidget::new_1a(packed_file_view.get_mut_widget());
+
+        let fill_widget = QWidget::new_1a(packed_file_view.get_mut_widget());
+
+        layout.add_widget_5a(&version_label, 0, 0, 1, 1);
+        layout.add_widget_5a(&skeleton_label, 1, 0, 1, 1);
+        layout.add_widget_5a(&framerate_label, 2, 0, 1, 1);
+        layout.add_widget_5a(&end_time_label, 3, 0, 1, 1);
+        layout.add_widget_5a(&num_frames_label, 4, 0, 1, 1);
+        layout.add_widget_5a(&bones_label, 5, 0, 1, 2);
+
+        layout.add_widget_5a(&version_data_label, 0, 1, 1, 1);
+        layout.add_widget_5a(&skeleton_data_label, 1, 1, 1, 1);
+        layout.add_widget_5a(&framerate_data_label, 2, 1, 1, 1);
+        layout.add_widget_5a(&end_time_data_label, 3, 1, 1, 1);
+        layout.add_widget_5a(&num_frames_data_label, 4, 1, 1, 1);
+        layout.add_widget_5a(&bones_list, 6, 0, 1, 2);
+
+        layout.add_widget_5a(&fill_widget, 0, 2, 1, 1);
+        layout.set_row_stretch(6, 99);
+        layout.set_column_stretch(2, 99);
+
+        let view = Self {
+            version_data_label,
+            skeleton_data_label,
+            framerate_data_label,
+            end_time_data_label,
+            num_frames_data_label,
+            bones_list,
+        };
+
+        view.reload_view(&data);
+
+        packed_file_view.view = ViewType::Internal(View::Anim(Arc::new(view)));
+        packed_file_view.packed_file_type = PackedFileType::Anim;
+
+        Ok(packed_file_info)
+    }
+
+    /// Function to reload the data of the view without having to delete the view itself.
+    pub unsafe fn reload_view(&self, data: &Anim) {
+        let num_frames = match data.get_frame_count() {
+            Some(frame_count) => frame_count.to_string(),
+            None => qtr("anim_tracks_not_decoded").to_std_string(),
+        };
+
+        self.version_data_label.set_text(&QString::from_std_str(data.get_ref_version().to_string()));
+        self.skeleton_data_label.set_text(&QString::from_std_str(data.get_ref_skeleton_name()));
+        self.framerate_data_label.set_text(&QString::from_std_str(format!("{} FPS.", data.get_ref_frame_rate())));
+        self.end_time_data_label.set_text(&QString::from_std_str(format!("{} s.", data.get_ref_end_time())));
+        self.num_frames_data_label.set_text(&QString::from_std_str(num_frames));
+
+        self.bones_list.clear();
+        for (index, bone) in data.get_ref_bones().iter().enumerate() {
+            self.bones_list.add_item_q_string(&QString::from_std_str(format!("{} - {} (parent: {})", index, bone.get_ref_name(), bone.get_ref_parent_id())));
+        }
+    }
+}
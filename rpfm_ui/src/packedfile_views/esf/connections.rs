@@ -30,6 +30,7 @@ pub unsafe fn set_connections(ui: &Arc<PackedFileESFView>, slots: &PackedFileESF
     ui.filter_autoexpand_matches_button.toggled().connect(&slots.filter_change_autoexpand_matches);
     ui.filter_case_sensitive_button.toggled().connect(&slots.filter_change_case_sensitive);
     ui.filter_line_edit.text_changed().connect(&slots.filter_check_regex);
+    ui.query_line_edit.return_pressed().connect(&slots.query_trigger);
 
      ui.tree_view.selection_model().selection_changed().connect(&slots.open_node);
 }
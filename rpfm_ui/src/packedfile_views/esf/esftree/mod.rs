@@ -21,6 +21,7 @@ use qt_gui::QStandardItem;
 use qt_gui::QStandardItemModel;
 use qt_gui::QListOfQStandardItem;
 
+use qt_core::q_item_selection_model::SelectionFlag;
 use qt_core::QModelIndex;
 use qt_core::QVariant;
 use qt_core::QBox;
@@ -36,6 +37,7 @@ use cpp_core::Ref;
 
 use rpfm_lib::packedfile::esf::RecordNodeFlags;
 use rpfm_lib::packedfile::esf::{ESF, NodeType};
+use rpfm_lib::packedfile::esf::query::ESFNodePath;
 
 const ESF_DATA: i32 = 40;
 const CHILDLESS_NODE: i32 = 41;
@@ -70,6 +72,11 @@ pub(crate) trait ESFTree {
     /// This function gives you the item corresponding to an specific path.
     unsafe fn get_item_from_path(path: &[String], model: &QPtr<QStandardItemModel>) -> Ptr<QStandardItem>;
 
+    /// This function selects the items of the nodes in the provided paths, scrolling to the first one.
+    ///
+    /// Only record nodes have items in the `TreeView`, so for any other node the item of its parent is selected.
+    unsafe fn select_nodes(&self, has_filter: bool, esf: &ESF, paths: &[ESFNodePath]);
+
     /// This function takes care of EVERY operation that manipulates the provided TreeView.
     /// It does one thing or another, depending on the operation we provide it.
     unsafe fn update_treeview(&self, has_filter: bool, operation: ESFTreeViewOperation);
@@ -184,6 +191,57 @@ impl ESFTree for QBox<QTreeView> {
         item
     }
 
+    unsafe fn select_nodes(&self, has_filter: bool, esf: &ESF, paths: &[ESFNodePath]) {
+        let filter: Option<QPtr<QSortFilterProxyModel>> = if has_filter { Some(self.model().static_downcast()) } else { None };
+        let model: QPtr<QStandardItemModel> = if let Some(ref filter) = filter { filter.source_model().static_downcast() } else { self.model().static_downcast() };
+
+        self.selection_model().clear_selection();
+        let mut first_index = None;
+        for path in paths {
+            let mut item = model.item_1a(0);
+            let mut node = esf.get_ref_root_node();
+            for (depth, (group, index)) in path.iter().enumerate() {
+                if let NodeType::Record(record) = node {
+                    let children = record.get_ref_children();
+                    let child = &children[*group][*index];
+                    if let NodeType::Record(_) = child {
+
+                        // The root node and nodes without nested blocks show their records directly.
+                        // The rest show their groups first, and then the records of each group.
+                        if depth != 0 && record.get_ref_record_flags().contains(RecordNodeFlags::HAS_NESTED_BLOCKS) {
+                            let row = children[*group][..*index].iter().filter(|node| matches!(node, NodeType::Record(_))).count();
+                            item = item.child_1a(*group as i32).child_1a(row as i32);
+                        } else {
+                            let row = children.iter()
+                                .enumerate()
+                                .flat_map(|(group_index, nodes)| nodes.iter().enumerate().map(move |(node_index, node)| (group_index, node_index, node)))
+                                .take_while(|(group_index, node_index, _)| (group_index, node_index) < (group, index))
+                                .filter(|(_, _, node)| matches!(node, NodeType::Record(_)))
+                                .count();
+                            item = item.child_1a(row as i32);
+                        }
+
+                        node = child;
+                    } else { break; }
+                }
+            }
+
+            if !item.is_null() {
+                let index = if let Some(ref filter) = filter { filter.map_from_source(&item.index()) } else { item.index() };
+                if index.is_valid() {
+                    self.selection_model().select_q_model_index_q_flags_selection_flag(&index, SelectionFlag::Select | SelectionFlag::Rows);
+                    if first_index.is_none() {
+                        first_index = Some(index);
+                    }
+                }
+            }
+        }
+
+        if let Some(index) = first_index {
+            self.scroll_to_1a(&index);
+        }
+    }
+
     unsafe fn update_treeview(&self, has_filter: bool, operation: ESFTreeViewOperation) {
         let filter: Option<QPtr<QSortFilterProxyModel>> = if has_filter { Some(self.model().static_downcast()) } else { None };
         let model: QPtr<QStandardItemModel> = if let Some(ref filter) = filter { filter.source_model().static_downcast() } else { self.model().static_downcast() };
@@ -270,11 +328,7 @@ unsafe fn load_node_to_view(parent: &CppBox<QStandardItem>, child: &NodeType, bl
         // Prepare the data in a way or another, depending if we have nested blocks or not.
         if node.get_ref_record_flags().contains(RecordNodeFlags::HAS_NESTED_BLOCKS) {
             for (index, node_group) in node.get_ref_children().iter().enumerate() {
-                let node_group_name = if node_group.len() == 2 {
-                    if let NodeType::Ascii(ref key) = node_group[0] {
-                        key.to_owned()
-                    } else { format!("{}_{}", node.get_ref_name(), index) }
-                } else { format!("{}_{}", node.get_ref_name(), index) };
+                let node_group_name = node.get_group_name(index);
                 let node_group_item = QStandardItem::from_q_string(&QString::from_std_str(&node_group_name));
                 let node_group_state_item = QStandardItem::new();
                 node_group_item.set_editable(false);
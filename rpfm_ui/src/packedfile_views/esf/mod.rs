@@ -46,13 +46,13 @@ use crate::CENTRAL_COMMAND;
 use crate::communications::*;
 use crate::ffi::*;
 use crate::global_search_ui::GlobalSearchUI;
-use crate::locale::qtr;
+use crate::locale::{qtr, tr};
 use crate::packedfile_views::esf::esftree::*;
 use crate::packedfile_views::esf::slots::PackedFileESFViewSlots;
 use crate::packedfile_views::PackedFileView;
 use crate::packedfile_views::PackFileContentsUI;
 use crate::references_ui::ReferencesUI;
-use crate::utils::create_grid_layout;
+use crate::utils::{create_grid_layout, show_dialog};
 
 use self::esf_detailed_view::ESFDetailedView;
 
@@ -78,6 +78,8 @@ pub struct PackedFileESFView {
     filter_case_sensitive_button: QBox<QPushButton>,
     filter_timer_delayed_updates: QBox<QTimer>,
 
+    query_line_edit: QBox<QLineEdit>,
+
     node_data_panel: QBox<QWidget>,
 
     detailed_view: Arc<RwLock<ESFDetailedView>>,
@@ -140,6 +142,11 @@ impl PackedFileESFView {
         filter_autoexpand_matches_button.set_checkable(true);
         filter_case_sensitive_button.set_checkable(true);
 
+        // Create the widget to search nodes with path queries.
+        let query_line_edit = QLineEdit::from_q_widget(packed_file_view.get_mut_widget());
+        query_line_edit.set_placeholder_text(&qtr("esf_query"));
+        query_line_edit.set_clear_button_enabled(true);
+
         let tree_panel = QWidget::new_1a(&splitter);
         let tree_layout = create_grid_layout(tree_panel.static_upcast());
         tree_panel.set_minimum_width(200);
@@ -149,6 +156,7 @@ impl PackedFileESFView {
         tree_layout.add_widget_5a(&filter_line_edit, 1, 0, 1, 2);
         tree_layout.add_widget_5a(&filter_autoexpand_matches_button, 2, 0, 1, 1);
         tree_layout.add_widget_5a(&filter_case_sensitive_button, 2, 1, 1, 1);
+        tree_layout.add_widget_5a(&query_line_edit, 3, 0, 1, 2);
 
         let node_data_panel = QWidget::new_1a(&splitter);
         let node_data_layout = create_grid_layout(node_data_panel.static_upcast());
@@ -170,6 +178,8 @@ impl PackedFileESFView {
             filter_case_sensitive_button,
             filter_timer_delayed_updates,
 
+            query_line_edit,
+
             node_data_panel,
 
             detailed_view: Arc::new(RwLock::new(ESFDetailedView::default())),
@@ -231,6 +241,26 @@ impl PackedFileESFView {
         }
     }
 
+    /// Function to select the nodes matching the query in the query box.
+    pub unsafe fn query_nodes(view: &Arc<Self>) {
+        let query = view.query_line_edit.text().to_std_string();
+        if query.is_empty() {
+            return;
+        }
+
+        let esf = view.save_view();
+        match esf.query_paths(&query) {
+            Ok(paths) => {
+                if paths.is_empty() {
+                    show_dialog(&view.tree_view, tr("esf_query_no_matches"), false);
+                } else {
+                    view.tree_view.select_nodes(true, &esf, &paths);
+                }
+            }
+            Err(error) => show_dialog(&view.tree_view, error, false),
+        }
+    }
+
     pub unsafe fn start_delayed_updates_timer(view: &Arc<Self>,) {
         view.filter_timer_delayed_updates.set_interval(500);
         view.filter_timer_delayed_updates.start_0a();
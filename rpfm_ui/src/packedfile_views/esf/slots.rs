@@ -44,6 +44,7 @@ pub struct PackedFileESFViewSlots {
     pub filter_change_autoexpand_matches: QBox<SlotOfBool>,
     pub filter_change_case_sensitive: QBox<SlotOfBool>,
     pub filter_check_regex: QBox<SlotOfQString>,
+    pub query_trigger: QBox<SlotNoArgs>,
 
     pub open_node: QBox<SlotNoArgs>,
 }
@@ -98,6 +99,13 @@ impl PackedFileESFViewSlots {
             }
         ));
 
+        // What happens when we trigger a path query.
+        let query_trigger = SlotNoArgs::new(&view.tree_view, clone!(
+            view => move || {
+                PackedFileESFView::query_nodes(&view);
+            }
+        ));
+
         // Slot to change the format of the video to CAMV.
         let open_node = SlotNoArgs::new(&view.tree_view, clone!(
             app_ui,
@@ -125,6 +133,7 @@ impl PackedFileESFViewSlots {
             filter_change_autoexpand_matches,
             filter_change_case_sensitive,
             filter_check_regex,
+            query_trigger,
 
             open_node,
         }
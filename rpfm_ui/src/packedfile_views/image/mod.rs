@@ -62,15 +62,15 @@ impl PackedFileImageView {
         // Get the path of the extracted Image.
         let receiver = CENTRAL_COMMAND.send_background(Command::DecodePackedFile(packed_file_view.get_path(), packed_file_view.get_data_source()));
         let response = CentralCommand::recv(&receiver);
-        let (image, packed_file_info) = match response {
-            Response::ImagePackedFileInfo((image, packed_file_info)) => (image, packed_file_info),
+        let (image_data, packed_file_info) = match response {
+            Response::ImagePackedFileInfo((image_data, packed_file_info)) => (image_data, packed_file_info),
             Response::Error(error) => return Err(error),
             Response::Unknown => return Err(ErrorKind::PackedFileTypeUnknown.into()),
             _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
         };
 
         // Create the image in the UI.
-        let byte_array = QByteArray::from_slice(image.get_data()).into_ptr();
+        let byte_array = QByteArray::from_slice(image_data.get_data()).into_ptr();
 
         #[cfg(feature = "support_modern_dds")]
         let mut image = QPixmap::new();
@@ -78,8 +78,8 @@ impl PackedFileImageView {
         #[cfg(not(feature = "support_modern_dds"))]
         let image = QPixmap::new();
 
-        // If it fails to load and it's a dds, try the modern loader if its enabled.
-        if !image.load_from_data_q_byte_array(byte_array.as_ref().unwrap()) {
+        // If it fails to load, try converting it to PNG first. If that fails too and it's a dds, try the modern loader if its enabled.
+        if !image.load_from_data_q_byte_array(byte_array.as_ref().unwrap()) && !Self::load_converted_image(&image, &image_data) {
 
             #[cfg(feature = "support_modern_dds")] {
                 if packed_file_info.path.last().unwrap().to_lowercase().ends_with(".dds") {
@@ -118,7 +118,20 @@ impl PackedFileImageView {
     /// Function to reload the data of the view without having to delete the view itself.
     pub unsafe fn reload_view(&self, data: &Image) {
         let byte_array = QByteArray::from_slice(data.get_data());
-        self.image.load_from_data_q_byte_array(byte_array.into_ptr().as_ref().unwrap());
+        if !self.image.load_from_data_q_byte_array(byte_array.into_ptr().as_ref().unwrap()) {
+            Self::load_converted_image(&self.image, data);
+        }
+
         set_pixmap_on_resizable_label_safe(&self.label.as_ptr(), &self.image.as_ptr());
     }
+
+    /// This function tries to load an image Qt cannot read into the provided pixmap, by converting it to PNG first.
+    ///
+    /// It returns true if the image was loaded.
+    unsafe fn load_converted_image(pixmap: &QPixmap, image: &Image) -> bool {
+        match image.to_png() {
+            Ok(png) => pixmap.load_from_data_q_byte_array(QByteArray::from_slice(&png).into_ptr().as_ref().unwrap()),
+            Err(_) => false,
+        }
+    }
 }
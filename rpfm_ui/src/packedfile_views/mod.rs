@@ -26,8 +26,7 @@ use std::sync::{Arc, RwLock, RwLockReadGuard};
 use rpfm_error::{ErrorKind, Result};
 
 use rpfm_lib::packedfile::{DecodedPackedFile, PackedFileType};
-use rpfm_lib::packedfile::table::{animtable::AnimTable, db::DB, loc::Loc, matched_combat::MatchedCombat};
-use rpfm_lib::packedfile::text::Text;
+use rpfm_lib::packedfile::table::{animtable::AnimTable, db::DB, group_formations::GroupFormations, loc::Loc, matched_combat::MatchedCombat};
 
 use crate::app_ui::AppUI;
 use crate::CENTRAL_COMMAND;
@@ -41,6 +40,7 @@ use crate::utils::show_dialog;
 use crate::UI_STATE;
 use crate::views::table::TableType;
 
+use self::anim::PackedFileAnimView;
 use self::anim_fragment::{PackedFileAnimFragmentView, PackedFileAnimFragmentDebugView};
 use self::animpack::PackedFileAnimPackView;
 use self::ca_vp8::PackedFileCaVp8View;
@@ -62,6 +62,7 @@ use self::rigidmodel::PackedFileRigidModelView;
 use self::uic::PackedFileUICView;
 use self::unit_variant::PackedFileUnitVariantView;
 
+pub mod anim;
 pub mod anim_fragment;
 pub mod animpack;
 pub mod ca_vp8;
@@ -134,6 +135,7 @@ pub enum DataSource {
 
 /// This enum is used to hold in a common way all the view types we have.
 pub enum View {
+    Anim(Arc<PackedFileAnimView>),
     AnimFragment(Arc<PackedFileAnimFragmentView>),
     AnimFragmentDebug(Arc<PackedFileAnimFragmentDebugView>),
     AnimPack(Arc<PackedFileAnimPackView>),
@@ -298,6 +300,7 @@ impl PackedFileView {
                         let data = match self.packed_file_type {
                             PackedFileType::AnimTable |
                             PackedFileType::DB |
+                            PackedFileType::GroupFormations |
                             PackedFileType::Loc |
                             PackedFileType::MatchedCombat => if let View::Table(view) = view {
 
@@ -317,6 +320,10 @@ impl PackedFileView {
                                         table.set_table_data(new_table.get_ref_table_data())?;
                                         DecodedPackedFile::DB(table)
                                     }
+                                    PackedFileType::GroupFormations => {
+                                        let table = GroupFormations::from(new_table);
+                                        DecodedPackedFile::GroupFormations(table)
+                                    }
                                     PackedFileType::Loc => {
                                         let table = Loc::from(new_table);
                                         DecodedPackedFile::Loc(table)
@@ -329,8 +336,9 @@ impl PackedFileView {
                                 }
                             } else { return Err(ErrorKind::PackedFileSaveError(self.get_path()).into()) },
 
-                            // Images are read-only.
-                            PackedFileType::Image => return Ok(()),
+                            // Images and Anims are read-only.
+                            PackedFileType::Image |
+                            PackedFileType::Anim => return Ok(()),
 
                             // AnimPacks save on edit.
                             PackedFileType::AnimPack => return Ok(()),
@@ -361,7 +369,7 @@ impl PackedFileView {
 
                             PackedFileType::Text(_) => {
                                 if let View::Text(view) = view {
-                                    let mut text = Text::default();
+                                    let mut text = view.get_ref_text().read().unwrap().clone();
                                     let widget = view.get_mut_editor();
                                     let string = get_text_safe(widget).to_std_string();
                                     text.set_contents(&string);
@@ -429,14 +437,10 @@ impl PackedFileView {
                                 } else { return Err(ErrorKind::PackedFileSaveError(self.get_path()).into()) }
                             },
 
-                            // Disable saving UIC until support for saving them is wired up.
                             #[cfg(feature = "support_uic")]
-                            PackedFileType::UIC => {
-                                return Ok(());
-                                //if let View::UIC(view) = view {
-                                //    DecodedPackedFile::UIC(view.save_view())
-                                //} else { return Err(ErrorKind::PackedFileSaveError(self.get_path()).into()) }
-                            },
+                            PackedFileType::UIC => if let View::UIC(view) = view {
+                                DecodedPackedFile::UIC(view.save_view())
+                            } else { return Err(ErrorKind::PackedFileSaveError(self.get_path()).into()) },
 
                             // UnitVariant use custom saving.
                             PackedFileType::UnitVariant => return Ok(()),
@@ -499,6 +503,16 @@ impl PackedFileView {
 
                     match response {
 
+                        Response::AnimPackedFileInfo((anim, packed_file_info)) => {
+                            if let View::Anim(old_anim) = view {
+                                old_anim.reload_view(&anim);
+                                pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::UpdateTooltip(vec![packed_file_info;1]), DataSource::PackFile);
+                            }
+                            else {
+                                return Err(ErrorKind::NewDataIsNotDecodeableTheSameWayAsOldDAta.into());
+                            }
+                        },
+
                         Response::AnimFragmentPackedFileInfo((fragment, packed_file_info)) => {
                             if let View::AnimFragment(old_fragment) = view {
                                 if old_fragment.reload_view(fragment).is_err() {
@@ -567,6 +581,18 @@ impl PackedFileView {
                             }
                         },
 
+                        Response::GroupFormationsPackedFileInfo((table, packed_file_info)) => {
+                            if let View::Table(old_table) = view {
+                                let old_table = old_table.get_ref_table();
+                                old_table.reload_view(TableType::GroupFormations(table));
+                                pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::UpdateTooltip(vec![packed_file_info;1]), DataSource::PackFile);
+
+                            }
+                            else {
+                                return Err(ErrorKind::NewDataIsNotDecodeableTheSameWayAsOldDAta.into());
+                            }
+                        },
+
                         Response::LocPackedFileInfo((table, packed_file_info)) => {
                             if let View::Table(old_table) = view {
                                 let old_table = old_table.get_ref_table();
@@ -665,6 +691,7 @@ impl PackedFileView {
                     match self.packed_file_type {
                         PackedFileType::AnimTable |
                         PackedFileType::DB |
+                        PackedFileType::GroupFormations |
                         PackedFileType::Loc |
                         PackedFileType::MatchedCombat => if let View::Table(view) = view {
                             view.get_ref_table().clear_markings();
@@ -69,7 +69,7 @@ impl PackedFileRigidModelView {
         };
 
         // Create the new view and populate it.
-        let data = QByteArray::from_slice(&rigid_model.data);
+        let data = QByteArray::from_slice(&rigid_model.save()?);
         let editor = new_rigid_model_view_safe(&mut packed_file_view.get_mut_widget().as_ptr());
         set_rigid_model_view_safe(&mut editor.as_ptr(), &data.as_ptr())?;
 
@@ -89,15 +89,13 @@ impl PackedFileRigidModelView {
     /// Function to save the view and encode it into a RigidModel struct.
     pub unsafe fn save_view(&self) -> Result<RigidModel> {
         let qdata = get_rigid_model_from_view_safe(&self.editor)?;
-        let data = std::slice::from_raw_parts(qdata.data_mut() as *mut u8, qdata.length() as usize).to_vec();
-        Ok(RigidModel {
-            data
-        })
+        let data = std::slice::from_raw_parts(qdata.data_mut() as *mut u8, qdata.length() as usize);
+        Ok(RigidModel::read_or_keep_raw(data))
     }
 
     /// Function to reload the data of the view without having to delete the view itself.
     pub unsafe fn reload_view(&self, data: &RigidModel) -> Result<()> {
-        let byte_array = QByteArray::from_slice(&data.data);
+        let byte_array = QByteArray::from_slice(&data.save()?);
         set_rigid_model_view_safe(&mut self.editor.as_ptr(), &byte_array.as_ptr())
     }
 }
@@ -66,6 +66,7 @@ impl PackedFileTableView {
         let (table_data, packed_file_info) = match response {
             Response::AnimTablePackedFileInfo((table, packed_file_info)) => (TableType::AnimTable(table), Some(packed_file_info)),
             Response::DBPackedFileInfo((table, packed_file_info)) => (TableType::DB(table), Some(packed_file_info)),
+            Response::GroupFormationsPackedFileInfo((table, packed_file_info)) => (TableType::GroupFormations(table), Some(packed_file_info)),
             Response::LocPackedFileInfo((table, packed_file_info)) => (TableType::Loc(table), Some(packed_file_info)),
             Response::MatchedCombatPackedFileInfo((table, packed_file_info)) => (TableType::MatchedCombat(table), Some(packed_file_info)),
             Response::Error(error) => return Err(error),
@@ -79,6 +80,7 @@ impl PackedFileTableView {
             TableType::AnimFragment(_) => PackedFileType::AnimFragment,
             TableType::AnimTable(_) => PackedFileType::AnimTable,
             TableType::DB(_) => PackedFileType::DB,
+            TableType::GroupFormations(_) => PackedFileType::GroupFormations,
             TableType::Loc(_) => PackedFileType::Loc,
             TableType::MatchedCombat(_) => PackedFileType::MatchedCombat,
             _ => unimplemented!()
@@ -55,6 +55,9 @@ pub struct PackedFileTextView {
     editor: QBox<QWidget>,
     packed_file_path: Option<Arc<RwLock<Vec<String>>>>,
     data_source: Arc<RwLock<DataSource>>,
+
+    /// The Text as it was decoded, so we can save it back with the same encoding and line endings.
+    text: Arc<RwLock<Text>>,
 }
 
 //-------------------------------------------------------------------------------//
@@ -104,6 +107,7 @@ impl PackedFileTextView {
             editor,
             packed_file_path: Some(packed_file_view.get_path_raw()),
             data_source: Arc::new(RwLock::new(packed_file_view.get_data_source())),
+            text: Arc::new(RwLock::new(text.clone())),
         });
 
         let slots = PackedFileTextViewSlots::new(&view, app_ui, pack_file_contents_ui);
@@ -121,8 +125,14 @@ impl PackedFileTextView {
         &self.editor
     }
 
+    /// This function returns the Text shown in this view, with its contents as they were when it was last loaded.
+    pub fn get_ref_text(&self) -> &Arc<RwLock<Text>> {
+        &self.text
+    }
+
     /// Function to reload the data of the view without having to delete the view itself.
     pub unsafe fn reload_view(&self, data: &Text) {
+        *self.text.write().unwrap() = data.clone();
 
         let highlighting_mode = match data.get_text_type() {
             TextType::Cpp => QString::from_std_str(CPP),
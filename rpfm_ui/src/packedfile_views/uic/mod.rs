@@ -50,6 +50,9 @@ pub struct PackedFileUICView {
     viewer: QBox<QGraphicsView>,
     scene: QBox<QGraphicsScene>,
     properties: QBox<QWidget>,
+
+    /// The decoded UIC, so it can be saved back without changes.
+    uic: UIC,
 }
 
 //-------------------------------------------------------------------------------//
@@ -81,7 +84,7 @@ impl PackedFileUICView {
         viewer.set_scene(&scene);
         viewer.set_drag_mode(DragMode::ScrollHandDrag);
 
-        let test_item = scene.add_text_1a(&qt_core::QString::from_std_str(&format!("{:?}", data)));
+        let test_item = scene.add_text_1a(&qt_core::QString::from_std_str(&data.to_xml()?));
         let flags = QFlags::from(qt_widgets::q_graphics_item::GraphicsItemFlag::ItemIsMovable.to_int());
         test_item.as_ptr().static_upcast::<QGraphicsItem>().set_flags(flags);
 
@@ -91,6 +94,13 @@ impl PackedFileUICView {
         let test_label = QLabel::from_q_string_q_widget(&qtr("format"), &properties);
         properties_layout.add_widget_5a(&test_label, 0, 0, 1, 1);
 
+        // Let the user know if we couldn't decode the layout, as the view only shows its raw data.
+        if !data.is_decoded() {
+            let not_decoded_label = QLabel::from_q_string_q_widget(&qtr("uic_layout_not_decoded"), &properties);
+            not_decoded_label.set_word_wrap(true);
+            properties_layout.add_widget_5a(&not_decoded_label, 1, 0, 1, 1);
+        }
+
         layout.add_widget_5a(&viewer, 0, 0, 1, 1);
         layout.add_widget_5a(&properties, 0, 1, 1, 1);
 
@@ -98,6 +108,7 @@ impl PackedFileUICView {
             scene,
             viewer,
             properties,
+            uic: data,
         });
 
         //let pack_file_settings_slots = PackFileSettingsSlots::new(
@@ -114,8 +125,6 @@ impl PackedFileUICView {
 
     /// This function saves a PackFileSettingsView into a PackFileSetting.
     pub unsafe fn save_view(&self) -> UIC {
-        let uic = UIC::default();
-
-        uic
+        self.uic.clone()
     }
 }
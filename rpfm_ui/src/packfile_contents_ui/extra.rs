@@ -24,12 +24,20 @@ use qt_widgets::QGroupBox;
 use qt_widgets::QLabel;
 use qt_widgets::QLineEdit;
 use qt_widgets::QPushButton;
+use qt_widgets::QTableView;
+use qt_widgets::q_header_view::ResizeMode;
+
+use qt_gui::QListOfQStandardItem;
+use qt_gui::QStandardItem;
+use qt_gui::QStandardItemModel;
 
 use qt_core::CaseSensitivity;
+use qt_core::Orientation;
 use qt_core::QBox;
 use qt_core::QRegExp;
 use qt_core::QString;
 use qt_core::SlotNoArgs;
+use qt_core::QVariant;
 
 use std::cell::RefCell;
 use std::path::PathBuf;
@@ -37,6 +45,7 @@ use std::rc::Rc;
 
 use rpfm_error::ErrorKind;
 
+use rpfm_lib::packedfile::table::MergedTable;
 use rpfm_lib::packfile::PathType;
 use rpfm_lib::SETTINGS;
 
@@ -300,6 +309,53 @@ impl PackFileContentsUI {
         else { None }
     }
 
+    /// This function creates a read-only dialog showing the merged version of a DB Table.
+    ///
+    /// Each row has two extra columns at the end, with the Pack and the File the row comes from.
+    pub unsafe fn create_merged_table_dialog(app_ui: &Rc<AppUI>, table_name: &str, merged_table: &MergedTable) {
+
+        // Create and configure the dialog.
+        let dialog = QDialog::new_1a(&app_ui.main_window);
+        dialog.set_window_title(&qtre("merged_table_title", &[table_name]));
+        dialog.set_modal(true);
+        dialog.resize_2a(1000, 600);
+        let main_grid = create_grid_layout(dialog.static_upcast());
+
+        let table_view = QTableView::new_1a(&dialog);
+        let table_model = QStandardItemModel::new_1a(&dialog);
+        table_view.set_model(&table_model);
+        table_view.set_sorting_enabled(true);
+        main_grid.add_widget_5a(&table_view, 0, 0, 1, 1);
+
+        let table = merged_table.get_ref_table();
+        for (row, provenance) in table.get_ref_table_data().iter().zip(merged_table.get_ref_provenance()) {
+            let qlist = QListOfQStandardItem::new();
+            let texts = row.iter()
+                .map(|cell| cell.data_to_string())
+                .chain(vec![provenance.pack.to_owned(), provenance.path.join("/")]);
+
+            for text in texts {
+                let item = QStandardItem::from_q_string(&QString::from_std_str(&text));
+                item.set_editable(false);
+                qlist.append_q_standard_item(&item.into_ptr().as_mut_raw_ptr());
+            }
+
+            table_model.append_row_q_list_of_q_standard_item(qlist.as_ref());
+        }
+
+        let fields = table.get_ref_definition().get_fields_processed();
+        for (index, field) in fields.iter().enumerate() {
+            table_model.set_header_data_3a(index as i32, Orientation::Horizontal, &QVariant::from_q_string(&QString::from_std_str(field.get_name())));
+        }
+        table_model.set_header_data_3a(fields.len() as i32, Orientation::Horizontal, &QVariant::from_q_string(&qtr("merged_table_pack")));
+        table_model.set_header_data_3a(fields.len() as i32 + 1, Orientation::Horizontal, &QVariant::from_q_string(&qtr("merged_table_file")));
+
+        table_view.horizontal_header().set_stretch_last_section(true);
+        table_view.horizontal_header().resize_sections(ResizeMode::ResizeToContents);
+
+        dialog.exec();
+    }
+
     pub unsafe fn extract_packed_files(
         app_ui: &Rc<AppUI>,
         pack_file_contents_ui: &Rc<Self>,
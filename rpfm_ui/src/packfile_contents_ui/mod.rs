@@ -89,6 +89,7 @@ pub struct PackFileContentsUI {
     pub context_menu_open_packfile_settings: QPtr<QAction>,
     pub context_menu_open_with_external_program: QPtr<QAction>,
     pub context_menu_open_notes: QPtr<QAction>,
+    pub context_menu_open_merged_table: QPtr<QAction>,
     pub context_menu_merge_tables: QPtr<QAction>,
     pub context_menu_update_table: QPtr<QAction>,
     pub context_menu_generate_missing_loc_data: QPtr<QAction>,
@@ -201,6 +202,7 @@ impl PackFileContentsUI {
         let context_menu_open_packfile_settings = menu_open.add_action_q_string(&qtr("context_menu_open_packfile_settings"));
         let context_menu_open_with_external_program = menu_open.add_action_q_string(&qtr("context_menu_open_with_external_program"));
         let context_menu_open_notes = menu_open.add_action_q_string(&qtr("context_menu_open_notes"));
+        let context_menu_open_merged_table = menu_open.add_action_q_string(&qtr("context_menu_open_merged_table"));
         let context_menu_merge_tables = packfile_contents_tree_view_context_menu.add_action_q_string(&qtr("context_menu_merge_tables"));
         let context_menu_update_table = packfile_contents_tree_view_context_menu.add_action_q_string(&qtr("context_menu_update_table"));
         let context_menu_generate_missing_loc_data = packfile_contents_tree_view_context_menu.add_action_q_string(&qtr("context_menu_generate_missing_loc_data"));
@@ -234,6 +236,7 @@ impl PackFileContentsUI {
         context_menu_open_packfile_settings.set_enabled(false);
         context_menu_open_with_external_program.set_enabled(false);
         context_menu_open_notes.set_enabled(false);
+        context_menu_open_merged_table.set_enabled(false);
 
         // Create ***Da monsta***.
         Self {
@@ -281,6 +284,7 @@ impl PackFileContentsUI {
             context_menu_open_packfile_settings,
             context_menu_open_with_external_program,
             context_menu_open_notes,
+            context_menu_open_merged_table,
 
             context_menu_merge_tables,
             context_menu_update_table,
@@ -93,6 +93,7 @@ pub struct PackFileContentsSlots {
     pub contextual_menu_open_in_external_program: QBox<SlotOfBool>,
     pub contextual_menu_open_packfile_settings: QBox<SlotOfBool>,
     pub contextual_menu_open_notes: QBox<SlotOfBool>,
+    pub contextual_menu_open_merged_table: QBox<SlotOfBool>,
 
     pub contextual_menu_tables_merge_tables: QBox<SlotOfBool>,
     pub contextual_menu_tables_update_table: QBox<SlotOfBool>,
@@ -437,9 +438,15 @@ impl PackFileContentsSlots {
                     _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                 };
 
+                // The merged view of a table is only available if we have one table, or one table folder, selected.
+                let selected_paths = pack_file_contents_ui.packfile_contents_tree_view.get_path_from_selection();
+                let enabled = selected_paths.len() == 1 && selected_paths[0].len() >= 2 && selected_paths[0][0].to_lowercase() == "db";
+                pack_file_contents_ui.context_menu_open_merged_table.set_enabled(enabled);
+
                 // If there is no dependency_database or schema for our GameSelected, ALWAYS disable creating new DB Tables and exporting them.
                 if !is_there_a_dependency_database || SCHEMA.read().unwrap().is_none() {
                     pack_file_contents_ui.context_menu_update_table.set_enabled(false);
+                    pack_file_contents_ui.context_menu_open_merged_table.set_enabled(false);
                     pack_file_contents_ui.context_menu_mass_import_tsv.set_enabled(false);
                     pack_file_contents_ui.context_menu_mass_export_tsv.set_enabled(false);
                 }
@@ -990,6 +997,28 @@ impl PackFileContentsSlots {
             AppUI::open_packedfile(&app_ui, &pack_file_contents_ui, &global_search_ui, &diagnostics_ui, &dependencies_ui, &references_ui, Some(vec![RESERVED_NAME_NOTES.to_owned()]), false, false, DataSource::PackFile);
         }));
 
+        // What happens when we trigger the "Open Merged Table" Action.
+        let contextual_menu_open_merged_table = SlotOfBool::new(&pack_file_contents_ui.packfile_contents_dock_widget, clone!(
+            app_ui,
+            pack_file_contents_ui => move |_| {
+            info!("Triggering `Open Merged Table` By Slot");
+
+            // The table name is always the second item of the path, whether we have the table or its folder selected.
+            let selected_paths = pack_file_contents_ui.packfile_contents_tree_view.get_path_from_selection();
+            match selected_paths.get(0) {
+                Some(path) if path.len() >= 2 && path[0].to_lowercase() == "db" => {
+                    let receiver = CENTRAL_COMMAND.send_background(Command::GetMergedDBTable(path[1].to_owned()));
+                    let response = CentralCommand::recv(&receiver);
+                    match response {
+                        Response::MergedTable(merged_table) => PackFileContentsUI::create_merged_table_dialog(&app_ui, &path[1], &merged_table),
+                        Response::Error(error) => show_dialog(&app_ui.main_window, error, false),
+                        _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+                    }
+                }
+                _ => show_dialog(&app_ui.main_window, ErrorKind::NoDBTableSelected, false),
+            }
+        }));
+
         // What happens when we trigger the "Merge Tables" action in the Contextual Menu.
         let contextual_menu_tables_merge_tables = SlotOfBool::new(&pack_file_contents_ui.packfile_contents_dock_widget, clone!(
             app_ui,
@@ -1291,6 +1320,7 @@ impl PackFileContentsSlots {
             contextual_menu_open_in_external_program,
             contextual_menu_open_packfile_settings,
             contextual_menu_open_notes,
+            contextual_menu_open_merged_table,
 
             contextual_menu_tables_merge_tables,
             contextual_menu_tables_update_table,
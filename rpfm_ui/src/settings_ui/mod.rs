@@ -48,7 +48,7 @@ use rpfm_error::Result;
 
 use rpfm_lib::SUPPORTED_GAMES;
 use rpfm_lib::games::supported_games::*;
-use rpfm_lib::settings::{Settings, MYMOD_BASE_PATH, ZIP_PATH};
+use rpfm_lib::settings::{Settings, MYMOD_BASE_PATH};
 use rpfm_lib::updater::{BETA, STABLE, get_update_channel, UpdateChannel};
 
 use crate::AppUI;
@@ -82,9 +82,6 @@ pub struct SettingsUI {
     //-------------------------------------------------------------------------------//
     // `Path` section of the `Settings` dialog.
     //-------------------------------------------------------------------------------//
-    pub paths_zip_label: QBox<QLabel>,
-    pub paths_zip_line_edit: QBox<QLineEdit>,
-    pub paths_zip_button: QBox<QPushButton>,
     pub paths_mymod_label: QBox<QLabel>,
     pub paths_mymod_line_edit: QBox<QLineEdit>,
     pub paths_mymod_button: QBox<QPushButton>,
@@ -364,16 +361,6 @@ impl SettingsUI {
         extra_paths_grid.add_widget_5a(&paths_mymod_line_edit, 0, 1, 1, 1);
         extra_paths_grid.add_widget_5a(&paths_mymod_button, 0, 2, 1, 1);
 
-        // Create the 7Zip path stuff.
-        let paths_zip_label = QLabel::from_q_string_q_widget(&qtr("settings_paths_zip"), &extra_paths_frame);
-        let paths_zip_line_edit = QLineEdit::from_q_widget(&extra_paths_frame);
-        let paths_zip_button = QPushButton::from_q_string_q_widget(&QString::from_std_str("..."), &extra_paths_frame);
-        paths_zip_line_edit.set_placeholder_text(&qtr("settings_paths_zip_ph"));
-
-        extra_paths_grid.add_widget_5a(&paths_zip_label, 1, 0, 1, 1);
-        extra_paths_grid.add_widget_5a(&paths_zip_line_edit, 1, 1, 1, 1);
-        extra_paths_grid.add_widget_5a(&paths_zip_button, 1, 2, 1, 1);
-
         paths_grid.add_widget_5a(&extra_paths_frame, 1, 0, 1, 3);
 
         //-----------------------------------------------//
@@ -790,9 +777,6 @@ impl SettingsUI {
             //-------------------------------------------------------------------------------//
             // `Path` section of the `Settings` dialog.
             //-------------------------------------------------------------------------------//
-            paths_zip_label,
-            paths_zip_line_edit,
-            paths_zip_button,
             paths_mymod_label,
             paths_mymod_line_edit,
             paths_mymod_button,
@@ -940,9 +924,8 @@ impl SettingsUI {
     /// This function loads the data from the provided `Settings` into our `SettingsUI`.
     pub unsafe fn load(&self, settings: &Settings) -> Result<()> {
 
-        // Load the MyMod and 7Zip paths, if exists.
+        // Load the MyMod path, if exists.
         self.paths_mymod_line_edit.set_text(&QString::from_std_str(settings.paths[MYMOD_BASE_PATH].clone().unwrap_or_else(PathBuf::new).to_string_lossy()));
-        self.paths_zip_line_edit.set_text(&QString::from_std_str(settings.paths[ZIP_PATH].clone().unwrap_or_else(PathBuf::new).to_string_lossy()));
 
         // Load the Game Paths, if they exists.
         for (key, path) in self.paths_games_line_edits.iter() {
@@ -1094,9 +1077,6 @@ impl SettingsUI {
         let mymod_new_path = PathBuf::from(self.paths_mymod_line_edit.text().to_std_string());
         settings.paths.insert(MYMOD_BASE_PATH.to_owned(), if mymod_new_path.is_dir() { Some(mymod_new_path) } else { None });
 
-        let zip_new_path = PathBuf::from(self.paths_zip_line_edit.text().to_std_string());
-        settings.paths.insert(ZIP_PATH.to_owned(), if zip_new_path.is_file() { Some(zip_new_path) } else { None });
-
         // For each entry, we check if it's a valid directory and save it into Settings.
         for (key, line_edit) in self.paths_games_line_edits.iter() {
             let new_path = PathBuf::from(line_edit.text().to_std_string());
@@ -1205,7 +1185,6 @@ impl SettingsUI {
                 Some(line_edit) => (line_edit, false),
                 None => match game {
                     MYMOD_BASE_PATH => (&self.paths_mymod_line_edit, false),
-                    ZIP_PATH => (&self.paths_zip_line_edit, true),
                     _ => return,
                 }
             }
@@ -1214,7 +1193,6 @@ impl SettingsUI {
                 Some(line_edit) => (line_edit, false),
                 None => match game {
                     MYMOD_BASE_PATH => (&self.paths_mymod_line_edit, false),
-                    ZIP_PATH => (&self.paths_zip_line_edit, true),
                     _ => return,
                 }
             }
@@ -34,7 +34,7 @@ use std::rc::Rc;
 use std::process::Command as SystemCommand;
 
 use rpfm_lib::common::*;
-use rpfm_lib::settings::{init_config_path, Settings, MYMOD_BASE_PATH, ZIP_PATH};
+use rpfm_lib::settings::{init_config_path, Settings, MYMOD_BASE_PATH};
 
 use crate::AppUI;
 use crate::CENTRAL_COMMAND;
@@ -58,7 +58,6 @@ use crate::utils::show_dialog;
 pub struct SettingsUISlots {
     pub restore_default: QBox<SlotNoArgs>,
     pub select_mymod_path: QBox<SlotNoArgs>,
-    pub select_zip_path: QBox<SlotNoArgs>,
     pub select_game_paths: BTreeMap<String, QBox<SlotNoArgs>>,
     pub select_asskit_paths: BTreeMap<String, QBox<SlotNoArgs>>,
     pub shortcuts: QBox<SlotNoArgs>,
@@ -123,12 +122,6 @@ impl SettingsUISlots {
             }
         ));
 
-        // What happens when we hit the "..." button for 7Zip.
-        let select_zip_path = SlotNoArgs::new(&ui.dialog, clone!(
-            ui => move || {
-            ui.update_entry_path(ZIP_PATH, false);
-        }));
-
         // What happens when we hit any of the "..." buttons for the games.
         let mut select_game_paths = BTreeMap::new();
         for key in ui.paths_games_line_edits.keys() {
@@ -313,7 +306,6 @@ impl SettingsUISlots {
 		Self {
             restore_default,
             select_mymod_path,
-            select_zip_path,
             select_game_paths,
             select_asskit_paths,
             shortcuts,
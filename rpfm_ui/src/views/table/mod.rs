@@ -57,7 +57,7 @@ use rpfm_error::{ErrorKind, Result};
 use rpfm_lib::common::parse_str_as_bool;
 use rpfm_lib::GAME_SELECTED;
 use rpfm_lib::packedfile::PackedFileType;
-use rpfm_lib::packedfile::table::{DependencyData, anim_fragment::AnimFragment, animtable::AnimTable, DecodedData, db::DB, loc::Loc, matched_combat::MatchedCombat, Table};
+use rpfm_lib::packedfile::table::{DependencyData, anim_fragment::AnimFragment, animtable::AnimTable, DecodedData, db::DB, group_formations::GroupFormations, loc::Loc, matched_combat::MatchedCombat, Table};
 use rpfm_lib::schema::{Definition, FieldType, Schema, VersionedFile};
 use rpfm_lib::SCHEMA;
 use rpfm_lib::SETTINGS;
@@ -113,6 +113,7 @@ pub enum TableType {
     AnimTable(AnimTable),
     DependencyManager(Vec<Vec<DecodedData>>),
     DB(DB),
+    GroupFormations(GroupFormations),
     Loc(Loc),
     MatchedCombat(MatchedCombat),
 
@@ -293,6 +294,7 @@ impl TableView {
                 }
             },
             TableType::DB(ref table) => (table.get_definition(), Some(table.get_table_name()), Some(table.get_uuid()), PackedFileType::DB),
+            TableType::GroupFormations(ref table) => (table.get_definition(), None, None, PackedFileType::GroupFormations),
             TableType::Loc(ref table) => (table.get_definition(), None, None, PackedFileType::Loc),
             TableType::MatchedCombat(ref table) => (table.get_definition(), None, None, PackedFileType::MatchedCombat),
             TableType::AnimTable(ref table) => (table.get_definition(), None, None, PackedFileType::AnimTable),
@@ -714,6 +716,7 @@ impl TableView {
             TableType::AnimFragment(ref table) => table.get_definition(),
             TableType::AnimTable(ref table) => table.get_definition(),
             TableType::DB(ref table) => table.get_definition(),
+            TableType::GroupFormations(ref table) => table.get_definition(),
             TableType::Loc(ref table) => table.get_definition(),
             TableType::MatchedCombat(ref table) => table.get_definition(),
             TableType::NormalTable(ref table) => table.get_definition(),
@@ -782,6 +782,7 @@ impl TableViewSlots {
                     let table: Table = serde_json::from_str(&data).unwrap();
                     let table_data = match *view.packed_file_type {
                         PackedFileType::DB => TableType::DB(From::from(table)),
+                        PackedFileType::GroupFormations => TableType::GroupFormations(From::from(table)),
                         PackedFileType::Loc => TableType::Loc(From::from(table)),
                         PackedFileType::MatchedCombat => TableType::MatchedCombat(From::from(table)),
                         PackedFileType::AnimTable => TableType::AnimTable(From::from(table)),
@@ -437,6 +437,7 @@ pub unsafe fn load_data(
         TableType::AnimTable(data) => (data.get_ref_table_data(), None),
         TableType::DependencyManager(data) => (&**data, None),
         TableType::DB(data) => (data.get_ref_table_data(), Some(data.get_table_name())),
+        TableType::GroupFormations(data) => (data.get_ref_table_data(), None),
         TableType::Loc(data) => (data.get_ref_table_data(), None),
         TableType::MatchedCombat(data) => (data.get_ref_table_data(), None),
         TableType::NormalTable(data) => (data.get_ref_table_data(), None),